@@ -0,0 +1,153 @@
+// Persistent storage plugin for the web build of Asteroids.
+//
+// Exposes browser storage to the game so that high scores, settings and key
+// bindings survive page reloads. Documents live in localStorage where it is
+// available. Where it is not, as in some private browsing modes or when site
+// data is blocked, they are kept in IndexedDB instead: IndexedDB is
+// asynchronous, so its documents are read into memory before the game starts,
+// and written back as the game saves them. Keys are prefixed to avoid
+// clashing with anything else hosted on the domain.
+const asteroidsStorage = (function () {
+    const prefix = "asteroids:";
+
+    function localStore() {
+        try {
+            const store = window.localStorage;
+            store.setItem(prefix + "probe", "");
+            store.removeItem(prefix + "probe");
+            return store;
+        } catch (e) {
+            return null;
+        }
+    }
+
+    // Documents held in IndexedDB, mirrored in memory
+    function indexedStore() {
+        const documents = new Map();
+        let database = null;
+
+        const ready = new Promise(function (resolve) {
+            let request;
+            try {
+                request = window.indexedDB.open("asteroids", 1);
+            } catch (e) {
+                resolve();
+                return;
+            }
+
+            request.onupgradeneeded = function () {
+                request.result.createObjectStore("documents");
+            };
+            request.onerror = function () {
+                console.warn("Unable to open IndexedDB, nothing will be saved");
+                resolve();
+            };
+            request.onsuccess = function () {
+                database = request.result;
+
+                const cursor = database.transaction("documents").objectStore("documents").openCursor();
+                cursor.onsuccess = function () {
+                    if (cursor.result) {
+                        documents.set(cursor.result.key, cursor.result.value);
+                        cursor.result.continue();
+                    } else {
+                        resolve();
+                    }
+                };
+                cursor.onerror = function () {
+                    resolve();
+                };
+            };
+        });
+
+        return {
+            ready: ready,
+            getItem: function (key) {
+                return documents.has(key) ? documents.get(key) : null;
+            },
+            setItem: function (key, value) {
+                documents.set(key, value);
+
+                if (database !== null) {
+                    database.transaction("documents", "readwrite").objectStore("documents").put(value, key);
+                }
+            },
+        };
+    }
+
+    const local = localStore();
+    const store = local !== null ? local : indexedStore();
+
+    return {
+        prefix: prefix,
+        ready: store.ready || Promise.resolve(),
+        getItem: function (key) {
+            return store.getItem(prefix + key);
+        },
+        setItem: function (key, value) {
+            store.setItem(prefix + key, value);
+        },
+    };
+})();
+
+miniquad_add_plugin({
+    register_plugin: function (importObject) {
+        const decoder = new TextDecoder();
+        const encoder = new TextEncoder();
+
+        function read_string(ptr, len) {
+            return decoder.decode(new Uint8Array(wasm_memory.buffer, ptr, len));
+        }
+
+        importObject.env.storage_length = function (key_ptr, key_len) {
+            const value = asteroidsStorage.getItem(read_string(key_ptr, key_len));
+            return value === null ? -1 : encoder.encode(value).length;
+        };
+
+        importObject.env.storage_read = function (key_ptr, key_len, buffer_ptr, buffer_len) {
+            const value = asteroidsStorage.getItem(read_string(key_ptr, key_len)) || "";
+            const bytes = encoder.encode(value).subarray(0, buffer_len);
+            new Uint8Array(wasm_memory.buffer, buffer_ptr, buffer_len).set(bytes);
+        };
+
+        importObject.env.storage_write = function (key_ptr, key_len, value_ptr, value_len) {
+            try {
+                asteroidsStorage.setItem(read_string(key_ptr, key_len), read_string(value_ptr, value_len));
+            } catch (e) {
+                console.warn("Unable to save " + read_string(key_ptr, key_len) + ": " + e);
+            }
+        };
+    },
+    name: "asteroids_storage",
+    version: 2
+});
+
+// Move exported profiles in and out of the browser. Export a profile from the
+// options screen, then call asteroidsDownloadProfile() from the console to save
+// it as a file; asteroidsUploadProfile() picks a profile file to import from
+// the options screen.
+function asteroidsDownloadProfile() {
+    const profile = asteroidsStorage.getItem("profile.json");
+    if (profile === null) {
+        console.warn("Export a profile from the options screen first");
+        return;
+    }
+
+    const link = document.createElement("a");
+    link.href = URL.createObjectURL(new Blob([profile], { type: "application/json" }));
+    link.download = "asteroids-profile.json";
+    link.click();
+    URL.revokeObjectURL(link.href);
+}
+
+function asteroidsUploadProfile() {
+    const input = document.createElement("input");
+    input.type = "file";
+    input.accept = ".json,application/json";
+    input.onchange = async function () {
+        if (input.files.length > 0) {
+            asteroidsStorage.setItem("profile.json", await input.files[0].text());
+        }
+    };
+    input.click();
+}
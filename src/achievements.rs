@@ -0,0 +1,231 @@
+//! Achievements.
+//!
+//! Goals set across games, checked against what happens in play: destroying
+//! a thousand asteroids over every game played, reaching wave 10 without
+//! jumping to hyperspace, and shooting down a small saucer with a ricochet.
+//! Bullets wrap around the screen rather than bounce, so a ricochet is a shot
+//! that crossed an edge of the screen on its way to the saucer.
+//!
+//! Achievements are only earned in games played by the player, not in the
+//! attract mode demo, a watched replay code or a tool-assisted run. Each is
+//! earned once, remembered between sessions, and announced by a toast that
+//! pops up over the HUD; toasts earned together are shown one after another.
+
+use std::collections::VecDeque;
+
+use crate::savedata::{self, SaveFormat};
+use crate::saucer::SaucerSize;
+
+/// Achievement
+///
+/// A goal the player can earn.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Achievement {
+    Demolition,
+    Grounded,
+    TrickShot,
+}
+
+impl Achievement {
+    /// All achievements, in the order they are listed.
+    pub const ALL: [Achievement; 3] = [
+        Achievement::Demolition,
+        Achievement::Grounded,
+        Achievement::TrickShot,
+    ];
+
+    /// Asteroids to destroy, across every game, for `Demolition`.
+    pub const DEMOLITION_ASTEROIDS: u64 = 1000;
+
+    /// Wave to reach without hyperspace for `Grounded`.
+    pub const GROUNDED_WAVE: u32 = 10;
+
+    /// Name under which the achievement is remembered once earned.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Achievement::Demolition => "demolition",
+            Achievement::Grounded => "grounded",
+            Achievement::TrickShot => "trick_shot",
+        }
+    }
+
+    /// Achievement remembered under a name.
+    pub fn from_name(name: &str) -> Option<Achievement> {
+        Self::ALL.into_iter().find(|achievement| achievement.name() == name)
+    }
+
+    /// Title shown when the achievement is earned.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Achievement::Demolition => "Demolition",
+            Achievement::Grounded => "Grounded",
+            Achievement::TrickShot => "Trick Shot",
+        }
+    }
+
+    /// What the achievement asks of the player.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::Demolition => "Destroy 1000 asteroids",
+            Achievement::Grounded => "Reach wave 10 without hyperspace",
+            Achievement::TrickShot => "Kill a small saucer with a ricochet",
+        }
+    }
+
+    /// Check if something that happened in play earns the achievement.
+    pub fn is_earned_by(&self, event: &AchievementEvent) -> bool {
+        match (self, event) {
+            (Achievement::Demolition, AchievementEvent::AsteroidsDestroyed(total)) => *total >= Self::DEMOLITION_ASTEROIDS,
+            (Achievement::Grounded, AchievementEvent::WaveReached { wave, hyperspace_used }) => {
+                *wave >= Self::GROUNDED_WAVE && !hyperspace_used
+            },
+            (Achievement::TrickShot, AchievementEvent::SaucerShot { size, ricochet }) => *size == SaucerSize::Small && *ricochet,
+            _ => false,
+        }
+    }
+}
+
+/// Achievement event
+///
+/// Something that happened in play which achievements are checked against.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AchievementEvent {
+    /// Asteroids have been destroyed, bringing the total across every game
+    /// to a number.
+    AsteroidsDestroyed(u64),
+    /// A wave has been reached, with or without hyperspace used in the game.
+    WaveReached { wave: u32, hyperspace_used: bool },
+    /// A saucer has been shot down, by a bullet that may have crossed an
+    /// edge of the screen.
+    SaucerShot { size: SaucerSize, ricochet: bool },
+}
+
+/// Achievements
+///
+/// The achievements the player has earned, saved between sessions.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::achievements::{Achievement, AchievementEvent, Achievements};
+/// # use asteroids::saucer::SaucerSize;
+/// let mut achievements = Achievements::default();
+///
+/// let event = AchievementEvent::WaveReached { wave: 10, hyperspace_used: true };
+/// assert!(achievements.check(&event).is_empty());
+///
+/// let event = AchievementEvent::SaucerShot { size: SaucerSize::Small, ricochet: true };
+/// assert_eq!(achievements.check(&event), [Achievement::TrickShot]);
+///
+/// // Each achievement is only earned once
+/// assert!(achievements.check(&event).is_empty());
+///
+/// let restored = Achievements::parse(&achievements.to_text());
+/// assert!(restored.has_earned(Achievement::TrickShot));
+/// assert!(!restored.has_earned(Achievement::Demolition));
+/// ```
+#[derive(Clone, Default)]
+pub struct Achievements {
+    earned: Vec<Achievement>,
+}
+
+impl Achievements {
+    /// Storage key under which earned achievements are saved.
+    const STORAGE_KEY: &'static str = "achievements.txt";
+
+    /// Format earned achievements are saved in.
+    const SAVE_FORMAT: SaveFormat = SaveFormat::INITIAL;
+
+    /// Check if the player has earned an achievement.
+    pub fn has_earned(&self, achievement: Achievement) -> bool {
+        self.earned.contains(&achievement)
+    }
+
+    /// Check an event against the achievements not yet earned, returning
+    /// those it earns.
+    pub fn check(&mut self, event: &AchievementEvent) -> Vec<Achievement> {
+        let earned: Vec<Achievement> = Achievement::ALL.into_iter()
+            .filter(|achievement| !self.has_earned(*achievement) && achievement.is_earned_by(event))
+            .collect();
+
+        self.earned.extend(&earned);
+        earned
+    }
+
+    /// Load the earned achievements, starting afresh if none are saved.
+    pub fn load() -> Self {
+        savedata::load(Self::STORAGE_KEY, &Self::SAVE_FORMAT, |text| Some(Self::parse(text)))
+            .unwrap_or_default()
+    }
+
+    /// Save the earned achievements.
+    pub fn save(&self) {
+        savedata::save(Self::STORAGE_KEY, &Self::SAVE_FORMAT, &self.to_text());
+    }
+
+    /// Parse earned achievements from one name per line. Unknown names are
+    /// ignored.
+    pub fn parse(text: &str) -> Self {
+        let mut achievements = Self::default();
+
+        for achievement in text.lines().filter_map(|line| Achievement::from_name(line.trim())) {
+            if !achievements.has_earned(achievement) {
+                achievements.earned.push(achievement);
+            }
+        }
+
+        achievements
+    }
+
+    /// Format earned achievements as one name per line.
+    pub fn to_text(&self) -> String {
+        self.earned.iter().map(|achievement| format!("{}\n", achievement.name())).collect()
+    }
+}
+
+/// Achievement toasts
+///
+/// Achievements just earned, each shown for a few seconds in turn.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::achievements::{Achievement, AchievementToasts};
+/// let mut toasts = AchievementToasts::default();
+/// toasts.push(Achievement::Grounded, 1.0);
+/// toasts.push(Achievement::TrickShot, 1.5);
+///
+/// assert_eq!(toasts.visible(2.0), Some(Achievement::Grounded));
+/// assert_eq!(toasts.visible(1.0 + AchievementToasts::DURATION), Some(Achievement::TrickShot));
+/// assert_eq!(toasts.visible(1.0 + AchievementToasts::DURATION * 2.0), None);
+/// ```
+#[derive(Default)]
+pub struct AchievementToasts {
+    toasts: VecDeque<(f64, Achievement)>,
+}
+
+impl AchievementToasts {
+    /// Time in seconds each toast is shown.
+    pub const DURATION: f64 = 4.0;
+
+    /// Queue a toast for an achievement earned at a time, shown once those
+    /// before it have been.
+    pub fn push(&mut self, achievement: Achievement, now: f64) {
+        self.toasts.retain(|(shown_at, _)| now - shown_at < Self::DURATION);
+
+        let shown_at = self.toasts.back().map_or(now, |(previous, _)| now.max(previous + Self::DURATION));
+        self.toasts.push_back((shown_at, achievement));
+    }
+
+    /// Achievement whose toast is up at a time, if any.
+    pub fn visible(&self, now: f64) -> Option<Achievement> {
+        self.toasts.iter()
+            .find(|(shown_at, _)| (*shown_at..shown_at + Self::DURATION).contains(&now))
+            .map(|(_, achievement)| *achievement)
+    }
+
+    /// Take every toast down.
+    pub fn clear(&mut self) {
+        self.toasts.clear();
+    }
+}
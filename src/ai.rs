@@ -0,0 +1,198 @@
+//! Computer-controlled pilots.
+
+use macroquad::prelude::*;
+
+use crate::asteroid::Asteroid;
+use crate::collision::{wrapped_delta, Collidable};
+use crate::detmath;
+use crate::saucer::Saucer;
+use crate::ship::Ship;
+
+/// Target
+///
+/// Something an enemy aims at: a ship or a decoy, with its velocity so shots
+/// can lead it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Target {
+    pub position: Vec2,
+    pub velocity: Vec2,
+}
+
+/// Point to aim at, relative to the shooter, so a shot fired now at a given
+/// speed meets a target at an offset from the shooter moving with a velocity.
+/// Shots are assumed not to inherit the shooter's velocity.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::ai::lead;
+/// # use macroquad::prelude::*;
+/// // A target crossing at half the shot's speed is led ahead of its position
+/// let aim = lead(Vec2::new(100.0, 0.0), Vec2::new(0.0, 1.0), 2.0);
+/// assert!(aim.y > 40.0 && aim.y < 60.0);
+/// ```
+pub fn lead(delta: Vec2, velocity: Vec2, shot_speed: f32) -> Vec2 {
+    let mut aim = delta;
+
+    // A few refinements of the shot's flight time are plenty
+    for _ in 0..3 {
+        let time = aim.length() / shot_speed.max(f32::EPSILON);
+        aim = delta + velocity * time;
+    }
+
+    aim
+}
+
+/// Pilot command
+///
+/// The controls a pilot works on a single frame: a turn rate as passed to
+/// `Ship::steer`, and whether to thrust, fire or jump to hyperspace.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct PilotCommand {
+    pub steer: f32,
+    pub thrust: bool,
+    pub fire: bool,
+    pub hyperspace: bool,
+}
+
+/// Auto pilot
+///
+/// A simple bot that flies the ship in the attract mode demo. Each frame it
+/// looks for the most pressing threat, an asteroid or saucer on course to hit
+/// the ship; it turns away and thrusts clear of threats it has time to dodge,
+/// and jumps to hyperspace, if charged, when a collision is moments away.
+/// With nothing to dodge it turns towards the nearest target, leading it by
+/// the bullets' flight time, and fires once lined up.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::ai::AutoPilot;
+/// # use asteroids::ship::Ship;
+/// let pilot = AutoPilot::default();
+/// let ship = Ship::spawn_new();
+///
+/// let command = pilot.fly(&ship, &[], &[], 0.0);
+/// assert!(!command.fire);
+/// ```
+#[derive(Clone, Copy)]
+pub struct AutoPilot {
+    /// Frames ahead the pilot watches for collisions.
+    pub lookahead: f32,
+    /// Frames before a collision at which the pilot gives up dodging and
+    /// jumps to hyperspace.
+    pub panic: f32,
+    /// Angle in radians within which the pilot fires at its target.
+    pub aim_tolerance: f32,
+    /// Margin added to the ship's radius when judging whether a threat will
+    /// hit it, as a multiple of the radius.
+    pub caution: f32,
+}
+
+impl Default for AutoPilot {
+    fn default() -> Self {
+        Self {
+            lookahead: 60.0,
+            panic: 10.0,
+            aim_tolerance: 0.08,
+            caution: 1.5,
+        }
+    }
+}
+
+/// Something the pilot tracks: its offset from the ship, velocity relative to
+/// the ship, and radius.
+struct Contact {
+    delta: Vec2,
+    velocity: Vec2,
+    radius: f32,
+}
+
+impl AutoPilot {
+    /// Maximum turn rate, as used for the player's keyboard steering.
+    const TURN_RATE: f32 = 0.1;
+
+    /// Decide the controls for the frame at a time.
+    pub fn fly(&self, ship: &Ship, asteroids: &[Asteroid], saucers: &[Saucer], now: f64) -> PilotCommand {
+        // Contacts are gone over twice rather than collected, so flying
+        // allocates nothing
+        let contacts = || asteroids.iter()
+            .filter(|asteroid| asteroid.is_alive())
+            .map(|asteroid| Contact {
+                delta: wrapped_delta(ship.position, asteroid.position),
+                velocity: asteroid.velocity - ship.velocity,
+                radius: asteroid.get_radius(),
+            })
+            .chain(saucers.iter().filter(|saucer| saucer.is_alive()).map(|saucer| Contact {
+                delta: wrapped_delta(ship.position, saucer.position),
+                velocity: saucer.velocity - ship.velocity,
+                radius: saucer.get_radius(),
+            }));
+
+        // Dodge the threat closest to impact, or jump out if it is too close
+        let threat = contacts()
+            .filter_map(|contact| self.time_to_impact(ship, &contact).map(|time| (time, contact)))
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        if let Some((time, contact)) = threat {
+            if time < self.panic && ship.hyperspace_cooldown < now {
+                return PilotCommand {
+                    hyperspace: true,
+                    ..Default::default()
+                };
+            }
+
+            // Flee at right angles to the threat's approach, on the side away
+            // from its path
+            let across = contact.velocity.perp();
+            let away = if across.dot(contact.delta) > 0.0 { -across } else { across };
+
+            return PilotCommand {
+                steer: Self::steer_towards(ship, detmath::angle_of(away)),
+                thrust: true,
+                ..Default::default()
+            };
+        }
+
+        // Otherwise line up on the nearest target and fire
+        let Some(target) = contacts().min_by(|a, b| a.delta.length_squared().total_cmp(&b.delta.length_squared())) else {
+            return PilotCommand::default();
+        };
+
+        // Bullets do not inherit the ship's velocity
+        let aim = lead(target.delta, target.velocity + ship.velocity, ship.shot_speed);
+        let steer = Self::steer_towards(ship, detmath::angle_of(aim));
+
+        PilotCommand {
+            steer,
+            fire: steer.abs() < self.aim_tolerance,
+            ..Default::default()
+        }
+    }
+
+    /// Frames until a contact hits the ship on their current courses, if it
+    /// will within the lookahead.
+    fn time_to_impact(&self, ship: &Ship, contact: &Contact) -> Option<f32> {
+        let speed_squared = contact.velocity.length_squared();
+
+        if speed_squared == 0.0 {
+            return None;
+        }
+
+        // Time of closest approach, and how close the contact passes then
+        let time = -contact.delta.dot(contact.velocity) / speed_squared;
+        let miss = (contact.delta + contact.velocity * time).length();
+
+        let reach = contact.radius + ship.radius * self.caution;
+        (time > 0.0 && time < self.lookahead && miss < reach).then_some(time)
+    }
+
+    /// Turn rate bringing the ship's heading round to an angle, without
+    /// overshooting it.
+    fn steer_towards(ship: &Ship, angle: f32) -> f32 {
+        let heading = ship.rotation - std::f32::consts::FRAC_PI_2;
+        let difference = detmath::angle_of(detmath::direction(angle - heading));
+
+        difference.clamp(-Self::TURN_RATE, Self::TURN_RATE)
+    }
+}
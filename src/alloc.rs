@@ -0,0 +1,173 @@
+//! Allocation tracking.
+//!
+//! Steady-state frames should not touch the heap: allocations cost time, and
+//! on the web they also grow the wasm memory, which is never returned. To keep
+//! an eye on this, `CountingAllocator` wraps the system allocator and counts
+//! every allocation made through it. It is installed as the global allocator
+//! when the `alloc-tracking` feature is enabled, and the game can then report
+//! frames that exceed an allocation budget (see `--alloc-budget`), failing a
+//! movie verified with `--verify` if any of its frames do. It also
+//! keeps track of the bytes live on the heap and their high-water mark, which
+//! soak tests report to catch slow leaks.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Allocations made through any `CountingAllocator` since the program started.
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Bytes allocated through any `CountingAllocator` and not yet freed.
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Most bytes live at any one time since the program started.
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Count bytes newly allocated, raising the high-water mark if need be.
+fn grow(bytes: usize) {
+    let live = LIVE_BYTES.fetch_add(bytes, Ordering::Relaxed) + bytes;
+    PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+}
+
+/// Count bytes freed.
+fn shrink(bytes: usize) {
+    LIVE_BYTES.fetch_sub(bytes, Ordering::Relaxed);
+}
+
+/// Counting allocator
+///
+/// A global allocator that forwards to the system allocator, counting each
+/// allocation and reallocation along the way. Deallocations are not counted,
+/// but the bytes they free are.
+///
+/// # Examples
+///
+/// ```
+/// use asteroids::alloc::{allocations, live_bytes, peak_bytes, CountingAllocator};
+///
+/// #[global_allocator]
+/// static ALLOCATOR: CountingAllocator = CountingAllocator;
+///
+/// let before = allocations();
+/// let numbers: Vec<u32> = Vec::with_capacity(16);
+/// assert_eq!(allocations() - before, 1);
+///
+/// let before = allocations();
+/// let total: u32 = numbers.iter().sum();
+/// assert_eq!(allocations() - before, 0);
+/// # let _ = total;
+///
+/// assert!(live_bytes() >= 64);
+/// assert!(peak_bytes() >= live_bytes());
+/// ```
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        grow(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        grow(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+
+        if new_size > layout.size() {
+            grow(new_size - layout.size());
+        } else {
+            shrink(layout.size() - new_size);
+        }
+
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        shrink(layout.size());
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Number of allocations made through `CountingAllocator` so far. Always zero
+/// if it is not installed as the global allocator.
+pub fn allocations() -> usize {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+/// Bytes live on the heap, allocated through `CountingAllocator`. Always zero
+/// if it is not installed as the global allocator.
+pub fn live_bytes() -> usize {
+    LIVE_BYTES.load(Ordering::Relaxed)
+}
+
+/// Most bytes live on the heap at any one time. Always zero if
+/// `CountingAllocator` is not installed as the global allocator.
+pub fn peak_bytes() -> usize {
+    PEAK_BYTES.load(Ordering::Relaxed)
+}
+
+/// Allocation budget
+///
+/// Checks that frames stay within a number of allocations, keeping track of
+/// the worst frame seen and how many went over. Which frames of a game may
+/// allocate at all is set out, and checked, in `tests/alloc.rs`.
+///
+/// # Examples
+///
+/// ```
+/// use asteroids::alloc::AllocationBudget;
+///
+/// let mut budget = AllocationBudget::new(0);
+///
+/// budget.begin_frame();
+/// let numbers: Vec<u32> = (0..4).collect();
+/// # let _ = numbers;
+///
+/// // Nothing is counted unless `CountingAllocator` is the global allocator
+/// assert_eq!(budget.end_frame(), None);
+/// assert!(budget.passed());
+/// ```
+pub struct AllocationBudget {
+    pub limit: usize,
+    pub worst: usize,
+    pub overruns: usize,
+    frame_start: usize,
+}
+
+impl AllocationBudget {
+    /// Create a budget of a number of allocations per frame.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            worst: 0,
+            overruns: 0,
+            frame_start: allocations(),
+        }
+    }
+
+    /// Mark the start of a frame.
+    pub fn begin_frame(&mut self) {
+        self.frame_start = allocations();
+    }
+
+    /// Mark the end of a frame. Returns the number of allocations made during
+    /// the frame if it exceeded the budget.
+    pub fn end_frame(&mut self) -> Option<usize> {
+        let count = allocations() - self.frame_start;
+        self.worst = self.worst.max(count);
+
+        let over = count > self.limit;
+        self.overruns += over as usize;
+
+        over.then_some(count)
+    }
+
+    /// Check if every frame so far stayed within the budget.
+    pub fn passed(&self) -> bool {
+        self.overruns == 0
+    }
+}
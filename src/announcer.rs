@@ -0,0 +1,184 @@
+//! Announcements.
+//!
+//! With the Announcements option on, key events are put into words for
+//! players who cannot follow them on screen: "Wave 5", "Small saucer
+//! approaching from the left", "One life remaining". The game queues them as
+//! they happen and hands them over once a frame to a text output. Launched
+//! with `--speech <program>`, each is spoken by running the program with the
+//! text as its argument, such as `espeak` or `say`, so screen-reader users
+//! hear them from the platform's own text-to-speech; without it, or once the
+//! program fails to run, they are shown as captions along the bottom of the
+//! screen.
+
+use std::collections::VecDeque;
+use std::process::Command;
+
+/// Speech output
+///
+/// A channel speaking announcements aloud.
+pub trait SpeechOutput {
+    /// Speak a line of text, returning false if it could not be.
+    fn speak(&mut self, text: &str) -> bool;
+}
+
+/// Command speech
+///
+/// Speaks by running a text-to-speech program with each line as its
+/// argument. The program is left to run alongside the game, so a long line
+/// never holds up a frame.
+pub struct CommandSpeech {
+    program: String,
+}
+
+impl CommandSpeech {
+    /// Speak through a program.
+    pub fn new(program: &str) -> Self {
+        Self { program: program.to_string() }
+    }
+}
+
+impl SpeechOutput for CommandSpeech {
+    fn speak(&mut self, text: &str) -> bool {
+        Command::new(&self.program).arg(text).spawn().is_ok()
+    }
+}
+
+/// Caption feed
+///
+/// The last few announcements, shown on screen for a few seconds each. An
+/// announcement repeated while its caption is still up refreshes it rather
+/// than being shown twice.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::announcer::CaptionFeed;
+/// let mut feed = CaptionFeed::default();
+/// feed.post("Wave 5", 0.0);
+/// feed.post("Saucer approaching from the left", 1.0);
+/// feed.post("Wave 5", 2.0);
+///
+/// let lines: Vec<&str> = feed.visible(2.0).collect();
+/// assert_eq!(lines, ["Saucer approaching from the left", "Wave 5"]);
+///
+/// // Captions come down once they have been up long enough
+/// let lines: Vec<&str> = feed.visible(1.0 + CaptionFeed::DURATION).collect();
+/// assert_eq!(lines, ["Wave 5"]);
+///
+/// // Only the latest few are kept
+/// for wave in 6..20 {
+///     feed.post(&format!("Wave {}", wave), 3.0);
+/// }
+///
+/// assert_eq!(feed.visible(3.0).count(), CaptionFeed::MAX_CAPTIONS);
+/// ```
+#[derive(Default)]
+pub struct CaptionFeed {
+    captions: VecDeque<(f64, String)>,
+}
+
+impl CaptionFeed {
+    /// Time in seconds a caption stays up.
+    pub const DURATION: f64 = 4.0;
+
+    /// Most captions shown at once.
+    pub const MAX_CAPTIONS: usize = 3;
+
+    /// Show a caption, posted at a time.
+    pub fn post(&mut self, text: &str, now: f64) {
+        self.captions.retain(|(_, caption)| caption != text);
+        self.captions.push_back((now, text.to_string()));
+
+        while self.captions.len() > Self::MAX_CAPTIONS {
+            self.captions.pop_front();
+        }
+    }
+
+    /// Captions still up at a time, oldest first.
+    pub fn visible(&self, now: f64) -> impl Iterator<Item = &str> {
+        self.captions.iter()
+            .filter(move |(posted, _)| now - posted < Self::DURATION)
+            .map(|(_, caption)| caption.as_str())
+    }
+
+    /// Take every caption down.
+    pub fn clear(&mut self) {
+        self.captions.clear();
+    }
+}
+
+/// Announcer
+///
+/// The queue of announcements made since the last frame, and where they go:
+/// spoken, if a speech output is plugged in and working, or else captioned.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::announcer::{Announcer, SpeechOutput};
+/// # use std::rc::Rc;
+/// # use std::cell::RefCell;
+/// struct Transcript(Rc<RefCell<Vec<String>>>);
+///
+/// impl SpeechOutput for Transcript {
+///     fn speak(&mut self, text: &str) -> bool {
+///         self.0.borrow_mut().push(text.to_string());
+///         true
+///     }
+/// }
+///
+/// // Without speech, announcements are captioned once delivered
+/// let mut announcer = Announcer::default();
+/// announcer.announce("Wave 1");
+/// assert_eq!(announcer.captions.visible(0.0).count(), 0);
+///
+/// announcer.deliver(0.0);
+/// assert_eq!(announcer.captions.visible(0.0).collect::<Vec<_>>(), ["Wave 1"]);
+///
+/// // With speech, they are spoken instead
+/// let spoken = Rc::new(RefCell::new(Vec::new()));
+/// let mut announcer = Announcer::with_speech(Box::new(Transcript(spoken.clone())));
+/// announcer.announce("One life remaining");
+/// announcer.deliver(0.0);
+///
+/// assert_eq!(*spoken.borrow(), ["One life remaining"]);
+/// assert_eq!(announcer.captions.visible(0.0).count(), 0);
+/// ```
+#[derive(Default)]
+pub struct Announcer {
+    queue: Vec<String>,
+    pub captions: CaptionFeed,
+    speech: Option<Box<dyn SpeechOutput>>,
+}
+
+impl Announcer {
+    /// Announce through a speech output.
+    pub fn with_speech(speech: Box<dyn SpeechOutput>) -> Self {
+        Self { speech: Some(speech), ..Default::default() }
+    }
+
+    /// Queue an announcement for the end of the frame.
+    pub fn announce(&mut self, text: impl Into<String>) {
+        self.queue.push(text.into());
+    }
+
+    /// Hand the queued announcements to the speech output, or caption them.
+    /// A speech output that fails is unplugged, falling back on captions.
+    pub fn deliver(&mut self, now: f64) {
+        for text in self.queue.drain(..) {
+            if self.speech.as_mut().is_some_and(|speech| !speech.speak(&text)) {
+                self.speech = None;
+            }
+
+            if self.speech.is_none() {
+                self.captions.post(&text, now);
+            }
+        }
+    }
+
+    /// Drop the queued announcements and take the captions down.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.captions.clear();
+    }
+}
@@ -0,0 +1,46 @@
+//! Assets.
+//!
+//! The game's only asset file is its font. It is loaded from the asset
+//! directory, the working directory unless `--assets <dir>` says otherwise, so
+//! it can be swapped without rebuilding. A copy is also built into the game,
+//! so if the file cannot be loaded, for example because the game was started
+//! from another directory, the built-in copy is used and the reason is shown
+//! on the title screen rather than the game failing to start.
+
+use macroquad::prelude::*;
+
+/// Directory assets are loaded from unless another is given.
+pub const DEFAULT_DIRECTORY: &str = ".";
+
+/// File name of the font.
+pub const FONT_FILE: &str = "Hyperspace.ttf";
+
+/// Copy of the font built into the game.
+const EMBEDDED_FONT: &[u8] = include_bytes!("../Hyperspace.ttf");
+
+/// Path of an asset file in a directory.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::assets::asset_path;
+/// assert_eq!(asset_path(".", "Hyperspace.ttf"), "./Hyperspace.ttf");
+/// assert_eq!(asset_path("assets/", "Hyperspace.ttf"), "assets/Hyperspace.ttf");
+/// ```
+pub fn asset_path(directory: &str, file: &str) -> String {
+    format!("{}/{}", directory.trim_end_matches('/'), file)
+}
+
+/// Load the font from an asset directory, or the default one. If it cannot
+/// be loaded, the built-in copy is returned along with the reason.
+pub async fn load_font(directory: Option<&str>) -> (Font, Option<String>) {
+    let path = asset_path(directory.unwrap_or(DEFAULT_DIRECTORY), FONT_FILE);
+
+    match load_ttf_font(&path).await {
+        Ok(font) => (font, None),
+        Err(error) => {
+            let font = load_ttf_font_from_bytes(EMBEDDED_FONT).expect("built-in font is valid");
+            (font, Some(format!("Could not load {}, using the built-in font: {}", path, error)))
+        },
+    }
+}
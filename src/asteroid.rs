@@ -0,0 +1,651 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::camera::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::collision::{wrapped_delta, Collidable, Outline};
+use crate::config;
+use crate::detmath;
+use crate::linebatch::LineBatch;
+use crate::wrap::{self, WrapDraw};
+
+/// Asteroid size
+/// 
+/// The three classic asteroid sizes: small, medium, and large. Asteroids may be
+/// any size in between or beyond; these are presets on that continuum, each half
+/// the size of the next.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AsteroidSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl AsteroidSize {
+    /// Scale of the preset, relative to a large asteroid.
+    pub fn scale(&self) -> f32 {
+        match self {
+            AsteroidSize::Small => 0.25,
+            AsteroidSize::Medium => 0.5,
+            AsteroidSize::Large => 1.0,
+        }
+    }
+}
+
+/// Asteroid kind
+///
+/// What an asteroid is made of. Most asteroids are plain rock, but from the
+/// third wave on a few are armoured, taking two hits to break, volatile,
+/// blowing up with everything near them rather than splitting, or crystal,
+/// always leaving salvage behind. What sets each kind apart is data, in its
+/// `KindTraits`.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::asteroid::AsteroidKind;
+/// # use macroquad::rand::RandGenerator;
+/// let rng = RandGenerator::new();
+///
+/// // Early waves are plain rock
+/// assert_eq!(AsteroidKind::roll(&rng, 1), AsteroidKind::Plain);
+///
+/// // Later, some of each kind turn up
+/// let kinds: Vec<AsteroidKind> = (0..1000).map(|_| AsteroidKind::roll(&rng, 5)).collect();
+/// assert!(AsteroidKind::ALL.iter().all(|kind| kinds.contains(kind)));
+///
+/// assert_eq!(AsteroidKind::Armored.traits().hits, 2);
+/// assert!(AsteroidKind::Crystal.traits().salvage);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum AsteroidKind {
+    #[default]
+    Plain,
+    Armored,
+    Volatile,
+    Crystal,
+}
+
+/// Kind traits
+///
+/// How an asteroid of a kind behaves, looks and scores.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct KindTraits {
+    /// Hits it takes to break the asteroid.
+    pub hits: u32,
+    /// Factor on the points scored for the asteroid's size.
+    pub points: f32,
+    /// Nearest and farthest the outline's vertices lie from the centre, as
+    /// shares of the radius.
+    pub roughness: (f32, f32),
+    /// Whether the vertices alternate between near and far, for a spiky or
+    /// faceted outline, rather than falling anywhere between.
+    pub alternating: bool,
+    /// Whether the asteroid splits into fragments when destroyed.
+    pub splits: bool,
+    /// Reach of the blast the asteroid goes off with, as a multiple of its
+    /// radius; zero for none.
+    pub blast: f32,
+    /// Whether the asteroid always leaves a salvage crate behind.
+    pub salvage: bool,
+    /// Chance, from 0 to 1, of an asteroid spawned on a wave being this kind,
+    /// once kinds turn up.
+    pub chance: f32,
+}
+
+impl AsteroidKind {
+    /// All kinds.
+    pub const ALL: [AsteroidKind; 4] = [
+        AsteroidKind::Plain,
+        AsteroidKind::Armored,
+        AsteroidKind::Volatile,
+        AsteroidKind::Crystal,
+    ];
+
+    /// First wave kinds other than plain rock turn up on.
+    pub const FIRST_WAVE: u32 = 3;
+
+    /// Traits of the kind.
+    pub fn traits(&self) -> KindTraits {
+        match self {
+            AsteroidKind::Plain => KindTraits {
+                hits: 1, points: 1.0, roughness: (0.6, 1.0), alternating: false,
+                splits: true, blast: 0.0, salvage: false, chance: 0.0,
+            },
+            AsteroidKind::Armored => KindTraits {
+                hits: 2, points: 2.0, roughness: (0.85, 1.0), alternating: false,
+                splits: true, blast: 0.0, salvage: false, chance: 0.1,
+            },
+            AsteroidKind::Volatile => KindTraits {
+                hits: 1, points: 1.5, roughness: (0.45, 1.0), alternating: true,
+                splits: false, blast: 3.0, salvage: false, chance: 0.06,
+            },
+            AsteroidKind::Crystal => KindTraits {
+                hits: 1, points: 3.0, roughness: (0.7, 1.0), alternating: true,
+                splits: true, blast: 0.0, salvage: true, chance: 0.04,
+            },
+        }
+    }
+
+    /// Kind of an asteroid spawned on a wave. Before kinds turn up every
+    /// asteroid is plain, and nothing is drawn from the generator.
+    pub fn roll(rng: &RandGenerator, wave: u32) -> AsteroidKind {
+        if wave < Self::FIRST_WAVE {
+            return AsteroidKind::Plain;
+        }
+
+        let mut roll = rng.gen_range(0.0, 1.0);
+
+        for kind in Self::ALL {
+            let chance = kind.traits().chance;
+
+            if roll < chance {
+                return kind;
+            }
+
+            roll -= chance;
+        }
+
+        AsteroidKind::Plain
+    }
+}
+
+/// Asteroid object
+///
+/// Asteroids move in a random direction. They rotate slowly and wrap around the 
+/// screen when they reach the edge. When shot, they break into smaller asteroids 
+/// until they are small enough to be destroyed.
+/// 
+/// An asteroid's size is a continuous scale relative to a classic large
+/// asteroid, from which its diameter, number of sides, speed, spin, toughness
+/// and score are all derived. The classic sizes are presets on this scale (see
+/// `AsteroidSize`), so boss rocks are simply asteroids scaled above 1 and
+/// gravel asteroids scaled below a quarter.
+/// 
+/// Asteroids come in kinds, from plain rock to crystal; see `AsteroidKind`.
+/// Armoured asteroids keep count of the hits their armour can still take.
+///
+/// Asteroids may spawn in two ways: either at a random position on the edge of
+/// the screen, or at a specific position. The latter is used when an asteroid
+/// is destroyed and spawns smaller asteroids. Either way, the asteroid's shape
+/// and motion are drawn from the random number generator it is spawned with.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// # use asteroids::asteroid::{Asteroid, AsteroidSize};
+/// # use macroquad::prelude::*;
+/// # use macroquad::rand::RandGenerator;
+/// let rng = RandGenerator::new();
+/// let asteroid = Asteroid::spawn_new(&rng, AsteroidSize::Large.scale());
+/// let boss = Asteroid::spawn_new_at(&rng, 2.5, Vec2::new(0., 0.));
+/// ```
+#[derive(Clone)]
+pub struct Asteroid {
+    pub scale: f32,
+    pub diameter: f32,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub rotation: f32,
+    pub rotation_speed: f32,
+    pub vertices: Vec<Vec2>,
+    pub kind: AsteroidKind,
+    pub armor: u32,
+    pub is_alive: bool,
+}
+
+impl Asteroid {
+    /// Smallest scale an asteroid may be.
+    pub const MIN_SCALE: f32 = 0.05;
+
+    /// Smallest scale of the fragments an asteroid splits into. Asteroids
+    /// whose fragments would be smaller are destroyed outright.
+    pub const MIN_FRAGMENT_SCALE: f32 = 0.2;
+
+    /// Spawn new asteroid at a given position. 
+    /// 
+    /// Asteroid scale is used to determine the diameter, number of sides, and
+    /// angular velocity of the asteroid. The position is used to determine the
+    /// starting location of the asteroid. The velocity is determined randomly.
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// # use asteroids::asteroid::{Asteroid, AsteroidSize};
+    /// # use macroquad::prelude::*;
+    /// # use macroquad::rand::RandGenerator;
+    /// let rng = RandGenerator::new();
+    /// let asteroid = Asteroid::spawn_new_at(&rng, AsteroidSize::Medium.scale(), Vec2::new(0., 0.));
+    /// ```
+    pub fn spawn_new_at(rng: &RandGenerator, scale: f32, position: Vec2) -> Self {
+        Self::spawn_kind_at(rng, scale, position, AsteroidKind::Plain)
+    }
+
+    /// Spawn new asteroid of a kind at a given position. The kind shapes the
+    /// asteroid's outline; see `KindTraits`.
+    pub fn spawn_kind_at(rng: &RandGenerator, scale: f32, position: Vec2, kind: AsteroidKind) -> Self {
+        let traits = kind.traits();
+        let scale = scale.max(Self::MIN_SCALE);
+        let grade = Self::grade_of(scale);
+
+        let screen_edge: f32 = WORLD_WIDTH.min(WORLD_HEIGHT);
+        let balance = config::balance().asteroids;
+        
+        // A large asteroid spans a fifth of the screen; the rest scale with it
+        let diameter = screen_edge * balance.diameter * scale;
+
+        // Three more sides for each doubling in size, from six for a small asteroid
+        let sides = (6.0 + 3.0 * grade).round().clamp(5.0, 24.0);
+        
+        // Smaller asteroids spin and move faster, up to a limit
+        let angular_velocity = (balance.spin / scale).min(balance.max_spin);
+        let speed = screen_edge * (balance.speed / scale).min(balance.max_speed);
+
+        let mut vertices: Vec<Vec2> = Vec::new();
+
+        // Generate vertices, as rough as the kind of rock
+        let (near, far) = traits.roughness;
+
+        for i in 0..sides as usize {
+            let share = match (traits.alternating, i % 2) {
+                (true, 0) => far * rng.gen_range(0.9, 1.0),
+                (true, _) => near * rng.gen_range(0.9, 1.0),
+                (false, _) => rng.gen_range(near, far),
+            };
+
+            let radius = diameter / 2.0 * share;
+            let angle = i as f32 / sides * std::f32::consts::PI * 2.0;
+            let x = detmath::cos(angle) * radius;
+            let y = detmath::sin(angle) * radius;
+            vertices.push(Vec2::new(x, y));
+        }
+
+        // Generate random direction and velocity
+        let direction = rng.gen_range(0.0, std::f32::consts::PI * 2.0);
+        let velocity = detmath::rotation(direction).mul_vec2(Vec2::X * speed);
+        let rotation = rng.gen_range(0.0, std::f32::consts::PI * 2.0);
+        let rotation_speed = angular_velocity * rng.gen_range(-1.0, 1.0);
+
+        Self {
+            scale,
+            diameter,
+            position,
+            velocity,
+            rotation,
+            rotation_speed,
+            vertices,
+            kind,
+            armor: traits.hits - 1,
+            is_alive: true,
+        }
+    }
+    
+    /// Spawn new asteroid at a random position on the edge of the screen.
+    /// 
+    /// # Examples
+    /// 
+    /// ```no_run
+    /// # use asteroids::asteroid::{Asteroid, AsteroidSize};
+    /// # use macroquad::prelude::*;
+    /// # use macroquad::rand::RandGenerator;
+    /// let rng = RandGenerator::new();
+    /// let asteroid = Asteroid::spawn_new(&rng, AsteroidSize::Large.scale());
+    /// ```
+    pub fn spawn_new(rng: &RandGenerator, scale: f32) -> Self {
+        Self::spawn_kind(rng, scale, AsteroidKind::Plain)
+    }
+
+    /// Spawn new asteroid of a kind at a random position on the edge of the
+    /// screen.
+    pub fn spawn_kind(rng: &RandGenerator, scale: f32, kind: AsteroidKind) -> Self {
+        let position = match rng.gen_range(0, 4) { 
+            0 => Vec2::new(0., rng.gen_range(0.0, WORLD_HEIGHT)),
+            1 => Vec2::new(WORLD_WIDTH, rng.gen_range(0.0, WORLD_HEIGHT)),
+            2 => Vec2::new(rng.gen_range(0.0, WORLD_WIDTH), 0.),
+            3 => Vec2::new(rng.gen_range(0.0, WORLD_WIDTH), WORLD_HEIGHT),
+            _ => Vec2::new(0., 0.),
+        };
+
+        Self::spawn_kind_at(rng, scale, position, kind)
+    }
+
+    /// Number of times a scale is double the size of a small asteroid: 0 for
+    /// a small asteroid, 1 for a medium one and 2 for a large one. Gravel has a
+    /// negative grade and boss rocks a grade above 2.
+    fn grade_of(scale: f32) -> f32 {
+        (scale / AsteroidSize::Small.scale()).log2()
+    }
+
+    /// Size grade of the asteroid; see `grade_of`.
+    pub fn grade(&self) -> f32 {
+        Self::grade_of(self.scale)
+    }
+
+    /// Classic size closest to the asteroid's scale.
+    pub fn size(&self) -> AsteroidSize {
+        let grade = self.grade();
+
+        if grade < 0.5 {
+            AsteroidSize::Small
+        } else if grade < 1.5 {
+            AsteroidSize::Medium
+        } else {
+            AsteroidSize::Large
+        }
+    }
+
+    /// Interpolate a property between its values at the small, medium and
+    /// large presets. Scales outside the presets take the nearest value.
+    fn interpolate_presets(scale: f32, values: [f32; 3]) -> f32 {
+        let grade = Self::grade_of(scale).clamp(0.0, 2.0);
+        let (index, t) = if grade < 1.0 { (0, grade) } else { (1, grade - 1.0) };
+
+        values[index] + (values[index + 1] - values[index]) * t
+    }
+
+    /// Points scored for destroying the asteroid. Smaller asteroids are harder
+    /// to hit and score more: 100, 50 and 20 points for the classic sizes,
+    /// with gravel scoring ever more and boss rocks scoring by their size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::asteroid::{Asteroid, AsteroidSize};
+    /// assert_eq!(Asteroid::points_for(AsteroidSize::Small.scale()), 100);
+    /// assert_eq!(Asteroid::points_for(AsteroidSize::Medium.scale()), 50);
+    /// assert_eq!(Asteroid::points_for(AsteroidSize::Large.scale()), 20);
+    /// assert_eq!(Asteroid::points_for(0.125), 200);
+    /// assert_eq!(Asteroid::points_for(3.0), 60);
+    /// ```
+    pub fn points_for(scale: f32) -> u32 {
+        let points = if scale < AsteroidSize::Small.scale() {
+            25.0 / scale
+        } else if scale > AsteroidSize::Large.scale() {
+            20.0 * scale
+        } else {
+            let (small, medium, large) = config::balance().asteroids.points;
+            Self::interpolate_presets(scale, [small as f32, medium as f32, large as f32])
+        };
+
+        points.round() as u32
+    }
+
+    /// Points scored for destroying the asteroid: those for its size, see
+    /// `points_for`, scaled for its kind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::asteroid::{Asteroid, AsteroidKind, AsteroidSize};
+    /// # use macroquad::prelude::*;
+    /// # use macroquad::rand::RandGenerator;
+    /// let rng = RandGenerator::new();
+    /// let crystal = Asteroid::spawn_kind_at(&rng, AsteroidSize::Small.scale(), Vec2::ZERO, AsteroidKind::Crystal);
+    /// assert_eq!(crystal.points(), 300);
+    /// ```
+    pub fn points(&self) -> u32 {
+        (Self::points_for(self.scale) as f32 * self.kind.traits().points).round() as u32
+    }
+
+    /// Scales of the fragments the asteroid splits into when destroyed: two
+    /// halves, unless they would be too small to survive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::asteroid::{Asteroid, AsteroidSize};
+    /// assert_eq!(Asteroid::fragments_of(AsteroidSize::Large.scale()), vec![0.5, 0.5]);
+    /// assert!(Asteroid::fragments_of(AsteroidSize::Small.scale()).is_empty());
+    /// ```
+    pub fn fragments_of(scale: f32) -> Vec<f32> {
+        let fragment = scale / 2.0;
+
+        if fragment < Self::MIN_FRAGMENT_SCALE {
+            Vec::new()
+        } else {
+            vec![fragment; 2]
+        }
+    }
+
+    /// Share of the momentum of whatever hits an asteroid passed on to its
+    /// fragments.
+    const IMPACT_TRANSFER: f32 = 0.05;
+
+    /// Most the fragments' paths stray, in radians, from flying apart square
+    /// to the impact.
+    const SPLIT_SPREAD: f32 = 0.6;
+
+    /// Share of the speed at which a spinning asteroid's rim turns that its
+    /// fragments are flung with.
+    const SPIN_FLING: f32 = 0.6;
+
+    /// Share of the asteroid's spin its fragments keep, the rest being their
+    /// own.
+    const SPIN_INHERITANCE: f32 = 0.5;
+
+    /// Spawn the fragments the asteroid splits into, at its position, when hit
+    /// at a point by something moving at a velocity, such as a bullet; zero
+    /// for hits carrying no momentum.
+    ///
+    /// Taking an asteroid's mass to be its scale, the fragments share the
+    /// parent's momentum and a little of the impact's, so together they carry
+    /// on in the parent's direction, pushed away from the hit. On top of that
+    /// they fly apart at their usual speed, roughly square to the impact and
+    /// in opposite directions, which leaves the total momentum unchanged.
+    ///
+    /// A hit off centre also sets the asteroid spinning faster one way or the
+    /// other, as a disc would turn. The fragments are flung along the way the
+    /// asteroid was spinning as it broke, like pieces thrown off the rim of a
+    /// wheel, which again leaves the total momentum unchanged, and keep part
+    /// of its spin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::asteroid::{Asteroid, AsteroidSize};
+    /// # use macroquad::prelude::*;
+    /// # use macroquad::rand::RandGenerator;
+    /// let rng = RandGenerator::new();
+    /// let mut asteroid = Asteroid::spawn_new_at(&rng, AsteroidSize::Large.scale(), Vec2::new(500.0, 375.0));
+    /// asteroid.velocity = Vec2::new(0.0, 1.0);
+    /// asteroid.rotation_speed = 0.0;
+    ///
+    /// // Shot from the left, through the middle
+    /// let impact = Vec2::new(8.0, 0.0);
+    /// let fragments = asteroid.split(&rng, impact, asteroid.position);
+    /// assert_eq!(fragments.len(), 2);
+    ///
+    /// // Momentum is conserved, with the impact's share added
+    /// let momentum: Vec2 = fragments.iter().map(|fragment| fragment.velocity * fragment.scale).sum();
+    /// let expected = asteroid.velocity * asteroid.scale + impact * 0.05;
+    /// assert!(momentum.distance(expected) < 1e-4);
+    ///
+    /// // Together they are pushed to the right, and they fly apart
+    /// assert!(momentum.x > 0.0);
+    /// assert!(fragments[0].velocity.distance(fragments[1].velocity) > 1.0);
+    ///
+    /// // Split the same way while spinning, the fragments are flung round
+    /// // square to their paths apart, along the spin, and keep some of it
+    /// let still = asteroid.clone();
+    /// asteroid.rotation_speed = 0.05;
+    ///
+    /// rng.srand(7);
+    /// let still = still.split(&rng, impact, asteroid.position);
+    /// rng.srand(7);
+    /// let spun = asteroid.split(&rng, impact, asteroid.position);
+    ///
+    /// for (still, spun) in still.iter().zip(&spun) {
+    ///     let apart = still.velocity - asteroid.velocity - impact * 0.05;
+    ///     let fling = spun.velocity - still.velocity;
+    ///
+    ///     assert!(fling.dot(apart).abs() < 1e-3);
+    ///     assert!(apart.perp_dot(fling) > 0.0);
+    ///     assert!(spun.rotation_speed > still.rotation_speed);
+    /// }
+    /// ```
+    ///
+    /// Fragments are plain rock, whatever the asteroid was, and asteroids of
+    /// kinds that do not split leave none.
+    pub fn split(&self, rng: &RandGenerator, impact: Vec2, point: Vec2) -> Vec<Asteroid> {
+        if !self.kind.traits().splits {
+            return Vec::new();
+        }
+
+        let mut fragments: Vec<Asteroid> = Self::fragments_of(self.scale).into_iter()
+            .map(|scale| Self::spawn_new_at(rng, scale, self.position))
+            .collect();
+
+        if fragments.is_empty() {
+            return fragments;
+        }
+
+        // Velocity of the fragments' centre of mass
+        let drift = self.velocity + impact * Self::IMPACT_TRANSFER / self.scale;
+
+        // Fragments fly apart evenly spaced around a direction square to the
+        // impact, or a random one for a hit without momentum
+        let square = if impact == Vec2::ZERO {
+            rng.gen_range(0.0, std::f32::consts::PI * 2.0)
+        } else {
+            detmath::angle_of(impact) + std::f32::consts::FRAC_PI_2
+        };
+
+        let heading = square + rng.gen_range(-Self::SPLIT_SPREAD, Self::SPLIT_SPREAD);
+        let spacing = std::f32::consts::PI * 2.0 / fragments.len() as f32;
+
+        // Spin as the asteroid breaks, taking the asteroid for a disc whose
+        // moment of inertia is half its mass times its radius squared
+        let radius = self.diameter / 2.0;
+        let torque = wrapped_delta(self.position, point).perp_dot(impact * Self::IMPACT_TRANSFER);
+        let spin = self.rotation_speed + 2.0 * torque / (self.scale * radius * radius);
+
+        // Speed at which the fragments, halfway out to the rim, are flung
+        let fling = spin * radius / 2.0 * Self::SPIN_FLING;
+
+        for (index, fragment) in fragments.iter_mut().enumerate() {
+            let direction = detmath::direction(heading + spacing * index as f32);
+
+            fragment.velocity = drift + direction * fragment.velocity.length() + direction.perp() * fling;
+            fragment.rotation_speed += (spin - fragment.rotation_speed) * Self::SPIN_INHERITANCE;
+
+            // A fragment has a narrower wrap margin than the asteroid, so one
+            // breaking off beyond an edge may already be due to wrap
+            fragment.position = wrap::wrap(fragment.position, fragment.diameter / 2.0);
+        }
+
+        fragments
+    }
+
+    /// Take a hit, returning true if it breaks the asteroid. Armour takes hits
+    /// until it is gone, leaving the asteroid whole.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::asteroid::{Asteroid, AsteroidKind, AsteroidSize};
+    /// # use macroquad::prelude::*;
+    /// # use macroquad::rand::RandGenerator;
+    /// let rng = RandGenerator::new();
+    /// let mut asteroid = Asteroid::spawn_kind_at(&rng, AsteroidSize::Large.scale(), Vec2::ZERO, AsteroidKind::Armored);
+    ///
+    /// assert!(!asteroid.hit());
+    /// assert!(asteroid.hit());
+    /// ```
+    pub fn hit(&mut self) -> bool {
+        if self.armor == 0 {
+            return true;
+        }
+
+        self.armor -= 1;
+        false
+    }
+
+    /// Destroy asteroid by marking it dead. Any calls to `is_alive` will return
+    /// false after this function is called.
+    pub fn destroy(&mut self) {
+        self.is_alive = false;
+    }
+
+    /// Check if asteroid is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.is_alive
+    }
+
+    /// Damage needed to destroy the asteroid with a single hit from weapons
+    /// that deal partial damage, such as chain lightning.
+    pub fn toughness(&self) -> f32 {
+        self.base_toughness() * (self.armor + 1) as f32
+    }
+
+    /// Toughness of plain rock of the asteroid's size; see `toughness`.
+    /// Armour adds as much again for every hit it can take.
+    fn base_toughness(&self) -> f32 {
+        if self.scale < AsteroidSize::Small.scale() {
+            0.8 * self.scale
+        } else if self.scale > AsteroidSize::Large.scale() {
+            0.7 * self.scale
+        } else {
+            Self::interpolate_presets(self.scale, [0.2, 0.4, 0.7])
+        }
+    }
+
+    /// Update asteroid position and rotation.
+    /// 
+    /// Asteroids move in a random direction. They rotate slowly and wrap around the
+    /// screen when they reach the edge.
+    pub fn update(&mut self) {
+        self.position += self.velocity;
+        self.rotation += self.rotation_speed;
+
+        self.position = wrap::wrap(self.position, self.diameter / 2.0);
+    }
+
+    /// Size of the inner outline of an armoured asteroid, against the outer.
+    const ARMOR_OUTLINE: f32 = 0.75;
+
+    /// Draw asteroid into a batch of lines.
+    /// 
+    /// Asteroids are drawn as polygons with a random number of sides. The vertices
+    /// are rotated by the asteroid's rotation. Asteroids are drawn multiple times
+    /// when they wrap around the screen, to prevent them from disappearing when
+    /// they reach the edge.
+    pub fn draw(&self, lines: &mut LineBatch) {
+        self.draw_tinted(lines, WHITE);
+    }
+
+    /// Draw asteroid into a batch of lines in a colour, such as a faded white
+    /// when it is out of sight.
+    pub fn draw_tinted(&self, lines: &mut LineBatch, color: Color) {
+        let rotation = detmath::rotation(self.rotation);
+
+        // Draw asteroid, and its ghosts across the edges it straddles, with
+        // a second outline inside while it has armour
+        self.draw_wrapped(|position| {
+            lines.push_polygon(position, &self.vertices, rotation, 2., color);
+
+            if self.armor > 0 {
+                lines.push_polygon(position, &self.vertices, rotation * Self::ARMOR_OUTLINE, 1.5, color);
+            }
+        });
+    }
+}
+
+impl Collidable for Asteroid {
+    fn is_colliding(&self, other: &dyn Collidable) -> bool {
+        <dyn Collidable>::shape_intersection(self, other)
+    }
+
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn get_radius(&self) -> f32 {
+        self.diameter / 2.0
+    }
+
+    fn get_outline(&self) -> Option<Outline<'_>> {
+        Some(Outline {
+            vertices: &self.vertices,
+            rotation: self.rotation,
+        })
+    }
+}
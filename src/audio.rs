@@ -0,0 +1,402 @@
+use macroquad::audio::{load_sound_from_bytes, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound};
+
+use crate::asteroid::AsteroidSize;
+
+/// Sample rate used for all synthesised effects.
+const SAMPLE_RATE: u32 = 22050;
+
+/// Sound effect
+///
+/// The classic effects used by the game. Effects are synthesised when the audio
+/// subsystem is loaded, so no sound files need to be shipped with the game.
+#[derive(Clone, Copy)]
+pub enum SoundEffect {
+    Shot,
+    Thrust,
+    Saucer,
+    SmallExplosion,
+    MediumExplosion,
+    LargeExplosion,
+    Hyperspace,
+    ExtraLife,
+    BeatLow,
+    BeatHigh,
+    Lightning,
+}
+
+impl SoundEffect {
+    /// Explosion effect matching the size of a destroyed asteroid.
+    pub fn explosion(size: &AsteroidSize) -> Self {
+        match size {
+            AsteroidSize::Small => SoundEffect::SmallExplosion,
+            AsteroidSize::Medium => SoundEffect::MediumExplosion,
+            AsteroidSize::Large => SoundEffect::LargeExplosion,
+        }
+    }
+}
+
+/// Audio subsystem
+///
+/// Owns the loaded sound effects and tracks which looping effects (thrust and
+/// saucer warble) are currently playing, so that they are only started and
+/// stopped on state changes. If an effect fails to load it is silently skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use asteroids::audio::{Audio, SoundEffect};
+/// # async fn example() {
+/// let mut audio = Audio::load().await;
+/// audio.play(SoundEffect::Shot);
+/// audio.set_looping(SoundEffect::Thrust, true);
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Audio {
+    shot: Option<Sound>,
+    thrust: Option<Sound>,
+    saucer: Option<Sound>,
+    small_explosion: Option<Sound>,
+    medium_explosion: Option<Sound>,
+    large_explosion: Option<Sound>,
+    hyperspace: Option<Sound>,
+    extra_life: Option<Sound>,
+    beat_low: Option<Sound>,
+    beat_high: Option<Sound>,
+    lightning: Option<Sound>,
+    thrust_playing: bool,
+    saucer_playing: bool,
+    volume: f32,
+}
+
+impl Audio {
+    /// Synthesise and load all sound effects.
+    pub async fn load() -> Self {
+        Self {
+            shot: Self::load_samples(&synth::shot()).await,
+            thrust: Self::load_samples(&synth::thrust()).await,
+            saucer: Self::load_samples(&synth::saucer()).await,
+            small_explosion: Self::load_samples(&synth::explosion(0.3, 0.6)).await,
+            medium_explosion: Self::load_samples(&synth::explosion(0.5, 0.3)).await,
+            large_explosion: Self::load_samples(&synth::explosion(0.9, 0.12)).await,
+            hyperspace: Self::load_samples(&synth::hyperspace()).await,
+            extra_life: Self::load_samples(&synth::extra_life()).await,
+            beat_low: Self::load_samples(&synth::beat(55.0)).await,
+            beat_high: Self::load_samples(&synth::beat(62.0)).await,
+            lightning: Self::load_samples(&synth::lightning()).await,
+            thrust_playing: false,
+            saucer_playing: false,
+            volume: 0.5,
+        }
+    }
+
+    /// Create audio subsystem without any sounds loaded.
+    pub fn silent() -> Self {
+        Self::default()
+    }
+
+    /// Load sound from raw samples.
+    async fn load_samples(samples: &[f32]) -> Option<Sound> {
+        load_sound_from_bytes(&synth::to_wav(samples)).await.ok()
+    }
+
+    /// Get sound for effect, if loaded.
+    fn sound(&self, effect: SoundEffect) -> Option<&Sound> {
+        match effect {
+            SoundEffect::Shot => self.shot.as_ref(),
+            SoundEffect::Thrust => self.thrust.as_ref(),
+            SoundEffect::Saucer => self.saucer.as_ref(),
+            SoundEffect::SmallExplosion => self.small_explosion.as_ref(),
+            SoundEffect::MediumExplosion => self.medium_explosion.as_ref(),
+            SoundEffect::LargeExplosion => self.large_explosion.as_ref(),
+            SoundEffect::Hyperspace => self.hyperspace.as_ref(),
+            SoundEffect::ExtraLife => self.extra_life.as_ref(),
+            SoundEffect::BeatLow => self.beat_low.as_ref(),
+            SoundEffect::BeatHigh => self.beat_high.as_ref(),
+            SoundEffect::Lightning => self.lightning.as_ref(),
+        }
+    }
+
+    /// Play effect once.
+    pub fn play(&self, effect: SoundEffect) {
+        if let Some(sound) = self.sound(effect) {
+            play_sound(sound, PlaySoundParams { looped: false, volume: self.volume });
+        }
+    }
+
+    /// Start or stop a looping effect. Only thrust and saucer effects loop; the
+    /// call does nothing if the effect is already in the requested state.
+    pub fn set_looping(&mut self, effect: SoundEffect, playing: bool) {
+        let was_playing = match effect {
+            SoundEffect::Thrust => std::mem::replace(&mut self.thrust_playing, playing),
+            SoundEffect::Saucer => std::mem::replace(&mut self.saucer_playing, playing),
+            _ => return,
+        };
+
+        if was_playing == playing {
+            return;
+        }
+
+        if let Some(sound) = self.sound(effect) {
+            if playing {
+                play_sound(sound, PlaySoundParams { looped: true, volume: self.volume });
+            } else {
+                stop_sound(sound);
+            }
+        }
+    }
+
+    /// Change the volume of all effects, from 0 to 1, including any already
+    /// looping.
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+
+        for (effect, playing) in [(SoundEffect::Thrust, self.thrust_playing), (SoundEffect::Saucer, self.saucer_playing)] {
+            if let (Some(sound), true) = (self.sound(effect), playing) {
+                set_sound_volume(sound, self.volume);
+            }
+        }
+    }
+
+    /// Stop all looping effects.
+    pub fn stop_loops(&mut self) {
+        self.set_looping(SoundEffect::Thrust, false);
+        self.set_looping(SoundEffect::Saucer, false);
+    }
+}
+
+/// Sound director
+///
+/// Plays the iconic two-tone "heartbeat" in the background of a wave. The beat
+/// alternates between a low and a high tone, and its interval shortens as the
+/// number of asteroids left in the wave decreases, building tension towards the
+/// end of the wave. The tempo is relative to the largest number of asteroids
+/// seen during the wave, since splitting asteroids raises the count at first.
+pub struct SoundDirector {
+    next_beat: f64,
+    high: bool,
+    peak_asteroids: usize,
+    tension: f32,
+}
+
+impl SoundDirector {
+    /// Slowest interval between beats, at the start of a wave.
+    const SLOWEST_INTERVAL: f64 = 1.0;
+
+    /// Fastest interval between beats, with one asteroid left.
+    const FASTEST_INTERVAL: f64 = 0.25;
+
+    /// Create a new sound director.
+    pub fn new() -> Self {
+        Self {
+            next_beat: 0.0,
+            high: false,
+            peak_asteroids: 0,
+            tension: 0.0,
+        }
+    }
+
+    /// Reset tempo at the start of a wave, at a time.
+    pub fn reset(&mut self, now: f64) {
+        self.next_beat = now + Self::SLOWEST_INTERVAL;
+        self.high = false;
+        self.peak_asteroids = 0;
+        self.tension = 0.0;
+    }
+
+    /// Tension of the wave, from 0 at its start rising towards 1 as its
+    /// asteroids are cleared, which sets the tempo of the beat.
+    pub fn tension(&self) -> f32 {
+        self.tension
+    }
+
+    /// Interval between beats for the number of asteroids left.
+    fn interval(&self, asteroids: usize) -> f64 {
+        if self.peak_asteroids == 0 {
+            return Self::SLOWEST_INTERVAL;
+        }
+
+        let remaining = asteroids as f64 / self.peak_asteroids as f64;
+        Self::FASTEST_INTERVAL + (Self::SLOWEST_INTERVAL - Self::FASTEST_INTERVAL) * remaining
+    }
+
+    /// Play the next beat when it is due at a time.
+    pub fn update(&mut self, audio: &Audio, asteroids: usize, now: f64) {
+        self.peak_asteroids = self.peak_asteroids.max(asteroids);
+        self.tension = ((Self::SLOWEST_INTERVAL - self.interval(asteroids)) / (Self::SLOWEST_INTERVAL - Self::FASTEST_INTERVAL)) as f32;
+
+        if self.next_beat > now || asteroids == 0 {
+            return;
+        }
+
+        audio.play(if self.high { SoundEffect::BeatHigh } else { SoundEffect::BeatLow });
+
+        self.high = !self.high;
+        self.next_beat = now + self.interval(asteroids);
+    }
+}
+
+impl Default for SoundDirector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sound synthesis
+///
+/// Simple generators for retro effects. All generators return mono samples in
+/// the range [-1, 1] at `SAMPLE_RATE`.
+mod synth {
+    use super::SAMPLE_RATE;
+    use std::f32::consts::PI;
+
+    /// Number of samples for a duration in seconds.
+    fn samples_for(duration: f32) -> usize {
+        (duration * SAMPLE_RATE as f32) as usize
+    }
+
+    /// Deterministic white noise, so effects do not consume the game's random
+    /// number sequence.
+    fn noise(count: usize) -> Vec<f32> {
+        let mut state: u32 = 0x1234_5678;
+
+        (0..count).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as f32 / u32::MAX as f32 * 2.0 - 1.0
+        }).collect()
+    }
+
+    /// Square wave oscillator with a frequency that may vary over time.
+    fn square(duration: f32, frequency: impl Fn(f32) -> f32) -> Vec<f32> {
+        let mut phase = 0.0;
+
+        (0..samples_for(duration)).map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            phase = (phase + frequency(t) / SAMPLE_RATE as f32) % 1.0;
+            if phase < 0.5 { 1.0 } else { -1.0 }
+        }).collect()
+    }
+
+    /// Player shot: a short descending blip.
+    pub fn shot() -> Vec<f32> {
+        let duration = 0.12;
+
+        square(duration, |t| 1200.0 - 900.0 * t / duration)
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s * 0.3 * (1.0 - i as f32 / samples_for(duration) as f32))
+            .collect()
+    }
+
+    /// Thrust: a low rumble, designed to be looped.
+    pub fn thrust() -> Vec<f32> {
+        let mut filtered = 0.0;
+
+        noise(samples_for(0.5)).iter().map(|s| {
+            filtered += (s - filtered) * 0.05;
+            filtered * 1.5
+        }).collect()
+    }
+
+    /// Saucer: a warbling siren, designed to be looped.
+    pub fn saucer() -> Vec<f32> {
+        square(0.5, |t| 500.0 + 120.0 * (2.0 * PI * 8.0 * t).sin())
+            .iter()
+            .map(|s| s * 0.15)
+            .collect()
+    }
+
+    /// Explosion: filtered noise with exponential decay. Larger explosions last
+    /// longer and use a lower cutoff for a deeper sound.
+    pub fn explosion(duration: f32, cutoff: f32) -> Vec<f32> {
+        let count = samples_for(duration);
+        let mut filtered = 0.0;
+
+        noise(count).iter().enumerate().map(|(i, s)| {
+            filtered += (s - filtered) * cutoff;
+            let envelope = (-5.0 * i as f32 / count as f32).exp();
+            filtered * envelope
+        }).collect()
+    }
+
+    /// Hyperspace: a rising sine sweep.
+    pub fn hyperspace() -> Vec<f32> {
+        let duration = 0.4;
+        let count = samples_for(duration);
+        let mut phase: f32 = 0.0;
+
+        (0..count).map(|i| {
+            let t = i as f32 / count as f32;
+            phase += (200.0 + 1400.0 * t * t) / SAMPLE_RATE as f32;
+            (2.0 * PI * phase).sin() * 0.4 * (1.0 - t)
+        }).collect()
+    }
+
+    /// Extra life: a run of high pitched pings.
+    pub fn extra_life() -> Vec<f32> {
+        let ping = samples_for(0.1);
+
+        square(0.6, |_| 1000.0)
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let envelope = 1.0 - (i % ping) as f32 / ping as f32;
+                s * 0.25 * envelope
+            })
+            .collect()
+    }
+
+    /// Heartbeat: a short, low thump at the given frequency.
+    pub fn beat(frequency: f32) -> Vec<f32> {
+        let count = samples_for(0.1);
+
+        square(0.1, |_| frequency)
+            .iter()
+            .enumerate()
+            .map(|(i, s)| s * 0.5 * (1.0 - i as f32 / count as f32))
+            .collect()
+    }
+
+    /// Lightning: crackling noise gated by a fast square wave.
+    pub fn lightning() -> Vec<f32> {
+        let count = samples_for(0.3);
+        let gate = square(0.3, |t| 30.0 + 60.0 * t);
+
+        noise(count).iter().zip(gate).enumerate().map(|(i, (s, g))| {
+            s * (0.5 + 0.5 * g) * 0.5 * (1.0 - i as f32 / count as f32)
+        }).collect()
+    }
+
+    /// Encode samples as a 16-bit mono PCM WAV file.
+    pub fn to_wav(samples: &[f32]) -> Vec<u8> {
+        let data_size = (samples.len() * 2) as u32;
+        let mut wav = Vec::with_capacity(44 + data_size as usize);
+
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+
+        // Format chunk: PCM, mono, 16 bits per sample
+        wav.extend_from_slice(b"fmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+        wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+
+        // Data chunk
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&data_size.to_le_bytes());
+
+        for sample in samples {
+            let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            wav.extend_from_slice(&value.to_le_bytes());
+        }
+
+        wav
+    }
+}
@@ -0,0 +1,145 @@
+//! Benchmarks.
+//!
+//! Launched with `--bench <ticks>`, the game runs the simulation through a few
+//! seeded scenarios, from a sparse field to a crowded one with saucers, for a
+//! number of ticks each without drawing or reading input. Every tick is timed,
+//! and the distribution of tick times is reported for each scenario, so a
+//! change to the simulation can be measured before and after on identical
+//! fields.
+//!
+//! The benchmark runs on a headless world, so it plays the same on any
+//! machine. It times the game's own world, the only representation there is:
+//! comparing a redesigned world against it, such as an entity-component one,
+//! waits on that world being written, and would run it through the same
+//! scenarios and seed.
+//!
+//! Timing needs a clock, so benchmarks only run on native builds.
+
+use crate::scenario::Scenario;
+use crate::world::GameWorld;
+
+/// Seed every benchmark scenario is played from.
+pub const SEED: u64 = 1979;
+
+/// Time in seconds each tick moves the clock and the field on by.
+pub const STEP: f32 = 1.0 / 60.0;
+
+/// Scenarios benchmarked, by name.
+pub const SCENARIOS: [(&str, &str); 3] = [
+    ("sparse", "large_asteroids = 4"),
+    ("crowded", "large_asteroids = 12\nmedium_asteroids = 12\nsmall_asteroids = 24"),
+    ("saucers", "wave = 6\nlarge_asteroids = 6\nlarge_saucers = 2\nsmall_saucers = 2"),
+];
+
+/// Tick times
+///
+/// Times taken by the ticks of a benchmark, in milliseconds.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::bench::TickTimes;
+/// let mut times = TickTimes::default();
+///
+/// for tick in 1..=100 {
+///     times.push(tick as f64);
+/// }
+///
+/// assert_eq!(times.len(), 100);
+/// assert_eq!(times.mean(), 50.5);
+/// assert_eq!(times.percentile(50.0), 50.0);
+/// assert_eq!(times.percentile(99.0), 99.0);
+/// assert_eq!(times.percentile(100.0), 100.0);
+/// ```
+#[derive(Clone, Default)]
+pub struct TickTimes {
+    samples: Vec<f64>,
+}
+
+impl TickTimes {
+    /// Record the time a tick took.
+    pub fn push(&mut self, milliseconds: f64) {
+        self.samples.push(milliseconds);
+    }
+
+    /// Number of ticks timed.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Check if no ticks were timed.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Mean time a tick took, or 0 if none were timed.
+    pub fn mean(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+
+        self.samples.iter().sum::<f64>() / self.len() as f64
+    }
+
+    /// Time within which a percentage of ticks finished, or 0 if none were
+    /// timed.
+    pub fn percentile(&self, percent: f64) -> f64 {
+        let mut sorted = self.samples.clone();
+        sorted.sort_by(f64::total_cmp);
+
+        let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+
+        sorted.get(rank.clamp(1, sorted.len().max(1)) - 1).copied().unwrap_or(0.0)
+    }
+
+    /// One line summing up the distribution, for a scenario of a name.
+    pub fn report(&self, name: &str) -> String {
+        format!(
+            "{:<8} {:>6} ticks  mean {:.3} ms  p50 {:.3} ms  p95 {:.3} ms  p99 {:.3} ms  max {:.3} ms",
+            name, self.len(), self.mean(), self.percentile(50.0), self.percentile(95.0), self.percentile(99.0), self.percentile(100.0)
+        )
+    }
+}
+
+/// Run every scenario on a world for a number of ticks, timing each tick.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::bench;
+/// # use asteroids::options::LaunchOptions;
+/// # use asteroids::world::GameWorld;
+/// let mut world = GameWorld::headless(&LaunchOptions::default());
+/// let results = bench::run(&mut world, 10);
+///
+/// assert_eq!(results.len(), bench::SCENARIOS.len());
+/// assert_eq!(results[0].0, "sparse");
+/// assert!(results.iter().all(|(_, times)| times.len() == 10));
+///
+/// // The clock moves on with the field
+/// assert!(world.clock.now() > 0.1);
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run(world: &mut GameWorld, ticks: usize) -> Vec<(&'static str, TickTimes)> {
+    use std::time::Instant;
+
+    let mut results = Vec::new();
+
+    for (name, text) in SCENARIOS {
+        let scenario = Scenario::parse(text).expect("benchmark scenarios are valid");
+        let mut times = TickTimes::default();
+
+        world.start_scenario(&scenario, SEED);
+
+        for _ in 0..ticks {
+            let start = Instant::now();
+            world.clock.advance(STEP);
+            world.update();
+            times.push(start.elapsed().as_secs_f64() * 1000.0);
+        }
+
+        results.push((name, times));
+    }
+
+    results
+}
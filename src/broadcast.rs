@@ -0,0 +1,151 @@
+use crate::world::GameState;
+
+/// Broadcast snapshot
+/// 
+/// The subset of game state tracked by the broadcaster in order to detect
+/// changes between frames.
+#[derive(Clone, Copy, PartialEq)]
+pub struct BroadcastSnapshot {
+    pub state: &'static str,
+    pub score: u32,
+    pub lives: u32,
+    pub wave: u32,
+}
+
+/// Broadcaster
+/// 
+/// Broadcasts game events as JSON messages to any client connected to a local
+/// websocket server, so that external tools (stream overlays, lights, score
+/// displays) can react in real time. The server runs on its own thread and is
+/// fed through a channel, so the frame loop never blocks on the network.
+/// 
+/// Events are derived by comparing snapshots of the game state between frames:
+/// 
+/// - `{"event":"state","state":"playing"}` when the game state changes.
+/// - `{"event":"score","score":1200}` when the score changes.
+/// - `{"event":"wave","wave":3}` when a new wave starts.
+/// - `{"event":"death","lives":2}` when the player loses a life.
+/// 
+/// A default broadcaster has no server and discards all events.
+#[derive(Default)]
+pub struct Broadcaster {
+    sender: Option<std::sync::mpsc::Sender<String>>,
+    last_snapshot: Option<BroadcastSnapshot>,
+}
+
+impl Broadcaster {
+    /// How long a client may take over the websocket handshake.
+    pub const HANDSHAKE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+    /// How long a send to one client may block before the client is dropped.
+    pub const SEND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+    /// Start a websocket server on localhost at the given port and broadcast
+    /// events to it. Fails if the port cannot be bound.
+    ///
+    /// Each handshake runs on its own thread, so a client that stalls the
+    /// handshake never holds up broadcasting to everyone else.
+    ///
+    /// ```
+    /// use asteroids::broadcast::Broadcaster;
+    ///
+    /// let taken = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    /// let port = taken.local_addr().unwrap().port();
+    ///
+    /// assert!(Broadcaster::start(port).is_err());
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start(port: u16) -> std::io::Result<Self> {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", port))?;
+
+        let (sender, receiver) = std::sync::mpsc::channel::<String>();
+        let (joined, handshaken) = std::sync::mpsc::channel();
+
+        // Accept connections, handing each to a thread of its own for the handshake
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let joined = joined.clone();
+
+                std::thread::spawn(move || {
+                    if let Some(client) = Self::handshake(stream) {
+                        let _ = joined.send(client);
+                    }
+                });
+            }
+        });
+
+        // Forward messages to all clients, dropping any that disconnected
+        std::thread::spawn(move || {
+            let mut clients = Vec::new();
+
+            loop {
+                clients.extend(handshaken.try_iter());
+
+                match receiver.recv_timeout(std::time::Duration::from_millis(50)) {
+                    Ok(message) => {
+                        clients.retain_mut(|client| client.send(tungstenite::Message::text(message.clone())).is_ok());
+                    },
+                    Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {},
+                    Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+
+        Ok(Self {
+            sender: Some(sender),
+            last_snapshot: None,
+        })
+    }
+
+    /// Complete the websocket handshake, giving up on clients that take too
+    /// long, and bound how long later sends may block.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn handshake(stream: std::net::TcpStream) -> Option<tungstenite::WebSocket<std::net::TcpStream>> {
+        stream.set_read_timeout(Some(Self::HANDSHAKE_TIMEOUT)).ok()?;
+        stream.set_write_timeout(Some(Self::HANDSHAKE_TIMEOUT)).ok()?;
+
+        let client = tungstenite::accept(stream).ok()?;
+        client.get_ref().set_write_timeout(Some(Self::SEND_TIMEOUT)).ok()?;
+
+        Some(client)
+    }
+
+    /// Compare snapshot with the previous one and broadcast any changes.
+    pub fn observe(&mut self, snapshot: BroadcastSnapshot) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        let last = self.last_snapshot.replace(snapshot);
+        if last == Some(snapshot) {
+            return;
+        }
+
+        let mut events = Vec::new();
+
+        if last.is_none_or(|last| last.state != snapshot.state) {
+            events.push(format!("{{\"event\":\"state\",\"state\":\"{}\"}}", snapshot.state));
+        }
+
+        if last.is_none_or(|last| last.score != snapshot.score) {
+            events.push(format!("{{\"event\":\"score\",\"score\":{}}}", snapshot.score));
+        }
+
+        if last.is_none_or(|last| last.wave != snapshot.wave) {
+            events.push(format!("{{\"event\":\"wave\",\"wave\":{}}}", snapshot.wave));
+        }
+
+        if let Some(last) = last {
+            let died = snapshot.lives < last.lives 
+                || (snapshot.state == GameState::GameOver.name() && last.state != snapshot.state);
+
+            if died {
+                events.push(format!("{{\"event\":\"death\",\"lives\":{}}}", snapshot.lives));
+            }
+        }
+
+        for event in events {
+            let _ = sender.send(event);
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! Bug reports.
+//!
+//! Pressing F1 during a game freezes it on the pause menu and captures a bug
+//! report: a zip the player can attach to an issue, saved alongside the other
+//! saved data as `bugreport-<time>.zip`. It holds
+//!
+//! - `screenshot.png`, the frame on screen when the key was pressed;
+//! - `replay.txt`, the last ten seconds of the flight recorder, every object's
+//!   collision circle frame by frame, for looking into collision and spawn
+//!   anomalies;
+//! - `movie.tas`, the game's seed, settings and controls so far as a movie,
+//!   which replays the game with `--movie` (see the `tas` module);
+//! - `settings.toml`, the player's settings;
+//! - `log.txt`, the debug log of the game's recent events; and
+//! - `report.txt`, a summary of the game when the report was made.
+//!
+//! The zip and the PNG are written here, compressed with `miniz_oxide`, so the
+//! game needs no other dependencies to make them.
+
+use std::collections::VecDeque;
+
+use macroquad::prelude::*;
+
+use crate::replay::{Circle, Recording};
+
+/// Debug log
+///
+/// The most recent notable events of play, such as waves starting and ships
+/// lost, with the times they happened, kept for bug reports. The oldest lines
+/// are dropped once the log is full.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::bugreport::DebugLog;
+/// let mut log = DebugLog::default();
+/// log.push(1.5, "wave 1");
+/// log.push(12.25, "ship lost at (500, 375)");
+///
+/// assert_eq!(log.to_text(), "    1.500  wave 1\n   12.250  ship lost at (500, 375)\n");
+///
+/// for line in 0..DebugLog::CAPACITY {
+///     log.push(20.0, format!("line {}", line));
+/// }
+///
+/// assert!(!log.to_text().contains("wave 1"));
+/// ```
+#[derive(Default)]
+pub struct DebugLog {
+    lines: VecDeque<(f64, String)>,
+}
+
+impl DebugLog {
+    /// Most lines kept.
+    pub const CAPACITY: usize = 200;
+
+    /// Log a line at a time, dropping the oldest once the log is full.
+    pub fn push(&mut self, time: f64, line: impl Into<String>) {
+        if self.lines.len() == Self::CAPACITY {
+            self.lines.pop_front();
+        }
+
+        self.lines.push_back((time, line.into()));
+    }
+
+    /// The log as text, a line per event with its time in seconds.
+    pub fn to_text(&self) -> String {
+        self.lines.iter().map(|(time, line)| format!("{:>9.3}  {}\n", time, line)).collect()
+    }
+}
+
+/// Bug report
+///
+/// The files of a bug report, written out as a zip.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::bugreport::BugReport;
+/// let mut report = BugReport::default();
+/// report.add("report.txt", "seed 42\n");
+/// report.add("log.txt", "wave 1\n".repeat(100));
+///
+/// let zip = report.to_zip();
+///
+/// // A zip starts with its first file's header, and ends with a directory
+/// // of its two files
+/// assert_eq!(&zip[..4], b"PK\x03\x04");
+/// let end = &zip[zip.len() - 22..];
+/// assert_eq!(&end[..4], b"PK\x05\x06");
+/// assert_eq!(u16::from_le_bytes([end[10], end[11]]), 2);
+///
+/// // The first file is deflated after its 30-byte header and name
+/// let size = u32::from_le_bytes([zip[18], zip[19], zip[20], zip[21]]) as usize;
+/// let data = &zip[30 + "report.txt".len()..][..size];
+/// assert_eq!(miniz_oxide::inflate::decompress_to_vec(data).unwrap(), b"seed 42\n");
+/// ```
+#[derive(Default)]
+pub struct BugReport {
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl BugReport {
+    /// Key that captures a bug report.
+    pub const KEY: KeyCode = KeyCode::F1;
+
+    /// Seconds of the flight recorder included.
+    pub const REPLAY_SECONDS: f64 = 10.0;
+
+    /// Compression level files are deflated at.
+    const LEVEL: u8 = 6;
+
+    /// Add a file to the report.
+    pub fn add(&mut self, name: &str, contents: impl Into<Vec<u8>>) {
+        self.files.push((name.to_string(), contents.into()));
+    }
+
+    /// Write the report out as a zip, every file deflated.
+    pub fn to_zip(&self) -> Vec<u8> {
+        // Zip dates count from 1980; every file is dated its first day
+        const DATE: u16 = 1 << 5 | 1;
+
+        let mut zip = Vec::new();
+        let mut directory = Vec::new();
+
+        for (name, contents) in &self.files {
+            let data = miniz_oxide::deflate::compress_to_vec(contents, Self::LEVEL);
+            let offset = zip.len() as u32;
+
+            // What a file's local header and directory entry both record:
+            // version needed, flags, deflated, time, date, checksum and sizes
+            let mut common = Vec::new();
+            common.extend(20u16.to_le_bytes());
+            common.extend(0u16.to_le_bytes());
+            common.extend(8u16.to_le_bytes());
+            common.extend(0u16.to_le_bytes());
+            common.extend(DATE.to_le_bytes());
+            common.extend(crc32(contents).to_le_bytes());
+            common.extend((data.len() as u32).to_le_bytes());
+            common.extend((contents.len() as u32).to_le_bytes());
+            common.extend((name.len() as u16).to_le_bytes());
+            common.extend(0u16.to_le_bytes());
+
+            zip.extend(0x04034b50u32.to_le_bytes());
+            zip.extend(&common);
+            zip.extend(name.as_bytes());
+            zip.extend(&data);
+
+            // Made by the same version, with no comment, on the first disk,
+            // with no attributes, then where the local header is
+            directory.extend(0x02014b50u32.to_le_bytes());
+            directory.extend(20u16.to_le_bytes());
+            directory.extend(&common);
+            directory.extend([0; 10]);
+            directory.extend(offset.to_le_bytes());
+            directory.extend(name.as_bytes());
+        }
+
+        let count = self.files.len() as u16;
+        let offset = zip.len() as u32;
+
+        zip.extend(&directory);
+
+        // End of the directory, all on the first disk, with no comment
+        zip.extend(0x06054b50u32.to_le_bytes());
+        zip.extend([0; 4]);
+        zip.extend(count.to_le_bytes());
+        zip.extend(count.to_le_bytes());
+        zip.extend((directory.len() as u32).to_le_bytes());
+        zip.extend(offset.to_le_bytes());
+        zip.extend(0u16.to_le_bytes());
+
+        zip
+    }
+}
+
+/// CRC-32 checksum of some bytes, as used by zips and PNGs.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::bugreport::crc32;
+/// assert_eq!(crc32(b"123456789"), 0xcbf43926);
+/// assert_eq!(crc32(b""), 0);
+/// ```
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for byte in bytes {
+        crc ^= *byte as u32;
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { crc >> 1 ^ 0xedb88320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+/// Encode an image, given as rows of RGBA pixels from the top down, as a PNG.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::bugreport::encode_png;
+/// let png = encode_png(2, 1, &[255, 0, 0, 255, 0, 0, 255, 255]);
+///
+/// assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+/// assert_eq!(&png[12..16], b"IHDR");
+/// assert_eq!(&png[png.len() - 8..png.len() - 4], b"IEND");
+/// ```
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Vec<u8> {
+    let chunk = |png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]| {
+        let mut checked = kind.to_vec();
+        checked.extend(data);
+
+        png.extend((data.len() as u32).to_be_bytes());
+        png.extend(&checked);
+        png.extend(crc32(&checked).to_be_bytes());
+    };
+
+    // Eight bits a channel, RGBA, not interlaced
+    let mut header = Vec::new();
+    header.extend(width.to_be_bytes());
+    header.extend(height.to_be_bytes());
+    header.extend([8, 6, 0, 0, 0]);
+
+    // Every row is left unfiltered
+    let mut pixels = Vec::with_capacity(rgba.len() + height as usize);
+
+    for row in rgba.chunks(width as usize * 4) {
+        pixels.push(0);
+        pixels.extend(row);
+    }
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &miniz_oxide::deflate::compress_to_vec_zlib(&pixels, BugReport::LEVEL));
+    chunk(&mut png, b"IEND", &[]);
+
+    png
+}
+
+/// The frame on screen, as a PNG.
+pub fn screenshot() -> Vec<u8> {
+    let image = get_screen_data();
+    let row = image.width as usize * 4;
+
+    // The screen is read from the bottom up
+    let rgba: Vec<u8> = image.bytes.chunks(row).rev().flatten().copied().collect();
+
+    encode_png(image.width as u32, image.height as u32, &rgba)
+}
+
+/// The last seconds of a recording as text: a line for each frame, with its
+/// time, wave and score, followed by a line for each object in play with its
+/// collision circle, and the heading of each ship.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::bugreport::replay_text;
+/// # use asteroids::replay::{Circle, Recording};
+/// # use macroquad::prelude::*;
+/// let mut recording = Recording::default();
+///
+/// for frame in 0..3 {
+///     let snapshot = recording.record();
+///     snapshot.time = frame as f64 * 5.0;
+///     snapshot.asteroids.push(Circle::new(Vec2::new(100.0, 200.0), 30.0));
+/// }
+///
+/// // Frames up to 5 seconds before the last are included
+/// let text = replay_text(&recording, 5.0);
+/// assert!(text.starts_with("frame 1 time 5.000 wave 0 score 0\n"));
+/// assert!(text.contains("asteroid 100.0 200.0 30.0\n"));
+/// assert!(!text.contains("frame 0"));
+/// ```
+pub fn replay_text(recording: &Recording, seconds: f64) -> String {
+    let latest = recording.len().checked_sub(1).and_then(|index| recording.frame(index)).map_or(0.0, |frame| frame.time);
+    let mut text = String::new();
+
+    let circle = |text: &mut String, label: &str, circle: &Circle| {
+        *text += &format!("{} {:.1} {:.1} {:.1}\n", label, circle.position.x, circle.position.y, circle.radius);
+    };
+
+    for index in 0..recording.len() {
+        let Some(frame) = recording.frame(index).filter(|frame| latest - frame.time <= seconds) else {
+            continue;
+        };
+
+        text += &format!("frame {} time {:.3} wave {} score {}\n", index, frame.time, frame.wave, frame.score);
+
+        for (ship, heading) in &frame.ships {
+            text += &format!("ship {:.1} {:.1} {:.1} heading {:.3}\n", ship.position.x, ship.position.y, ship.radius, heading);
+        }
+
+        for asteroid in &frame.asteroids {
+            circle(&mut text, "asteroid", asteroid);
+        }
+
+        for saucer in &frame.saucers {
+            circle(&mut text, "saucer", saucer);
+        }
+
+        for bullet in &frame.player_bullets {
+            circle(&mut text, "bullet", bullet);
+        }
+
+        for bullet in &frame.enemy_bullets {
+            circle(&mut text, "enemy_bullet", bullet);
+        }
+    }
+
+    text
+}
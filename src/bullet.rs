@@ -0,0 +1,278 @@
+use macroquad::prelude::*;
+
+use crate::camera::WORLD_SIZE;
+use crate::clock::TICK;
+use crate::collision::Collidable;
+use crate::detmath;
+use crate::wrap::{self, wrap_draw};
+
+/// Bullet type
+/// 
+/// Bullets come in two types: player and enemy. Player bullets are smaller and
+/// have a shorter lifespan, in seconds. They also wrap around the screen when they reach the
+/// edge. Enemy bullets are larger and have a longer lifespan. They disappear when
+/// they reach the edge.
+/// 
+#[derive(Clone, Copy, PartialEq)]
+pub enum BulletType {
+    Player,
+    Enemy,
+}
+
+/// Weapon kind
+/// 
+/// The weapons the ship's cannon can be fitted with: the standard cannon fires
+/// a single bullet, the spread cannon a fan of three, and the piercing cannon a
+/// single bullet that survives its first asteroid hit.
+#[derive(Clone, Copy, PartialEq)]
+pub enum WeaponKind {
+    Standard,
+    Spread,
+    Piercing,
+}
+
+impl WeaponKind {
+    /// All weapons, in the order they are cycled through.
+    pub const ALL: [WeaponKind; 3] = [WeaponKind::Standard, WeaponKind::Spread, WeaponKind::Piercing];
+
+    /// Name shown in the HUD.
+    pub fn label(&self) -> &'static str {
+        match self {
+            WeaponKind::Standard => "Standard",
+            WeaponKind::Spread => "Spread",
+            WeaponKind::Piercing => "Piercing",
+        }
+    }
+
+    /// Pattern the weapon fires in.
+    pub fn pattern(&self) -> BulletPattern {
+        match self {
+            WeaponKind::Spread => BulletPattern::Spread { count: 3, angle: 0.3 },
+            _ => BulletPattern::Single,
+        }
+    }
+
+    /// Number of asteroid hits the weapon's bullets survive.
+    pub fn pierce(&self) -> u32 {
+        match self {
+            WeaponKind::Piercing => 1,
+            _ => 0,
+        }
+    }
+
+    /// The weapon following this one, wrapping around.
+    pub fn next(&self) -> WeaponKind {
+        let index = Self::ALL.iter().position(|weapon| weapon == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// Bullet pattern
+/// 
+/// Describes how a weapon releases bullets when it fires: a single bullet along
+/// the firing direction, or a fan of bullets spread evenly around it.
+#[derive(Clone)]
+pub enum BulletPattern {
+    Single,
+    Spread { count: u32, angle: f32 },
+}
+
+impl BulletPattern {
+    /// Fire bullets from a position along a direction (in radians).
+    pub fn fire(&self, position: Vec2, direction: f32, speed: f32, lifespan: f32, bullet_type: BulletType) -> Vec<Bullet> {
+        let directions: Vec<f32> = match self {
+            BulletPattern::Single => vec![direction],
+            BulletPattern::Spread { count, .. } if *count <= 1 => vec![direction],
+            BulletPattern::Spread { count, angle } => {
+                let step = angle / (count - 1) as f32;
+                (0..*count).map(|i| direction - angle / 2.0 + step * i as f32).collect()
+            },
+        };
+
+        directions.iter().map(|direction| {
+            let velocity = detmath::rotation(*direction).mul_vec2(Vec2::X * speed);
+            Bullet::spawn_new(position, velocity, lifespan, bullet_type)
+        }).collect()
+    }
+}
+
+/// Bullet object
+/// 
+/// Bullets are shot by the player. They move in a straight line, at a
+/// velocity in units a tick, and disappear after a certain amount of time. Bullets wrap around the screen when they
+/// reach the edge. Piercing bullets survive a number of asteroid hits before
+/// they are destroyed. Player bullets remember which player fired them, so
+/// the points they score go to the right player, and whether they have
+/// wrapped around an edge of the screen.
+#[derive(Clone)]
+pub struct Bullet {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub lifespan: f32,
+    pub bullet_type: BulletType,
+    pub pierce: u32,
+    pub owner: usize,
+    pub sweep: Vec2,
+    pub wrapped: bool,
+}
+
+impl Bullet {
+    /// Radius bullets are drawn out to, taking in the ring marking a piercing
+    /// round.
+    const DRAWN_RADIUS: f32 = 4.0;
+
+    /// Spawn new bullet at a given position.
+    pub fn spawn_new(position: Vec2, velocity: Vec2, lifespan: f32, bullet_type: BulletType) -> Self {
+        Self {
+            position,
+            velocity,
+            lifespan,
+            bullet_type,
+            pierce: 0,
+            owner: 0,
+            sweep: Vec2::ZERO,
+            wrapped: false,
+        }
+    }
+
+    /// Make bullet survive a number of asteroid hits.
+    pub fn with_pierce(mut self, pierce: u32) -> Self {
+        self.pierce = pierce;
+        self
+    }
+
+    /// Mark bullet as fired by a player, numbered from zero.
+    pub fn with_owner(mut self, owner: usize) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    /// Register a hit on an asteroid. Piercing bullets lose one hit of pierce;
+    /// other bullets are destroyed.
+    pub fn hit(&mut self) {
+        if self.pierce > 0 {
+            self.pierce -= 1;
+        } else {
+            self.destroy();
+        }
+    }
+
+    /// Destroy bullet by marking it dead. Any calls to `is_alive` will return
+    /// false after this function is called.
+    pub fn destroy(&mut self) {
+        self.lifespan = 0.0;
+    }
+
+    /// Check if bullet is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.lifespan > 0.0
+    }
+
+    /// Age bullet by a number of seconds.
+    ///
+    /// # Examples
+    ///
+    /// A bullet lasts as long at any frame rate, give or take a frame:
+    ///
+    /// ```
+    /// # use asteroids::bullet::{Bullet, BulletType};
+    /// # use macroquad::prelude::*;
+    /// for fps in [30.0, 60.0, 144.0] {
+    ///     let mut bullet = Bullet::spawn_new(Vec2::ZERO, Vec2::X, 0.8, BulletType::Player);
+    ///     let mut frames = 0;
+    ///
+    ///     while bullet.is_alive() {
+    ///         bullet.age(1.0 / fps);
+    ///         frames += 1;
+    ///     }
+    ///
+    ///     assert!((frames as f32 / fps - 0.8).abs() <= 1.0 / fps);
+    /// }
+    /// ```
+    pub fn age(&mut self, dt: f32) {
+        self.lifespan -= dt;
+    }
+
+    /// Move bullet and age it by the time the frame took. The step taken is
+    /// kept as the bullet's sweep, so it hits anything it passed through on
+    /// the way, however fast it flies.
+    ///
+    /// # Examples
+    ///
+    /// A bullet flies as far at any frame rate, give or take a frame:
+    ///
+    /// ```
+    /// # use asteroids::bullet::{Bullet, BulletType};
+    /// # use asteroids::clock::TICK;
+    /// # use macroquad::prelude::*;
+    /// let range = 0.8 / TICK * 5.0;
+    ///
+    /// for fps in [30.0, 60.0, 144.0] {
+    ///     let mut bullet = Bullet::spawn_new(Vec2::new(100.0, 100.0), Vec2::X * 5.0, 0.8, BulletType::Player);
+    ///     let mut travelled = 0.0;
+    ///
+    ///     while bullet.is_alive() {
+    ///         bullet.update(1.0 / fps);
+    ///         travelled += bullet.sweep.length();
+    ///     }
+    ///
+    ///     assert!((travelled - range).abs() < 1.5 * 5.0 / fps / TICK);
+    /// }
+    /// ```
+    pub fn update(&mut self, dt: f32) {
+        let step = self.velocity * (dt / TICK);
+
+        self.position += step;
+        self.sweep = step;
+        self.age(dt);
+
+        // Handle screen edges once the bullet is drawn clear of them
+        if self.bullet_type == BulletType::Player {
+            let position = wrap::wrap(self.position, Self::DRAWN_RADIUS);
+
+            self.wrapped |= position != self.position;
+            self.position = position;
+        } else if !wrap::is_within(self.position, Self::DRAWN_RADIUS * wrap::WRAP_MARGIN, WORLD_SIZE) {
+            self.lifespan = 0.0;
+        }
+    }
+
+    /// Draw bullet.
+    pub fn draw(&self) {
+        self.draw_tinted(WHITE);
+    }
+
+    /// Draw bullet in a colour, such as a faded white when it is out of sight.
+    pub fn draw_tinted(&self, color: Color) {
+        // Drawn out to the ring marking a piercing round
+        wrap_draw(self.position, Self::DRAWN_RADIUS, |position| {
+            if self.bullet_type == BulletType::Player {
+                draw_circle(position.x, position.y, 2., color);
+
+                if self.pierce > 0 {
+                    draw_circle_lines(position.x, position.y, 4., 1., color);
+                }
+            } else {
+                draw_circle(position.x, position.y, 3., color);
+            }
+        });
+    }
+}
+
+impl Collidable for Bullet {
+    fn is_colliding(&self, other: &dyn Collidable) -> bool {
+        <dyn Collidable>::shape_intersection(self, other)
+    }
+
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn get_radius(&self) -> f32 {
+        2.0
+    }
+
+    fn get_sweep(&self) -> Vec2 {
+        self.sweep
+    }
+}
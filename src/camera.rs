@@ -0,0 +1,199 @@
+//! Cameras.
+//!
+//! The game is simulated on a playfield of a fixed logical size, whatever the
+//! size of the window, so objects keep their size and speed, and the game its
+//! difficulty, when the window is resized. The playfield is drawn through a
+//! camera scaling it to fit the window, with black bars along the sides or
+//! top and bottom where the window's shape differs from the playfield's. Text
+//! and menus are drawn over it at the window's own resolution. Background
+//! layers are drawn into the same viewport through cameras of their own, at
+//! scales of their own.
+
+use macroquad::prelude::*;
+
+/// Width of the playfield, in world units.
+pub const WORLD_WIDTH: f32 = 1000.0;
+
+/// Height of the playfield, in world units.
+pub const WORLD_HEIGHT: f32 = 750.0;
+
+/// Size of the playfield, in world units.
+pub const WORLD_SIZE: Vec2 = Vec2::new(WORLD_WIDTH, WORLD_HEIGHT);
+
+/// Part of a window, in pixels, the playfield is drawn into: as large as fits,
+/// keeping the playfield's shape, and centred.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::camera::viewport;
+/// # use macroquad::prelude::*;
+/// // A window of the playfield's shape is filled
+/// assert_eq!(viewport(Vec2::new(800.0, 600.0)), Rect::new(0.0, 0.0, 800.0, 600.0));
+///
+/// // A wide window has bars at either side, a tall one above and below
+/// assert_eq!(viewport(Vec2::new(1000.0, 600.0)), Rect::new(100.0, 0.0, 800.0, 600.0));
+/// assert_eq!(viewport(Vec2::new(800.0, 800.0)), Rect::new(0.0, 100.0, 800.0, 600.0));
+/// ```
+pub fn viewport(window: Vec2) -> Rect {
+    let scale = (window.x / WORLD_WIDTH).min(window.y / WORLD_HEIGHT);
+    let size = WORLD_SIZE * scale;
+    let corner = (window - size) / 2.0;
+
+    Rect::new(corner.x, corner.y, size.x, size.y)
+}
+
+/// Camera drawing the playfield into the window's viewport, displaced by an
+/// offset in world units, for example to shake the screen.
+pub fn world_camera(offset: Vec2) -> Camera2D {
+    layer_camera(1.0, offset)
+}
+
+/// Camera drawing a layer into the same viewport as the playfield, at a scale
+/// of its own and displaced by an offset in the layer's units. A layer drawn
+/// at half scale spans twice the playfield's size, so its objects look half
+/// as large; see `layer_size`.
+pub fn layer_camera(scale: f32, offset: Vec2) -> Camera2D {
+    let viewport = viewport(Vec2::new(screen_width(), screen_height()));
+    let size = layer_size(scale);
+
+    // Viewports are measured from the bottom of the window, which for one
+    // centred in it is the same as from the top
+    Camera2D {
+        viewport: Some((viewport.x as i32, viewport.y as i32, viewport.w as i32, viewport.h as i32)),
+        ..Camera2D::from_display_rect(Rect::new(-offset.x, -offset.y, size.x, size.y))
+    }
+}
+
+/// Size, in its own units, of a layer drawn at a scale to fill the viewport.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::camera::{layer_size, WORLD_SIZE};
+/// assert_eq!(layer_size(1.0), WORLD_SIZE);
+/// assert_eq!(layer_size(0.5), WORLD_SIZE * 2.0);
+/// ```
+pub fn layer_size(scale: f32) -> Vec2 {
+    WORLD_SIZE / scale
+}
+
+/// Position on the playfield under a point in the window, such as the mouse.
+pub fn screen_to_world(point: Vec2) -> Vec2 {
+    let viewport = viewport(Vec2::new(screen_width(), screen_height()));
+
+    (point - viewport.point()) * WORLD_WIDTH / viewport.w
+}
+
+/// Shake
+///
+/// The events that shake the screen: a hard jolt when a ship is destroyed, and
+/// a lighter one when a large asteroid blows apart.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Shake {
+    ShipDestroyed,
+    LargeExplosion,
+}
+
+impl Shake {
+    /// Trauma the event adds, from 0.0 to 1.0.
+    pub fn trauma(&self) -> f32 {
+        match self {
+            Shake::ShipDestroyed => 0.8,
+            Shake::LargeExplosion => 0.35,
+        }
+    }
+}
+
+/// Screen shake
+///
+/// Shakes the view by offsetting the camera the game world is drawn through,
+/// so nothing drawn needs to know about it. Shakes add trauma, which decays
+/// over time; the view is displaced by the square of the trauma, so small
+/// knocks stay subtle while big ones fade out quickly. The intensity setting
+/// scales every shake, and turns shaking off altogether at 0.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::camera::{ScreenShake, Shake};
+/// let mut shake = ScreenShake::new(1.0);
+/// assert!(!shake.is_shaking());
+///
+/// shake.add(Shake::ShipDestroyed);
+/// shake.update(0.1);
+/// assert!(shake.is_shaking());
+/// assert!(shake.offset().length() <= ScreenShake::MAX_OFFSET);
+///
+/// shake.update(1.0);
+/// assert!(!shake.is_shaking());
+///
+/// // Shaking can be turned off
+/// let mut shake = ScreenShake::new(0.0);
+/// shake.add(Shake::ShipDestroyed);
+/// assert!(!shake.is_shaking());
+/// ```
+pub struct ScreenShake {
+    pub intensity: f32,
+    trauma: f32,
+    phase: f32,
+}
+
+impl ScreenShake {
+    /// Furthest the view is displaced, in world units, at full trauma and
+    /// intensity.
+    pub const MAX_OFFSET: f32 = 12.0;
+
+    /// Trauma lost per second.
+    const DECAY: f32 = 1.2;
+
+    /// Create screen shake at an intensity from 0.0 to 1.0.
+    pub fn new(intensity: f32) -> Self {
+        Self {
+            intensity,
+            trauma: 0.0,
+            phase: 0.0,
+        }
+    }
+
+    /// Shake the screen for an event.
+    pub fn add(&mut self, shake: Shake) {
+        self.trauma = (self.trauma + shake.trauma()).min(1.0);
+    }
+
+    /// Stop shaking straight away, for example when a game ends.
+    pub fn clear(&mut self) {
+        self.trauma = 0.0;
+    }
+
+    /// Decay trauma by the time the frame took. The shake only moves on while
+    /// updated, so it holds still while the game is paused.
+    pub fn update(&mut self, dt: f32) {
+        self.trauma = (self.trauma - Self::DECAY * dt).max(0.0);
+        self.phase += dt;
+    }
+
+    /// Check if the screen is displaced.
+    pub fn is_shaking(&self) -> bool {
+        self.trauma > 0.0 && self.intensity > 0.0
+    }
+
+    /// Current displacement of the view. Two waves of unrelated frequencies
+    /// on each axis give a jitter that does not visibly repeat.
+    pub fn offset(&self) -> Vec2 {
+        let amount = Self::MAX_OFFSET * self.intensity * self.trauma * self.trauma;
+        let t = self.phase;
+
+        let x = (t * 53.0).sin() * 0.7 + (t * 31.7).sin() * 0.3;
+        let y = (t * 47.0).cos() * 0.7 + (t * 27.3).cos() * 0.3;
+
+        Vec2::new(x, y) * amount
+    }
+
+    /// Camera drawing the playfield, displaced by the current offset.
+    pub fn camera(&self) -> Camera2D {
+        let offset = if self.is_shaking() { self.offset() } else { Vec2::ZERO };
+
+        world_camera(offset)
+    }
+}
@@ -0,0 +1,96 @@
+//! Between-wave carry-over.
+//!
+//! Most of the ship's state carries from one wave to the next untouched. A few
+//! consumables are partly restored between waves instead, so a rough wave does
+//! not doom the rest of the run: spent decoys are restocked, and strain built
+//! up on the hyperspace drive is shed. The fractions restored are tunable.
+//!
+//! The ship has no hull damage, ammunition or weapon heat; decoys are its only
+//! limited ammunition and hyperspace strain its only heat, so those are what
+//! the rules cover.
+
+use macroquad::prelude::*;
+
+use crate::ship::Ship;
+
+/// Carry-over rules
+///
+/// How much of the ship's consumables are restored between waves.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::carryover::CarryOver;
+/// let rules = CarryOver::default();
+///
+/// assert_eq!(rules.decoys_restocked(0), 2);
+/// assert_eq!(rules.decoys_restocked(2), 1);
+/// assert_eq!(rules.decoys_restocked(3), 0);
+/// ```
+#[derive(Clone, Copy)]
+pub struct CarryOver {
+    /// Decoys the ship is restocked up to.
+    pub decoy_capacity: u32,
+    /// Fraction of the missing decoys restocked, rounded up.
+    pub decoy_refill: f32,
+    /// Fraction of the hyperspace drive's strain shed.
+    pub strain_relief: f32,
+}
+
+impl Default for CarryOver {
+    fn default() -> Self {
+        Self {
+            decoy_capacity: 3,
+            decoy_refill: 0.5,
+            strain_relief: 1.0,
+        }
+    }
+}
+
+/// Carry-over report
+///
+/// What was restored between two waves, for the wave summary.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct CarryOverReport {
+    pub decoys_restocked: u32,
+    pub strain_shed: f32,
+}
+
+impl CarryOver {
+    /// Number of decoys restocked for a ship carrying a number of decoys.
+    pub fn decoys_restocked(&self, decoys: u32) -> u32 {
+        let missing = self.decoy_capacity.saturating_sub(decoys);
+        (missing as f32 * self.decoy_refill.clamp(0.0, 1.0)).ceil() as u32
+    }
+
+    /// Restore a ship's consumables between waves, at a time.
+    pub fn apply(&self, ship: &mut Ship, now: f64) -> CarryOverReport {
+        let decoys_restocked = self.decoys_restocked(ship.decoys);
+        ship.decoys += decoys_restocked;
+
+        let strain_shed = ship.cool_hyperspace(self.strain_relief.clamp(0.0, 1.0), now);
+
+        CarryOverReport {
+            decoys_restocked,
+            strain_shed,
+        }
+    }
+}
+
+impl CarryOverReport {
+    /// Summary shown when the next wave starts, or `None` if nothing was
+    /// restored.
+    pub fn summary(&self) -> Option<String> {
+        let mut parts = Vec::new();
+
+        if self.decoys_restocked > 0 {
+            parts.push(format!("Decoys +{}", self.decoys_restocked));
+        }
+
+        if self.strain_shed > 0.0 {
+            parts.push("Hyperspace drive cooled".to_string());
+        }
+
+        (!parts.is_empty()).then(|| parts.join("  "))
+    }
+}
@@ -0,0 +1,255 @@
+//! Challenge ladder.
+//!
+//! Challenges are hand-authored scenarios with objectives, played one field at
+//! a time rather than as endless waves. Clearing a challenge's field earns one
+//! star, and each of its two objectives met along the way earns another, for
+//! up to three stars. Challenges are played in order: each one unlocks once
+//! the previous one has been cleared, and later tiers also need a number of
+//! stars earned in total.
+//!
+//! The ladder itself is built into the game (see `challenges.toml`); the best
+//! number of stars earned on each challenge is saved between sessions.
+
+use serde::Deserialize;
+
+use crate::savedata::{self, SaveFormat};
+use crate::scenario::Scenario;
+use crate::score::format_score;
+
+/// Challenge objective
+///
+/// A goal to meet while clearing a challenge's field, earning a star.
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(tag = "goal", rename_all = "snake_case")]
+pub enum Objective {
+    Within { seconds: u32 },
+    NoDeaths,
+    MaxShots { shots: u32 },
+    NoHyperspace,
+    Score { points: u32 },
+}
+
+impl Objective {
+    /// Description shown on the ladder.
+    pub fn description(&self) -> String {
+        match self {
+            Objective::Within { seconds } => format!("Clear within {} seconds", seconds),
+            Objective::NoDeaths => "Lose no ships".to_string(),
+            Objective::MaxShots { shots } => format!("Fire at most {} shots", shots),
+            Objective::NoHyperspace => "Never use hyperspace".to_string(),
+            Objective::Score { points } => format!("Score {} points", format_score(*points)),
+        }
+    }
+
+    /// Check if the objective was met during a run.
+    pub fn is_met(&self, stats: &ChallengeStats) -> bool {
+        match *self {
+            Objective::Within { seconds } => stats.elapsed <= seconds as f64,
+            Objective::NoDeaths => stats.deaths == 0,
+            Objective::MaxShots { shots } => stats.shots <= shots,
+            Objective::NoHyperspace => stats.jumps == 0,
+            Objective::Score { points } => stats.score >= points,
+        }
+    }
+}
+
+/// Challenge statistics
+///
+/// What happened during a challenge run, as needed to check its objectives.
+#[derive(Clone, Copy, Default)]
+pub struct ChallengeStats {
+    pub elapsed: f64,
+    pub deaths: u32,
+    pub shots: u32,
+    pub jumps: u32,
+    pub score: u32,
+}
+
+/// Challenge
+///
+/// A scenario with a name, two objectives, and optionally a time limit after
+/// which the run is failed.
+#[derive(Clone, Deserialize)]
+pub struct Challenge {
+    pub name: String,
+    #[serde(default)]
+    pub unlock_stars: u32,
+    pub time_limit: Option<u32>,
+    pub objectives: [Objective; 2],
+    #[serde(flatten)]
+    pub scenario: Scenario,
+}
+
+impl Challenge {
+    /// Most stars a challenge can earn.
+    pub const MAX_STARS: u8 = 3;
+
+    /// Stars earned by a run: none if the field was not cleared, otherwise one
+    /// plus one for each objective met.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::challenge::{ChallengeLadder, ChallengeStats};
+    /// let ladder = ChallengeLadder::new();
+    /// let challenge = &ladder.challenges[0];
+    /// let flawless = ChallengeStats { elapsed: 10.0, ..Default::default() };
+    /// let clumsy = ChallengeStats { elapsed: 100.0, deaths: 2, ..Default::default() };
+    ///
+    /// assert_eq!(challenge.stars(true, &flawless), 3);
+    /// assert_eq!(challenge.stars(true, &clumsy), 1);
+    /// assert_eq!(challenge.stars(false, &flawless), 0);
+    /// ```
+    pub fn stars(&self, cleared: bool, stats: &ChallengeStats) -> u8 {
+        if !cleared {
+            return 0;
+        }
+
+        1 + self.objectives.iter().filter(|objective| objective.is_met(stats)).count() as u8
+    }
+
+    /// Check if a run has run out of time.
+    pub fn is_out_of_time(&self, elapsed: f64) -> bool {
+        self.time_limit.is_some_and(|limit| elapsed > limit as f64)
+    }
+}
+
+/// The ladder file, as parsed.
+#[derive(Deserialize)]
+struct LadderFile {
+    challenge: Vec<Challenge>,
+}
+
+/// Challenge ladder
+///
+/// The built-in challenges, in order, with the best number of stars earned on
+/// each. Progress is stored as plain text, one `STARS NAME` entry per line, so
+/// it survives challenges being reordered or added.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::challenge::ChallengeLadder;
+/// let mut ladder = ChallengeLadder::new();
+/// assert_eq!(ladder.challenges.len(), 30);
+/// assert!(ladder.is_unlocked(0) && !ladder.is_unlocked(1));
+///
+/// assert!(ladder.record(0, 2));
+/// assert!(!ladder.record(0, 1));
+/// assert!(ladder.is_unlocked(1));
+/// assert_eq!(ladder.total_stars(), 2);
+///
+/// let mut restored = ChallengeLadder::new();
+/// restored.parse_progress(&ladder.progress_text());
+/// assert_eq!(restored.stars(0), 2);
+/// ```
+pub struct ChallengeLadder {
+    pub challenges: Vec<Challenge>,
+    stars: Vec<u8>,
+}
+
+impl ChallengeLadder {
+    /// Storage key of the saved progress.
+    const STORAGE_KEY: &'static str = "challenges.txt";
+
+    /// Format progress is saved in.
+    const SAVE_FORMAT: SaveFormat = SaveFormat::INITIAL;
+
+    /// Create the ladder of built-in challenges, with no progress.
+    pub fn new() -> Self {
+        let file: LadderFile = toml::from_str(include_str!("challenges.toml"))
+            .expect("built-in challenges are valid");
+        let stars = vec![0; file.challenge.len()];
+
+        Self {
+            challenges: file.challenge,
+            stars,
+        }
+    }
+
+    /// Create the ladder, restoring saved progress.
+    pub fn load() -> Self {
+        let mut ladder = Self::new();
+
+        if let Some(contents) = savedata::load(Self::STORAGE_KEY, &Self::SAVE_FORMAT, |contents| Some(contents.to_string())) {
+            ladder.parse_progress(&contents);
+        }
+
+        ladder
+    }
+
+    /// Save progress.
+    pub fn save(&self) {
+        savedata::save(Self::STORAGE_KEY, &Self::SAVE_FORMAT, &self.progress_text());
+    }
+
+    /// Restore progress from text. Entries for unknown challenges are skipped.
+    pub fn parse_progress(&mut self, contents: &str) {
+        for line in contents.lines() {
+            if let Some((stars, name)) = line.trim().split_once(' ') {
+                let index = self.challenges.iter().position(|challenge| challenge.name == name);
+
+                if let (Some(index), Ok(stars)) = (index, stars.parse::<u8>()) {
+                    self.stars[index] = stars.min(Challenge::MAX_STARS);
+                }
+            }
+        }
+    }
+
+    /// Format progress as text, listing challenges with at least one star.
+    pub fn progress_text(&self) -> String {
+        self.challenges.iter().zip(&self.stars)
+            .filter(|(_, stars)| **stars > 0)
+            .map(|(challenge, stars)| format!("{} {}\n", stars, challenge.name))
+            .collect()
+    }
+
+    /// Best number of stars earned on a challenge.
+    pub fn stars(&self, index: usize) -> u8 {
+        self.stars[index]
+    }
+
+    /// Stars earned across all challenges.
+    pub fn total_stars(&self) -> u32 {
+        self.stars.iter().map(|stars| *stars as u32).sum()
+    }
+
+    /// Check if a challenge can be played: the previous challenge must have
+    /// been cleared and enough stars earned in total.
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        let previous_cleared = index == 0 || self.stars[index - 1] > 0;
+
+        previous_cleared && self.total_stars() >= self.challenges[index].unlock_stars
+    }
+
+    /// Record the stars earned by a run. Returns true if they beat the best
+    /// previously earned on the challenge.
+    pub fn record(&mut self, index: usize, stars: u8) -> bool {
+        let is_best = stars > self.stars[index];
+
+        if is_best {
+            self.stars[index] = stars;
+        }
+
+        is_best
+    }
+}
+
+impl Default for ChallengeLadder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Star rating as text, with stars earned shown as `*` and the rest as `-`.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::challenge::rating_text;
+/// assert_eq!(rating_text(2), "**-");
+/// assert_eq!(rating_text(0), "---");
+/// ```
+pub fn rating_text(stars: u8) -> String {
+    (0..Challenge::MAX_STARS).map(|i| if i < stars { '*' } else { '-' }).collect()
+}
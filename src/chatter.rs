@@ -0,0 +1,103 @@
+//! Radio chatter.
+//!
+//! Saucers give themselves away with short bursts of radio chatter, drawn as
+//! a few garbled symbols beside them, when they turn and when they charge a
+//! shot. The bursts are telegraphing with a bit of character, and show cues
+//! that would otherwise only be heard.
+
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::entity::EntityId;
+use crate::saucer::SaucerCue;
+use crate::textcache::TextMeasureCache;
+
+/// Radio chatter
+///
+/// A burst of symbols shown beside a saucer, following it until the burst
+/// fades out over its lifespan, in seconds.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::chatter::RadioChatter;
+/// # use asteroids::entity::EntityId;
+/// # use asteroids::saucer::SaucerCue;
+/// # use macroquad::rand::RandGenerator;
+/// let rng = RandGenerator::new();
+/// rng.srand(7);
+///
+/// let mut burst = RadioChatter::burst(&rng, EntityId::next(), SaucerCue::Charge);
+/// assert!(burst.text.starts_with('!'));
+/// assert!((3..=6).contains(&burst.text.chars().count()));
+///
+/// burst.update(RadioChatter::LIFESPAN);
+/// assert!(!burst.is_alive());
+/// ```
+pub struct RadioChatter {
+    pub saucer: EntityId,
+    pub text: String,
+    pub cue: SaucerCue,
+    pub age: f32,
+}
+
+impl RadioChatter {
+    /// Time in seconds a burst is shown.
+    pub const LIFESPAN: f32 = 0.6;
+
+    /// Symbols a burst is made of.
+    const GLYPHS: [char; 12] = ['#', '%', '&', '*', '+', '=', '<', '>', '/', '?', '@', '~'];
+
+    /// Font size bursts are drawn at.
+    const FONT_SIZE: u16 = 16;
+
+    /// Colour of a burst warning of a shot.
+    const CHARGE_COLOR: Color = Color::new(1.0, 0.5, 0.3, 1.0);
+
+    /// Make a burst of chatter for a saucer's cue. A burst warning of a shot
+    /// starts with an exclamation mark.
+    pub fn burst(rng: &RandGenerator, saucer: EntityId, cue: SaucerCue) -> Self {
+        let length = rng.gen_range(3, 7);
+        let glyphs = (0..length).map(|_| Self::GLYPHS[rng.gen_range(0, Self::GLYPHS.len())]);
+
+        let text = match cue {
+            SaucerCue::Charge => std::iter::once('!').chain(glyphs.skip(1)).collect(),
+            SaucerCue::Turn => glyphs.collect(),
+        };
+
+        Self { saucer, text, cue, age: 0.0 }
+    }
+
+    /// Check if the burst is still shown.
+    pub fn is_alive(&self) -> bool {
+        self.age < Self::LIFESPAN
+    }
+
+    /// Age the burst by the time the frame took.
+    pub fn update(&mut self, dt: f32) {
+        self.age += dt;
+    }
+
+    /// Draw the burst centred above a saucer of a diameter at a position,
+    /// flickering as it fades out.
+    pub fn draw(&self, font: &Font, text_widths: &TextMeasureCache, position: Vec2, diameter: f32) {
+        let width = text_widths.width(font, &self.text, Self::FONT_SIZE);
+        let fade = (1.0 - self.age / Self::LIFESPAN).clamp(0.0, 1.0);
+        let flicker = if ((self.age * 30.0) as u32).is_multiple_of(2) { 1.0 } else { 0.6 };
+
+        let color = match self.cue {
+            SaucerCue::Charge => Self::CHARGE_COLOR,
+            SaucerCue::Turn => WHITE,
+        };
+
+        draw_text_ex(
+            &self.text, position.x - width / 2.0, position.y - diameter,
+            TextParams {
+                font_size: Self::FONT_SIZE,
+                font: Some(font),
+                color: Color { a: fade * flicker, ..color },
+                ..Default::default()
+            },
+        );
+    }
+}
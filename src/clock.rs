@@ -0,0 +1,102 @@
+//! Game clock.
+//!
+//! Every timer in the game reads the world's `GameClock` rather than
+//! macroquad's, passed down as the time now to whatever needs it. The clock
+//! only moves when it is advanced: by the time each frame takes in the game
+//! loop, or by the time asked for when a world is stepped, so a headless world
+//! needs no window and a seeded game stepped the same way always plays out the
+//! same. It stands still while the game is held in a menu, and can be paused
+//! or run faster or slower than real time. Only the latency diagnostics,
+//! gamepad rumble and hot reloading, which deal in real time, read
+//! macroquad's clock.
+
+/// Length in seconds of the tick velocities are given per. Bullet and
+/// particle velocities are in units a tick, and scaled by the time a frame
+/// takes, so they cover the same distance a second at any frame rate.
+pub const TICK: f32 = 1.0 / 60.0;
+
+/// Game clock
+///
+/// Time in seconds the game has run for, moved on by hand.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::clock::GameClock;
+/// let mut clock = GameClock::new();
+/// clock.advance(0.5);
+/// assert_eq!(clock.now(), 0.5);
+///
+/// // Time stands still while paused
+/// clock.pause();
+/// clock.advance(0.5);
+/// assert_eq!((clock.now(), clock.frame_time()), (0.5, 0.0));
+///
+/// // And runs at half speed when scaled down
+/// clock.resume();
+/// clock.set_scale(0.5);
+/// clock.advance(0.5);
+/// assert_eq!((clock.now(), clock.frame_time()), (0.75, 0.25));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameClock {
+    time: f64,
+    frame_time: f32,
+    scale: f32,
+    is_paused: bool,
+}
+
+impl GameClock {
+    /// Clock at zero, running at real speed.
+    pub fn new() -> Self {
+        Self { time: 0.0, frame_time: 0.0, scale: 1.0, is_paused: false }
+    }
+
+    /// Time in seconds the clock has run for.
+    pub fn now(&self) -> f64 {
+        self.time
+    }
+
+    /// Time in seconds the clock moved on by when last advanced.
+    pub fn frame_time(&self) -> f32 {
+        self.frame_time
+    }
+
+    /// Move the clock on by a time in seconds, scaled, unless it is paused.
+    pub fn advance(&mut self, dt: f32) {
+        self.frame_time = if self.is_paused { 0.0 } else { dt * self.scale };
+        self.time += self.frame_time as f64;
+    }
+
+    /// Stop the clock until it is resumed.
+    pub fn pause(&mut self) {
+        self.is_paused = true;
+    }
+
+    /// Start a paused clock again.
+    pub fn resume(&mut self) {
+        self.is_paused = false;
+    }
+
+    /// Check if the clock is paused.
+    pub fn is_paused(&self) -> bool {
+        self.is_paused
+    }
+
+    /// Speed the clock runs at, as a multiple of the time it is advanced by.
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Run the clock faster or slower than the time it is advanced by.
+    /// Negative scales are taken as zero.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale.max(0.0);
+    }
+}
+
+impl Default for GameClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,474 @@
+use macroquad::prelude::*;
+
+use crate::camera::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::detmath;
+
+/// Collidable trait
+/// 
+/// This trait is used to determine if two objects are colliding. It is used by
+/// the collision detection system to determine if two objects are colliding with
+/// each other even when straddling the edge of the screen (due to wrapping).
+/// 
+/// Objects collide as circles unless they give an outline, in which case the
+/// circle around the outline only rules out objects too far apart to touch, and
+/// the outline itself decides whether they do. Fast objects give the distance
+/// they swept over the last frame, and collide with everything they passed
+/// through on the way rather than only where they ended up.
+/// 
+pub trait Collidable {
+    fn is_colliding(&self, other: &dyn Collidable) -> bool;
+    fn get_position(&self) -> Vec2;
+    fn get_radius(&self) -> f32;
+
+    /// Outline of the object, if it is drawn as a polygon.
+    fn get_outline(&self) -> Option<Outline<'_>> {
+        None
+    }
+
+    /// Distance the object moved over the last frame, ending at its position.
+    fn get_sweep(&self) -> Vec2 {
+        Vec2::ZERO
+    }
+}
+
+impl dyn Collidable {
+    pub fn circle_circle_intersection(circle1: &dyn Collidable, circle2: &dyn Collidable) -> bool 
+    {   
+        // Calculate the distance between the two circles, wrapping around the
+        // region if necessary.
+        let delta = wrapped_delta(circle2.get_position(), circle1.get_position());
+
+        // Calculate the squared distance between the two circles.
+        let distance_squared = delta.length_squared();
+
+        // If the squared distance is less than the sum of the radii squared, then the
+        // two circles are colliding.
+        let radii = circle1.get_radius() + circle2.get_radius();
+
+        distance_squared < radii * radii
+    }
+
+    /// Check if two objects overlap, by their outlines where they have them
+    /// and as circles otherwise, anywhere along their sweeps over the last
+    /// frame. Two outlines are only checked where they ended up.
+    pub fn shape_intersection(first: &dyn Collidable, second: &dyn Collidable) -> bool {
+        let first_outline = first.get_outline();
+        let second_outline = second.get_outline();
+
+        // Seen from the first object, the second moved by the difference in
+        // their sweeps, to end up the short way around the screen from it
+        let delta = wrapped_delta(first.get_position(), second.get_position());
+        let sweep = second.get_sweep() - first.get_sweep();
+
+        // Broad phase: the circles around both shapes must overlap somewhere
+        // along the sweep
+        let reach = |outline: &Option<Outline>, collidable: &dyn Collidable| {
+            outline.map_or(collidable.get_radius(), |outline| outline.bounding_radius())
+        };
+
+        let radii = reach(&first_outline, first) + reach(&second_outline, second);
+
+        if segment_distance_squared(Vec2::ZERO, delta - sweep, delta) >= radii * radii {
+            return false;
+        }
+
+        // Narrow phase: a circle sweeps out a capsule, from where it started
+        // to where it ended up
+        match (first_outline, second_outline) {
+            (None, None) => true,
+            (Some(outline), None) => polygon_capsule_intersection(outline.points(Vec2::ZERO), delta - sweep, delta, second.get_radius()),
+            (None, Some(outline)) => polygon_capsule_intersection(outline.points(delta), sweep, Vec2::ZERO, first.get_radius()),
+            (Some(first), Some(second)) => polygon_polygon_intersection(first.points(Vec2::ZERO), second.points(delta)),
+        }
+    }
+}
+
+/// Outline
+///
+/// The polygon an object is drawn as: its vertices in order around it,
+/// relative to its position, and the rotation they are turned by.
+#[derive(Clone, Copy)]
+pub struct Outline<'a> {
+    pub vertices: &'a [Vec2],
+    pub rotation: f32,
+}
+
+impl<'a> Outline<'a> {
+    /// Vertices turned by the rotation, and moved by an offset from the
+    /// object's position.
+    pub fn points(self, offset: Vec2) -> impl Iterator<Item = Vec2> + Clone + 'a {
+        let rotation = detmath::rotation(self.rotation);
+
+        self.vertices.iter().map(move |vertex| offset + rotation.mul_vec2(*vertex))
+    }
+
+    /// Radius of the smallest circle around the object's position enclosing
+    /// the outline.
+    pub fn bounding_radius(&self) -> f32 {
+        self.vertices.iter().map(|vertex| vertex.length()).fold(0.0, f32::max)
+    }
+}
+
+/// Edges of a polygon, as pairs of consecutive vertices.
+fn edges(polygon: impl Iterator<Item = Vec2> + Clone) -> impl Iterator<Item = (Vec2, Vec2)> {
+    polygon.clone().zip(polygon.cycle().skip(1))
+}
+
+/// Check if a point lies inside a polygon, given its vertices in order. The
+/// polygon need not be convex.
+pub fn polygon_contains(polygon: impl Iterator<Item = Vec2> + Clone, point: Vec2) -> bool {
+    // Count the edges crossed by a ray from the point
+    let mut inside = false;
+
+    for (a, b) in edges(polygon) {
+        if (a.y > point.y) != (b.y > point.y) && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x) {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+/// Squared distance from a point to the nearest point on a line segment.
+fn segment_distance_squared(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let t = ((point - start).dot(segment) / segment.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+
+    (start + segment * t).distance_squared(point)
+}
+
+/// Check if two line segments cross.
+fn segments_cross((a, b): (Vec2, Vec2), (c, d): (Vec2, Vec2)) -> bool {
+    let (ab, cd) = (b - a, d - c);
+
+    (ab.perp_dot(c - a) > 0.0) != (ab.perp_dot(d - a) > 0.0)
+        && (cd.perp_dot(a - c) > 0.0) != (cd.perp_dot(b - c) > 0.0)
+}
+
+/// Check if a circle overlaps a polygon, either crossing its edges or lying
+/// inside it.
+pub fn polygon_circle_intersection(polygon: impl Iterator<Item = Vec2> + Clone, centre: Vec2, radius: f32) -> bool {
+    polygon_capsule_intersection(polygon, centre, centre, radius)
+}
+
+/// Check if a circle swept along a line segment overlaps a polygon anywhere
+/// along the way: the segment crosses the polygon's edges or passes within
+/// the radius of them, or lies inside it.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::collision::polygon_capsule_intersection;
+/// # use macroquad::prelude::*;
+/// let square = [Vec2::new(0., 0.), Vec2::new(10., 0.), Vec2::new(10., 10.), Vec2::new(0., 10.)];
+///
+/// // A bullet fast enough to skip clean over the square still hits it
+/// assert!(polygon_capsule_intersection(square.iter().copied(), Vec2::new(-20., 5.), Vec2::new(30., 5.), 2.0));
+/// assert!(!polygon_capsule_intersection(square.iter().copied(), Vec2::new(-20., 15.), Vec2::new(30., 15.), 2.0));
+/// ```
+pub fn polygon_capsule_intersection(polygon: impl Iterator<Item = Vec2> + Clone, start: Vec2, end: Vec2, radius: f32) -> bool {
+    let touches_edge = |(a, b): (Vec2, Vec2)| {
+        segments_cross((a, b), (start, end))
+            || segment_distance_squared(start, a, b) < radius * radius
+            || segment_distance_squared(end, a, b) < radius * radius
+            || segment_distance_squared(a, start, end) < radius * radius
+            || segment_distance_squared(b, start, end) < radius * radius
+    };
+
+    polygon_contains(polygon.clone(), start) || edges(polygon).any(touches_edge)
+}
+
+/// Check if two polygons overlap, either crossing edges or one lying wholly
+/// inside the other. The polygons need not be convex.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::collision::{polygon_circle_intersection, polygon_polygon_intersection};
+/// # use macroquad::prelude::*;
+/// let square = [Vec2::new(0., 0.), Vec2::new(10., 0.), Vec2::new(10., 10.), Vec2::new(0., 10.)];
+/// let shifted = |offset: Vec2| square.iter().map(move |vertex| *vertex + offset);
+///
+/// assert!(polygon_polygon_intersection(shifted(Vec2::ZERO), shifted(Vec2::new(5., 5.))));
+/// assert!(!polygon_polygon_intersection(shifted(Vec2::ZERO), shifted(Vec2::new(11., 0.))));
+///
+/// // A small square wholly inside a large one crosses none of its edges
+/// let small = [Vec2::new(4., 4.), Vec2::new(6., 4.), Vec2::new(6., 6.), Vec2::new(4., 6.)];
+/// assert!(polygon_polygon_intersection(shifted(Vec2::ZERO), small.iter().copied()));
+///
+/// // Circles near a corner only touch if they reach past it
+/// assert!(!polygon_circle_intersection(shifted(Vec2::ZERO), Vec2::new(13., 13.), 4.0));
+/// assert!(polygon_circle_intersection(shifted(Vec2::ZERO), Vec2::new(13., 13.), 5.0));
+/// ```
+pub fn polygon_polygon_intersection(first: impl Iterator<Item = Vec2> + Clone, second: impl Iterator<Item = Vec2> + Clone) -> bool {
+    edges(first.clone()).any(|edge| edges(second.clone()).any(|other| segments_cross(edge, other)))
+        || second.clone().next().is_some_and(|vertex| polygon_contains(first.clone(), vertex))
+        || first.clone().next().is_some_and(|vertex| polygon_contains(second.clone(), vertex))
+}
+
+/// Shortest vector from one point to another on the wrapping screen.
+///
+/// Since the screen wraps around at the edges, the shortest path between two
+/// points may cross an edge. The returned vector may therefore point off screen
+/// when added to `from`. Either point may itself be beyond an edge, within its
+/// wrap margin.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::collision::wrapped_delta;
+/// # use macroquad::prelude::*;
+/// // Across the left edge
+/// assert_eq!(wrapped_delta(Vec2::new(10.0, 300.0), Vec2::new(990.0, 300.0)), Vec2::new(-20.0, 0.0));
+///
+/// // From beyond the right edge, yet to wrap, to just inside the left one
+/// assert_eq!(wrapped_delta(Vec2::new(1015.0, 300.0), Vec2::new(5.0, 300.0)), Vec2::new(-10.0, 0.0));
+/// ```
+pub fn wrapped_delta(from: Vec2, to: Vec2) -> Vec2 {
+    let mut dx = to.x - from.x;
+    let mut dy = to.y - from.y;
+
+    if dx > WORLD_WIDTH / 2.0 {
+        dx -= WORLD_WIDTH;
+    } else if dx < -WORLD_WIDTH / 2.0 {
+        dx += WORLD_WIDTH;
+    }
+
+    if dy > WORLD_HEIGHT / 2.0 {
+        dy -= WORLD_HEIGHT;
+    } else if dy < -WORLD_HEIGHT / 2.0 {
+        dy += WORLD_HEIGHT;
+    }
+
+    Vec2::new(dx, dy)
+}
+
+/// Contact
+///
+/// Where two overlapping circles touch: the unit normal pointing from the
+/// first circle towards the second, and how deep they overlap along it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Contact {
+    pub normal: Vec2,
+    pub depth: f32,
+}
+
+impl Contact {
+    /// Contact between two circles on the wrapping screen, if they overlap.
+    pub fn between(first: &dyn Collidable, first_radius: f32, second: &dyn Collidable) -> Option<Contact> {
+        let delta = wrapped_delta(first.get_position(), second.get_position());
+        let depth = first_radius + second.get_radius() - delta.length();
+
+        (depth > 0.0).then(|| Contact {
+            normal: delta.try_normalize().unwrap_or(Vec2::Y),
+            depth,
+        })
+    }
+
+    /// Impulse, along the normal, that bounces the second body off the first
+    /// given the second's velocity relative to the first. Restitution runs
+    /// from 0, where the bodies stop closing, to 1, a perfectly elastic bounce.
+    /// Bodies already separating need no impulse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::collision::Contact;
+    /// # use macroquad::prelude::*;
+    /// let contact = Contact { normal: Vec2::X, depth: 1.0 };
+    ///
+    /// // Closing at 2 units per frame, with half the speed given back
+    /// assert_eq!(contact.impulse(Vec2::new(-2.0, 5.0), 0.5), 3.0);
+    /// assert_eq!(contact.impulse(Vec2::new(2.0, 0.0), 0.5), 0.0);
+    /// ```
+    pub fn impulse(&self, relative_velocity: Vec2, restitution: f32) -> f32 {
+        let closing = relative_velocity.dot(self.normal);
+
+        if closing >= 0.0 {
+            0.0
+        } else {
+            -(1.0 + restitution) * closing
+        }
+    }
+}
+
+/// Body
+///
+/// A circle that moves and has mass, for resolving collisions between
+/// objects that bounce off each other rather than pass through.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Body {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub radius: f32,
+    pub mass: f32,
+}
+
+/// Bounce overlapping bodies off each other, pair by pair, on the wrapping
+/// screen. Each collision is perfectly elastic, conserving the pair's momentum
+/// and energy, with the lighter body taking more of the impulse. Overlapping
+/// bodies are also pushed apart, by no more than a maximum correction per
+/// call, so bodies spawned on top of each other ease apart over a few frames
+/// rather than jittering or flying off. Bodies already moving apart are only
+/// pushed. Returns the number of pairs that bounced.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::collision::{resolve_elastic, Body};
+/// # use macroquad::prelude::*;
+/// let mut bodies = [
+///     Body { position: Vec2::new(100.0, 100.0), velocity: Vec2::new(1.0, 0.0), radius: 10.0, mass: 1.0 },
+///     Body { position: Vec2::new(119.0, 100.0), velocity: Vec2::new(-1.0, 0.0), radius: 10.0, mass: 1.0 },
+/// ];
+///
+/// // Equal masses meeting head on swap velocities
+/// assert_eq!(resolve_elastic(&mut bodies, 5.0), 1);
+/// assert_eq!(bodies[0].velocity, Vec2::new(-1.0, 0.0));
+/// assert_eq!(bodies[1].velocity, Vec2::new(1.0, 0.0));
+/// assert!(bodies[1].position.x - bodies[0].position.x >= 20.0);
+///
+/// // A pair spawned on top of each other is only eased apart
+/// let mut bodies = [
+///     Body { position: Vec2::new(5.0, 50.0), velocity: Vec2::ZERO, radius: 10.0, mass: 1.0 },
+///     Body { position: Vec2::new(995.0, 50.0), velocity: Vec2::ZERO, radius: 10.0, mass: 1.0 },
+/// ];
+///
+/// assert_eq!(resolve_elastic(&mut bodies, 2.0), 0);
+/// assert_eq!(bodies[0].position, Vec2::new(6.0, 50.0));
+/// assert_eq!(bodies[1].position, Vec2::new(994.0, 50.0));
+/// ```
+pub fn resolve_elastic(bodies: &mut [Body], max_correction: f32) -> usize {
+    let mut bounces = 0;
+
+    for first in 0..bodies.len() {
+        for second in first + 1..bodies.len() {
+            let (a, b) = (bodies[first], bodies[second]);
+            let delta = wrapped_delta(a.position, b.position);
+            let depth = a.radius + b.radius - delta.length();
+
+            if depth <= 0.0 {
+                continue;
+            }
+
+            let contact = Contact { normal: delta.try_normalize().unwrap_or(Vec2::Y), depth };
+            let (inverse_a, inverse_b) = (1.0 / a.mass, 1.0 / b.mass);
+            let share = inverse_a + inverse_b;
+
+            // Push the pair apart, the lighter body moving further
+            let correction = contact.normal * contact.depth.min(max_correction) / share;
+            bodies[first].position -= correction * inverse_a;
+            bodies[second].position += correction * inverse_b;
+
+            let impulse = contact.impulse(b.velocity - a.velocity, 1.0) / share;
+
+            if impulse > 0.0 {
+                bodies[first].velocity -= contact.normal * impulse * inverse_a;
+                bodies[second].velocity += contact.normal * impulse * inverse_b;
+                bounces += 1;
+            }
+        }
+    }
+
+    bounces
+}
+
+/// Spatial hash
+///
+/// A uniform grid over the screen bucketing points by cell, used to answer
+/// proximity queries ("what is near this point?") without testing every point.
+/// Queries are wrap-aware, so points near opposite edges of the screen are
+/// considered close to each other. The hash stores indices into the slice of
+/// positions it was built from, so callers can map results back to objects.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use asteroids::collision::SpatialHash;
+/// # use macroquad::prelude::*;
+/// let positions = vec![Vec2::new(10., 10.), Vec2::new(400., 300.)];
+/// let hash = SpatialHash::build(64.0, &positions);
+/// let nearest = hash.nearest(Vec2::new(0., 0.), 100.0, |_| true);
+/// ```
+pub struct SpatialHash {
+    cell_size: f32,
+    columns: i32,
+    rows: i32,
+    cells: Vec<Vec<usize>>,
+    positions: Vec<Vec2>,
+}
+
+impl SpatialHash {
+    /// Build a spatial hash over the given positions.
+    pub fn build(cell_size: f32, positions: &[Vec2]) -> Self {
+        let columns = (WORLD_WIDTH / cell_size).ceil().max(1.0) as i32;
+        let rows = (WORLD_HEIGHT / cell_size).ceil().max(1.0) as i32;
+
+        let mut hash = Self {
+            cell_size,
+            columns,
+            rows,
+            cells: vec![Vec::new(); (columns * rows) as usize],
+            positions: positions.to_vec(),
+        };
+
+        for (index, position) in positions.iter().enumerate() {
+            let (column, row) = hash.cell_of(*position);
+            let cell = hash.cell_index(column, row);
+            hash.cells[cell].push(index);
+        }
+
+        hash
+    }
+
+    /// Cell coordinates containing a position, or the point on the screen it
+    /// stands for while beyond an edge, yet to wrap.
+    fn cell_of(&self, position: Vec2) -> (i32, i32) {
+        (
+            ((position.x.rem_euclid(WORLD_WIDTH) / self.cell_size) as i32).clamp(0, self.columns - 1),
+            ((position.y.rem_euclid(WORLD_HEIGHT) / self.cell_size) as i32).clamp(0, self.rows - 1),
+        )
+    }
+
+    /// Index of cell at the given coordinates, wrapping around the grid.
+    fn cell_index(&self, column: i32, row: i32) -> usize {
+        (row.rem_euclid(self.rows) * self.columns + column.rem_euclid(self.columns)) as usize
+    }
+
+    /// Indices of all points within radius of a position.
+    pub fn query_radius(&self, position: Vec2, radius: f32) -> Vec<usize> {
+        let (column, row) = self.cell_of(position);
+        let reach = (radius / self.cell_size).ceil() as i32;
+
+        // Visit each cell once, even if the query wraps onto itself
+        let columns = (-reach..=reach).map(|c| column + c).take(self.columns as usize);
+        let rows: Vec<i32> = (-reach..=reach).map(|r| row + r).take(self.rows as usize).collect();
+
+        let mut results = Vec::new();
+
+        for c in columns {
+            for r in &rows {
+                for index in &self.cells[self.cell_index(c, *r)] {
+                    if wrapped_delta(position, self.positions[*index]).length() <= radius {
+                        results.push(*index);
+                    }
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Index of the nearest point within radius of a position, considering only
+    /// points accepted by the filter.
+    pub fn nearest(&self, position: Vec2, radius: f32, filter: impl Fn(usize) -> bool) -> Option<usize> {
+        self.query_radius(position, radius)
+            .into_iter()
+            .filter(|index| filter(*index))
+            .min_by(|a, b| {
+                let distance_a = wrapped_delta(position, self.positions[*a]).length_squared();
+                let distance_b = wrapped_delta(position, self.positions[*b]).length_squared();
+                distance_a.total_cmp(&distance_b)
+            })
+    }
+}
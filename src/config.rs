@@ -0,0 +1,198 @@
+//! Game balance.
+//!
+//! The numbers the game is tuned by: how big and fast asteroids and saucers
+//! are and what they score, and how the ship handles. They are built into the
+//! game, and can be overridden without rebuilding by a `balance.ron` file in
+//! the asset directory, read at startup, and again whenever it changes in
+//! debug builds. Values missing from the file keep their defaults, for
+//! example:
+//!
+//! ```ron
+//! (
+//!     asteroids: (speed: 0.0015, points: (150, 75, 30)),
+//!     saucers: (small_points: 2000),
+//!     ship: (thrust: 0.0004),
+//! )
+//! ```
+//!
+//! Sizes and speeds are shares of the shorter side of the playfield, speeds
+//! per frame. A file that cannot be read leaves the defaults in place, and the
+//! reason is shown on the title screen. Replay codes, movies and challenge
+//! leaderboards assume the defaults, so a tuned balance is for trying things
+//! out rather than for competing.
+
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::assets;
+
+/// Asteroid balance
+///
+/// Sizes, speeds and spin are for a large asteroid; smaller ones are faster
+/// and spin faster, up to the limits. Points are for small, medium and large
+/// asteroids, in that order.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AsteroidBalance {
+    pub diameter: f32,
+    pub speed: f32,
+    pub max_speed: f32,
+    pub spin: f32,
+    pub max_spin: f32,
+    pub points: (u32, u32, u32),
+}
+
+impl AsteroidBalance {
+    const DEFAULT: Self = Self {
+        diameter: 0.2,
+        speed: 0.001,
+        max_speed: 0.006,
+        spin: 0.05,
+        max_spin: 0.3,
+        points: (100, 50, 20),
+    };
+}
+
+impl Default for AsteroidBalance {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Saucer balance
+///
+/// Sizes and speeds of small and large saucers, what they score, and the
+/// speed of their shots before the difficulty changes it.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaucerBalance {
+    pub small_diameter: f32,
+    pub large_diameter: f32,
+    pub small_speed: f32,
+    pub large_speed: f32,
+    pub small_points: u32,
+    pub large_points: u32,
+    pub shot_speed: f32,
+}
+
+impl SaucerBalance {
+    const DEFAULT: Self = Self {
+        small_diameter: 0.035,
+        large_diameter: 0.07,
+        small_speed: 0.0025,
+        large_speed: 0.00125,
+        small_points: 1000,
+        large_points: 200,
+        shot_speed: 2.0,
+    };
+}
+
+impl Default for SaucerBalance {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Ship balance
+///
+/// The thrust of the ship's main engine and its top speed, and the share of
+/// its velocity it keeps from one frame to the next.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShipBalance {
+    pub thrust: f32,
+    pub max_speed: f32,
+    pub drag: f32,
+}
+
+impl ShipBalance {
+    const DEFAULT: Self = Self {
+        thrust: 0.0003,
+        max_speed: 0.005,
+        drag: 0.99,
+    };
+}
+
+impl Default for ShipBalance {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Balance
+///
+/// The numbers the game is tuned by.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::config::Balance;
+/// let balance = Balance::parse("(saucers: (small_points: 2000))").unwrap();
+///
+/// assert_eq!(balance.saucers.small_points, 2000);
+/// assert_eq!(balance.saucers.large_points, Balance::default().saucers.large_points);
+/// assert_eq!(balance.ship, Balance::default().ship);
+///
+/// assert_eq!(Balance::parse(&balance.to_ron()).unwrap(), balance);
+/// assert!(Balance::parse("(ship: (thrust: \"fast\"))").is_err());
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Balance {
+    pub asteroids: AsteroidBalance,
+    pub saucers: SaucerBalance,
+    pub ship: ShipBalance,
+}
+
+/// Balance the game is played with.
+static BALANCE: RwLock<Balance> = RwLock::new(Balance::DEFAULT);
+
+impl Balance {
+    /// File name of the balance, in the asset directory.
+    pub const FILE: &'static str = "balance.ron";
+
+    /// Balance built into the game.
+    pub const DEFAULT: Self = Self {
+        asteroids: AsteroidBalance::DEFAULT,
+        saucers: SaucerBalance::DEFAULT,
+        ship: ShipBalance::DEFAULT,
+    };
+
+    /// Parse a balance from a RON document.
+    pub fn parse(text: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(text)
+    }
+
+    /// Format a balance as a RON document.
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).unwrap_or_default()
+    }
+
+    /// Load the balance from an asset directory, or the default one. Without
+    /// a file the built-in balance is used; with one that cannot be parsed,
+    /// the built-in balance is returned along with the reason.
+    pub async fn load(directory: Option<&str>) -> (Self, Option<String>) {
+        let path = assets::asset_path(directory.unwrap_or(assets::DEFAULT_DIRECTORY), Self::FILE);
+
+        let Ok(text) = macroquad::file::load_string(&path).await else {
+            return (Self::DEFAULT, None);
+        };
+
+        match Self::parse(&text) {
+            Ok(balance) => (balance, None),
+            Err(error) => (Self::DEFAULT, Some(format!("Could not read {}, using the built-in balance: {}", path, error))),
+        }
+    }
+}
+
+/// Balance the game is played with.
+pub fn balance() -> Balance {
+    *BALANCE.read().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Play the game with a balance from now on. Objects already in play keep
+/// the balance they were made with.
+pub fn set_balance(balance: Balance) {
+    *BALANCE.write().unwrap_or_else(|error| error.into_inner()) = balance;
+}
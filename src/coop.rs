@@ -0,0 +1,99 @@
+use macroquad::prelude::*;
+
+use crate::ship::Ship;
+
+/// Player number of the first player, as recorded on the bullets they fire.
+pub const PLAYER_ONE: usize = 0;
+
+/// Player number of the second player, the wingman.
+pub const PLAYER_TWO: usize = 1;
+
+/// Wingman
+///
+/// The second player in two-player co-op. The wingman flies their own ship
+/// alongside the first player, with their own lives and score, and shares the
+/// keyboard: A and D steer, W thrusts, S jumps to hyperspace and the left
+/// Control key fires, or plays on the second gamepad. The game goes on while
+/// either player has a ship left.
+///
+/// A wingman may also drop in to a classic or co-op game already under way,
+/// by pressing 2 or Start on the second gamepad, and drop out again the same
+/// way.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::coop::Wingman;
+/// let mut wingman = Wingman::new();
+/// wingman.score += 100;
+///
+/// // A wingman dropping in is shielded for a few seconds
+/// let wingman = Wingman::join(2, 10.0);
+/// assert!(wingman.ship.is_shield_active(11.0));
+/// assert!(!wingman.ship.is_shield_active(20.0));
+/// ```
+#[derive(Clone)]
+pub struct Wingman {
+    pub ship: Ship,
+    pub lives: u32,
+    pub score: u32,
+}
+
+impl Wingman {
+    /// Colour of the wingman's ship, to tell it apart from the first player's.
+    pub const COLOR: Color = Color::new(0.4, 0.8, 1.0, 1.0);
+
+    /// Key steering the wingman's ship left.
+    pub const STEER_LEFT: KeyCode = KeyCode::A;
+
+    /// Key steering the wingman's ship right.
+    pub const STEER_RIGHT: KeyCode = KeyCode::D;
+
+    /// Key firing the wingman's thrusters.
+    pub const THRUST: KeyCode = KeyCode::W;
+
+    /// Key sending the wingman's ship into hyperspace.
+    pub const HYPERSPACE: KeyCode = KeyCode::S;
+
+    /// Key firing the wingman's cannon.
+    pub const FIRE: KeyCode = KeyCode::LeftControl;
+
+    /// Key dropping the wingman in to a game under way, or out of it.
+    pub const JOIN: KeyCode = KeyCode::Key2;
+
+    /// Time in seconds a wingman dropping in is shielded for.
+    const JOIN_SHIELD: f64 = 3.0;
+
+    /// Create a wingman with a fresh ship and three lives.
+    pub fn new() -> Self {
+        let mut ship = Ship::spawn_new();
+        ship.color = Self::COLOR;
+
+        Self {
+            ship,
+            lives: 3,
+            score: 0,
+        }
+    }
+
+    /// Create a wingman dropping in to a game under way at a time, with a
+    /// number of lives. Their ship appears in the middle of the screen,
+    /// shielded while they find their bearings.
+    pub fn join(lives: u32, now: f64) -> Self {
+        let mut wingman = Self { lives, ..Self::new() };
+        wingman.ship.shield_lifespan = now + Self::JOIN_SHIELD;
+
+        wingman
+    }
+
+    /// Check if the wingman has lost their last ship.
+    pub fn is_out(&self) -> bool {
+        self.ship.is_retired()
+    }
+}
+
+impl Default for Wingman {
+    fn default() -> Self {
+        Self::new()
+    }
+}
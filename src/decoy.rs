@@ -0,0 +1,87 @@
+use macroquad::prelude::*;
+
+use crate::collision::Collidable;
+use crate::wrap::{self, wrap_draw};
+
+/// Decoy object
+/// 
+/// Decoys are deployed by the player and emit the ship's signature, drawing
+/// the attention of enemies that aim at the player for a few seconds. Decoys
+/// drift in the direction they were released, wrap around the screen like the
+/// ship, and are destroyed when hit by bullets or asteroids.
+#[derive(Clone)]
+pub struct Decoy {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub radius: f32,
+    pub expiry: f64,
+    pub is_alive: bool,
+}
+
+impl Decoy {
+    /// Time in seconds a decoy lasts before expiring.
+    pub const LIFESPAN: f64 = 5.0;
+
+    /// Spawn new decoy at a given position and time.
+    pub fn spawn_new(position: Vec2, velocity: Vec2, radius: f32, now: f64) -> Self {
+        Self {
+            position,
+            velocity,
+            radius,
+            expiry: now + Self::LIFESPAN,
+            is_alive: true,
+        }
+    }
+
+    /// Destroy decoy by marking it dead. Any calls to `is_alive` will return
+    /// false after this function is called.
+    pub fn destroy(&mut self) {
+        self.is_alive = false;
+    }
+
+    /// Check if decoy is still alive at a time.
+    pub fn is_alive(&self, now: f64) -> bool {
+        self.is_alive && now < self.expiry
+    }
+
+    /// Update decoy position.
+    pub fn update(&mut self) {
+        self.position += self.velocity;
+        self.velocity *= 0.98;
+
+        // Wrap around screen
+        self.position = wrap::wrap(self.position, self.radius);
+    }
+
+    /// Draw decoy as a diamond with a signature ring, pulsing with time.
+    pub fn draw(&self, now: f64) {
+        let r = self.radius;
+        let pulse = (now * 2.0).fract() as f32;
+
+        // Drawn out to the widest pulse
+        wrap_draw(self.position, r * 4.0, |position| {
+            let (x, y) = (position.x, position.y);
+
+            draw_line(x, y - r, x + r, y, 2., WHITE);
+            draw_line(x + r, y, x, y + r, 2., WHITE);
+            draw_line(x, y + r, x - r, y, 2., WHITE);
+            draw_line(x - r, y, x, y - r, 2., WHITE);
+
+            draw_circle_lines(x, y, r * (1.0 + pulse * 3.0), 1.0, Color::new(1.0, 1.0, 1.0, 1.0 - pulse));
+        });
+    }
+}
+
+impl Collidable for Decoy {
+    fn is_colliding(&self, other: &dyn Collidable) -> bool {
+        <dyn Collidable>::shape_intersection(self, other)
+    }
+
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn get_radius(&self) -> f32 {
+        self.radius
+    }
+}
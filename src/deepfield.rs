@@ -0,0 +1,139 @@
+//! Deep field.
+//!
+//! With the deep field turned on, a second belt of small, dim asteroids drifts
+//! behind the playfield, purely for atmosphere: nothing in it can be shot or
+//! collided with. The belt is drawn in a pass of its own, through a camera
+//! at a smaller scale than the playfield's, and shifts a little against the
+//! ship's movements and the screen's shakes, so it looks far away.
+//!
+//! The belt is laid out afresh for every run, from the game's seed, but from a
+//! generator of its own so it never disturbs the game itself.
+
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::asteroid::{Asteroid, AsteroidSize};
+use crate::camera::{layer_size, WORLD_SIZE, WORLD_WIDTH};
+use crate::detmath;
+use crate::linebatch::LineBatch;
+use crate::wrap::ghost_positions;
+
+/// Deep field
+///
+/// The background belt, and the point it is seen from: eased towards the
+/// ship, so the belt glides rather than jumps when the ship hyperspaces.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::deepfield::DeepField;
+/// # use asteroids::camera::{layer_size, WORLD_SIZE};
+/// # use macroquad::prelude::*;
+/// // The same seed lays out the same belt
+/// let mut field = DeepField::generate(42);
+/// let positions: Vec<Vec2> = field.rocks.iter().map(|rock| rock.position).collect();
+/// assert_eq!(positions, DeepField::generate(42).rocks.iter().map(|rock| rock.position).collect::<Vec<_>>());
+///
+/// // Seen from the middle of the playfield, the belt sits still
+/// assert_eq!(field.offset(Vec2::ZERO), Vec2::ZERO);
+///
+/// // With the ship off to the right, it shifts slightly left
+/// for _ in 0..120 {
+///     field.update(1.0 / 60.0, Vec2::new(900.0, WORLD_SIZE.y / 2.0));
+/// }
+///
+/// let offset = field.offset(Vec2::ZERO);
+/// assert!(offset.x < 0.0 && offset.x > -50.0);
+///
+/// // And it drifts without leaving its own field
+/// let size = layer_size(DeepField::SCALE);
+/// assert!(field.rocks.iter().all(|rock| (0.0..size.x).contains(&rock.position.x) && (0.0..size.y).contains(&rock.position.y)));
+/// ```
+pub struct DeepField {
+    pub rocks: Vec<Asteroid>,
+    pub focus: Vec2,
+}
+
+impl DeepField {
+    /// Scale the belt is drawn at, against the playfield's.
+    pub const SCALE: f32 = 0.6;
+
+    /// How far the belt shifts for each unit the ship moves or the screen
+    /// shakes; the smaller, the farther away it looks.
+    const DEPTH: f32 = 0.08;
+
+    /// Rocks in the belt.
+    const ROCKS: usize = 36;
+
+    /// Part of their playfield speed the rocks drift and spin at.
+    const DRIFT: f32 = 0.4;
+
+    /// Brightness of the rocks' outlines.
+    const BRIGHTNESS: f32 = 0.22;
+
+    /// Rate at which the point the belt is seen from catches up with the
+    /// ship, per second.
+    const EASE: f32 = 1.5;
+
+    /// Value mixed into the seed, so the belt's stream differs from the
+    /// game's.
+    const STREAM: u64 = 0xc2b2_ae3d_27d4_eb4f;
+
+    /// Lay out a belt from a seed.
+    pub fn generate(seed: u64) -> Self {
+        let rng = RandGenerator::new();
+        rng.srand(seed ^ Self::STREAM);
+
+        let size = layer_size(Self::SCALE);
+        let rocks = (0..Self::ROCKS).map(|_| {
+            let position = Vec2::new(rng.gen_range(0.0, size.x), rng.gen_range(0.0, size.y));
+            let scale = AsteroidSize::Small.scale() * rng.gen_range(0.5, 1.5);
+
+            let mut rock = Asteroid::spawn_new_at(&rng, scale, position);
+            rock.velocity *= Self::DRIFT;
+            rock.rotation_speed *= Self::DRIFT;
+            rock
+        }).collect();
+
+        Self { rocks, focus: WORLD_SIZE / 2.0 }
+    }
+
+    /// Offset, in the belt's units, to draw it at for the screen shaken by an
+    /// offset.
+    pub fn offset(&self, shake: Vec2) -> Vec2 {
+        (shake + WORLD_SIZE / 2.0 - self.focus) * Self::DEPTH
+    }
+
+    /// Drift the rocks, wrapping them around the belt's edges, and ease the
+    /// point the belt is seen from towards a target.
+    pub fn update(&mut self, dt: f32, target: Vec2) {
+        let size = layer_size(Self::SCALE);
+
+        for rock in &mut self.rocks {
+            rock.position = (rock.position + rock.velocity).rem_euclid(size);
+            rock.rotation += rock.rotation_speed;
+        }
+
+        self.focus += (target - self.focus) * (dt * Self::EASE).min(1.0);
+    }
+
+    /// Draw the rocks into a batch of lines, through the belt's camera. Rocks
+    /// near an edge are also drawn across it, far enough out to cover the
+    /// most the belt is ever shifted.
+    pub fn draw(&self, lines: &mut LineBatch) {
+        let size = layer_size(Self::SCALE);
+        let margin = WORLD_WIDTH * Self::DEPTH;
+        let color = Color::new(Self::BRIGHTNESS, Self::BRIGHTNESS, Self::BRIGHTNESS, 1.0);
+
+        for rock in &self.rocks {
+            let rotation = detmath::rotation(rock.rotation);
+            let radius = rock.diameter / 2.0 + margin;
+
+            for position in std::iter::once(rock.position).chain(ghost_positions(rock.position, radius, size)) {
+                lines.push_polygon(position, &rock.vertices, rotation, 1.5, color);
+            }
+        }
+
+        lines.draw();
+    }
+}
@@ -0,0 +1,191 @@
+//! Deterministic math.
+//!
+//! Replays and shared seeds only work if the simulation produces bit-identical
+//! results on every platform. Rust never fuses floating-point operations on its
+//! own, and addition, subtraction, multiplication, division and square roots
+//! are correctly rounded by IEEE 754, so they give the same results everywhere.
+//! The transcendental functions (`sin`, `cos`, `atan2` and friends) are not:
+//! they are provided by the platform's maths library, and native and web builds
+//! disagree in the last bits.
+//!
+//! This module provides the transcendental functions the simulation needs,
+//! built from correctly rounded operations only. They are used for anything
+//! that affects gameplay (movement, aiming, collision shapes); purely cosmetic
+//! effects such as particles and rendering keep using the platform functions.
+//!
+//! Normalising a zero vector is the other common source of divergence, since
+//! it produces NaNs that then spread through the simulation. Gameplay code uses
+//! `normalize_or_zero` or `try_normalize` instead.
+//!
+//! `world_hash` fingerprints the gameplay state of a world. A seeded headless
+//! run through a fixed set of controls, in `tests/replay_hash.rs`, is checked
+//! against `SIMULATION_HASH`, so anything that makes the simulation as a whole platform-dependent, or
+//! changes it unawares, is caught.
+
+use macroquad::prelude::*;
+
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+/// Sine of an angle in radians.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::detmath;
+/// for i in -1000..1000 {
+///     let angle = i as f32 * 0.01;
+///     assert!((detmath::sin(angle) - angle.sin()).abs() < 1e-5);
+///     assert!((detmath::cos(angle) - angle.cos()).abs() < 1e-5);
+/// }
+/// ```
+pub fn sin(angle: f32) -> f32 {
+    // Reduce to [-PI, PI], then fold onto [-PI/2, PI/2] using sin(x) = sin(PI - x)
+    let mut x = angle - (angle / TAU).round() * TAU;
+
+    if x > FRAC_PI_2 {
+        x = PI - x;
+    } else if x < -FRAC_PI_2 {
+        x = -PI - x;
+    }
+
+    // Taylor series to the 11th power, accurate to within 1e-7 on [-PI/2, PI/2]
+    let x2 = x * x;
+    x * (1.0 + x2 * (-1.0 / 6.0 + x2 * (1.0 / 120.0 + x2 * (-1.0 / 5040.0 + x2 * (1.0 / 362880.0 + x2 * (-1.0 / 39916800.0))))))
+}
+
+/// Cosine of an angle in radians.
+pub fn cos(angle: f32) -> f32 {
+    sin(angle + FRAC_PI_2)
+}
+
+/// Sine and cosine of an angle in radians.
+pub fn sin_cos(angle: f32) -> (f32, f32) {
+    (sin(angle), cos(angle))
+}
+
+/// Arc tangent of a value, in radians.
+pub fn atan(value: f32) -> f32 {
+    if value.abs() > 1.0 {
+        return value.signum() * FRAC_PI_2 - atan(1.0 / value);
+    }
+
+    // Halve the angle so the series converges quickly: atan(z) = 2 atan(z / (1 + sqrt(1 + z^2)))
+    let z = value / (1.0 + (1.0 + value * value).sqrt());
+    let z2 = z * z;
+
+    let series = z * (1.0 + z2 * (-1.0 / 3.0 + z2 * (1.0 / 5.0 + z2 * (-1.0 / 7.0 + z2 * (1.0 / 9.0
+        + z2 * (-1.0 / 11.0 + z2 * (1.0 / 13.0 + z2 * (-1.0 / 15.0))))))));
+
+    2.0 * series
+}
+
+/// Four-quadrant arc tangent of `y / x`, in radians in the range [-PI, PI].
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::detmath;
+/// assert!((detmath::atan2(1.0, -1.0) - 1.0f32.atan2(-1.0)).abs() < 1e-6);
+/// assert_eq!(detmath::atan2(0.0, 0.0), 0.0);
+/// ```
+pub fn atan2(y: f32, x: f32) -> f32 {
+    if x > 0.0 {
+        atan(y / x)
+    } else if x < 0.0 {
+        if y >= 0.0 { atan(y / x) + PI } else { atan(y / x) - PI }
+    } else if y > 0.0 {
+        FRAC_PI_2
+    } else if y < 0.0 {
+        -FRAC_PI_2
+    } else {
+        0.0
+    }
+}
+
+/// Rotation matrix for an angle in radians; a deterministic `Mat2::from_angle`.
+///
+/// # Examples
+///
+/// The results are bit-identical on every platform, so a hash over them is a
+/// fixed value:
+///
+/// ```
+/// # use asteroids::detmath;
+/// let hash = (0..1000).fold(0u32, |hash, i| {
+///     let rotation = detmath::rotation(i as f32 * 0.37 - 100.0);
+///     let bits = rotation.x_axis.x.to_bits() ^ rotation.x_axis.y.to_bits().rotate_left(16);
+///     hash.wrapping_mul(31).wrapping_add(bits)
+/// });
+///
+/// assert_eq!(hash, detmath::ROTATION_HASH);
+/// ```
+pub fn rotation(angle: f32) -> Mat2 {
+    let (sin, cos) = sin_cos(angle);
+    Mat2::from_cols(Vec2::new(cos, sin), Vec2::new(-sin, cos))
+}
+
+/// Unit vector pointing along an angle in radians.
+pub fn direction(angle: f32) -> Vec2 {
+    let (sin, cos) = sin_cos(angle);
+    Vec2::new(cos, sin)
+}
+
+/// Angle in radians of a vector, in the range [-PI, PI].
+pub fn angle_of(vector: Vec2) -> f32 {
+    atan2(vector.y, vector.x)
+}
+
+/// Reference hash of `rotation` over a fixed set of angles. A build whose
+/// rotations hash differently is not replay compatible.
+pub const ROTATION_HASH: u32 = 2117167772;
+
+/// Hash of the gameplay state of a world: the ship, asteroids, saucers and
+/// bullets, the score, wave and lives, and the time on its clock. Particles
+/// and other effects are left out, since they use the platform functions.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::detmath;
+/// # use asteroids::options::LaunchOptions;
+/// # use asteroids::world::GameWorld;
+/// let options = LaunchOptions { seed: Some(1979), ..Default::default() };
+/// let (mut first, mut second) = (GameWorld::headless(&options), GameWorld::headless(&options));
+///
+/// first.start(false);
+/// second.start(false);
+///
+/// assert_eq!(detmath::world_hash(&first), detmath::world_hash(&second));
+/// ```
+pub fn world_hash(world: &crate::world::GameWorld) -> u32 {
+    let mut bits = vec![world.player_score, world.wave_number, world.player_lives];
+    let mut push = |vector: Vec2| bits.extend([vector.x.to_bits(), vector.y.to_bits()]);
+
+    push(world.ship.position);
+    push(world.ship.velocity);
+
+    for asteroid in &world.asteroids {
+        push(asteroid.position);
+        push(asteroid.velocity);
+    }
+
+    for saucer in &world.saucers {
+        push(saucer.position);
+        push(saucer.velocity);
+    }
+
+    for bullet in world.player_bullets.iter().chain(&world.enemy_bullets) {
+        push(bullet.position);
+        push(bullet.velocity);
+    }
+
+    let time = world.clock.now().to_bits();
+    bits.extend([world.ship.rotation.to_bits(), time as u32, (time >> 32) as u32]);
+
+    bits.into_iter().fold(0u32, |hash, bits| hash.wrapping_mul(31).wrapping_add(bits))
+}
+
+/// Reference hash of `world_hash` after the seeded run in
+/// `tests/replay_hash.rs`. A build that hashes it differently is not replay
+/// compatible.
+pub const SIMULATION_HASH: u32 = 587403267;
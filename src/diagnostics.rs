@@ -0,0 +1,214 @@
+//! Latency diagnostics.
+//!
+//! A hidden screen, opened with F3 from the title screen, for tuning vsync and
+//! fullscreen settings and for checking that changes to the game loop have not
+//! added lag. Pressing a key flashes the screen white for one frame, and the
+//! screen reports frame pacing statistics along with an estimate of the input
+//! to photon latency.
+//!
+//! The latency cannot be measured directly without a camera or light sensor,
+//! so it is estimated from frame timing: input is polled at the start of a
+//! frame, so a key press waits on average half a frame before it is seen; the
+//! flash is then drawn and presented, and the next frame starts once the
+//! presented frame has been handed to the display. The estimate leaves out the
+//! display's own processing and scan-out time, so it is a lower bound.
+
+/// Samples
+///
+/// A window of the most recent samples of a measurement, with summary
+/// statistics. The window is a fixed-size ring, so recording samples never
+/// allocates.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::diagnostics::Samples;
+/// let mut samples = Samples::<4>::new();
+/// assert_eq!(samples.mean(), None);
+///
+/// for sample in [1.0, 2.0, 3.0, 4.0, 5.0] {
+///     samples.push(sample);
+/// }
+///
+/// assert_eq!(samples.len(), 4);
+/// assert_eq!(samples.mean(), Some(3.5));
+/// assert_eq!(samples.min(), Some(2.0));
+/// assert_eq!(samples.max(), Some(5.0));
+/// ```
+pub struct Samples<const N: usize> {
+    values: [f64; N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> Samples<N> {
+    /// Create an empty window.
+    pub fn new() -> Self {
+        Self {
+            values: [0.0; N],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Record a sample, replacing the oldest one once the window is full.
+    pub fn push(&mut self, value: f64) {
+        self.values[self.next] = value;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Forget all samples.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+
+    /// Number of samples in the window.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the window has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn values(&self) -> &[f64] {
+        &self.values[..self.len]
+    }
+
+    /// Mean of the samples, if there are any.
+    pub fn mean(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.values().iter().sum::<f64>() / self.len as f64)
+    }
+
+    /// Smallest sample, if there are any.
+    pub fn min(&self) -> Option<f64> {
+        self.values().iter().copied().reduce(f64::min)
+    }
+
+    /// Largest sample, if there are any.
+    pub fn max(&self) -> Option<f64> {
+        self.values().iter().copied().reduce(f64::max)
+    }
+
+    /// Standard deviation of the samples, if there are any.
+    pub fn std_dev(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        let variance = self.values().iter().map(|value| (value - mean).powi(2)).sum::<f64>() / self.len as f64;
+
+        Some(variance.sqrt())
+    }
+
+    /// Number of samples above a threshold.
+    pub fn count_above(&self, threshold: f64) -> usize {
+        self.values().iter().filter(|value| **value > threshold).count()
+    }
+}
+
+impl<const N: usize> Default for Samples<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Latency probe
+///
+/// Frame timing and latency estimates gathered by the diagnostics screen. The
+/// probe is told when each frame starts and when a key press is seen; all
+/// times are in seconds.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::diagnostics::LatencyProbe;
+/// let mut probe = LatencyProbe::new();
+///
+/// probe.begin_frame(0.0);
+/// probe.begin_frame(0.016);
+/// probe.press(0.016);
+/// assert!(probe.is_flashing());
+///
+/// probe.begin_frame(0.032);
+/// assert!(!probe.is_flashing());
+///
+/// // Half a frame waiting to be polled, then a frame to be presented
+/// let latency = probe.latency.mean().unwrap();
+/// assert!((latency - 0.024).abs() < 1e-9);
+/// ```
+pub struct LatencyProbe {
+    /// Time between the starts of consecutive frames.
+    pub frame_times: Samples<{ LatencyProbe::FRAME_WINDOW }>,
+    /// Estimated input to photon latency of each key press.
+    pub latency: Samples<{ LatencyProbe::LATENCY_WINDOW }>,
+    last_frame: Option<f64>,
+    pending_press: Option<f64>,
+}
+
+impl LatencyProbe {
+    /// Number of frames the frame pacing statistics cover.
+    pub const FRAME_WINDOW: usize = 240;
+
+    /// Number of key presses the latency estimate covers.
+    pub const LATENCY_WINDOW: usize = 20;
+
+    /// A frame taking this much longer than average counts as a hitch.
+    pub const HITCH_FACTOR: f64 = 1.5;
+
+    /// Create a probe with no measurements.
+    pub fn new() -> Self {
+        Self {
+            frame_times: Samples::new(),
+            latency: Samples::new(),
+            last_frame: None,
+            pending_press: None,
+        }
+    }
+
+    /// Forget all measurements, for example when the screen is reopened.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Record the start of a frame. A flash drawn in the previous frame has
+    /// been presented by now, which completes its latency estimate.
+    pub fn begin_frame(&mut self, now: f64) {
+        let interval = self.last_frame.map(|last| now - last);
+
+        if let Some(interval) = interval {
+            self.frame_times.push(interval);
+        }
+
+        if let Some(pressed) = self.pending_press.take() {
+            let polling_delay = self.frame_times.mean().unwrap_or(interval.unwrap_or(0.0)) / 2.0;
+            self.latency.push(now - pressed + polling_delay);
+        }
+
+        self.last_frame = Some(now);
+    }
+
+    /// Record a key press seen at the start of the current frame. The screen
+    /// flashes for the rest of the frame.
+    pub fn press(&mut self, now: f64) {
+        self.pending_press.get_or_insert(now);
+    }
+
+    /// Check if the screen should flash this frame.
+    pub fn is_flashing(&self) -> bool {
+        self.pending_press.is_some()
+    }
+
+    /// Number of frames in the window that took noticeably longer than the
+    /// average, such as missed vsync intervals.
+    pub fn hitches(&self) -> usize {
+        self.frame_times.mean()
+            .map_or(0, |mean| self.frame_times.count_above(mean * Self::HITCH_FACTOR))
+    }
+}
+
+impl Default for LatencyProbe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
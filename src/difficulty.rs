@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use crate::saucer::Saucer;
+
+/// Difficulty
+///
+/// How hard the classic game plays. Normal is the game as it has always been;
+/// Easy eases off, while Arcade and Insane crowd the waves with more
+/// asteroids and send saucers more often, aiming better and firing faster.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::difficulty::Difficulty;
+/// assert_eq!(Difficulty::Normal.asteroid_count(1), 5);
+/// assert!(Difficulty::Easy.asteroid_count(1) < Difficulty::Insane.asteroid_count(1));
+/// assert!(Difficulty::Insane.saucer_accuracy(1) > Difficulty::Normal.saucer_accuracy(1));
+/// assert_eq!(Difficulty::Insane.saucer_accuracy(50), 1.0);
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Arcade,
+    Insane,
+}
+
+impl Difficulty {
+    /// All difficulties, from easiest to hardest.
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Arcade,
+        Difficulty::Insane,
+    ];
+
+    /// Most large asteroids a wave starts with.
+    pub const MAX_WAVE_ASTEROIDS: u32 = 16;
+
+    /// Label shown on the options screen.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Arcade => "Arcade",
+            Difficulty::Insane => "Insane",
+        }
+    }
+
+    /// Next harder difficulty, wrapping around to the easiest.
+    pub fn next(&self) -> Difficulty {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    /// Next easier difficulty, wrapping around to the hardest.
+    pub fn previous(&self) -> Difficulty {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|difficulty| difficulty == self).unwrap_or(1)
+    }
+
+    /// Number of large asteroids a wave starts with: one more each wave, from
+    /// five on Normal, up to a limit.
+    pub fn asteroid_count(&self, wave: u32) -> u32 {
+        let extra = match self {
+            Difficulty::Easy => 3,
+            Difficulty::Normal => 4,
+            Difficulty::Arcade => 5,
+            Difficulty::Insane => 6,
+        };
+
+        wave.saturating_add(extra).min(Self::MAX_WAVE_ASTEROIDS)
+    }
+
+    /// Time in seconds between chances of a saucer appearing.
+    pub fn saucer_interval(&self) -> f64 {
+        match self {
+            Difficulty::Easy => 15.0,
+            Difficulty::Normal => 10.0,
+            Difficulty::Arcade => 8.0,
+            Difficulty::Insane => 6.0,
+        }
+    }
+
+    /// Chance, from 0 to 1, of a saucer appearing each interval.
+    pub fn saucer_chance(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.2,
+            Difficulty::Normal => 0.25,
+            Difficulty::Arcade => 0.35,
+            Difficulty::Insane => 0.5,
+        }
+    }
+
+    /// Accuracy of small saucers in a wave, from 0 to 1.
+    pub fn saucer_accuracy(&self, wave: u32) -> f32 {
+        let scale = match self {
+            Difficulty::Easy => 0.6,
+            Difficulty::Normal => 1.0,
+            Difficulty::Arcade => 1.2,
+            Difficulty::Insane => 1.5,
+        };
+
+        (Saucer::accuracy_for_wave(wave) * scale).min(1.0)
+    }
+
+    /// Speed of enemy bullets, relative to Normal.
+    pub fn bullet_speed(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Arcade => 1.15,
+            Difficulty::Insane => 1.35,
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Saucer director.
+//!
+//! Decides when saucers join a wave and what size they are: every interval
+//! there is a chance of a saucer, both set by the difficulty. Pressure builds
+//! with the wave and the score, shortening the interval, raising the chance,
+//! letting more saucers into the field at once and sending more of them
+//! small. The director keeps a history of
+//! what it worked with for the spawn telemetry overlay, toggled with F5, so
+//! the graphs shown while tuning the balance are the director's own values
+//! rather than a second guess at them.
+
+use std::collections::VecDeque;
+
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::difficulty::Difficulty;
+use crate::saucer::SaucerSize;
+
+/// Trigger
+///
+/// The rules the director acts on, marked on the telemetry graph as they
+/// fire: a chance of a saucer that came to nothing, a saucer sent in, and the
+/// pressure rising far enough to let another saucer into the field at once.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Trigger {
+    Missed,
+    Spawned(SaucerSize),
+    MoreSaucers(usize),
+}
+
+impl Trigger {
+    /// Colour the trigger is marked in.
+    fn color(&self) -> Color {
+        match self {
+            Trigger::Missed => GRAY,
+            Trigger::Spawned(SaucerSize::Large) => ORANGE,
+            Trigger::Spawned(SaucerSize::Small) => RED,
+            Trigger::MoreSaucers(_) => MAGENTA,
+        }
+    }
+}
+
+/// Telemetry sample
+///
+/// The director's state on one frame: the time left until the next chance of
+/// a saucer as a fraction of the interval, the chance of a saucer at it, the
+/// saucer pressure, and the tension of the wave, from 0 at its start rising
+/// towards 1 as it is cleared.
+#[derive(Clone, Copy)]
+pub struct TelemetrySample {
+    pub time: f64,
+    pub cooldown: f32,
+    pub chance: f32,
+    pub pressure: f32,
+    pub tension: f32,
+}
+
+impl TelemetrySample {
+    /// Values graphed, each from 0 to 1.
+    pub fn values(&self) -> [f32; 4] {
+        [self.cooldown, self.chance, self.pressure, self.tension]
+    }
+}
+
+/// Spawn telemetry
+///
+/// The director's recent samples and triggers, covering a window of time.
+/// Storage is reused once the window is full, so recording does not allocate.
+#[derive(Default)]
+pub struct SpawnTelemetry {
+    samples: VecDeque<TelemetrySample>,
+    triggers: VecDeque<(f64, Trigger)>,
+}
+
+impl SpawnTelemetry {
+    /// Time in seconds covered by the telemetry.
+    pub const WINDOW: f64 = 30.0;
+
+    /// Label and colour of each value's trace, in the order of
+    /// `TelemetrySample::values`.
+    const TRACES: [(&'static str, Color); 4] = [("Cooldown", SKYBLUE), ("Chance", GREEN), ("Pressure", MAGENTA), ("Tension", YELLOW)];
+
+    /// Size of the telemetry graph, in pixels.
+    const GRAPH_SIZE: Vec2 = Vec2::new(360.0, 120.0);
+
+    /// Record a sample, forgetting whatever has fallen out of the window.
+    fn record(&mut self, sample: TelemetrySample) {
+        let start = sample.time - Self::WINDOW;
+
+        while self.samples.front().is_some_and(|oldest| oldest.time < start) {
+            self.samples.pop_front();
+        }
+
+        while self.triggers.front().is_some_and(|(time, _)| *time < start) {
+            self.triggers.pop_front();
+        }
+
+        self.samples.push_back(sample);
+    }
+
+    /// Mark a trigger firing.
+    fn mark(&mut self, time: f64, trigger: Trigger) {
+        self.triggers.push_back((time, trigger));
+    }
+
+    /// Forget everything recorded, for example when a new game starts.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.triggers.clear();
+    }
+
+    /// Samples in the window, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = &TelemetrySample> {
+        self.samples.iter()
+    }
+
+    /// Triggers in the window and when they fired, oldest first.
+    pub fn triggers(&self) -> impl Iterator<Item = &(f64, Trigger)> {
+        self.triggers.iter()
+    }
+
+    /// Draw the telemetry as a graph in the bottom right corner of the
+    /// screen: a trace for each value over the window, triggers as ticks
+    /// beneath, and the latest values alongside.
+    pub fn draw(&self, font: &Font) {
+        let Some(latest) = self.samples.back() else {
+            return;
+        };
+
+        let size = Self::GRAPH_SIZE;
+        let origin = Vec2::new(screen_width() - size.x - 20.0, screen_height() - size.y - 40.0);
+        let start = latest.time - Self::WINDOW;
+
+        let x_of = |time: f64| origin.x + ((time - start) / Self::WINDOW) as f32 * size.x;
+        let y_of = |value: f32| origin.y + (1.0 - value.clamp(0.0, 1.0)) * size.y;
+
+        draw_rectangle(origin.x - 8.0, origin.y - 76.0, size.x + 16.0, size.y + 100.0, Color::new(0.0, 0.0, 0.0, 0.7));
+        draw_rectangle_lines(origin.x, origin.y, size.x, size.y, 1.0, DARKGRAY);
+
+        for (i, (label, color)) in Self::TRACES.iter().enumerate() {
+            for (from, to) in self.samples.iter().zip(self.samples.iter().skip(1)) {
+                draw_line(x_of(from.time), y_of(from.values()[i]), x_of(to.time), y_of(to.values()[i]), 1.5, *color);
+            }
+
+            let text = format!("{} {:.2}", label, latest.values()[i]);
+            draw_text_ex(&text, origin.x + i as f32 * size.x / Self::TRACES.len() as f32, origin.y - 12.0, TextParams {
+                font_size: 18,
+                font: Some(font),
+                color: *color,
+                ..Default::default()
+            });
+        }
+
+        for (time, trigger) in &self.triggers {
+            let x = x_of(*time);
+            draw_line(x, origin.y + size.y + 2.0, x, origin.y + size.y + 14.0, 2.0, trigger.color());
+        }
+
+        draw_text_ex("Spawn Telemetry", origin.x, origin.y - 48.0, TextParams {
+            font_size: 22,
+            font: Some(font),
+            ..Default::default()
+        });
+    }
+}
+
+/// Saucer director
+///
+/// Rolls for a saucer at each interval of the wave, as long as the field has
+/// room for another, and sizes the saucers it sends in by the pressure.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::difficulty::Difficulty;
+/// # use asteroids::director::{SaucerDirector, Trigger};
+/// # use macroquad::rand::RandGenerator;
+/// let rng = RandGenerator::new();
+/// let mut director = SaucerDirector::default();
+///
+/// director.reset(0.0, Difficulty::Normal, 1, 0);
+/// assert_eq!(director.cooldown(4.0), 6.0);
+/// assert!(director.update(4.0, Difficulty::Normal, 1, 0, 0, &rng).is_none());
+/// assert_eq!(director.telemetry.triggers().count(), 0);
+///
+/// // Each interval brings a chance of a saucer, spawned or missed
+/// director.update(11.0, Difficulty::Normal, 1, 0, 0, &rng);
+/// assert_eq!(director.telemetry.triggers().count(), 1);
+///
+/// // A field with as many saucers as it may hold gets no more
+/// assert!(director.update(30.0, Difficulty::Normal, 1, 0, 1, &rng).is_none());
+/// assert_eq!(director.telemetry.triggers().last(), Some(&(30.0, Trigger::Missed)));
+///
+/// // Pressure rising far enough lets another saucer in, marked as it happens
+/// director.update(31.0, Difficulty::Normal, 6, 20000, 1, &rng);
+/// assert_eq!(director.telemetry.triggers().last(), Some(&(31.0, Trigger::MoreSaucers(2))));
+///
+/// // Late in a long game, saucers are as frequent, and as many, as they get
+/// assert_eq!(SaucerDirector::pressure(50, 0), 1.0);
+/// assert_eq!(SaucerDirector::max_saucers(1.0), SaucerDirector::MAX_SAUCERS);
+/// assert_eq!(SaucerDirector::interval(Difficulty::Normal, 1.0), 5.0);
+/// ```
+#[derive(Default)]
+pub struct SaucerDirector {
+    next_chance: f64,
+    interval: f64,
+    chance: f32,
+    pressure: f32,
+    max_saucers: usize,
+    pub telemetry: SpawnTelemetry,
+}
+
+impl SaucerDirector {
+    /// Wave by which the pressure is at its height, however low the score.
+    pub const PRESSURE_WAVE: u32 = 12;
+
+    /// Score by which the pressure is at its height, whatever the wave.
+    pub const PRESSURE_SCORE: u32 = 50000;
+
+    /// Most saucers let into the field at once, at the height of the
+    /// pressure.
+    pub const MAX_SAUCERS: usize = 3;
+
+    /// Share of the interval between chances of a saucer taken off at the
+    /// height of the pressure.
+    const INTERVAL_CUT: f64 = 0.5;
+
+    /// Share of the chance of missing a saucer taken off at the height of the
+    /// pressure.
+    const CHANCE_BOOST: f32 = 0.5;
+
+    /// Saucer pressure in a wave at a score, from 0 in the first wave of a
+    /// new game to 1, from whichever of the two has come further.
+    pub fn pressure(wave: u32, score: u32) -> f32 {
+        let by_wave = wave.saturating_sub(1) as f32 / (Self::PRESSURE_WAVE - 1) as f32;
+        let by_score = score as f32 / Self::PRESSURE_SCORE as f32;
+
+        by_wave.max(by_score).min(1.0)
+    }
+
+    /// Most saucers let into the field at once under a pressure.
+    pub fn max_saucers(pressure: f32) -> usize {
+        1 + (pressure * (Self::MAX_SAUCERS - 1) as f32).round() as usize
+    }
+
+    /// Time in seconds between chances of a saucer under a pressure.
+    pub fn interval(difficulty: Difficulty, pressure: f32) -> f64 {
+        difficulty.saucer_interval() * (1.0 - Self::INTERVAL_CUT * pressure as f64)
+    }
+
+    /// Chance, from 0 to 1, of a saucer each interval under a pressure.
+    pub fn chance(difficulty: Difficulty, pressure: f32) -> f32 {
+        let chance = difficulty.saucer_chance();
+        chance + (1.0 - chance) * Self::CHANCE_BOOST * pressure
+    }
+
+    /// Start counting down to the first chance of a saucer in a wave.
+    pub fn reset(&mut self, now: f64, difficulty: Difficulty, wave: u32, score: u32) {
+        self.pressure = Self::pressure(wave, score);
+        self.max_saucers = Self::max_saucers(self.pressure);
+        self.interval = Self::interval(difficulty, self.pressure);
+        self.chance = Self::chance(difficulty, self.pressure);
+        self.next_chance = now + self.interval;
+    }
+
+    /// Time in seconds until the next chance of a saucer.
+    pub fn cooldown(&self, now: f64) -> f64 {
+        (self.next_chance - now).max(0.0)
+    }
+
+    /// Decide whether a saucer joins a wave with some saucers already in it,
+    /// returning its size if one does. A chance that comes while the field
+    /// holds as many saucers as it may is missed. The share of saucers that
+    /// come small is the pressure.
+    pub fn update(&mut self, now: f64, difficulty: Difficulty, wave: u32, score: u32, saucers: usize, rng: &RandGenerator) -> Option<SaucerSize> {
+        self.pressure = Self::pressure(wave, score);
+        self.interval = Self::interval(difficulty, self.pressure);
+        self.chance = Self::chance(difficulty, self.pressure);
+
+        let max_saucers = Self::max_saucers(self.pressure);
+
+        if max_saucers > self.max_saucers {
+            self.telemetry.mark(now, Trigger::MoreSaucers(max_saucers));
+        }
+
+        self.max_saucers = max_saucers;
+
+        if self.next_chance >= now {
+            return None;
+        }
+
+        self.next_chance = now + self.interval;
+
+        if saucers < max_saucers && rng.gen_range(0.0, 1.0) > 1.0 - self.chance {
+            let size = if rng.gen_range(0.0, 1.0) < self.pressure { SaucerSize::Small } else { SaucerSize::Large };
+
+            self.telemetry.mark(now, Trigger::Spawned(size));
+            Some(size)
+        } else {
+            self.telemetry.mark(now, Trigger::Missed);
+            None
+        }
+    }
+
+    /// Record the director's state this frame, along with the wave's tension,
+    /// for the telemetry.
+    pub fn sample(&mut self, now: f64, tension: f32) {
+        let cooldown = if self.interval > 0.0 { self.cooldown(now) / self.interval } else { 0.0 };
+
+        self.telemetry.record(TelemetrySample {
+            time: now,
+            cooldown: cooldown as f32,
+            chance: self.chance,
+            pressure: self.pressure,
+            tension,
+        });
+    }
+}
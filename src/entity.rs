@@ -0,0 +1,19 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Entity handle
+/// 
+/// A unique, copyable handle identifying a game object. Handles allow one
+/// object to refer to another (e.g. a turret to the hull it is mounted on)
+/// without holding a reference into the world's entity lists, which are
+/// reshuffled whenever dead objects are removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EntityId(u32);
+
+impl EntityId {
+    /// Allocate a new, never before used handle.
+    pub fn next() -> Self {
+        static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,141 @@
+//! Game events.
+//!
+//! Collision code decides what happened, such as an asteroid shot apart, a
+//! saucer downed or a ship hit, and posts it to the event bus. What follows
+//! from it is left to the systems that consume the events once collisions
+//! are resolved: scoring, which also takes a life from a ship that was hit,
+//! then audio, particles and achievements, in that order. Mods can listen in
+//! too, subscribing through `GameWorld::events`, without touching collision
+//! code; listeners hear each event after the game's own systems.
+//!
+//! Events say what destroyed each asteroid, saucer, hunter and turret, so that
+//! only what a player destroyed scores: a saucer blundering into an asteroid, or an
+//! asteroid caught by a saucer's shot, is worth nothing unless the arcade
+//! scoring ruleset is chosen, which credits the first player with it all.
+
+use macroquad::prelude::*;
+
+use crate::asteroid::AsteroidSize;
+use crate::saucer::SaucerSize;
+
+/// Game event
+///
+/// Something that happened in play, for the systems consuming events.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameEvent {
+    /// An asteroid of a size and grade, worth some points, was destroyed at
+    /// a position.
+    AsteroidDestroyed { position: Vec2, size: AsteroidSize, grade: f32, points: u32, cause: Cause },
+    /// A saucer, worth some points, was destroyed at a position. A ricochet
+    /// is a shot that crossed an edge of the screen on its way.
+    SaucerDestroyed { position: Vec2, size: SaucerSize, points: u32, cause: Cause, ricochet: bool },
+    /// A hunter, worth some points, was destroyed at a position.
+    HunterDestroyed { position: Vec2, points: u32, cause: Cause },
+    /// A turret, worth some points, was knocked out at a position.
+    TurretDestroyed { position: Vec2, points: u32, cause: Cause },
+    /// An entity added by a plugin, worth some points, was destroyed at a
+    /// position.
+    EntityDestroyed { position: Vec2, points: u32, cause: Cause },
+    /// A player's ship was hit at a position.
+    ShipHit { player: usize, position: Vec2 },
+    /// A wave was cleared.
+    WaveCleared { wave: u32 },
+    /// A player earned an extra life.
+    ExtraLife { player: usize },
+}
+
+/// Cause
+///
+/// What destroyed an asteroid, saucer or hunter: a player, by shooting or ramming it,
+/// a player's smart bomb, a saucer's shot, or a crash into something else in the field.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::events::Cause;
+/// assert_eq!(Cause::Player(1).player(), Some(1));
+/// assert_eq!(Cause::Bomb(0).player(), Some(0));
+/// assert_eq!(Cause::Enemy.player(), None);
+/// assert_eq!(Cause::Crash.player(), None);
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Cause {
+    Player(usize),
+    Bomb(usize),
+    Enemy,
+    Crash,
+}
+
+impl Cause {
+    /// Player who caused the destruction, if one did.
+    pub fn player(&self) -> Option<usize> {
+        match self {
+            Cause::Player(player) | Cause::Bomb(player) => Some(*player),
+            Cause::Enemy | Cause::Crash => None,
+        }
+    }
+}
+
+/// Listener hearing every event dispatched.
+pub type Listener = Box<dyn FnMut(&GameEvent)>;
+
+/// Event bus
+///
+/// Events posted since they were last dispatched, and the listeners
+/// subscribed to hear them.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::events::{EventBus, GameEvent};
+/// # use std::rc::Rc;
+/// # use std::cell::RefCell;
+/// let mut bus = EventBus::default();
+/// let heard = Rc::new(RefCell::new(Vec::new()));
+///
+/// let log = heard.clone();
+/// bus.subscribe(move |event| log.borrow_mut().push(*event));
+///
+/// bus.emit(GameEvent::WaveCleared { wave: 3 });
+/// bus.emit(GameEvent::ExtraLife { player: 0 });
+///
+/// // Events are held until taken for dispatch
+/// assert!(heard.borrow().is_empty());
+///
+/// for event in bus.take() {
+///     bus.notify(&event);
+/// }
+///
+/// assert_eq!(*heard.borrow(), [GameEvent::WaveCleared { wave: 3 }, GameEvent::ExtraLife { player: 0 }]);
+/// assert!(bus.take().is_empty());
+/// ```
+#[derive(Default)]
+pub struct EventBus {
+    pending: Vec<GameEvent>,
+    listeners: Vec<Listener>,
+}
+
+impl EventBus {
+    /// Post an event, to be dispatched once collisions are resolved.
+    pub fn emit(&mut self, event: GameEvent) {
+        self.pending.push(event);
+    }
+
+    /// Subscribe a listener to hear every event dispatched from now on.
+    pub fn subscribe(&mut self, listener: impl FnMut(&GameEvent) + 'static) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Take the events posted since the last dispatch, in the order they were
+    /// posted.
+    pub fn take(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Tell every listener of an event.
+    pub fn notify(&mut self, event: &GameEvent) {
+        for listener in &mut self.listeners {
+            listener(event);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use macroquad::prelude::*;
+
+use crate::textcache::TextMeasureCache;
+
+/// Floating text object
+///
+/// A short label, such as the points scored for a kill, shown where something
+/// happened. It drifts upwards and fades out over its lifespan, in seconds.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::floatingtext::FloatingText;
+/// # use macroquad::prelude::*;
+/// let mut popup = FloatingText::points(100, Vec2::new(50.0, 50.0));
+/// assert_eq!(popup.text, "+100");
+///
+/// popup.update(FloatingText::LIFESPAN / 2.0);
+/// assert!(popup.position.y < 50.0);
+/// assert!(popup.is_alive());
+///
+/// popup.update(FloatingText::LIFESPAN);
+/// assert!(!popup.is_alive());
+/// ```
+pub struct FloatingText {
+    pub text: String,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub age: f32,
+    pub color: Color,
+}
+
+impl FloatingText {
+    /// Time in seconds the text is shown.
+    pub const LIFESPAN: f32 = 0.8;
+
+    /// Speed in pixels per second at which the text drifts upwards.
+    const RISE_SPEED: f32 = 40.0;
+
+    /// Font size the text is drawn at.
+    const FONT_SIZE: u16 = 24;
+
+    /// Spawn new text at a given position.
+    pub fn spawn_new(text: String, position: Vec2) -> Self {
+        Self {
+            text,
+            position,
+            velocity: Vec2::new(0.0, -Self::RISE_SPEED),
+            age: 0.0,
+            color: WHITE,
+        }
+    }
+
+    /// Spawn text showing points scored at a given position.
+    pub fn points(points: u32, position: Vec2) -> Self {
+        Self::spawn_new(format!("+{}", points), position)
+    }
+
+    /// Draw the text in a given colour.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Check if text is still shown.
+    pub fn is_alive(&self) -> bool {
+        self.age < Self::LIFESPAN
+    }
+
+    /// Update text position and age by the time the frame took.
+    pub fn update(&mut self, dt: f32) {
+        self.position += self.velocity * dt;
+        self.age += dt;
+    }
+
+    /// Draw text centred on its position, fading out as it ages.
+    pub fn draw(&self, font: &Font, text_widths: &TextMeasureCache) {
+        let width = text_widths.width(font, &self.text, Self::FONT_SIZE);
+        let alpha = (1.0 - self.age / Self::LIFESPAN).clamp(0.0, 1.0);
+
+        draw_text_ex(
+            &self.text, self.position.x - width / 2.0, self.position.y,
+            TextParams {
+                font_size: Self::FONT_SIZE,
+                font: Some(font),
+                color: Color { a: self.color.a * alpha, ..self.color },
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use crate::menu::MenuCommand;
+
+/// Gamepad state
+///
+/// The controls read from the gamepad in a single frame. Steering is analog,
+/// ranging from -1.0 (full left) to 1.0 (full right); the buttons are digital.
+/// `fire`, `hyperspace`, `overdrive` and `start` are only set on the frame the
+/// button goes down.
+#[derive(Clone, Copy, Default)]
+pub struct GamepadState {
+    pub steer: f32,
+    pub thrust: bool,
+    pub fire: bool,
+    pub hyperspace: bool,
+    pub overdrive: bool,
+    pub bomb: bool,
+    pub shield: bool,
+    pub start: bool,
+}
+
+/// Rumble pulse
+///
+/// The haptic feedback the game gives through the gamepad: a tiny tick for
+/// each shot, a strong jolt when the ship is hit, and a long rumble for smart
+/// bombs.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Rumble {
+    Shot,
+    Hit,
+    SmartBomb,
+}
+
+impl Rumble {
+    /// Strength of the pulse from 0.0 to 1.0, before the rumble intensity
+    /// setting is applied.
+    pub fn strength(&self) -> f32 {
+        match self {
+            Rumble::Shot => 0.15,
+            Rumble::Hit => 1.0,
+            Rumble::SmartBomb => 0.6,
+        }
+    }
+
+    /// Duration of the pulse in milliseconds.
+    pub fn duration(&self) -> u32 {
+        match self {
+            Rumble::Shot => 40,
+            Rumble::Hit => 400,
+            Rumble::SmartBomb => 1200,
+        }
+    }
+}
+
+/// Gamepad
+///
+/// Reads the first two connected gamepads, one for each player. The left stick or d-pad steers, the south
+/// face button (A on Xbox pads, cross on PlayStation pads) thrusts, the west face
+/// button or right trigger fires, the east face button enters hyperspace, the
+/// north face button triggers overdrive, the left bumper raises the shield for
+/// as long as it is held, and the left trigger sets off a smart bomb. Start on
+/// the second gamepad drops
+/// the second player into a game, or out of it.
+///
+/// Gamepads that support force feedback rumble in response to game events,
+/// scaled by `rumble_intensity`; a weaker pulse never cuts a stronger one short.
+/// Gamepads without force feedback are simply left alone.
+///
+/// In menus, the d-pad or left stick moves through the items, the south face
+/// button accepts and the east face button goes back.
+///
+/// Gamepads are only supported on native builds with the `gamepad` feature
+/// enabled. Otherwise, the gamepad never reports any input, and never rumbles.
+pub struct Gamepad {
+    pub rumble_intensity: f32,
+    #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+    gilrs: Option<gilrs::Gilrs>,
+    #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+    effect: Option<(gilrs::ff::Effect, f64, f32)>,
+    previous: [GamepadState; 2],
+    previous_menu: Option<MenuCommand>,
+}
+
+impl Gamepad {
+    /// Stick deflection below which the stick is considered centred.
+    pub const DEAD_ZONE: f32 = 0.2;
+
+    /// Open the gamepad subsystem.
+    pub fn new() -> Self {
+        Self {
+            rumble_intensity: 1.0,
+            #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+            gilrs: gilrs::Gilrs::new().ok(),
+            #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+            effect: None,
+            previous: [GamepadState::default(); 2],
+            previous_menu: None,
+        }
+    }
+
+    /// Poll the first player's gamepad for this frame's input.
+    pub fn poll(&mut self) -> GamepadState {
+        self.poll_player(0)
+    }
+
+    /// Poll a player's gamepad, numbered from zero, for this frame's input.
+    pub fn poll_player(&mut self, player: usize) -> GamepadState {
+        let held = self.read(player);
+        let previous = self.previous[player];
+
+        let state = GamepadState {
+            fire: held.fire && !previous.fire,
+            hyperspace: held.hyperspace && !previous.hyperspace,
+            overdrive: held.overdrive && !previous.overdrive,
+            bomb: held.bomb && !previous.bomb,
+            start: held.start && !previous.start,
+            ..held
+        };
+
+        self.previous[player] = held;
+        state
+    }
+
+    /// Poll the gamepad for a menu command, given only on the frame its
+    /// button is pressed or the stick is pushed.
+    pub fn poll_menu(&mut self) -> Option<MenuCommand> {
+        let held = self.read_menu();
+        let command = held.filter(|command| self.previous_menu != Some(*command));
+
+        self.previous_menu = held;
+        command
+    }
+
+    /// Read the menu command currently held on the first connected gamepad.
+    #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+    fn read_menu(&mut self) -> Option<MenuCommand> {
+        use gilrs::{Axis, Button};
+
+        let gilrs = self.gilrs.as_mut()?;
+
+        while gilrs.next_event().is_some() {}
+
+        let (_, pad) = gilrs.gamepads().next()?;
+        let (x, y) = (pad.value(Axis::LeftStickX), pad.value(Axis::LeftStickY));
+
+        // The stick has to be pushed well past the dead zone to move through
+        // a menu, so it does not wander while resting
+        if pad.is_pressed(Button::DPadUp) || y > 0.5 {
+            Some(MenuCommand::Up)
+        } else if pad.is_pressed(Button::DPadDown) || y < -0.5 {
+            Some(MenuCommand::Down)
+        } else if pad.is_pressed(Button::DPadLeft) || x < -0.5 {
+            Some(MenuCommand::Left)
+        } else if pad.is_pressed(Button::DPadRight) || x > 0.5 {
+            Some(MenuCommand::Right)
+        } else if pad.is_pressed(Button::South) {
+            Some(MenuCommand::Accept)
+        } else if pad.is_pressed(Button::East) {
+            Some(MenuCommand::Back)
+        } else {
+            None
+        }
+    }
+
+    /// Gamepad support is not compiled in.
+    #[cfg(not(all(feature = "gamepad", not(target_arch = "wasm32"))))]
+    fn read_menu(&mut self) -> Option<MenuCommand> {
+        None
+    }
+
+    /// Read the controls currently held on a player's gamepad: the first
+    /// connected for the first player, the second for the second.
+    #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+    fn read(&mut self, player: usize) -> GamepadState {
+        use gilrs::{Axis, Button};
+
+        let Some(gilrs) = self.gilrs.as_mut() else {
+            return GamepadState::default();
+        };
+
+        // Drain events so the cached gamepad state is up to date
+        while gilrs.next_event().is_some() {}
+
+        let Some((_, pad)) = gilrs.gamepads().nth(player) else {
+            return GamepadState::default();
+        };
+
+        let stick = pad.value(Axis::LeftStickX);
+        let steer = if pad.is_pressed(Button::DPadLeft) {
+            -1.0
+        } else if pad.is_pressed(Button::DPadRight) {
+            1.0
+        } else if stick.abs() > Self::DEAD_ZONE {
+            // Rescale so steering starts from zero at the edge of the dead zone
+            stick.signum() * (stick.abs() - Self::DEAD_ZONE) / (1.0 - Self::DEAD_ZONE)
+        } else {
+            0.0
+        };
+
+        GamepadState {
+            steer,
+            thrust: pad.is_pressed(Button::South),
+            fire: pad.is_pressed(Button::West) || pad.is_pressed(Button::RightTrigger2),
+            hyperspace: pad.is_pressed(Button::East),
+            overdrive: pad.is_pressed(Button::North),
+            bomb: pad.is_pressed(Button::LeftTrigger2),
+            shield: pad.is_pressed(Button::LeftTrigger),
+            start: pad.is_pressed(Button::Start),
+        }
+    }
+
+    /// Gamepad support is not compiled in.
+    #[cfg(not(all(feature = "gamepad", not(target_arch = "wasm32"))))]
+    fn read(&mut self, _player: usize) -> GamepadState {
+        GamepadState::default()
+    }
+
+    /// Check if a connected gamepad supports rumble.
+    #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+    pub fn supports_rumble(&self) -> bool {
+        self.gilrs.as_ref().is_some_and(|gilrs| gilrs.gamepads().any(|(_, pad)| pad.is_ff_supported()))
+    }
+
+    /// Gamepad support is not compiled in.
+    #[cfg(not(all(feature = "gamepad", not(target_arch = "wasm32"))))]
+    pub fn supports_rumble(&self) -> bool {
+        false
+    }
+
+    /// Play a rumble pulse on the first gamepad that supports rumble.
+    #[cfg(all(feature = "gamepad", not(target_arch = "wasm32")))]
+    pub fn rumble(&mut self, pulse: Rumble) {
+        use gilrs::ff::{BaseEffect, BaseEffectType, EffectBuilder, Repeat, Replay, Ticks};
+
+        let strength = (pulse.strength() * self.rumble_intensity).clamp(0.0, 1.0);
+        let now = macroquad::time::get_time();
+
+        // Leave stronger pulses that are still playing alone
+        if strength <= 0.0 || self.effect.as_ref().is_some_and(|(_, until, playing)| now < *until && *playing > strength) {
+            return;
+        }
+
+        let Some(gilrs) = self.gilrs.as_mut() else {
+            return;
+        };
+
+        let Some(id) = gilrs.gamepads().find(|(_, pad)| pad.is_ff_supported()).map(|(id, _)| id) else {
+            return;
+        };
+
+        let magnitude = (strength * u16::MAX as f32) as u16;
+        let kind = match pulse {
+            Rumble::Shot => BaseEffectType::Weak { magnitude },
+            _ => BaseEffectType::Strong { magnitude },
+        };
+
+        let duration = Ticks::from_ms(pulse.duration());
+        let effect = EffectBuilder::new()
+            .add_effect(BaseEffect {
+                kind,
+                scheduling: Replay { play_for: duration, ..Default::default() },
+                ..Default::default()
+            })
+            .gamepads(&[id])
+            .repeat(Repeat::For(duration))
+            .finish(gilrs);
+
+        // Effects stop when dropped, so keep the latest one alive
+        if let Ok(effect) = effect {
+            if effect.play().is_ok() {
+                self.effect = Some((effect, now + pulse.duration() as f64 / 1000.0, strength));
+            }
+        }
+    }
+
+    /// Gamepad support is not compiled in.
+    #[cfg(not(all(feature = "gamepad", not(target_arch = "wasm32"))))]
+    pub fn rumble(&mut self, _pulse: Rumble) {}
+}
+
+impl Default for Gamepad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,222 @@
+//! Gold seam bonus round.
+//!
+//! Every few waves of a classic or co-op game, clearing the field brings in a
+//! single huge rock veined with glowing seams of gold. Leaving it alone lets
+//! the next wave start after a few seconds; shooting it starts a short bonus
+//! round, in which every seam shot out scores points. The round ends when
+//! time runs out or the rock breaks apart, either from the chips taken out of
+//! its bare surface or from the last seam being mined, which earns a bonus.
+//!
+//! Seams are mounted on the rock the way turrets are mounted on a saucer: each
+//! keeps its offset from the rock's centre and follows it as it drifts and
+//! spins.
+
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::asteroid::Asteroid;
+use crate::camera::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::collision::Collidable;
+use crate::detmath;
+use crate::linebatch::LineBatch;
+use crate::wrap::WrapDraw;
+
+/// Seam
+///
+/// A glowing vein of gold on the rock, mined by a single shot.
+pub struct Seam {
+    pub offset: Vec2,
+    pub position: Vec2,
+    pub is_alive: bool,
+}
+
+impl Seam {
+    /// Radius of a seam, in pixels.
+    const RADIUS: f32 = 6.0;
+
+    /// Follow the rock, keeping the seam's place on its surface as it turns.
+    fn update(&mut self, rock: &Asteroid) {
+        self.position = rock.position + detmath::rotation(rock.rotation).mul_vec2(self.offset);
+    }
+
+    /// Mine the seam, marking it dead.
+    pub fn mine(&mut self) {
+        self.is_alive = false;
+    }
+
+    /// Check if the seam is still there to be mined.
+    pub fn is_alive(&self) -> bool {
+        self.is_alive
+    }
+
+    /// Draw seam as a short vein, pulsing between dull and bright gold.
+    fn draw(&self, rotation: f32, time: f64) {
+        let glow = 0.6 + 0.4 * (time * 6.0 + self.offset.x as f64).sin() as f32;
+        let color = Color::new(1.0, 0.8 * glow + 0.1, 0.1, glow);
+        let along = detmath::rotation(rotation + self.offset.y).mul_vec2(Vec2::X * Self::RADIUS);
+
+        self.draw_wrapped(|position| {
+            draw_line(position.x - along.x, position.y - along.y, position.x + along.x, position.y + along.y, 3.0, color);
+            draw_circle_lines(position.x, position.y, Self::RADIUS * 1.5, 1.0, Color { a: glow * 0.5, ..color });
+        });
+    }
+}
+
+impl Collidable for Seam {
+    fn is_colliding(&self, other: &dyn Collidable) -> bool {
+        <dyn Collidable>::shape_intersection(self, other)
+    }
+
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn get_radius(&self) -> f32 {
+        Self::RADIUS * 1.5
+    }
+}
+
+/// Gold seam
+///
+/// The rock of a bonus round with its seams, and when the round was offered
+/// and started.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::goldseam::GoldSeam;
+/// assert!(!GoldSeam::is_due(0));
+/// assert!(!GoldSeam::is_due(2));
+/// assert!(GoldSeam::is_due(3));
+/// assert!(GoldSeam::is_due(6));
+/// ```
+pub struct GoldSeam {
+    pub rock: Asteroid,
+    pub seams: Vec<Seam>,
+    pub offered_at: f64,
+    pub started_at: Option<f64>,
+    pub chips: u32,
+}
+
+impl GoldSeam {
+    /// Waves between bonus rounds.
+    pub const WAVE_INTERVAL: u32 = 3;
+
+    /// Time in seconds the rock waits to be shot before the next wave starts.
+    pub const OFFER_DURATION: f64 = 4.0;
+
+    /// Time in seconds a bonus round lasts once started.
+    pub const DURATION: f64 = 10.0;
+
+    /// Points scored for each seam mined.
+    pub const SEAM_POINTS: u32 = 250;
+
+    /// Points scored for mining every seam before time runs out.
+    pub const CLEAR_BONUS: u32 = 2000;
+
+    /// Shots the rock's bare surface takes before it breaks apart.
+    pub const ROCK_HITS: u32 = 20;
+
+    /// Number of seams on the rock.
+    const SEAM_COUNT: usize = 10;
+
+    /// Scale of the rock, well beyond a large asteroid.
+    const ROCK_SCALE: f32 = 3.0;
+
+    /// Check if a bonus round is offered once a wave is cleared.
+    pub fn is_due(wave: u32) -> bool {
+        wave > 0 && wave.is_multiple_of(Self::WAVE_INTERVAL)
+    }
+
+    /// Bring a rock into the middle of the screen, with seams scattered over
+    /// its surface, and offer the round.
+    pub fn spawn(rng: &RandGenerator, now: f64) -> Self {
+        let centre = Vec2::new(WORLD_WIDTH / 2.0, WORLD_HEIGHT / 2.0);
+        let mut rock = Asteroid::spawn_new_at(rng, Self::ROCK_SCALE, centre);
+
+        // Huge rocks barely move, so the seams stay within reach
+        rock.velocity *= 0.3;
+        rock.rotation_speed *= 0.5;
+
+        // Each seam lies between the centre and a corner of the rock, so it is
+        // always on its surface
+        let seams = (0..Self::SEAM_COUNT).map(|_| {
+            let corner = rock.vertices[rng.gen_range(0, rock.vertices.len())];
+            let offset = corner * rng.gen_range(0.3, 0.85);
+
+            Seam {
+                offset,
+                position: rock.position + detmath::rotation(rock.rotation).mul_vec2(offset),
+                is_alive: true,
+            }
+        }).collect();
+
+        Self {
+            rock,
+            seams,
+            offered_at: now,
+            started_at: None,
+            chips: 0,
+        }
+    }
+
+    /// Start the round, the first time the rock is shot.
+    pub fn start(&mut self, now: f64) {
+        self.started_at.get_or_insert(now);
+    }
+
+    /// Time in seconds left in the round, or to start it.
+    pub fn time_left(&self, now: f64) -> f64 {
+        let deadline = match self.started_at {
+            Some(start) => start + Self::DURATION,
+            None => self.offered_at + Self::OFFER_DURATION,
+        };
+
+        (deadline - now).max(0.0)
+    }
+
+    /// Check if the round is over: the rock was left alone, time ran out, or
+    /// the rock broke apart.
+    pub fn is_over(&self, now: f64) -> bool {
+        !self.rock.is_alive() || self.time_left(now) <= 0.0
+    }
+
+    /// Check if every seam was mined.
+    pub fn is_mined_out(&self) -> bool {
+        self.seams.iter().all(|seam| !seam.is_alive())
+    }
+
+    /// Take a chip out of the rock's bare surface, breaking it apart after
+    /// enough of them.
+    pub fn chip(&mut self) {
+        self.chips += 1;
+
+        if self.chips >= Self::ROCK_HITS {
+            self.rock.destroy();
+        }
+    }
+
+    /// Move the rock, and the seams with it. Mining the last seam breaks the
+    /// rock apart.
+    pub fn update(&mut self) {
+        self.rock.update();
+
+        for seam in &mut self.seams {
+            seam.update(&self.rock);
+        }
+
+        if self.is_mined_out() {
+            self.rock.destroy();
+        }
+    }
+
+    /// Draw the rock in dull gold with its remaining seams glowing on it.
+    pub fn draw(&self, lines: &mut LineBatch, time: f64) {
+        self.rock.draw_tinted(lines, Color::new(0.8, 0.65, 0.3, 1.0));
+        lines.draw();
+
+        for seam in self.seams.iter().filter(|seam| seam.is_alive()) {
+            seam.draw(self.rock.rotation, time);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Graphics quality.
+//!
+//! The game is drawn in thin lines, which look jagged without anti-aliasing
+//! and shimmer as they move once they are thinner than a pixel. Lines can be
+//! smoothed by multisampling, which is set when the window is created and so
+//! takes effect from the next launch, or, where multisampling is unavailable,
+//! as on WebGL 1, by drawing the line batch's quads with feathered edges.
+//! Lines are never drawn thinner than a pixel, and can be made thicker all
+//! round.
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::camera::{self, WORLD_WIDTH};
+use crate::linebatch::LineStyle;
+
+/// Anti-aliasing
+///
+/// How lines are smoothed: not at all, by multisampling at a number of
+/// samples per pixel, or by feathering their edges.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::graphics::Antialiasing;
+/// assert_eq!(Antialiasing::Msaa4.sample_count(), 4);
+/// assert_eq!(Antialiasing::Feathered.sample_count(), 1);
+///
+/// // Cycling through the modes comes back round
+/// let mut mode = Antialiasing::Off;
+///
+/// for _ in 0..Antialiasing::ALL.len() {
+///     mode = mode.next();
+/// }
+///
+/// assert_eq!(mode, Antialiasing::Off);
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Antialiasing {
+    Off,
+    Msaa2,
+    Msaa4,
+    Msaa8,
+    Feathered,
+}
+
+impl Default for Antialiasing {
+    /// Multisampling, except on the web, where it cannot be relied upon.
+    fn default() -> Self {
+        if cfg!(target_arch = "wasm32") {
+            Antialiasing::Feathered
+        } else {
+            Antialiasing::Msaa4
+        }
+    }
+}
+
+impl Antialiasing {
+    /// All modes, in the order they are cycled through.
+    pub const ALL: [Antialiasing; 5] = [
+        Antialiasing::Off,
+        Antialiasing::Msaa2,
+        Antialiasing::Msaa4,
+        Antialiasing::Msaa8,
+        Antialiasing::Feathered,
+    ];
+
+    /// Label shown in the settings.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Antialiasing::Off => "Off",
+            Antialiasing::Msaa2 => "MSAA 2x",
+            Antialiasing::Msaa4 => "MSAA 4x",
+            Antialiasing::Msaa8 => "MSAA 8x",
+            Antialiasing::Feathered => "Feathered",
+        }
+    }
+
+    /// Mode following this one, wrapping around to the first.
+    pub fn next(&self) -> Antialiasing {
+        let index = Self::ALL.iter().position(|mode| mode == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Samples per pixel the window is created with.
+    pub fn sample_count(&self) -> i32 {
+        match self {
+            Antialiasing::Msaa2 => 2,
+            Antialiasing::Msaa4 => 4,
+            Antialiasing::Msaa8 => 8,
+            Antialiasing::Off | Antialiasing::Feathered => 1,
+        }
+    }
+}
+
+/// Style to draw the line batch in, for lines widened by a factor and drawn
+/// through a camera at a scale, such as 1 for the playfield's own, where a
+/// pixel of the window spans a number of the playfield's units.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::graphics::{line_style_for, Antialiasing};
+/// // A window half the width of the playfield, with lines doubled in width
+/// let style = line_style_for(Antialiasing::Feathered, 2.0, 1.0, 2.0);
+/// assert_eq!(style.scale, 2.0);
+/// assert_eq!(style.min_thickness, 2.0);
+/// assert_eq!(style.feather, 2.0);
+///
+/// // Drawn at half scale, a pixel spans twice as many of the layer's units
+/// let style = line_style_for(Antialiasing::Msaa4, 1.0, 0.5, 2.0);
+/// assert_eq!(style.min_thickness, 4.0);
+/// assert_eq!(style.feather, 0.0);
+/// ```
+pub fn line_style_for(antialiasing: Antialiasing, width: f32, scale: f32, pixel: f32) -> LineStyle {
+    let pixel = pixel / scale;
+
+    LineStyle {
+        scale: width,
+        min_thickness: pixel,
+        feather: if antialiasing == Antialiasing::Feathered { pixel } else { 0.0 },
+    }
+}
+
+/// Style to draw the line batch in through a camera at a scale, in the
+/// window as it is now.
+pub fn line_style(antialiasing: Antialiasing, width: f32, scale: f32) -> LineStyle {
+    let viewport = camera::viewport(Vec2::new(screen_width(), screen_height()));
+
+    line_style_for(antialiasing, width, scale, WORLD_WIDTH / viewport.w.max(1.0))
+}
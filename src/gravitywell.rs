@@ -0,0 +1,162 @@
+//! Gravity wells.
+//!
+//! From the fourth wave of a classic, co-op or weekly game, a gravity well
+//! sometimes opens partway through the wave. While it lasts it pulls the
+//! ships, asteroids and bullets towards it, harder the closer they are, and
+//! swallows anything that reaches its core; a shield keeps a ship from being
+//! swallowed, but not from being pulled. After a while it collapses in on
+//! itself and is gone.
+
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::camera::WORLD_SIZE;
+use crate::collision::wrapped_delta;
+use crate::detmath;
+use crate::wrap::wrap_draw;
+
+/// Gravity well
+///
+/// A well at a position, and how long it has been open. Its pull follows
+/// the inverse square of the distance, eased in as the well opens and out as
+/// it collapses, and is the acceleration felt each frame, since objects move
+/// a step each frame.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::gravitywell::GravityWell;
+/// # use macroquad::prelude::*;
+/// let mut well = GravityWell::spawn_new(Vec2::new(500.0, 375.0));
+/// well.update(GravityWell::OPENING);
+///
+/// // Pulled towards the well, four times as hard at half the distance
+/// let far = well.pull(Vec2::new(700.0, 375.0));
+/// let near = well.pull(Vec2::new(600.0, 375.0));
+/// assert!(far.x < 0.0 && far.y == 0.0);
+/// assert!((near.x / far.x - 4.0).abs() < 1e-3);
+///
+/// // The pull reaches across the screen's edges
+/// let across = well.pull(Vec2::new(-400.0 + 1000.0, 375.0));
+/// assert!(across.x < 0.0);
+///
+/// assert!(well.swallows(Vec2::new(505.0, 375.0)));
+/// assert!(!well.swallows(Vec2::new(600.0, 375.0)));
+///
+/// // Once it has lasted its time, it collapses
+/// well.update(GravityWell::LIFESPAN);
+/// assert!(!well.is_alive());
+/// ```
+pub struct GravityWell {
+    pub position: Vec2,
+    pub age: f32,
+}
+
+impl GravityWell {
+    /// First wave a well can open on.
+    pub const FIRST_WAVE: u32 = 4;
+
+    /// Chance, from 0 to 1, of a well opening on a wave.
+    const CHANCE: f32 = 0.35;
+
+    /// Shortest and longest time, in seconds, into a wave a well opens.
+    const DELAY: (f64, f64) = (8.0, 25.0);
+
+    /// Distance from the first player's ship a well opens at.
+    const SPAWN_DISTANCE: f32 = 320.0;
+
+    /// Time in seconds a well stays open.
+    pub const LIFESPAN: f32 = 10.0;
+
+    /// Time in seconds a well takes to open to its full pull, and to close.
+    pub const OPENING: f32 = 1.5;
+
+    /// Strength of the pull: the acceleration, in units per frame each frame,
+    /// felt at a distance of one unit.
+    const STRENGTH: f32 = 250.0;
+
+    /// Distance within which the pull grows no stronger, so objects passing
+    /// close by are not flung about.
+    const SOFTENING: f32 = 30.0;
+
+    /// Radius of the core, which swallows whatever reaches it.
+    pub const CORE_RADIUS: f32 = 12.0;
+
+    /// Radius of the rings drawn spiralling into the core.
+    const RING_RADIUS: f32 = 90.0;
+
+    /// Time, in seconds into a wave, a well opens on it, if one does.
+    pub fn schedule(rng: &RandGenerator, wave: u32) -> Option<f64> {
+        if wave < Self::FIRST_WAVE || rng.gen_range(0.0, 1.0) >= Self::CHANCE {
+            return None;
+        }
+
+        Some(rng.gen_range(Self::DELAY.0, Self::DELAY.1))
+    }
+
+    /// Open a well at a position.
+    pub fn spawn_new(position: Vec2) -> Self {
+        Self { position, age: 0.0 }
+    }
+
+    /// Open a well in a random direction, well away from a ship.
+    pub fn spawn_away_from(rng: &RandGenerator, ship: Vec2) -> Self {
+        let direction = detmath::direction(rng.gen_range(0.0, std::f32::consts::PI * 2.0));
+
+        Self::spawn_new((ship + direction * Self::SPAWN_DISTANCE).rem_euclid(WORLD_SIZE))
+    }
+
+    /// Share, from 0 to 1, of its full pull the well has while it opens and
+    /// closes.
+    pub fn strength(&self) -> f32 {
+        let opening = self.age / Self::OPENING;
+        let closing = (Self::LIFESPAN - self.age) / Self::OPENING;
+
+        opening.min(closing).clamp(0.0, 1.0)
+    }
+
+    /// Acceleration towards the well felt at a position.
+    pub fn pull(&self, position: Vec2) -> Vec2 {
+        let delta = wrapped_delta(position, self.position);
+        let distance = delta.length().max(Self::SOFTENING);
+
+        delta.normalize_or_zero() * Self::STRENGTH * self.strength() / (distance * distance)
+    }
+
+    /// Check if something at a position has reached the core.
+    pub fn swallows(&self, position: Vec2) -> bool {
+        wrapped_delta(position, self.position).length() < Self::CORE_RADIUS
+    }
+
+    /// Age the well by the time the frame took.
+    pub fn update(&mut self, dt: f32) {
+        self.age += dt;
+    }
+
+    /// Check if the well is still open.
+    pub fn is_alive(&self) -> bool {
+        self.age < Self::LIFESPAN
+    }
+
+    /// Draw the core, and rings shrinking into it, fainter farther out and as
+    /// the well's pull fades.
+    pub fn draw(&self) {
+        const RINGS: usize = 4;
+
+        let strength = self.strength();
+        let phase = (self.age * 0.8).fract();
+
+        wrap_draw(self.position, Self::RING_RADIUS, |position| {
+            for ring in 0..RINGS {
+                let share = (ring as f32 + 1.0 - phase) / RINGS as f32;
+                let radius = Self::CORE_RADIUS + (Self::RING_RADIUS - Self::CORE_RADIUS) * share;
+                let alpha = strength * (1.0 - share) * 0.8;
+
+                draw_circle_lines(position.x, position.y, radius, 1.5, Color::new(1.0, 1.0, 1.0, alpha));
+            }
+
+            draw_circle(position.x, position.y, Self::CORE_RADIUS, BLACK);
+            draw_circle_lines(position.x, position.y, Self::CORE_RADIUS, 2.0, Color::new(1.0, 1.0, 1.0, strength));
+        });
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+use crate::savedata::{self, SaveFormat};
+
+/// High score entry
+/// 
+/// A score achieved by a player, signed with their three letter initials.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub initials: String,
+    pub score: u32,
+}
+
+/// High score table
+/// 
+/// Keeps the top scores in descending order, persisted between sessions. The
+/// table is stored as plain text, one `INITIALS SCORE` entry per line; lines
+/// which cannot be parsed are skipped.
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// # use asteroids::highscore::HighScoreTable;
+/// let mut table = HighScoreTable::load();
+/// if table.qualifies(12000) {
+///     table.insert("KB", 12000);
+///     table.save();
+/// }
+/// ```
+#[derive(Default)]
+pub struct HighScoreTable {
+    pub entries: Vec<HighScore>,
+}
+
+impl HighScoreTable {
+    /// Maximum number of entries kept in the table.
+    pub const CAPACITY: usize = 10;
+
+    /// Storage key of the table.
+    const STORAGE_KEY: &'static str = "highscores.txt";
+
+    /// Format tables are saved in.
+    const SAVE_FORMAT: SaveFormat = SaveFormat::INITIAL;
+
+    /// Load table from storage. A missing or unreadable table is empty.
+    pub fn load() -> Self {
+        Self::load_from(Self::STORAGE_KEY)
+    }
+
+    /// Save table to storage.
+    pub fn save(&self) {
+        self.save_to(Self::STORAGE_KEY);
+    }
+
+    /// Load a table kept under its own storage key, such as the leaderboard
+    /// of a weekly challenge.
+    pub fn load_from(key: &str) -> Self {
+        savedata::load(key, &Self::SAVE_FORMAT, |contents| Some(Self::parse(contents)))
+            .unwrap_or_default()
+    }
+
+    /// Save a table under its own storage key.
+    pub fn save_to(&self, key: &str) {
+        savedata::save(key, &Self::SAVE_FORMAT, &self.to_text());
+    }
+
+    /// Parse table from text.
+    pub fn parse(contents: &str) -> Self {
+        let mut table = Self::default();
+
+        for line in contents.lines() {
+            if let Some((initials, score)) = line.trim().split_once(' ') {
+                if let Ok(score) = score.trim().parse() {
+                    table.insert(initials, score);
+                }
+            }
+        }
+
+        table
+    }
+
+    /// Format table as text.
+    pub fn to_text(&self) -> String {
+        self.entries.iter()
+            .map(|entry| format!("{} {}\n", entry.initials, entry.score))
+            .collect()
+    }
+
+    /// Check if a score would earn a place in the table.
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0 && (self.entries.len() < Self::CAPACITY || self.entries.iter().any(|entry| score > entry.score))
+    }
+
+    /// Insert a score into the table, keeping it sorted and within capacity.
+    /// Initials are upper-cased and limited to three characters. Returns the
+    /// rank of the new entry, if it made it into the table.
+    pub fn insert(&mut self, initials: &str, score: u32) -> Option<usize> {
+        if !self.qualifies(score) {
+            return None;
+        }
+
+        let initials: String = initials.to_uppercase().chars().take(3).collect();
+        let rank = self.entries.iter().position(|entry| score > entry.score).unwrap_or(self.entries.len());
+
+        self.entries.insert(rank, HighScore { initials, score });
+        self.entries.truncate(Self::CAPACITY);
+
+        Some(rank)
+    }
+}
+
+/// Initials entry
+/// 
+/// Arcade-style entry of three initials: the letter under the cursor is cycled
+/// up or down through the alphabet, and accepted to move on to the next one.
+pub struct InitialsEntry {
+    pub letters: [u8; 3],
+    pub cursor: usize,
+}
+
+impl InitialsEntry {
+    /// Start entering initials, with every letter set to 'A'.
+    pub fn new() -> Self {
+        Self {
+            letters: [b'A'; 3],
+            cursor: 0,
+        }
+    }
+
+    /// Cycle the letter under the cursor forwards (positive) or backwards
+    /// (negative) through the alphabet.
+    pub fn cycle(&mut self, step: i32) {
+        let letter = self.letters[self.cursor] - b'A';
+        self.letters[self.cursor] = b'A' + (letter as i32 + step).rem_euclid(26) as u8;
+    }
+
+    /// Accept the letter under the cursor. Returns true once all letters have
+    /// been accepted.
+    pub fn accept(&mut self) -> bool {
+        self.cursor += 1;
+        self.is_complete()
+    }
+
+    /// Go back to the previous letter.
+    pub fn back(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Check if all letters have been accepted.
+    pub fn is_complete(&self) -> bool {
+        self.cursor >= self.letters.len()
+    }
+
+    /// Initials entered so far.
+    pub fn initials(&self) -> String {
+        self.letters.iter().map(|letter| *letter as char).collect()
+    }
+}
+
+impl Default for InitialsEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,232 @@
+//! Event hooks.
+//!
+//! Small scripted events fire as a game reaches a wave or a score, or when it
+//! is played on a particular date. The game uses them for a few easter eggs:
+//! a lucky gold saucer drops by on wave 13 for a word with the player, and
+//! confetti falls on the anniversary of the day the game was first played.
+//!
+//! A hook is plain data, a trigger and an action, so hooks need no code of
+//! their own to register. Mods register theirs alongside the built in ones,
+//! through `GameWorld::hooks` or from the scripting layer. Each hook fires at
+//! most once a game, the first time its trigger is met.
+
+use crate::savedata::{self, SaveFormat};
+
+/// Trigger
+///
+/// What a hook waits for.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Trigger {
+    /// A wave starts.
+    Wave(u32),
+    /// The score reaches at least a number of points.
+    Score(u32),
+    /// The game is played on a day of the year, its month and day numbered
+    /// from one.
+    Date { month: u32, day: u32 },
+    /// The game is played a whole number of years after a time, in seconds
+    /// since the epoch.
+    Anniversary(f64),
+}
+
+impl Trigger {
+    /// Check if the trigger is met in a game.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::hooks::{HookContext, Trigger};
+    /// // Noon on 13 May 2025, and a year later
+    /// let first_played = 1_747_137_600.0;
+    /// let context = HookContext { wave: 13, score: 5000, date: first_played + 365.0 * 86_400.0 };
+    ///
+    /// assert!(Trigger::Wave(13).is_met(&context));
+    /// assert!(Trigger::Score(5000).is_met(&context));
+    /// assert!(!Trigger::Score(5001).is_met(&context));
+    /// assert!(Trigger::Date { month: 5, day: 13 }.is_met(&context));
+    /// assert!(Trigger::Anniversary(first_played).is_met(&context));
+    ///
+    /// // Not on the day itself
+    /// assert!(!Trigger::Anniversary(context.date).is_met(&context));
+    /// ```
+    pub fn is_met(&self, context: &HookContext) -> bool {
+        match self {
+            Trigger::Wave(wave) => context.wave == *wave,
+            Trigger::Score(score) => context.score >= *score,
+            Trigger::Date { month, day } => {
+                let (_, today_month, today_day) = calendar_date(context.date);
+                (today_month, today_day) == (*month, *day)
+            },
+            Trigger::Anniversary(since) => {
+                let (year, month, day) = calendar_date(*since);
+                let (today_year, today_month, today_day) = calendar_date(context.date);
+
+                today_year > year && (today_month, today_day) == (month, day)
+            },
+        }
+    }
+}
+
+/// Hook action
+///
+/// What a hook does once fired. The world carries the actions out.
+#[derive(Clone, PartialEq, Debug)]
+pub enum HookAction {
+    /// A lucky gold saucer flies by. It never shoots, and is worth a bonus.
+    LuckySaucer,
+    /// Confetti falls over the field.
+    Confetti,
+    /// A line of text is shown across the field.
+    Message(String),
+}
+
+/// Hook
+///
+/// A trigger, the action it sets off, and whether it has already fired this
+/// game.
+#[derive(Clone, Debug)]
+pub struct Hook {
+    pub trigger: Trigger,
+    pub action: HookAction,
+    fired: bool,
+}
+
+/// Hook context
+///
+/// What hooks are checked against: the wave and score of the game, and the
+/// date it is played on, in seconds since the epoch.
+#[derive(Clone, Copy, Debug)]
+pub struct HookContext {
+    pub wave: u32,
+    pub score: u32,
+    pub date: f64,
+}
+
+/// Event hooks
+///
+/// Hooks waiting to fire during a game.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::hooks::{EventHooks, HookAction, HookContext, Trigger};
+/// let mut hooks = EventHooks::new();
+/// hooks.register(Trigger::Score(1000), HookAction::Message("A thousand!".to_string()));
+///
+/// let mut context = HookContext { wave: 1, score: 500, date: 0.0 };
+/// assert!(hooks.check(&context).is_empty());
+///
+/// // Fires once the score is reached, and only once a game
+/// context.score = 1200;
+/// assert_eq!(hooks.check(&context), [HookAction::Message("A thousand!".to_string())]);
+/// assert!(hooks.check(&context).is_empty());
+///
+/// hooks.rearm();
+/// assert_eq!(hooks.check(&context).len(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EventHooks {
+    hooks: Vec<Hook>,
+}
+
+impl EventHooks {
+    /// Wave on which the lucky saucer drops by.
+    pub const LUCKY_WAVE: u32 = 13;
+
+    /// Create a set of hooks with none registered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the hooks for the game's easter eggs, celebrating the
+    /// anniversary of the time the game was first played.
+    pub fn easter_eggs(first_played: f64) -> Self {
+        let mut hooks = Self::new();
+
+        hooks.register(Trigger::Wave(Self::LUCKY_WAVE), HookAction::LuckySaucer);
+        hooks.register(Trigger::Wave(Self::LUCKY_WAVE), HookAction::Message("Saucer: Lucky thirteen! Catch me if you can".to_string()));
+        hooks.register(Trigger::Anniversary(first_played), HookAction::Confetti);
+        hooks.register(Trigger::Anniversary(first_played), HookAction::Message("Happy anniversary, pilot!".to_string()));
+
+        hooks
+    }
+
+    /// Register a hook setting off an action when a trigger is met.
+    pub fn register(&mut self, trigger: Trigger, action: HookAction) {
+        self.hooks.push(Hook { trigger, action, fired: false });
+    }
+
+    /// Number of hooks registered.
+    pub fn len(&self) -> usize {
+        self.hooks.len()
+    }
+
+    /// Check if no hooks are registered.
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Let every hook fire again, for example when a new game starts.
+    pub fn rearm(&mut self) {
+        for hook in &mut self.hooks {
+            hook.fired = false;
+        }
+    }
+
+    /// Fire the hooks whose triggers are now met, returning their actions in
+    /// the order they were registered.
+    pub fn check(&mut self, context: &HookContext) -> Vec<HookAction> {
+        self.hooks.iter_mut()
+            .filter(|hook| !hook.fired && hook.trigger.is_met(context))
+            .map(|hook| {
+                hook.fired = true;
+                hook.action.clone()
+            })
+            .collect()
+    }
+}
+
+/// Storage key under which the time the game was first played is saved.
+const FIRST_PLAYED_KEY: &str = "first_played.txt";
+
+/// Format the time the game was first played is saved in.
+const FIRST_PLAYED_FORMAT: SaveFormat = SaveFormat::INITIAL;
+
+/// Time the game was first played, in seconds since the epoch. The first time
+/// it is asked for, that is now, and it is saved for later sessions.
+pub fn first_played() -> f64 {
+    savedata::load(FIRST_PLAYED_KEY, &FIRST_PLAYED_FORMAT, |text| text.trim().parse().ok())
+        .unwrap_or_else(|| {
+            let now = macroquad::miniquad::date::now();
+            savedata::save(FIRST_PLAYED_KEY, &FIRST_PLAYED_FORMAT, &now.to_string());
+
+            now
+        })
+}
+
+/// Calendar date, as its year, month and day, of a time in seconds since the
+/// epoch, in UTC.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::hooks::calendar_date;
+/// assert_eq!(calendar_date(0.0), (1970, 1, 1));
+/// assert_eq!(calendar_date(951_782_400.0), (2000, 2, 29));
+/// assert_eq!(calendar_date(1_767_225_599.0), (2025, 12, 31));
+/// ```
+pub fn calendar_date(time: f64) -> (i64, u32, u32) {
+    // Days since the epoch, counted in 400-year eras starting on 1 March
+    let days = (time / 86_400.0).floor() as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
@@ -0,0 +1,153 @@
+//! Hot reloading.
+//!
+//! Debug builds on native platforms watch the balance file and the saved
+//! settings while the game runs, so they can be tuned without restarting it.
+//! The files are checked once a second; when one has been changed since, it
+//! is read again and put into effect. A new balance applies to whatever is
+//! spawned from then on, and to the ship from the next game; objects already
+//! in play keep the balance they were made with. A balance that cannot be
+//! parsed is reported and the one in play is kept.
+
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::assets;
+use crate::config::{self, Balance};
+use crate::settings::Settings;
+use crate::storage;
+use crate::world::GameWorld;
+
+/// Reload
+///
+/// A watched file that has changed, read again.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Reload {
+    /// The balance file, parsed, or the reason it could not be.
+    Balance(Result<Balance, String>),
+    /// The saved settings.
+    Settings,
+}
+
+/// Watched file
+///
+/// A file being watched, and when it was last seen modified.
+struct WatchedFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl WatchedFile {
+    /// Start watching a file as it is now.
+    fn new(path: PathBuf) -> Self {
+        let modified = Self::modified(&path);
+        Self { path, modified }
+    }
+
+    /// When a file was last modified, if it exists.
+    fn modified(path: &PathBuf) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    /// Check if the file has been modified, created or removed since it was
+    /// last checked.
+    fn has_changed(&mut self) -> bool {
+        let modified = Self::modified(&self.path);
+        let changed = modified != self.modified;
+
+        self.modified = modified;
+        changed
+    }
+}
+
+/// Hot reload
+///
+/// The balance file and saved settings, watched for changes.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::config::Balance;
+/// # use asteroids::hotreload::{HotReload, Reload};
+/// # use std::time::{Duration, SystemTime};
+/// let directory = std::env::temp_dir().join("asteroids-hotreload-doctest");
+/// std::fs::create_dir_all(&directory).unwrap();
+///
+/// let path = directory.join(Balance::FILE);
+/// std::fs::write(&path, "()").unwrap();
+///
+/// let mut hot_reload = HotReload::new(directory.to_str());
+/// assert!(hot_reload.poll(0.0).is_empty());
+///
+/// std::fs::write(&path, "(saucers: (small_points: 2000))").unwrap();
+/// let file = std::fs::File::options().write(true).open(&path).unwrap();
+/// file.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+///
+/// // Changes are only looked for once a second
+/// assert!(hot_reload.poll(0.5).is_empty());
+///
+/// let Some(Reload::Balance(Ok(balance))) = hot_reload.poll(1.0).into_iter().next() else { panic!() };
+/// assert_eq!(balance.saucers.small_points, 2000);
+/// assert!(hot_reload.poll(2.0).is_empty());
+/// ```
+pub struct HotReload {
+    balance: WatchedFile,
+    settings: Option<WatchedFile>,
+    next_poll: f64,
+}
+
+impl HotReload {
+    /// Time in seconds between checks for changes.
+    pub const POLL_INTERVAL: f64 = 1.0;
+
+    /// Watch the balance file in an asset directory, or the default one, and
+    /// the saved settings.
+    pub fn new(directory: Option<&str>) -> Self {
+        let balance = assets::asset_path(directory.unwrap_or(assets::DEFAULT_DIRECTORY), Balance::FILE);
+
+        Self {
+            balance: WatchedFile::new(PathBuf::from(balance)),
+            settings: storage::path(Settings::STORAGE_KEY).map(WatchedFile::new),
+            next_poll: Self::POLL_INTERVAL,
+        }
+    }
+
+    /// Check the watched files at a time, if a check is due, reading again
+    /// those that have changed. A balance file that has been removed reads as
+    /// the built-in balance.
+    pub fn poll(&mut self, now: f64) -> Vec<Reload> {
+        let mut reloads = Vec::new();
+
+        if now < self.next_poll {
+            return reloads;
+        }
+
+        self.next_poll = now + Self::POLL_INTERVAL;
+
+        if self.balance.has_changed() {
+            let balance = match std::fs::read_to_string(&self.balance.path) {
+                Ok(text) => Balance::parse(&text).map_err(|error| format!("Could not read {}: {}", self.balance.path.display(), error)),
+                Err(_) => Ok(Balance::DEFAULT),
+            };
+
+            reloads.push(Reload::Balance(balance));
+        }
+
+        if self.settings.as_mut().is_some_and(WatchedFile::has_changed) {
+            reloads.push(Reload::Settings);
+        }
+
+        reloads
+    }
+
+    /// Check the watched files at a time, putting any that have changed into
+    /// effect in a game world.
+    pub fn apply(&mut self, world: &mut GameWorld, now: f64) {
+        for reload in self.poll(now) {
+            match reload {
+                Reload::Balance(Ok(balance)) => config::set_balance(balance),
+                Reload::Balance(Err(error)) => eprintln!("{}", error),
+                Reload::Settings => world.reload_settings(),
+            }
+        }
+    }
+}
@@ -0,0 +1,495 @@
+//! Heads-up display.
+//!
+//! The score, lives and status readouts drawn over a game, which of them are
+//! shown, and how they are laid out on screen.
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bullet::WeaponKind;
+use crate::coop::Wingman;
+use crate::savedata::{self, SaveFormat};
+use crate::score::format_arcade_score;
+use crate::textcache::CachedText;
+
+/// HUD element
+///
+/// The parts of the HUD that can be shown or hidden on their own.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HudElement {
+    Score,
+    Lives,
+    Wave,
+    Decoys,
+    Credits,
+    Overdrive,
+    Weapon,
+    Combo,
+    Bombs,
+    Shield,
+}
+
+impl HudElement {
+    /// All elements, in the order they are listed in the settings.
+    pub const ALL: [HudElement; 10] = [
+        HudElement::Score,
+        HudElement::Lives,
+        HudElement::Wave,
+        HudElement::Decoys,
+        HudElement::Credits,
+        HudElement::Overdrive,
+        HudElement::Weapon,
+        HudElement::Combo,
+        HudElement::Bombs,
+        HudElement::Shield,
+    ];
+
+    /// Label shown in the settings.
+    pub fn label(&self) -> &'static str {
+        match self {
+            HudElement::Score => "Score",
+            HudElement::Lives => "Lives",
+            HudElement::Wave => "Wave",
+            HudElement::Decoys => "Decoys",
+            HudElement::Credits => "Credits",
+            HudElement::Overdrive => "Overdrive",
+            HudElement::Weapon => "Weapon",
+            HudElement::Combo => "Combo Ring",
+            HudElement::Bombs => "Smart Bombs",
+            HudElement::Shield => "Shield Energy",
+        }
+    }
+}
+
+/// Combo anchor
+///
+/// What the combo ring is drawn around: the score, or the player's ship, where
+/// the eyes already are in the thick of a fight.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum ComboAnchor {
+    #[default]
+    Score,
+    Ship,
+}
+
+impl ComboAnchor {
+    /// Label shown in the settings.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ComboAnchor::Score => "Score",
+            ComboAnchor::Ship => "Ship",
+        }
+    }
+
+    /// The other anchor.
+    pub fn next(&self) -> ComboAnchor {
+        match self {
+            ComboAnchor::Score => ComboAnchor::Ship,
+            ComboAnchor::Ship => ComboAnchor::Score,
+        }
+    }
+}
+
+/// HUD preset
+///
+/// Ready-made choices of HUD elements: the classic score-only look, the
+/// essentials of score, lives, wave and shield energy, or everything.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HudPreset {
+    Minimal,
+    Standard,
+    Full,
+}
+
+impl HudPreset {
+    /// All presets, in the order they are cycled through.
+    pub const ALL: [HudPreset; 3] = [HudPreset::Minimal, HudPreset::Standard, HudPreset::Full];
+
+    /// Label shown in the settings.
+    pub fn label(&self) -> &'static str {
+        match self {
+            HudPreset::Minimal => "Minimal",
+            HudPreset::Standard => "Standard",
+            HudPreset::Full => "Full",
+        }
+    }
+
+    /// Preset following this one, wrapping around to the first.
+    pub fn next(&self) -> HudPreset {
+        match self {
+            HudPreset::Minimal => HudPreset::Standard,
+            HudPreset::Standard => HudPreset::Full,
+            HudPreset::Full => HudPreset::Minimal,
+        }
+    }
+}
+
+/// HUD layout
+///
+/// Which HUD elements are shown, and what the combo ring is drawn around. The
+/// layout is saved between sessions, so streamers keep their clean screen and
+/// purists their classic look. Presets leave the combo ring where it is.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::hud::{ComboAnchor, HudElement, HudLayout, HudPreset};
+/// let mut layout = HudLayout::from_preset(HudPreset::Minimal);
+/// assert!(layout.is_shown(HudElement::Score));
+/// assert!(!layout.is_shown(HudElement::Lives));
+///
+/// layout.toggle(HudElement::Lives);
+/// layout.toggle(HudElement::Wave);
+/// layout.toggle(HudElement::Shield);
+/// assert_eq!(layout.preset(), Some(HudPreset::Standard));
+///
+/// layout.combo_anchor = ComboAnchor::Ship;
+/// assert_eq!(layout.preset(), Some(HudPreset::Standard));
+///
+/// layout.toggle(HudElement::Credits);
+/// assert_eq!(layout.preset(), None);
+/// assert!(HudLayout::parse(&layout.to_toml()).unwrap() == layout);
+/// ```
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HudLayout {
+    pub score: bool,
+    pub lives: bool,
+    pub wave: bool,
+    pub decoys: bool,
+    pub credits: bool,
+    pub overdrive: bool,
+    pub weapon: bool,
+    pub combo: bool,
+    pub combo_anchor: ComboAnchor,
+    pub bombs: bool,
+    pub shield: bool,
+}
+
+impl Default for HudLayout {
+    fn default() -> Self {
+        Self::from_preset(HudPreset::Full)
+    }
+}
+
+impl HudLayout {
+    /// Storage key under which the layout is saved.
+    const STORAGE_KEY: &'static str = "hud.toml";
+
+    /// Format the layout is saved in.
+    const SAVE_FORMAT: SaveFormat = SaveFormat::INITIAL;
+
+    /// Layout showing the elements of a preset.
+    pub fn from_preset(preset: HudPreset) -> Self {
+        let standard = preset != HudPreset::Minimal;
+        let full = preset == HudPreset::Full;
+
+        Self {
+            score: true,
+            lives: standard,
+            wave: standard,
+            decoys: full,
+            credits: full,
+            overdrive: full,
+            weapon: full,
+            combo: full,
+            combo_anchor: ComboAnchor::default(),
+            bombs: full,
+            shield: standard,
+        }
+    }
+
+    /// Preset the layout matches, if it matches any, wherever its combo ring
+    /// is drawn.
+    pub fn preset(&self) -> Option<HudPreset> {
+        HudPreset::ALL.into_iter().find(|preset| {
+            Self { combo_anchor: self.combo_anchor, ..Self::from_preset(*preset) } == *self
+        })
+    }
+
+    /// Check if an element is shown.
+    pub fn is_shown(&self, element: HudElement) -> bool {
+        match element {
+            HudElement::Score => self.score,
+            HudElement::Lives => self.lives,
+            HudElement::Wave => self.wave,
+            HudElement::Decoys => self.decoys,
+            HudElement::Credits => self.credits,
+            HudElement::Overdrive => self.overdrive,
+            HudElement::Weapon => self.weapon,
+            HudElement::Combo => self.combo,
+            HudElement::Bombs => self.bombs,
+            HudElement::Shield => self.shield,
+        }
+    }
+
+    /// Show an element if it is hidden, or hide it if it is shown.
+    pub fn toggle(&mut self, element: HudElement) {
+        let shown = match element {
+            HudElement::Score => &mut self.score,
+            HudElement::Lives => &mut self.lives,
+            HudElement::Wave => &mut self.wave,
+            HudElement::Decoys => &mut self.decoys,
+            HudElement::Credits => &mut self.credits,
+            HudElement::Overdrive => &mut self.overdrive,
+            HudElement::Weapon => &mut self.weapon,
+            HudElement::Combo => &mut self.combo,
+            HudElement::Bombs => &mut self.bombs,
+            HudElement::Shield => &mut self.shield,
+        };
+
+        *shown = !*shown;
+    }
+
+    /// Load the saved layout. Elements that cannot be read keep their
+    /// defaults, as does the whole layout if none is saved.
+    pub fn load() -> Self {
+        savedata::load(Self::STORAGE_KEY, &Self::SAVE_FORMAT, |text| {
+            Self::parse(text).ok().or_else(|| savedata::salvage_toml(text))
+        })
+        .unwrap_or_default()
+    }
+
+    /// Save the layout.
+    pub fn save(&self) {
+        savedata::save(Self::STORAGE_KEY, &Self::SAVE_FORMAT, &self.to_toml());
+    }
+
+    /// Parse a layout from a TOML document.
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// Format the layout as a TOML document.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).unwrap_or_default()
+    }
+}
+
+/// HUD status
+///
+/// What the HUD shows on a frame, gathered from the game world. During a
+/// challenge the clock, in seconds, is shown in place of the wave; the arc's
+/// readiness is only known once it has been unlocked, and the combo only while
+/// one is running. The shield's energy runs from 0 to 1, and it is ready once
+/// it holds enough to be raised.
+pub struct HudStatus {
+    pub score: u32,
+    pub lives: u32,
+    pub wave: u32,
+    pub time: Option<u32>,
+    pub decoys: u32,
+    pub bombs: u32,
+    pub credits: u32,
+    pub arc_ready: Option<bool>,
+    pub overdrive_label: &'static str,
+    pub overdrive_meter: f32,
+    pub shield_energy: f32,
+    pub shield_ready: bool,
+    pub combo: Option<(u32, f32)>,
+    pub weapon: WeaponKind,
+    pub chronos: u32,
+    pub wingman: Option<(u32, u32)>,
+}
+
+/// HUD
+///
+/// Draws the HUD in the classic arcade style: the score as a zero-padded
+/// counter in the top left, with a row of ship outlines beneath it for the
+/// lives in reserve, and readouts down the right. The second player's score
+/// and lives sit in the bottom left, in their ship's colour. A running combo
+/// is shown as a ring beside the score, running down as the time to keep the
+/// combo alive does. Text is only formatted again when the values shown
+/// change.
+#[derive(Default)]
+pub struct Hud {
+    score: CachedText<u32>,
+    wave: CachedText<u32>,
+    time: CachedText<u32>,
+    decoys: CachedText<u32>,
+    bombs: CachedText<u32>,
+    credits: CachedText<u32>,
+    weapon: CachedText<WeaponKind>,
+    chronos: CachedText<u32>,
+    wingman_score: CachedText<u32>,
+    combo: CachedText<u32>,
+}
+
+impl Hud {
+    /// Font size of HUD text.
+    const FONT_SIZE: u16 = 30;
+
+    /// Outline of a life icon: the ship's shape, nose up.
+    const SHIP_ICON: [Vec2; 4] = [
+        Vec2::new(0.0, -10.0),
+        Vec2::new(5.0, 5.0),
+        Vec2::new(0.0, 3.0),
+        Vec2::new(-5.0, 5.0),
+    ];
+
+    /// Horizontal distance between life icons.
+    const ICON_SPACING: f32 = 16.0;
+
+    /// Most life icons drawn; any more lives are shown as a count.
+    const MAX_ICONS: u32 = 8;
+
+    /// Centre of the combo ring beside the score.
+    const COMBO_CENTRE: Vec2 = Vec2::new(50.0, 30.0);
+
+    /// Radius of the combo ring beside the score.
+    const COMBO_RADIUS: f32 = 18.0;
+
+    /// Draw the elements of a layout showing a status.
+    pub fn draw(&self, font: &Font, layout: &HudLayout, status: &HudStatus) {
+        let right = screen_width() * 0.75;
+
+        // Draw score
+        if layout.score {
+            Self::draw_label(font, &self.score.get(status.score, format_arcade_score), 80.0, 40.0, WHITE);
+        }
+
+        // Draw the running combo beside the score
+        if let (true, ComboAnchor::Score, Some(combo)) = (layout.combo, layout.combo_anchor, status.combo) {
+            self.draw_combo_ring(font, combo, Self::COMBO_CENTRE, Self::COMBO_RADIUS);
+        }
+
+        // Draw lives as ships in reserve
+        if layout.lives {
+            Self::draw_lives(font, status.lives, Vec2::new(85.0, 65.0), WHITE);
+        }
+
+        // Draw wave number, or the clock during a challenge
+        if layout.wave {
+            let text = match status.time {
+                Some(seconds) => self.time.get(seconds, |seconds| format!("Time: {}", seconds)),
+                None => self.wave.get(status.wave, |wave| format!("Wave: {}", wave)),
+            };
+
+            Self::draw_label(font, &text, right, 40.0, WHITE);
+        }
+
+        // Draw chain lightning charge
+        if let (true, Some(ready)) = (layout.weapon, status.arc_ready) {
+            Self::draw_label(font, if ready { "Arc: Ready" } else { "Arc: Charging" }, right, 80.0, WHITE);
+        }
+
+        // Draw overdrive meter
+        if layout.overdrive {
+            Self::draw_label(font, status.overdrive_label, right, 120.0, WHITE);
+
+            draw_rectangle_lines(right, 135.0, 150.0, 12.0, 2.0, WHITE);
+            draw_rectangle(right, 135.0, 150.0 * status.overdrive_meter, 12.0, WHITE);
+        }
+
+        // Draw shield energy, greyed out until the shield can be raised
+        if layout.shield {
+            let color = if status.shield_ready { WHITE } else { GRAY };
+
+            Self::draw_label(font, "Shield", right, 260.0, color);
+
+            draw_rectangle_lines(right, 275.0, 150.0, 12.0, 2.0, color);
+            draw_rectangle(right, 275.0, 150.0 * status.shield_energy, 12.0, color);
+        }
+
+        // Draw fitted weapon, unless it is the standard cannon
+        if layout.weapon && status.weapon != WeaponKind::Standard {
+            let text = self.weapon.get(status.weapon, |weapon| format!("Weapon: {}", weapon.label()));
+            Self::draw_label(font, &text, right, 180.0, WHITE);
+        }
+
+        // Draw chronos held, if any
+        if layout.weapon && status.chronos > 0 {
+            let text = self.chronos.get(status.chronos, |chronos| format!("Rewinds: {}", chronos));
+            Self::draw_label(font, &text, right, 220.0, WHITE);
+        }
+
+        // Draw remaining decoys
+        if layout.decoys {
+            Self::draw_label(font, &self.decoys.get(status.decoys, |decoys| format!("Decoys: {}", decoys)), 80.0, 120.0, WHITE);
+        }
+
+        // Draw remaining smart bombs
+        if layout.bombs {
+            Self::draw_label(font, &self.bombs.get(status.bombs, |bombs| format!("Bombs: {}", bombs)), 80.0, 200.0, WHITE);
+        }
+
+        // Draw credits available in the shop
+        if layout.credits {
+            Self::draw_label(font, &self.credits.get(status.credits, |credits| format!("Credits: {}", credits)), 80.0, 160.0, WHITE);
+        }
+
+        // Draw the second player's score and lives in their ship's colour
+        if let Some((score, lives)) = status.wingman {
+            if layout.score {
+                let text = self.wingman_score.get(score, |score| format!("P2 {}", format_arcade_score(score)));
+                Self::draw_label(font, &text, 80.0, screen_height() - 60.0, Wingman::COLOR);
+            }
+
+            if layout.lives {
+                Self::draw_lives(font, lives, Vec2::new(85.0, screen_height() - 35.0), Wingman::COLOR);
+            }
+        }
+    }
+
+    /// Draw a combo, its multiplier in a ring around a centre, the ring
+    /// running down clockwise from the top with the part of the combo's time
+    /// left.
+    pub fn draw_combo_ring(&self, font: &Font, (multiplier, remaining): (u32, f32), centre: Vec2, radius: f32) {
+        draw_arc(centre.x, centre.y, 48, radius, -90.0, 3.0, 360.0 * remaining, WHITE);
+
+        let text = self.combo.get(multiplier, |multiplier| format!("x{}", multiplier));
+        let size = measure_text(&text, Some(font), 18, 1.0);
+
+        draw_text_ex(
+            &text, centre.x - size.width / 2.0, centre.y + size.offset_y / 2.0,
+            TextParams {
+                font_size: 18,
+                font: Some(font),
+                color: WHITE,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Draw a line of HUD text.
+    fn draw_label(font: &Font, text: &str, x: f32, y: f32, color: Color) {
+        draw_text_ex(
+            text, x, y,
+            TextParams {
+                font_size: Self::FONT_SIZE,
+                font: Some(font),
+                color,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Draw a row of ship outlines, one for each life, starting at a position.
+    /// Too many lives to fit are shown as a single ship and a count.
+    fn draw_lives(font: &Font, lives: u32, position: Vec2, color: Color) {
+        let icons = if lives > Self::MAX_ICONS { 1 } else { lives };
+
+        for i in 0..icons {
+            let centre = position + Vec2::new(i as f32 * Self::ICON_SPACING, 0.0);
+
+            for (j, vertex) in Self::SHIP_ICON.iter().enumerate() {
+                let start = centre + *vertex;
+                let end = centre + Self::SHIP_ICON[(j + 1) % Self::SHIP_ICON.len()];
+
+                draw_line(start.x, start.y, end.x, end.y, 1.5, color);
+            }
+        }
+
+        if lives > Self::MAX_ICONS {
+            draw_text_ex(
+                &format!("x{}", lives), position.x + Self::ICON_SPACING, position.y + 8.0,
+                TextParams {
+                    font_size: 24,
+                    font: Some(font),
+                    color,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
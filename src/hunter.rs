@@ -0,0 +1,303 @@
+//! Hunter ships.
+//!
+//! From wave 7 of a classic, co-op or weekly game, a hunter joins partway
+//! through each wave: an enemy ship flown like the player's, turning and
+//! thrusting rather than drifting on a set course. It pursues the nearest
+//! ship, or a decoy nearer still, turning to lead it and firing aimed bursts
+//! once lined up, and breaks off to dodge player bullets and asteroids on
+//! course to hit it. A hunter stays until it is destroyed or the wave ends.
+
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::ai::{self, PilotCommand, Target};
+use crate::asteroid::Asteroid;
+use crate::bullet::{Bullet, BulletType};
+use crate::camera::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::collision::{wrapped_delta, Collidable, Outline};
+use crate::config;
+use crate::detmath;
+use crate::entity::EntityId;
+use crate::linebatch::LineBatch;
+use crate::wrap::{self, WrapDraw};
+
+/// Hunter
+///
+/// An enemy ship that hunts the players down.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::ai::Target;
+/// # use asteroids::bullet::{Bullet, BulletType};
+/// # use asteroids::hunter::Hunter;
+/// # use macroquad::prelude::*;
+/// let mut hunter = Hunter::new(Vec2::new(100.0, 300.0), 0.0, 0.0);
+///
+/// // A ship dead ahead is fired on with a burst
+/// let target = Target { position: Vec2::new(400.0, 300.0), velocity: Vec2::ZERO };
+/// let command = hunter.pilot(target, &[], &[]);
+/// assert!(command.fire && command.thrust);
+///
+/// assert!(hunter.shoot(command.fire, 0.0).is_some());
+/// assert!(hunter.shoot(false, Hunter::BURST_GAP).is_some());
+/// assert!(hunter.shoot(false, Hunter::BURST_GAP * 2.0).is_some());
+/// assert!(hunter.shoot(true, Hunter::BURST_GAP * 3.0).is_none());
+///
+/// // A bullet on course to hit it is dodged rather than the target chased
+/// let bullet = Bullet::spawn_new(Vec2::new(100.0, 200.0), Vec2::new(0.0, 5.0), 100.0, BulletType::Player);
+/// let command = hunter.pilot(target, &[bullet], &[]);
+/// assert!(!command.fire && command.thrust);
+/// assert!(command.steer != 0.0);
+/// ```
+#[derive(Clone)]
+pub struct Hunter {
+    pub id: EntityId,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub rotation: f32,
+    pub radius: f32,
+    pub shot_speed: f32,
+    pub is_alive: bool,
+    burst_left: u32,
+    next_shot: f64,
+    next_burst: f64,
+    vertices: Vec<Vec2>,
+}
+
+impl Hunter {
+    /// First wave a hunter joins.
+    pub const FIRST_WAVE: u32 = 7;
+
+    /// Time in seconds into a wave before its hunter joins.
+    pub const ARRIVAL_DELAY: f64 = 15.0;
+
+    /// Points scored for destroying a hunter.
+    pub const POINTS: u32 = 1500;
+
+    /// Shots fired in a burst.
+    pub const BURST_SHOTS: u32 = 3;
+
+    /// Time in seconds between the shots of a burst.
+    pub const BURST_GAP: f64 = 0.15;
+
+    /// Time in seconds from the start of one burst to the next.
+    pub const BURST_PERIOD: f64 = 2.5;
+
+    /// Fastest a hunter turns, in radians per frame; a little slower than
+    /// the player.
+    const TURN_RATE: f32 = 0.07;
+
+    /// Angle in radians within which a hunter fires on its target.
+    const AIM_TOLERANCE: f32 = 0.12;
+
+    /// Distance within which a hunter stops closing in, and fires.
+    const STANDOFF: f32 = 250.0;
+    const FIRE_RANGE: f32 = 450.0;
+
+    /// Frames ahead a hunter watches for bullets and asteroids on course to
+    /// hit it, and the margin, as a multiple of its radius, it keeps from
+    /// them.
+    const LOOKAHEAD: f32 = 40.0;
+    const CAUTION: f32 = 2.5;
+
+    /// Colour a hunter is drawn in.
+    const COLOR: Color = Color::new(1.0, 0.35, 0.35, 1.0);
+
+    /// Spawn a hunter at a random point on the left or right edge of the
+    /// screen, facing in, with its first burst a second off.
+    pub fn spawn_new(rng: &RandGenerator, now: f64) -> Self {
+        let (x, rotation) = if rng.gen_range(0, 2) == 0 { (0.0, 0.0) } else { (WORLD_WIDTH, std::f32::consts::PI) };
+
+        Self::new(Vec2::new(x, rng.gen_range(0.0, WORLD_HEIGHT)), rotation, now + 1.0)
+    }
+
+    /// Hunter at a position, facing along a rotation, ready to fire its first
+    /// burst at a time.
+    pub fn new(position: Vec2, rotation: f32, first_burst: f64) -> Self {
+        let screen_edge: f32 = WORLD_WIDTH.min(WORLD_HEIGHT);
+        let radius = screen_edge * 0.02;
+
+        // An arrowhead with swept-back wings, pointing along the x axis
+        let vertices = vec![
+            Vec2::new(radius * 1.2, 0.0),
+            Vec2::new(-radius, radius),
+            Vec2::new(-radius * 0.4, 0.0),
+            Vec2::new(-radius, -radius),
+        ];
+
+        Self {
+            id: EntityId::next(),
+            position,
+            velocity: Vec2::ZERO,
+            rotation,
+            radius,
+            shot_speed: 3.0,
+            is_alive: true,
+            burst_left: 0,
+            next_shot: 0.0,
+            next_burst: first_burst,
+            vertices,
+        }
+    }
+
+    /// Thrust, per frame, of a hunter's engine; the same as the player's.
+    fn thrust() -> f32 {
+        WORLD_WIDTH.min(WORLD_HEIGHT) * 0.0003
+    }
+
+    /// Top speed of a hunter, a little below the player's.
+    fn max_speed() -> f32 {
+        WORLD_WIDTH.min(WORLD_HEIGHT) * 0.004
+    }
+
+    /// Destroy hunter by marking it dead.
+    pub fn destroy(&mut self) {
+        self.is_alive = false;
+    }
+
+    /// Check if hunter is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.is_alive
+    }
+
+    /// Frames until an object at an offset from the hunter, moving with a
+    /// velocity relative to it, comes within a distance of it, if it does so
+    /// within the lookahead. Returns the frames and the offset at closest
+    /// approach.
+    fn approach(delta: Vec2, velocity: Vec2, distance: f32) -> Option<(f32, Vec2)> {
+        let speed_squared = velocity.length_squared();
+
+        if speed_squared < f32::EPSILON {
+            return None;
+        }
+
+        let time = (-delta.dot(velocity) / speed_squared).clamp(0.0, Self::LOOKAHEAD);
+        let closest = delta + velocity * time;
+
+        (time > 0.0 && closest.length() < distance).then_some((time, closest))
+    }
+
+    /// Decide the controls for this frame. The threat closest to impact, a
+    /// player bullet or an asteroid, is dodged by turning away from its path
+    /// and thrusting; with none, the hunter turns to lead its target, closes
+    /// in to a standoff distance, and fires once lined up and in range.
+    pub fn pilot(&self, target: Target, bullets: &[Bullet], asteroids: &[Asteroid]) -> PilotCommand {
+        let bullet_threats = bullets.iter()
+            .filter(|bullet| bullet.is_alive() && bullet.bullet_type == BulletType::Player)
+            .map(|bullet| (bullet.position, bullet.velocity, bullet.get_radius()));
+
+        let asteroid_threats = asteroids.iter()
+            .filter(|asteroid| asteroid.is_alive())
+            .map(|asteroid| (asteroid.position, asteroid.velocity, asteroid.get_radius()));
+
+        let threat = bullet_threats.chain(asteroid_threats)
+            .filter_map(|(position, velocity, radius)| {
+                let delta = wrapped_delta(self.position, position);
+                Self::approach(delta, velocity - self.velocity, radius + self.radius * Self::CAUTION)
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        if let Some((_, closest)) = threat {
+            // Head away from where the threat passes closest
+            let away = (-closest).try_normalize().unwrap_or(detmath::direction(self.rotation + std::f32::consts::FRAC_PI_2));
+
+            return PilotCommand {
+                steer: self.turn_towards(detmath::angle_of(away)),
+                thrust: true,
+                ..Default::default()
+            };
+        }
+
+        let delta = wrapped_delta(self.position, target.position);
+        let aim = ai::lead(delta, target.velocity, self.shot_speed);
+        let steer = self.turn_towards(detmath::angle_of(aim));
+        let error = detmath::angle_of(detmath::direction(detmath::angle_of(aim) - self.rotation)).abs();
+
+        PilotCommand {
+            steer,
+            thrust: delta.length() > Self::STANDOFF && error < std::f32::consts::FRAC_PI_2,
+            fire: error < Self::AIM_TOLERANCE && delta.length() < Self::FIRE_RANGE,
+            hyperspace: false,
+        }
+    }
+
+    /// Turn rate that brings the hunter round towards an angle.
+    fn turn_towards(&self, angle: f32) -> f32 {
+        let turn = detmath::angle_of(detmath::direction(angle - self.rotation));
+        turn.clamp(-Self::TURN_RATE, Self::TURN_RATE)
+    }
+
+    /// Fly the hunter by a pilot's command: turn, thrust up to its top speed,
+    /// move, wrap around the screen and coast down with drag, as the player's
+    /// ship does.
+    pub fn update(&mut self, command: &PilotCommand) {
+        self.rotation += command.steer;
+
+        if command.thrust {
+            self.velocity += detmath::direction(self.rotation) * Self::thrust();
+            self.velocity = self.velocity.clamp_length_max(Self::max_speed());
+        }
+
+        self.position += self.velocity;
+        self.position = wrap::wrap(self.position, self.radius);
+        self.velocity *= config::balance().ship.drag;
+    }
+
+    /// Shoot at a time, starting a burst if told to fire and the last one
+    /// has had its period, then firing the rest of the burst a shot at a
+    /// time whether lined up or not.
+    pub fn shoot(&mut self, fire: bool, now: f64) -> Option<Bullet> {
+        if self.burst_left == 0 {
+            if !fire || now < self.next_burst {
+                return None;
+            }
+
+            self.burst_left = Self::BURST_SHOTS;
+            self.next_burst = now + Self::BURST_PERIOD;
+            self.next_shot = now;
+        }
+
+        if now < self.next_shot {
+            return None;
+        }
+
+        self.burst_left -= 1;
+        self.next_shot = now + Self::BURST_GAP;
+
+        let heading = detmath::direction(self.rotation);
+        let muzzle = self.position + heading * self.radius * 1.2;
+
+        Some(Bullet::spawn_new(muzzle, heading * self.shot_speed, 100.0, BulletType::Enemy))
+    }
+
+    /// Draw hunter into a batch of lines in a colour, such as a faded white
+    /// when it is out of sight, tinting it red.
+    pub fn draw_tinted(&self, lines: &mut LineBatch, color: Color) {
+        let color = Color { a: color.a, ..Self::COLOR };
+        let rotation = detmath::rotation(self.rotation);
+
+        self.draw_wrapped(|position| lines.push_polygon(position, &self.vertices, rotation, 2., color));
+    }
+}
+
+impl Collidable for Hunter {
+    fn is_colliding(&self, other: &dyn Collidable) -> bool {
+        <dyn Collidable>::shape_intersection(self, other)
+    }
+
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn get_radius(&self) -> f32 {
+        self.radius
+    }
+
+    fn get_outline(&self) -> Option<Outline<'_>> {
+        Some(Outline {
+            vertices: &self.vertices,
+            rotation: self.rotation,
+        })
+    }
+}
@@ -0,0 +1,370 @@
+//! Keyboard bindings.
+//!
+//! Every gameplay action is bound to a single key. Bindings are stored as a
+//! TOML document alongside the other saved data, with one `action = "Key"`
+//! entry per action, for example:
+//!
+//! ```toml
+//! steer_left = "Left"
+//! steer_right = "Right"
+//! thrust = "Up"
+//! fire = "Space"
+//! hyperspace = "Down"
+//! ```
+//!
+//! Key names are those of macroquad's `KeyCode`. Actions missing from the file
+//! keep their default key, so the file may list only the keys that differ.
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::savedata::{self, SaveFormat};
+
+/// Action
+///
+/// The gameplay actions that can be bound to a key, in the order they are
+/// listed on the controls screen.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    SteerLeft,
+    SteerRight,
+    Thrust,
+    Fire,
+    Hyperspace,
+    ReverseThrust,
+    StrafeLeft,
+    StrafeRight,
+    Decoy,
+    Lightning,
+    Overdrive,
+    Radar,
+    Rewind,
+    Bomb,
+    Shield,
+}
+
+impl Action {
+    /// All actions, in menu order.
+    pub const ALL: [Action; 15] = [
+        Action::SteerLeft,
+        Action::SteerRight,
+        Action::Thrust,
+        Action::Fire,
+        Action::Hyperspace,
+        Action::ReverseThrust,
+        Action::StrafeLeft,
+        Action::StrafeRight,
+        Action::Decoy,
+        Action::Lightning,
+        Action::Overdrive,
+        Action::Radar,
+        Action::Rewind,
+        Action::Bomb,
+        Action::Shield,
+    ];
+
+    /// Label shown on the controls screen.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::SteerLeft => "Steer Left",
+            Action::SteerRight => "Steer Right",
+            Action::Thrust => "Thrust",
+            Action::Fire => "Fire",
+            Action::Hyperspace => "Hyperspace",
+            Action::ReverseThrust => "Reverse Thrust",
+            Action::StrafeLeft => "Strafe Left",
+            Action::StrafeRight => "Strafe Right",
+            Action::Decoy => "Decoy",
+            Action::Lightning => "Chain Lightning",
+            Action::Overdrive => "Overdrive",
+            Action::Radar => "Radar Pulse",
+            Action::Rewind => "Rewind",
+            Action::Bomb => "Smart Bomb",
+            Action::Shield => "Shield",
+        }
+    }
+}
+
+/// Control scheme
+///
+/// Ready-made sets of bindings: the arrow keys, or WASD for players who keep
+/// their left hand on the letters. The WASD keys are also the wingman's, so the
+/// arrow keys suit co-op and versus games better.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ControlScheme {
+    Arrows,
+    Wasd,
+}
+
+impl ControlScheme {
+    /// All schemes, in the order they are cycled through.
+    pub const ALL: [ControlScheme; 2] = [ControlScheme::Arrows, ControlScheme::Wasd];
+
+    /// Label shown on the options screen.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ControlScheme::Arrows => "Arrows",
+            ControlScheme::Wasd => "WASD",
+        }
+    }
+
+    /// Scheme following this one, wrapping around to the first.
+    pub fn next(&self) -> ControlScheme {
+        match self {
+            ControlScheme::Arrows => ControlScheme::Wasd,
+            ControlScheme::Wasd => ControlScheme::Arrows,
+        }
+    }
+
+    /// Bindings making up the scheme.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::input::{Bindings, ControlScheme};
+    /// assert_eq!(Bindings::default().scheme(), Some(ControlScheme::Arrows));
+    /// assert_eq!(ControlScheme::Wasd.bindings().scheme(), Some(ControlScheme::Wasd));
+    /// ```
+    pub fn bindings(&self) -> Bindings {
+        match self {
+            ControlScheme::Arrows => Bindings::default(),
+            ControlScheme::Wasd => Bindings {
+                steer_left: KeyCode::A,
+                steer_right: KeyCode::D,
+                thrust: KeyCode::W,
+                hyperspace: KeyCode::S,
+                ..Bindings::default()
+            },
+        }
+    }
+}
+
+/// Key bindings
+///
+/// Maps each action to a key. Bindings are loaded when the game starts and
+/// saved whenever they are changed on the controls screen.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use asteroids::input::{Action, Bindings};
+/// # use macroquad::prelude::*;
+/// let mut bindings = Bindings::load();
+/// bindings.bind(Action::Fire, KeyCode::LeftControl);
+/// bindings.save();
+/// ```
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bindings {
+    #[serde(with = "key_name")]
+    pub steer_left: KeyCode,
+    #[serde(with = "key_name")]
+    pub steer_right: KeyCode,
+    #[serde(with = "key_name")]
+    pub thrust: KeyCode,
+    #[serde(with = "key_name")]
+    pub fire: KeyCode,
+    #[serde(with = "key_name")]
+    pub hyperspace: KeyCode,
+    #[serde(with = "key_name")]
+    pub reverse_thrust: KeyCode,
+    #[serde(with = "key_name")]
+    pub strafe_left: KeyCode,
+    #[serde(with = "key_name")]
+    pub strafe_right: KeyCode,
+    #[serde(with = "key_name")]
+    pub decoy: KeyCode,
+    #[serde(with = "key_name")]
+    pub lightning: KeyCode,
+    #[serde(with = "key_name")]
+    pub overdrive: KeyCode,
+    #[serde(with = "key_name")]
+    pub radar: KeyCode,
+    #[serde(with = "key_name")]
+    pub rewind: KeyCode,
+    #[serde(with = "key_name")]
+    pub bomb: KeyCode,
+    #[serde(with = "key_name")]
+    pub shield: KeyCode,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            steer_left: KeyCode::Left,
+            steer_right: KeyCode::Right,
+            thrust: KeyCode::Up,
+            fire: KeyCode::Space,
+            hyperspace: KeyCode::Down,
+            reverse_thrust: KeyCode::R,
+            strafe_left: KeyCode::Q,
+            strafe_right: KeyCode::E,
+            decoy: KeyCode::C,
+            lightning: KeyCode::X,
+            overdrive: KeyCode::V,
+            radar: KeyCode::F,
+            rewind: KeyCode::T,
+            bomb: KeyCode::B,
+            shield: KeyCode::LeftShift,
+        }
+    }
+}
+
+impl Bindings {
+    /// Storage key under which bindings are saved.
+    const STORAGE_KEY: &'static str = "bindings.toml";
+
+    /// Format bindings are saved in.
+    const SAVE_FORMAT: SaveFormat = SaveFormat::INITIAL;
+
+    /// Keys that are reserved for menus and cannot be bound: Escape and P pause,
+    /// H toggles the HUD and 2 drops a second player in or out.
+    pub const RESERVED: [KeyCode; 4] = [KeyCode::Escape, KeyCode::P, KeyCode::H, KeyCode::Key2];
+
+    /// Load saved bindings. Bindings that cannot be read keep their defaults,
+    /// as do all of them if none are saved.
+    pub fn load() -> Self {
+        savedata::load(Self::STORAGE_KEY, &Self::SAVE_FORMAT, |text| {
+            Self::parse(text).ok().or_else(|| savedata::salvage_toml(text))
+        })
+        .unwrap_or_default()
+    }
+
+    /// Save bindings.
+    pub fn save(&self) {
+        savedata::save(Self::STORAGE_KEY, &Self::SAVE_FORMAT, &self.to_toml());
+    }
+
+    /// Parse bindings from a TOML document.
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// Format bindings as a TOML document.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).unwrap_or_default()
+    }
+
+    /// Control scheme the bindings match, if they match any.
+    pub fn scheme(&self) -> Option<ControlScheme> {
+        ControlScheme::ALL.into_iter().find(|scheme| scheme.bindings() == *self)
+    }
+
+    /// Key bound to an action.
+    pub fn key(&self, action: Action) -> KeyCode {
+        match action {
+            Action::SteerLeft => self.steer_left,
+            Action::SteerRight => self.steer_right,
+            Action::Thrust => self.thrust,
+            Action::Fire => self.fire,
+            Action::Hyperspace => self.hyperspace,
+            Action::ReverseThrust => self.reverse_thrust,
+            Action::StrafeLeft => self.strafe_left,
+            Action::StrafeRight => self.strafe_right,
+            Action::Decoy => self.decoy,
+            Action::Lightning => self.lightning,
+            Action::Overdrive => self.overdrive,
+            Action::Radar => self.radar,
+            Action::Rewind => self.rewind,
+            Action::Bomb => self.bomb,
+            Action::Shield => self.shield,
+        }
+    }
+
+    /// Mutable reference to the key bound to an action.
+    fn key_mut(&mut self, action: Action) -> &mut KeyCode {
+        match action {
+            Action::SteerLeft => &mut self.steer_left,
+            Action::SteerRight => &mut self.steer_right,
+            Action::Thrust => &mut self.thrust,
+            Action::Fire => &mut self.fire,
+            Action::Hyperspace => &mut self.hyperspace,
+            Action::ReverseThrust => &mut self.reverse_thrust,
+            Action::StrafeLeft => &mut self.strafe_left,
+            Action::StrafeRight => &mut self.strafe_right,
+            Action::Decoy => &mut self.decoy,
+            Action::Lightning => &mut self.lightning,
+            Action::Overdrive => &mut self.overdrive,
+            Action::Radar => &mut self.radar,
+            Action::Rewind => &mut self.rewind,
+            Action::Bomb => &mut self.bomb,
+            Action::Shield => &mut self.shield,
+        }
+    }
+
+    /// Bind a key to an action. If another action is already bound to the key,
+    /// the two actions swap keys so no key is ever bound twice. Returns false,
+    /// leaving the bindings untouched, if the key is reserved.
+    pub fn bind(&mut self, action: Action, key: KeyCode) -> bool {
+        if Self::RESERVED.contains(&key) {
+            return false;
+        }
+
+        let previous = self.key(action);
+
+        if let Some(other) = Action::ALL.iter().find(|other| self.key(**other) == key) {
+            *self.key_mut(*other) = previous;
+        }
+
+        *self.key_mut(action) = key;
+
+        true
+    }
+
+    /// Check if the key bound to an action is held down.
+    pub fn is_down(&self, action: Action) -> bool {
+        is_key_down(self.key(action))
+    }
+
+    /// Check if the key bound to an action was pressed this frame.
+    pub fn is_pressed(&self, action: Action) -> bool {
+        is_key_pressed(self.key(action))
+    }
+}
+
+/// Display name of a key, as used in the bindings file.
+pub fn key_label(key: KeyCode) -> String {
+    format!("{:?}", key)
+}
+
+/// Serialize keys by name rather than by code, so the bindings file can be
+/// edited by hand.
+mod key_name {
+    use macroquad::prelude::KeyCode;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    /// Keys that may appear in a bindings file.
+    const KEYS: [KeyCode; 82] = [
+        KeyCode::Space, KeyCode::Apostrophe, KeyCode::Comma, KeyCode::Minus, KeyCode::Period, KeyCode::Slash,
+        KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+        KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+        KeyCode::Semicolon, KeyCode::Equal,
+        KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+        KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+        KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+        KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+        KeyCode::LeftBracket, KeyCode::Backslash, KeyCode::RightBracket, KeyCode::GraveAccent,
+        KeyCode::Escape, KeyCode::Enter, KeyCode::Tab, KeyCode::Backspace, KeyCode::Insert, KeyCode::Delete,
+        KeyCode::Right, KeyCode::Left, KeyCode::Down, KeyCode::Up,
+        KeyCode::PageUp, KeyCode::PageDown, KeyCode::Home, KeyCode::End,
+        KeyCode::Kp0, KeyCode::Kp1, KeyCode::Kp2, KeyCode::Kp3, KeyCode::Kp4,
+        KeyCode::Kp5, KeyCode::Kp6, KeyCode::Kp7, KeyCode::Kp8, KeyCode::Kp9,
+        KeyCode::KpEnter,
+        KeyCode::LeftShift, KeyCode::LeftControl, KeyCode::LeftAlt, KeyCode::LeftSuper,
+        KeyCode::RightShift, KeyCode::RightControl, KeyCode::RightAlt, KeyCode::RightSuper,
+        KeyCode::Menu,
+    ];
+
+    pub fn serialize<S: Serializer>(key: &KeyCode, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::key_label(*key))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<KeyCode, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        KEYS.iter()
+            .find(|key| super::key_label(**key) == name)
+            .copied()
+            .ok_or_else(|| D::Error::custom(format!("unknown key `{}`", name)))
+    }
+}
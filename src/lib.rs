@@ -0,0 +1,86 @@
+//! Asteroids
+//! 
+//! A remake of the classic arcade game. The simulation lives in this library so
+//! it can be reused outside of the game binary; `GameWorld` is the entry point,
+//! owning every game object and driving updates, collisions and drawing.
+//! 
+
+pub mod achievements;
+pub mod ai;
+pub mod alloc;
+pub mod announcer;
+pub mod assets;
+pub mod asteroid;
+pub mod audio;
+pub mod bench;
+pub mod broadcast;
+pub mod bugreport;
+pub mod bullet;
+pub mod camera;
+pub mod carryover;
+pub mod challenge;
+pub mod chatter;
+pub mod clock;
+pub mod collision;
+pub mod config;
+pub mod coop;
+pub mod decoy;
+pub mod deepfield;
+pub mod detmath;
+pub mod diagnostics;
+pub mod difficulty;
+pub mod director;
+pub mod entity;
+pub mod events;
+pub mod floatingtext;
+pub mod gamepad;
+pub mod goldseam;
+pub mod graphics;
+pub mod gravitywell;
+pub mod highscore;
+pub mod hooks;
+#[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+pub mod hotreload;
+pub mod hunter;
+pub mod hud;
+pub mod input;
+pub mod lifetime;
+pub mod lightning;
+pub mod linebatch;
+pub mod lurker;
+pub mod menu;
+pub mod options;
+pub mod overdrive;
+pub mod overlay;
+pub mod particle;
+pub mod plugin;
+pub mod profile;
+pub mod replay;
+pub mod replaycode;
+pub mod rewind;
+pub mod salvage;
+pub mod savedata;
+pub mod saucer;
+pub mod scenario;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod scripting;
+pub mod score;
+pub mod settings;
+pub mod ship;
+pub mod shockwave;
+pub mod shop;
+pub mod soak;
+pub mod tally;
+pub mod tas;
+pub mod storage;
+pub mod textcache;
+pub mod touch;
+pub mod turret;
+pub mod tutorial;
+pub mod versus;
+pub mod visibility;
+pub mod weekly;
+pub mod world;
+pub mod wrap;
+
+pub use world::GameWorld;
@@ -0,0 +1,96 @@
+//! Lifetime statistics.
+//!
+//! Totals kept across every session: the games played, the points scored in
+//! them all, the furthest wave reached and the asteroids destroyed. A game
+//! counts once it ends, whether by losing the last ship or by finishing a
+//! challenge; games abandoned from the pause menu, watched from a replay code
+//! or played a frame at a time are not counted. The totals are shown on the
+//! stats screen, opened with S from the title screen.
+
+use serde::{Deserialize, Serialize};
+
+use crate::savedata::{self, SaveFormat};
+
+/// Lifetime stats
+///
+/// The player's totals across sessions, stored as TOML under
+/// `lifetime.toml`. Totals missing from the document start from zero.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::lifetime::LifetimeStats;
+/// let mut stats = LifetimeStats::default();
+/// stats.record_game(12000, 7, 140);
+/// stats.record_game(3000, 3, 35);
+///
+/// assert_eq!(stats.games_played, 2);
+/// assert_eq!(stats.total_score, 15000);
+/// assert_eq!(stats.best_wave, 7);
+/// assert_eq!(stats.asteroids_destroyed, 175);
+/// assert_eq!(stats.average_score(), 7500);
+///
+/// assert_eq!(LifetimeStats::parse(&stats.to_toml()).unwrap(), stats);
+/// assert_eq!(LifetimeStats::parse("games_played = 4").unwrap().total_score, 0);
+/// ```
+#[derive(Clone, Default, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub games_played: u32,
+    pub total_score: u64,
+    pub best_wave: u32,
+    pub asteroids_destroyed: u64,
+}
+
+impl LifetimeStats {
+    /// Storage key of the stats.
+    const STORAGE_KEY: &'static str = "lifetime.toml";
+
+    /// Format stats are saved in.
+    const SAVE_FORMAT: SaveFormat = SaveFormat::INITIAL;
+
+    /// Load stats from storage. Missing or unreadable stats start from zero.
+    pub fn load() -> Self {
+        savedata::load(Self::STORAGE_KEY, &Self::SAVE_FORMAT, |text| Self::parse(text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save stats to storage.
+    pub fn save(&self) {
+        savedata::save(Self::STORAGE_KEY, &Self::SAVE_FORMAT, &self.to_toml());
+    }
+
+    /// Parse stats from a TOML document.
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// Format stats as a TOML document.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).unwrap_or_default()
+    }
+
+    /// Add a finished game to the totals.
+    pub fn record_game(&mut self, score: u32, wave: u32, asteroids_destroyed: u32) {
+        self.games_played += 1;
+        self.total_score += score as u64;
+        self.best_wave = self.best_wave.max(wave);
+        self.asteroids_destroyed += asteroids_destroyed as u64;
+    }
+
+    /// Average score of a game, rounded down.
+    pub fn average_score(&self) -> u64 {
+        self.total_score.checked_div(self.games_played as u64).unwrap_or(0)
+    }
+
+    /// Lines of the stats screen, each a label and its value.
+    pub fn lines(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Games played", self.games_played.to_string()),
+            ("Total score", self.total_score.to_string()),
+            ("Average score", self.average_score().to_string()),
+            ("Best wave", self.best_wave.to_string()),
+            ("Asteroids destroyed", self.asteroids_destroyed.to_string()),
+        ]
+    }
+}
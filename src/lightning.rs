@@ -0,0 +1,98 @@
+use macroquad::prelude::*;
+
+use crate::collision::{wrapped_delta, SpatialHash};
+
+/// Chain lightning
+///
+/// A short-range electrical arc. The arc strikes the nearest target within
+/// range of its origin, then jumps from target to target, up to
+/// `MAX_JUMPS` additional times, always picking the nearest target not yet
+/// struck. Each jump deals less damage than the last. The struck path is kept
+/// so it can be drawn as a jagged bolt for a brief moment.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::lightning::ChainLightning;
+/// # use macroquad::prelude::*;
+/// let targets = vec![Vec2::new(100., 100.), Vec2::new(150., 120.)];
+/// let (bolt, hits) = ChainLightning::strike(Vec2::new(80., 80.), &targets, 120.0, 0.0);
+///
+/// assert_eq!(hits.iter().map(|(index, _)| *index).collect::<Vec<_>>(), [0, 1]);
+/// assert!(bolt.is_alive(0.1) && !bolt.is_alive(1.0));
+/// ```
+pub struct ChainLightning {
+    pub path: Vec<Vec2>,
+    pub expiry: f64,
+}
+
+impl ChainLightning {
+    /// Number of jumps after the first strike.
+    pub const MAX_JUMPS: usize = 3;
+
+    /// Damage dealt by the first strike; each jump deals a fraction of the previous.
+    pub const INITIAL_DAMAGE: f32 = 1.0;
+
+    /// Fraction of damage carried over on each jump.
+    pub const DAMAGE_FALLOFF: f32 = 0.6;
+
+    /// Strike targets from an origin at a time. Returns the bolt to draw and
+    /// the struck targets as (index into `targets`, damage) pairs, in strike
+    /// order.
+    pub fn strike(origin: Vec2, targets: &[Vec2], range: f32, now: f64) -> (Self, Vec<(usize, f32)>) {
+        let hash = SpatialHash::build(range, targets);
+
+        let mut hits: Vec<(usize, f32)> = Vec::new();
+        let mut path = vec![origin];
+        let mut current = origin;
+        let mut damage = Self::INITIAL_DAMAGE;
+
+        while hits.len() <= Self::MAX_JUMPS {
+            let Some(index) = hash.nearest(current, range, |i| hits.iter().all(|(hit, _)| *hit != i)) else {
+                break;
+            };
+
+            // Follow the shortest path, which may cross a screen edge
+            let next = *path.last().unwrap() + wrapped_delta(current, targets[index]);
+
+            hits.push((index, damage));
+            path.push(next);
+
+            current = targets[index];
+            damage *= Self::DAMAGE_FALLOFF;
+        }
+
+        let bolt = Self {
+            path,
+            expiry: now + 0.2,
+        };
+
+        (bolt, hits)
+    }
+
+    /// Check if bolt is still visible at a time.
+    pub fn is_alive(&self, now: f64) -> bool {
+        now < self.expiry
+    }
+
+    /// Draw bolt as a jagged line along its path. The jags are regenerated every
+    /// frame so the bolt crackles.
+    pub fn draw(&self) {
+        for segment in self.path.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            let normal = (end - start).perp().normalize_or_zero();
+            let steps = 6;
+
+            let mut previous = start;
+
+            for step in 1..=steps {
+                let t = step as f32 / steps as f32;
+                let jag = if step < steps { rand::gen_range(-8.0, 8.0) } else { 0.0 };
+                let point = start.lerp(end, t) + normal * jag;
+
+                draw_line(previous.x, previous.y, point.x, point.y, 2., WHITE);
+                previous = point;
+            }
+        }
+    }
+}
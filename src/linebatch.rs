@@ -0,0 +1,170 @@
+//! Line batching.
+//!
+//! The game is drawn almost entirely in lines. Drawing each line on its own
+//! rebuilds its quad and hands it to the renderer one at a time, so the
+//! outlines of a field full of asteroids are instead gathered into a single
+//! mesh and drawn together. The batch keeps its buffers between frames, so
+//! once it has grown to the size of a busy frame, drawing allocates nothing.
+//!
+//! The batch draws its lines in a style set by the graphics settings: widened
+//! by a factor, never thinner than a pixel, and optionally with feathered
+//! edges that fade out over a strip either side, smoothing lines where
+//! multisampling is unavailable.
+
+use macroquad::models::{draw_mesh, Mesh, Vertex};
+use macroquad::prelude::*;
+
+/// Line style
+///
+/// How the batch draws its lines: the factor their thickness is scaled by,
+/// the thinnest they may be, and the width of the feathered strip along
+/// either edge, or 0 for hard edges. Thicknesses are in the units of the
+/// camera the lines are drawn through.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LineStyle {
+    pub scale: f32,
+    pub min_thickness: f32,
+    pub feather: f32,
+}
+
+impl Default for LineStyle {
+    fn default() -> Self {
+        Self { scale: 1.0, min_thickness: 0.0, feather: 0.0 }
+    }
+}
+
+/// Line batch
+///
+/// Line segments gathered as quads, waiting to be drawn in one go.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::linebatch::{LineBatch, LineStyle};
+/// # use macroquad::prelude::*;
+/// let mut lines = LineBatch::new();
+/// let square = [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(1.0, 1.0), Vec2::new(-1.0, 1.0)];
+///
+/// lines.push_polygon(Vec2::ZERO, &square, Mat2::IDENTITY, 2.0, WHITE);
+/// assert_eq!(lines.len(), 4);
+///
+/// lines.push_line(Vec2::ZERO, Vec2::X, 2.0, WHITE);
+/// assert_eq!(lines.len(), 5);
+///
+/// // Feathered lines are still counted a segment at a time
+/// lines.set_style(LineStyle { feather: 1.0, ..Default::default() });
+/// lines.push_line(Vec2::ZERO, Vec2::Y, 2.0, WHITE);
+/// assert_eq!(lines.len(), 6);
+/// ```
+#[derive(Default)]
+pub struct LineBatch {
+    vertices: Vec<Vertex>,
+    indices: Vec<u16>,
+    segments: usize,
+    style: LineStyle,
+}
+
+impl LineBatch {
+    /// Most vertices in one mesh, as far as its 16-bit indices reach.
+    const MAX_VERTICES: usize = u16::MAX as usize + 1;
+
+    /// Create an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the style lines are drawn in from now on.
+    pub fn set_style(&mut self, style: LineStyle) {
+        self.style = style;
+    }
+
+    /// Number of segments waiting to be drawn.
+    pub fn len(&self) -> usize {
+        self.segments
+    }
+
+    /// Check if there is nothing to draw.
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// Add a line segment of a thickness and colour, in the batch's style.
+    pub fn push_line(&mut self, start: Vec2, end: Vec2, thickness: f32, color: Color) {
+        if self.vertices.len() + 8 > Self::MAX_VERTICES {
+            self.draw();
+        }
+
+        let thickness = (thickness * self.style.scale).max(self.style.min_thickness);
+        let across = (end - start).perp().normalize_or_zero();
+        let normal = across * thickness / 2.0;
+        let base = self.vertices.len() as u16;
+
+        self.segments += 1;
+
+        // A quad along the segment, half the thickness either side of it
+        if self.style.feather <= 0.0 {
+            for corner in [start + normal, end + normal, end - normal, start - normal] {
+                self.vertices.push(Vertex::new(corner.x, corner.y, 0.0, 0.0, 0.0, color));
+            }
+
+            self.indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+            return;
+        }
+
+        // Feathered, a strip of three quads across the segment: the solid core,
+        // and either side of it an edge fading out to nothing
+        let fringe = normal + across * self.style.feather;
+        let clear = Color { a: 0.0, ..color };
+
+        for (offset, color) in [(fringe, clear), (normal, color), (-normal, color), (-fringe, clear)] {
+            self.vertices.push(Vertex::new(start.x + offset.x, start.y + offset.y, 0.0, 0.0, 0.0, color));
+            self.vertices.push(Vertex::new(end.x + offset.x, end.y + offset.y, 0.0, 0.0, 0.0, color));
+        }
+
+        for row in 0..3 {
+            let (a, b) = (base + row * 2, base + row * 2 + 2);
+            self.indices.extend_from_slice(&[a, a + 1, b + 1, a, b + 1, b]);
+        }
+    }
+
+    /// Add the outline of a closed polygon, its vertices turned by a rotation
+    /// and placed around a position.
+    pub fn push_polygon(&mut self, position: Vec2, vertices: &[Vec2], rotation: Mat2, thickness: f32, color: Color) {
+        let Some(last) = vertices.last() else {
+            return;
+        };
+
+        // Each corner is turned once, and shared by the two sides meeting at it
+        let mut previous = position + rotation.mul_vec2(*last);
+
+        for vertex in vertices {
+            let corner = position + rotation.mul_vec2(*vertex);
+
+            self.push_line(previous, corner, thickness, color);
+            previous = corner;
+        }
+    }
+
+    /// Draw everything gathered as a single mesh, and empty the batch for the
+    /// next lot.
+    pub fn draw(&mut self) {
+        if self.is_empty() {
+            return;
+        }
+
+        let mesh = Mesh {
+            vertices: std::mem::take(&mut self.vertices),
+            indices: std::mem::take(&mut self.indices),
+            texture: None,
+        };
+
+        draw_mesh(&mesh);
+
+        // Keep the buffers, and their capacity, for the next frame
+        self.vertices = mesh.vertices;
+        self.indices = mesh.indices;
+        self.vertices.clear();
+        self.indices.clear();
+        self.segments = 0;
+    }
+}
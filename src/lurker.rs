@@ -0,0 +1,79 @@
+//! Lurker punishment.
+//!
+//! As in the arcade original, a player who hangs back with the last few
+//! asteroids of a wave, picking off saucers rather than finishing it, is
+//! hunted. Once a wave has gone on for a while with only a few asteroids
+//! left, small saucers are sent in one after another, each aiming better than
+//! the last, until the wave is cleared. Wave time is counted in the frames
+//! played, as it is for the wave tally.
+
+/// Lurker watch
+///
+/// Watches a wave for lurking, and sends in the hunters.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::lurker::LurkerWatch;
+/// let mut watch = LurkerWatch::default();
+///
+/// // A long wave is not lurking while plenty of asteroids are left
+/// assert_eq!(watch.update(LurkerWatch::WAVE_TIME, 8), None);
+///
+/// // With few left, hunters come in turn, each aiming better than the last
+/// let first = watch.update(LurkerWatch::WAVE_TIME, 2).unwrap();
+/// assert_eq!(watch.update(LurkerWatch::WAVE_TIME + 1.0, 2), None);
+///
+/// let second = watch.update(LurkerWatch::WAVE_TIME + LurkerWatch::HUNTER_INTERVAL, 1).unwrap();
+/// assert!(second > first);
+///
+/// watch.reset();
+/// assert_eq!(watch.update(LurkerWatch::WAVE_TIME, 2), Some(first));
+/// ```
+#[derive(Default)]
+pub struct LurkerWatch {
+    next_hunter: Option<f64>,
+    hunters: u32,
+}
+
+impl LurkerWatch {
+    /// Time in seconds into a wave from which hanging back counts as
+    /// lurking.
+    pub const WAVE_TIME: f64 = 30.0;
+
+    /// Most asteroids left in the field for the player to be lurking.
+    pub const FEW_ASTEROIDS: usize = 3;
+
+    /// Time in seconds between hunters while the player lurks.
+    pub const HUNTER_INTERVAL: f64 = 8.0;
+
+    /// Accuracy each hunter aims with beyond the wave's saucers, and beyond
+    /// the hunter before it.
+    pub const ACCURACY_STEP: f32 = 0.15;
+
+    /// Watch the wave at a time into it with some asteroids left, returning
+    /// the extra accuracy of a hunter to send in if one is due. Leaving the
+    /// asteroids be is lurking; breaking them up so more are left is not.
+    pub fn update(&mut self, elapsed: f64, asteroids: usize) -> Option<f32> {
+        if elapsed < Self::WAVE_TIME || asteroids > Self::FEW_ASTEROIDS {
+            self.next_hunter = None;
+            return None;
+        }
+
+        let due = *self.next_hunter.get_or_insert(elapsed);
+
+        if elapsed < due {
+            return None;
+        }
+
+        self.next_hunter = Some(elapsed + Self::HUNTER_INTERVAL);
+        self.hunters += 1;
+
+        Some(self.hunters as f32 * Self::ACCURACY_STEP)
+    }
+
+    /// Stop watching, for a new wave.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
@@ -0,0 +1,474 @@
+//! Menus.
+//!
+//! Every list screen in the game, from the pause menu to the options, is a
+//! `Menu`: a column of items, each a button, a choice cycled through with
+//! Left and Right, a slider, or a key binding captured from the next key
+//! pressed. Menus are navigated the same way everywhere, with the arrow keys,
+//! Enter and Escape, the gamepad's d-pad or left stick with A to accept and B
+//! to go back, or by pointing at an item with the mouse or a finger and
+//! clicking or tapping it. Actions that cannot be undone ask for confirmation
+//! in a dialog first.
+//!
+//! Menus only know about their items' labels and values; the screen owning
+//! a menu builds its items each frame and acts on the events the menu
+//! reports.
+
+use macroquad::prelude::*;
+
+use crate::gamepad::Gamepad;
+use crate::textcache::TextMeasureCache;
+
+/// Menu command
+///
+/// What the player asked a menu to do on a frame, from whichever device.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MenuCommand {
+    Up,
+    Down,
+    Left,
+    Right,
+    Accept,
+    Back,
+}
+
+impl MenuCommand {
+    /// Command given on the keyboard this frame, if any.
+    pub fn from_keyboard() -> Option<MenuCommand> {
+        if is_key_pressed(KeyCode::Up) {
+            Some(MenuCommand::Up)
+        } else if is_key_pressed(KeyCode::Down) {
+            Some(MenuCommand::Down)
+        } else if is_key_pressed(KeyCode::Left) {
+            Some(MenuCommand::Left)
+        } else if is_key_pressed(KeyCode::Right) {
+            Some(MenuCommand::Right)
+        } else if is_enter_pressed() || is_key_pressed(KeyCode::Space) {
+            Some(MenuCommand::Accept)
+        } else if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::Backspace) {
+            Some(MenuCommand::Back)
+        } else {
+            None
+        }
+    }
+}
+
+/// Check if Enter was pressed on its own this frame, rather than with Alt to
+/// toggle fullscreen.
+pub fn is_enter_pressed() -> bool {
+    is_key_pressed(KeyCode::Enter) && !is_alt_down()
+}
+
+/// Check if either Alt key is held.
+pub fn is_alt_down() -> bool {
+    is_key_down(KeyCode::LeftAlt) || is_key_down(KeyCode::RightAlt)
+}
+
+/// Widget
+///
+/// How an item shows its value and responds: a button does something when
+/// accepted, a choice shows its value and is cycled with Left and Right, a
+/// slider shows a level from 0 to 1 as a bar, and a key binding shows its key
+/// and captures a new one when accepted.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Widget {
+    Button,
+    Choice(String),
+    Slider(f32, String),
+    Key(String),
+}
+
+/// Menu item
+///
+/// An entry in a menu: its label, its widget, and whether it can be chosen at
+/// the moment. Disabled items are drawn dimmed and report nothing.
+#[derive(Clone, PartialEq, Debug)]
+pub struct MenuItem {
+    pub label: String,
+    pub widget: Widget,
+    pub enabled: bool,
+}
+
+impl MenuItem {
+    /// Item doing something when accepted.
+    pub fn button(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            widget: Widget::Button,
+            enabled: true,
+        }
+    }
+
+    /// Item showing a value, changed with Left and Right.
+    pub fn choice(label: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            widget: Widget::Choice(value.into()),
+            ..Self::button(label)
+        }
+    }
+
+    /// Item showing a level from 0 to 1 as a bar, with the level as text.
+    pub fn slider(label: impl Into<String>, level: f32, value: impl Into<String>) -> Self {
+        Self {
+            widget: Widget::Slider(level.clamp(0.0, 1.0), value.into()),
+            ..Self::button(label)
+        }
+    }
+
+    /// Item showing the key an action is bound to.
+    pub fn key(label: impl Into<String>, key: impl Into<String>) -> Self {
+        Self {
+            widget: Widget::Key(key.into()),
+            ..Self::button(label)
+        }
+    }
+
+    /// Enable or disable the item.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+/// Menu event
+///
+/// What happened in a menu on a frame, for the screen owning it to act on:
+/// an item was accepted or changed one way or the other, a key was captured
+/// for an item, an item's action was confirmed, or the player backed out.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MenuEvent {
+    Accept(usize),
+    Change(usize, bool),
+    Capture(usize, KeyCode),
+    Confirm(usize),
+    Back,
+}
+
+/// Menu layout
+///
+/// Where a menu's items are drawn: the height of its first row as a fraction
+/// of the screen's plus an offset in pixels, the spacing and text size of the rows, the width of the
+/// column labels and values are aligned to, and the number of rows shown
+/// before the menu scrolls.
+#[derive(Clone, Copy)]
+pub struct MenuLayout {
+    pub top: f32,
+    pub offset: f32,
+    pub row_height: f32,
+    pub font_size: u16,
+    pub width: f32,
+    pub visible_rows: usize,
+}
+
+/// Menu
+///
+/// A list of items with one selected, and any dialog or key capture open
+/// over it.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::menu::{Menu, MenuCommand, MenuEvent, MenuLayout};
+/// let layout = MenuLayout { top: 0.3, offset: 0.0, row_height: 40.0, font_size: 30, width: 400.0, visible_rows: 4 };
+/// let mut menu = Menu::new(layout);
+///
+/// // Selection wraps around the ends of the list
+/// assert_eq!(menu.command(MenuCommand::Up, 3), None);
+/// assert_eq!(menu.selection, 2);
+/// assert_eq!(menu.command(MenuCommand::Right, 3), Some(MenuEvent::Change(2, true)));
+/// assert_eq!(menu.command(MenuCommand::Accept, 3), Some(MenuEvent::Accept(2)));
+///
+/// // A dialog asks before the action goes ahead
+/// menu.confirm("Quit to the title?");
+/// assert_eq!(menu.command(MenuCommand::Accept, 3), None);
+/// assert!(!menu.is_confirming());
+///
+/// menu.confirm("Quit to the title?");
+/// assert_eq!(menu.command(MenuCommand::Left, 3), None);
+/// assert!(menu.is_confirming());
+/// assert_eq!(menu.command(MenuCommand::Accept, 3), Some(MenuEvent::Confirm(2)));
+/// assert!(!menu.is_confirming());
+///
+/// // Long menus scroll to keep the selection in view
+/// menu.selection = 9;
+/// assert_eq!(menu.first_visible_row(10), 6);
+/// ```
+pub struct Menu {
+    pub selection: usize,
+    pub layout: MenuLayout,
+    dialog: Option<(&'static str, bool)>,
+    capturing: bool,
+}
+
+impl Menu {
+    /// Colour of the dialog and selection outlines.
+    const OUTLINE: Color = Color::new(1.0, 1.0, 1.0, 0.6);
+
+    /// Colour of disabled items.
+    const DIMMED: Color = Color::new(1.0, 1.0, 1.0, 0.35);
+
+    /// Width of a slider's bar.
+    const SLIDER_WIDTH: f32 = 120.0;
+
+    /// Create a menu drawn with a layout, with its first item selected.
+    pub fn new(layout: MenuLayout) -> Self {
+        Self {
+            selection: 0,
+            layout,
+            dialog: None,
+            capturing: false,
+        }
+    }
+
+    /// Select the first item, closing any dialog or key capture, for example
+    /// when the menu is opened.
+    pub fn reset(&mut self) {
+        self.selection = 0;
+        self.dialog = None;
+        self.capturing = false;
+    }
+
+    /// Ask the player to confirm the selected item's action before it goes
+    /// ahead. The answer starts on No.
+    pub fn confirm(&mut self, question: &'static str) {
+        self.dialog = Some((question, false));
+    }
+
+    /// Check if a confirmation dialog is open.
+    pub fn is_confirming(&self) -> bool {
+        self.dialog.is_some()
+    }
+
+    /// Capture the next key pressed for the selected item.
+    pub fn capture(&mut self) {
+        self.capturing = true;
+    }
+
+    /// Check if the menu is waiting for a key to capture.
+    pub fn is_capturing(&self) -> bool {
+        self.capturing
+    }
+
+    /// First row shown, scrolling long menus to keep the selection near the
+    /// middle.
+    pub fn first_visible_row(&self, count: usize) -> usize {
+        let rows = self.layout.visible_rows;
+
+        self.selection.saturating_sub(rows / 2).min(count.saturating_sub(rows))
+    }
+
+    /// Vertical position of a row's text, counting from the first shown.
+    fn row_y(&self, row: usize) -> f32 {
+        screen_height() * self.layout.top + self.layout.offset + row as f32 * self.layout.row_height
+    }
+
+    /// Area of the screen covering a row, for pointing at it.
+    fn row_rect(&self, row: usize) -> Rect {
+        let y = self.row_y(row);
+        let height = self.layout.row_height;
+
+        Rect::new((screen_width() - self.layout.width) / 2.0 - 40.0, y - height * 0.75, self.layout.width + 80.0, height)
+    }
+
+    /// Read this frame's input from every device and act on it, for a menu
+    /// with some items.
+    pub fn update(&mut self, items: &[MenuItem], gamepad: &mut Gamepad) -> Option<MenuEvent> {
+        let count = items.len();
+
+        // Capture the next key pressed, or give up on Escape or the gamepad's
+        // back button
+        if self.capturing {
+            if gamepad.poll_menu() == Some(MenuCommand::Back) {
+                self.capturing = false;
+                return None;
+            }
+
+            return match get_last_key_pressed() {
+                Some(KeyCode::Escape) => {
+                    self.capturing = false;
+                    None
+                },
+                Some(key) => {
+                    self.capturing = false;
+                    Some(MenuEvent::Capture(self.selection, key))
+                },
+                None => None,
+            };
+        }
+
+        // Pointing at a row selects it, and clicking or tapping it accepts it
+        let tapped = touches().into_iter().find(|touch| touch.phase == TouchPhase::Started).map(|touch| touch.position);
+        let clicked = is_mouse_button_pressed(MouseButton::Left).then(|| Vec2::from(mouse_position()));
+        let moved = mouse_delta_position() != Vec2::ZERO;
+
+        if self.dialog.is_none() {
+            let pointer = tapped.or(clicked).or(moved.then(|| Vec2::from(mouse_position())));
+            let first = self.first_visible_row(count);
+
+            let pointed = pointer.and_then(|pointer| {
+                (first..count.min(first + self.layout.visible_rows)).find(|index| self.row_rect(index - first).contains(pointer))
+            });
+
+            if let Some(index) = pointed {
+                self.selection = index;
+
+                if (tapped.is_some() || clicked.is_some()) && items[index].enabled {
+                    return self.command(MenuCommand::Accept, count);
+                }
+            }
+        } else if tapped.is_some() || clicked.is_some() {
+            // Clicking a dialog's answer picks it
+            let pointer = tapped.or(clicked).unwrap_or_default();
+            let yes = pointer.x < screen_width() / 2.0;
+
+            self.dialog = self.dialog.map(|(question, _)| (question, yes));
+            return self.command(MenuCommand::Accept, count);
+        }
+
+        let command = MenuCommand::from_keyboard().or_else(|| gamepad.poll_menu())?;
+        let event = self.command(command, count)?;
+
+        // Disabled items do nothing, but can still be backed out of
+        match event {
+            MenuEvent::Accept(index) | MenuEvent::Change(index, _) if !items[index].enabled => None,
+            _ => Some(event),
+        }
+    }
+
+    /// Act on a command, for a menu with a number of items.
+    pub fn command(&mut self, command: MenuCommand, count: usize) -> Option<MenuEvent> {
+        if let Some((question, yes)) = self.dialog {
+            return match command {
+                MenuCommand::Left | MenuCommand::Right | MenuCommand::Up | MenuCommand::Down => {
+                    self.dialog = Some((question, !yes));
+                    None
+                },
+                MenuCommand::Accept => {
+                    self.dialog = None;
+                    yes.then_some(MenuEvent::Confirm(self.selection))
+                },
+                MenuCommand::Back => {
+                    self.dialog = None;
+                    None
+                },
+            };
+        }
+
+        if count == 0 {
+            return (command == MenuCommand::Back).then_some(MenuEvent::Back);
+        }
+
+        match command {
+            MenuCommand::Up => {
+                self.selection = (self.selection + count - 1) % count;
+                None
+            },
+            MenuCommand::Down => {
+                self.selection = (self.selection + 1) % count;
+                None
+            },
+            MenuCommand::Left => Some(MenuEvent::Change(self.selection, false)),
+            MenuCommand::Right => Some(MenuEvent::Change(self.selection, true)),
+            MenuCommand::Accept => Some(MenuEvent::Accept(self.selection)),
+            MenuCommand::Back => Some(MenuEvent::Back),
+        }
+    }
+
+    /// Draw the menu's items, with the selected one marked, and any dialog
+    /// open over them.
+    pub fn draw(&self, items: &[MenuItem], font: &Font, text_widths: &TextMeasureCache) {
+        let count = items.len();
+        let first = self.first_visible_row(count);
+        let size = self.layout.font_size;
+        let left = (screen_width() - self.layout.width) / 2.0;
+        let right = left + self.layout.width;
+
+        let text = |text: &str, x: f32, y: f32, color: Color| {
+            draw_text_ex(text, x, y, TextParams {
+                font_size: size,
+                font: Some(font),
+                color,
+                ..Default::default()
+            });
+        };
+
+        for (row, item) in items.iter().enumerate().skip(first).take(self.layout.visible_rows).map(|(index, item)| (index - first, item)) {
+            let y = self.row_y(row);
+            let color = if item.enabled { WHITE } else { Self::DIMMED };
+            let selected = first + row == self.selection;
+
+            // Buttons are centred; everything else has its label on the left
+            // and its value on the right
+            let value = match &item.widget {
+                Widget::Button => {
+                    let width = text_widths.width(font, &item.label, size);
+                    text(&item.label, (screen_width() - width) / 2.0, y, color);
+                    None
+                },
+                Widget::Choice(value) | Widget::Slider(_, value) => Some(value.as_str()),
+                Widget::Key(_) if selected && self.capturing => Some("..."),
+                Widget::Key(key) => Some(key.as_str()),
+            };
+
+            if let Some(value) = value {
+                text(&item.label, left, y, color);
+
+                let width = text_widths.width(font, value, size);
+                text(value, right - width, y, color);
+
+                // Sliders draw their level as a bar to the left of the value
+                if let Widget::Slider(level, _) = item.widget {
+                    let bar = Rect::new(right - width - 20.0 - Self::SLIDER_WIDTH, y - size as f32 * 0.5, Self::SLIDER_WIDTH, size as f32 * 0.4);
+
+                    draw_rectangle_lines(bar.x, bar.y, bar.w, bar.h, 1.5, color);
+                    draw_rectangle(bar.x, bar.y, bar.w * level, bar.h, color);
+                }
+            }
+
+            if selected {
+                text(">", left - 36.0, y, WHITE);
+                text("<", right + 20.0, y, WHITE);
+            }
+        }
+
+        // Arrows show there is more to scroll to
+        if first > 0 {
+            let width = text_widths.width(font, "^", size);
+            text("^", (screen_width() - width) / 2.0, self.row_y(0) - self.layout.row_height, Self::DIMMED);
+        }
+
+        if first + self.layout.visible_rows < count {
+            let width = text_widths.width(font, "v", size);
+            text("v", (screen_width() - width) / 2.0, self.row_y(self.layout.visible_rows), Self::DIMMED);
+        }
+
+        if let Some((question, yes)) = self.dialog {
+            self.draw_dialog(question, yes, font, text_widths);
+        }
+    }
+
+    /// Draw a confirmation dialog in the middle of the screen, marking the
+    /// answer picked.
+    fn draw_dialog(&self, question: &str, yes: bool, font: &Font, text_widths: &TextMeasureCache) {
+        let size = self.layout.font_size;
+        let width = text_widths.width(font, question, size).max(300.0) + 80.0;
+        let height = size as f32 * 4.0;
+        let area = Rect::new((screen_width() - width) / 2.0, (screen_height() - height) / 2.0, width, height);
+
+        draw_rectangle(area.x, area.y, area.w, area.h, Color::new(0.0, 0.0, 0.0, 0.9));
+        draw_rectangle_lines(area.x, area.y, area.w, area.h, 2.0, Self::OUTLINE);
+
+        let text = |text: &str, centre: f32, y: f32| {
+            let width = text_widths.width(font, text, size);
+
+            draw_text_ex(text, centre - width / 2.0, y, TextParams {
+                font_size: size,
+                font: Some(font),
+                ..Default::default()
+            });
+        };
+
+        text(question, screen_width() / 2.0, area.y + size as f32 * 1.5);
+        text(if yes { "> Yes <" } else { "Yes" }, screen_width() / 2.0 - width / 4.0, area.y + size as f32 * 3.0);
+        text(if yes { "No" } else { "> No <" }, screen_width() / 2.0 + width / 4.0, area.y + size as f32 * 3.0);
+    }
+}
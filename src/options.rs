@@ -0,0 +1,88 @@
+/// Launch options
+/// 
+/// Options passed on the command line when the game is started. These are only
+/// meaningful on native builds; on the web there are no arguments and the
+/// defaults are used.
+/// 
+/// # Examples
+/// 
+/// ```text
+/// asteroids --no-hud --overlay-file ./overlay.json --websocket 9001
+/// ```
+/// 
+/// `--rumble <percent>` sets the strength of gamepad rumble; 0 turns it off.
+/// 
+/// `--alloc-budget <n>` reports frames making more than `n` heap allocations.
+/// It only has an effect in builds with the `alloc-tracking` feature.
+/// 
+/// `--seed <n>` seeds every game with `n` instead of the clock, so runs with
+/// the same inputs play out identically.
+///
+/// `--assets <dir>` loads assets such as the font from `dir` instead of the
+/// working directory.
+///
+/// `--bench <ticks>` times `ticks` ticks of each benchmark scenario in a
+/// headless world, reports the tick times and exits without opening a window.
+///
+/// `--soak <hours>` lets the auto pilot play `hours` hours of frames as fast as
+/// it can in a headless world, checking the game's invariants every tick,
+/// reports what it saw and exits without opening a window.
+///
+/// `--tas` plays classic games a frame at a time, with savestates, recording
+/// a movie of the run; see the `tas` module.
+///
+/// `--movie <file>` watches the movie in `file` instead of starting at the
+/// title screen.
+///
+/// `--verify <file>` plays back the movie in `file` as fast as it can,
+/// reports whether it reaches the score and wave it claims, and exits instead
+/// of starting the game.
+///
+/// `--speech <program>` speaks announcements, with the Announcements option
+/// on, by running `program` with each as its argument; see the `announcer`
+/// module.
+#[derive(Default)]
+pub struct LaunchOptions {
+    pub no_hud: bool,
+    pub overlay_file: Option<String>,
+    pub websocket_port: Option<u16>,
+    pub rumble_intensity: Option<f32>,
+    pub alloc_budget: Option<usize>,
+    pub seed: Option<u64>,
+    pub bench_ticks: Option<usize>,
+    pub soak_hours: Option<f64>,
+    pub asset_dir: Option<String>,
+    pub tas: bool,
+    pub movie_file: Option<String>,
+    pub verify_file: Option<String>,
+    pub speech_program: Option<String>,
+}
+
+impl LaunchOptions {
+    /// Parse launch options from command line arguments. Unknown arguments are
+    /// ignored.
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        let mut options = Self::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--no-hud" => options.no_hud = true,
+                "--overlay-file" => options.overlay_file = args.next(),
+                "--websocket" => options.websocket_port = args.next().and_then(|port| port.parse().ok()),
+                "--rumble" => options.rumble_intensity = args.next().and_then(|percent| percent.parse::<f32>().ok()).map(|percent| percent / 100.0),
+                "--alloc-budget" => options.alloc_budget = args.next().and_then(|budget| budget.parse().ok()),
+                "--seed" => options.seed = args.next().and_then(|seed| seed.parse().ok()),
+                "--assets" => options.asset_dir = args.next(),
+                "--bench" => options.bench_ticks = args.next().and_then(|ticks| ticks.parse().ok()),
+                "--soak" => options.soak_hours = args.next().and_then(|hours| hours.parse().ok()),
+                "--tas" => options.tas = true,
+                "--movie" => options.movie_file = args.next(),
+                "--verify" => options.verify_file = args.next(),
+                "--speech" => options.speech_program = args.next(),
+                _ => {}
+            }
+        }
+
+        options
+    }
+}
@@ -0,0 +1,148 @@
+/// Overdrive meter
+///
+/// The meter charges as the player destroys enemies, and charges faster when
+/// kills follow each other in quick succession. Once full, the player may
+/// trigger overdrive: for a few seconds the ship fires and turns faster and
+/// all points scored are doubled. The meter drains while overdrive lasts, and
+/// is empty once it ends.
+///
+/// Kills following each other in quick succession form a combo, which lasts
+/// as long as each kill comes within the quick kill window of the last.
+///
+/// The meter only tracks charge and timing; the ship's temporary stat modifiers
+/// live on the ship itself.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::overdrive::Overdrive;
+/// let mut overdrive = Overdrive::new();
+///
+/// // A lone kill is no combo
+/// overdrive.register_kills(1, 10.0);
+/// assert_eq!(overdrive.combo(10.0), None);
+///
+/// // A quick follow-up starts one, which runs down over the window
+/// overdrive.register_kills(1, 10.5);
+/// assert_eq!(overdrive.combo(10.5), Some((2, 1.0)));
+/// assert_eq!(overdrive.combo(11.0), Some((2, 0.5)));
+/// assert_eq!(overdrive.combo(11.5), None);
+/// ```
+pub struct Overdrive {
+    pub meter: f32,
+    pub expiry: f64,
+    last_kill: f64,
+    chain: u32,
+}
+
+impl Overdrive {
+    /// Duration of overdrive in seconds.
+    pub const DURATION: f64 = 6.0;
+
+    /// Charge added to the meter by a kill.
+    pub const KILL_CHARGE: f32 = 0.03;
+
+    /// Charge added to the meter by a kill following closely after another.
+    pub const QUICK_KILL_CHARGE: f32 = 0.1;
+
+    /// Time in seconds within which a kill counts as following another.
+    pub const QUICK_KILL_WINDOW: f64 = 1.0;
+
+    /// Points scored during overdrive are multiplied by this factor.
+    pub const SCORE_MULTIPLIER: u32 = 2;
+
+    /// Create an empty meter.
+    pub fn new() -> Self {
+        Self {
+            meter: 0.0,
+            expiry: 0.0,
+            last_kill: f64::NEG_INFINITY,
+            chain: 0,
+        }
+    }
+
+    /// Empty the meter and cancel overdrive.
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Charge the meter for a number of kills made at the given time. The meter
+    /// does not charge while overdrive is active.
+    pub fn register_kills(&mut self, kills: usize, now: f64) {
+        if kills == 0 || self.is_active(now) {
+            return;
+        }
+
+        for _ in 0..kills {
+            let charge = if now - self.last_kill <= Self::QUICK_KILL_WINDOW {
+                self.chain += 1;
+                Self::QUICK_KILL_CHARGE
+            } else {
+                self.chain = 1;
+                Self::KILL_CHARGE
+            };
+
+            self.meter = (self.meter + charge).min(1.0);
+            self.last_kill = now;
+        }
+    }
+
+    /// Check if the meter is full and overdrive may be triggered.
+    pub fn is_full(&self) -> bool {
+        self.meter >= 1.0
+    }
+
+    /// Check if overdrive is active at the given time.
+    pub fn is_active(&self, now: f64) -> bool {
+        now < self.expiry
+    }
+
+    /// Trigger overdrive if the meter is full. Returns false if it is not.
+    pub fn activate(&mut self, now: f64) -> bool {
+        if !self.is_full() || self.is_active(now) {
+            return false;
+        }
+
+        self.expiry = now + Self::DURATION;
+
+        true
+    }
+
+    /// Drain the meter while overdrive is active, emptying it once it ends.
+    pub fn update(&mut self, now: f64) {
+        if self.expiry > 0.0 {
+            self.meter = ((self.expiry - now) / Self::DURATION).clamp(0.0, 1.0) as f32;
+
+            if !self.is_active(now) {
+                self.expiry = 0.0;
+            }
+        }
+    }
+
+    /// Multiplier shown for the combo running at the given time, and the part
+    /// of its time left to keep it going, from 1 down to 0. While overdrive is
+    /// active that is its score multiplier and the time it has left; otherwise
+    /// it is the number of kills in the combo and the time left for another.
+    /// There is none until a second kill makes a combo, or once it lapses.
+    pub fn combo(&self, now: f64) -> Option<(u32, f32)> {
+        if self.is_active(now) {
+            let remaining = (self.expiry - now) / Self::DURATION;
+            return Some((Self::SCORE_MULTIPLIER, remaining.clamp(0.0, 1.0) as f32));
+        }
+
+        let remaining = 1.0 - (now - self.last_kill) / Self::QUICK_KILL_WINDOW;
+
+        (self.chain >= 2 && remaining > 0.0).then(|| (self.chain, remaining.min(1.0) as f32))
+    }
+
+    /// Score multiplier in effect at the given time.
+    pub fn score_multiplier(&self, now: f64) -> u32 {
+        if self.is_active(now) { Self::SCORE_MULTIPLIER } else { 1 }
+    }
+}
+
+impl Default for Overdrive {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,47 @@
+/// Overlay output
+/// 
+/// Streamers often want to composite the gameplay and the HUD independently,
+/// e.g. by hiding the built-in HUD and drawing their own on top in OBS. The
+/// overlay publishes the game state as a small JSON document written to a file
+/// whenever it changes, which browser sources and scripts can poll.
+pub struct Overlay {
+    pub state_file: Option<String>,
+    last_state: String,
+}
+
+impl Overlay {
+    /// Create a new overlay. No output is written if no state file is given.
+    pub fn new(state_file: Option<String>) -> Self {
+        Self {
+            state_file,
+            last_state: String::new(),
+        }
+    }
+
+    /// Publish game state if it has changed since it was last published.
+    pub fn publish(&mut self, state: String) {
+        if self.state_file.is_none() || state == self.last_state {
+            return;
+        }
+
+        self.write(&state);
+        self.last_state = state;
+    }
+
+    /// Write game state to the state file. The file is written to a temporary
+    /// path first and then renamed, so readers never see a partial document.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write(&self, state: &str) {
+        if let Some(path) = &self.state_file {
+            let temp_path = format!("{}.tmp", path);
+
+            if std::fs::write(&temp_path, state).is_ok() {
+                let _ = std::fs::rename(&temp_path, path);
+            }
+        }
+    }
+
+    /// There is no filesystem on the web, so nothing is written.
+    #[cfg(target_arch = "wasm32")]
+    fn write(&self, _state: &str) {}
+}
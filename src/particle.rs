@@ -0,0 +1,189 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::camera::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::clock::TICK;
+
+/// Particle object
+/// 
+/// Particles are spawned when objects are destroyed. They move in a random
+/// direction, at a velocity in units a tick, and disappear after a certain
+/// amount of time. A particle shrinks as its lifespan decays, losing `decay`
+/// every second, so it lasts `lifespan / decay` seconds and drifts as far
+/// whatever the frame rate. Their randomness is drawn from the generator they
+/// are spawned with.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::clock::TICK;
+/// # use asteroids::particle::Particle;
+/// # use macroquad::prelude::*;
+/// for fps in [30.0, 60.0, 144.0] {
+///     let mut particle = Particle::spawn_new(Vec2::ZERO, Vec2::X, 1.2, 0.6);
+///     let mut frames = 0;
+///
+///     while particle.is_alive() {
+///         particle.update(1.0 / fps);
+///         frames += 1;
+///     }
+///
+///     // Two seconds and 120 units, give or take a frame
+///     assert!((frames as f32 / fps - 2.0).abs() <= 1.0 / fps);
+///     assert!((particle.position.x - 2.0 / TICK).abs() < 1.5 / fps / TICK);
+/// }
+/// ```
+pub struct Particle {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub lifespan: f32,
+    pub decay: f32,
+    pub color: Color,
+}
+
+impl Particle {
+    /// Spawn new particle at a given position.
+    pub fn spawn_new(position: Vec2, velocity: Vec2, lifespan: f32, decay: f32) -> Self {
+        Self {
+            position,
+            velocity,
+            lifespan,
+            decay,
+            color: WHITE,
+        }
+    }
+
+    /// Spawn a single stationary particle of the given colour, used to leave a
+    /// fading trail behind a moving object.
+    pub fn spawn_trail(position: Vec2, color: Color) -> Particle {
+        Particle {
+            color,
+            ..Self::spawn_new(position, Vec2::ZERO, 1.0, 2.4)
+        }
+    }
+
+    /// Spawn particles in a radial pattern.
+    /// 
+    /// # Examples
+    /// 
+    /// Generators seeded alike spawn identical particles:
+    /// 
+    /// ```
+    /// # use asteroids::particle::Particle;
+    /// # use macroquad::prelude::*;
+    /// # use macroquad::rand::RandGenerator;
+    /// let (first, second) = (RandGenerator::new(), RandGenerator::new());
+    /// first.srand(1979);
+    /// second.srand(1979);
+    /// 
+    /// let a = Particle::spawn_radial(&first, Vec2::ZERO, 10);
+    /// let b = Particle::spawn_radial(&second, Vec2::ZERO, 10);
+    /// assert!(a.iter().zip(&b).all(|(a, b)| a.velocity == b.velocity && a.lifespan == b.lifespan));
+    /// ```
+    pub fn spawn_radial(rng: &RandGenerator, position: Vec2, count: u32) -> Vec<Particle> {
+        let mut particles = Vec::new();
+
+        for _ in 0..count {
+            let direction = rng.gen_range(0.0, std::f32::consts::PI * 2.0);
+            let speed = rng.gen_range(0.4, 1.0);
+            let velocity = Mat2::from_angle(direction).mul_vec2(Vec2::X * speed);
+
+            particles.push(Self::spawn_new(position, velocity, rng.gen_range(0.2, 1.0), 0.6));
+        }
+
+        particles
+    }
+
+    /// Spawn particles in a conical pattern.
+    pub fn spawn_conical(rng: &RandGenerator, position: Vec2, direction: f32, spread: f32, count: u32) -> Vec<Particle> {
+        let mut particles = Vec::new();
+    
+        for _ in 0..count {
+            // Generate a random direction within the specified spread
+            let spread_angle = rng.gen_range(-spread / 2.0, spread / 2.0);
+            let cone_direction = direction + spread_angle;
+    
+            // Generate a random speed within a range
+            let speed = rng.gen_range(0.4, 1.0);
+    
+            // Calculate velocity based on the cone direction and speed
+            let velocity = Mat2::from_angle(cone_direction).mul_vec2(Vec2::Y * speed);
+    
+            particles.push(Self::spawn_new(position, velocity, rng.gen_range(0.2, 1.0), 0.6));
+        }
+    
+        particles
+    }
+    
+    /// Spawn larger particles with a quicker decay in a radial pattern.
+    pub fn spawn_debris(rng: &RandGenerator, position: Vec2, count: u32) -> Vec<Particle> {
+        let mut particles = Vec::new();
+
+        for _ in 0..count {
+            let direction = rng.gen_range(0.0, std::f32::consts::PI * 2.0);
+            let speed = rng.gen_range(0.4, 1.0);
+            let velocity = Mat2::from_angle(direction).mul_vec2(Vec2::X * speed);
+
+            particles.push(Self::spawn_new(position, velocity, rng.gen_range(2.0, 5.0), 6.0));
+        }
+
+        particles
+    }
+
+    /// Spawn particles in an imploding ring, converging on a position.
+    pub fn spawn_ring(rng: &RandGenerator, position: Vec2, radius: f32, count: u32) -> Vec<Particle> {
+        let mut particles = Vec::new();
+
+        for p in 0..count {
+            let direction = std::f32::consts::PI * 2.0 / count as f32 * p as f32;
+            let speed = rng.gen_range(0.4, 1.0);
+            let velocity = Mat2::from_angle(direction).mul_vec2(Vec2::X * speed);
+
+            particles.push(Self::spawn_new(position - velocity * radius, velocity, rng.gen_range(0.2, 1.0), 1.5));
+        }
+
+        particles
+    }
+
+    /// Spawn confetti in bright colours, drifting down from just above the top
+    /// of the screen.
+    pub fn spawn_confetti(rng: &RandGenerator, count: u32) -> Vec<Particle> {
+        const COLORS: [Color; 5] = [RED, YELLOW, GREEN, SKYBLUE, PINK];
+
+        let mut particles = Vec::new();
+
+        for _ in 0..count {
+            let position = Vec2::new(rng.gen_range(0.0, WORLD_WIDTH), rng.gen_range(-WORLD_HEIGHT * 0.2, 0.0));
+            let velocity = Vec2::new(rng.gen_range(-0.3, 0.3), rng.gen_range(0.8, 1.6));
+
+            particles.push(Particle {
+                color: COLORS[rng.gen_range(0, COLORS.len())],
+                ..Self::spawn_new(position, velocity, rng.gen_range(1.5, 2.5), 0.3)
+            });
+        }
+
+        particles
+    }
+
+    /// Destroy particle by marking it dead. Any calls to `is_alive` will return
+    /// false after this function is called.
+    pub fn destroy(&mut self) {
+        self.lifespan = 0.0;
+    }
+
+    /// Check if particle is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.lifespan > 0.0
+    }
+
+    /// Move particle and decay its lifespan by the time the frame took.
+    pub fn update(&mut self, dt: f32) {
+        self.position += self.velocity * (dt / TICK);
+        self.lifespan -= self.decay * dt;
+    }
+
+    /// Draw particle.
+    pub fn draw(&self) {
+        draw_circle(self.position.x, self.position.y, 1.0 + self.lifespan, self.color);
+    }
+}
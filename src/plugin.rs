@@ -0,0 +1,146 @@
+//! Plugins.
+//!
+//! Downstream crates can add hazards and rules of their own without forking
+//! the game, by implementing `GamePlugin` and registering it with
+//! `GameWorld::with_plugin`. A plugin registers the kinds of entity it adds
+//! when it is registered, then hears from the world as each wave starts,
+//! after each update, and as each event is dispatched, with the world in hand
+//! to change as it likes.
+//!
+//! Custom entities are spawned by name, through `GameWorld::spawn_entity`,
+//! from a factory the plugin registered. The world moves and draws them with
+//! everything else, and takes care of their collisions: a ship touching one
+//! destroys it, losing a life unless it is harmless, and a player's bullet
+//! shoots it down unless it cannot be shot, scoring its points through
+//! `GameEvent::EntityDestroyed` like anything else destroyed. Custom entities
+//! are cleared away with the saucers at the end of a wave, and are not
+//! rewound.
+
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::collision::Collidable;
+use crate::events::GameEvent;
+use crate::linebatch::LineBatch;
+use crate::world::GameWorld;
+
+/// Custom entity
+///
+/// An entity of a kind added by a plugin.
+pub trait CustomEntity: Collidable {
+    /// Move the entity on by a frame.
+    fn update(&mut self);
+
+    /// Draw the entity into a batch of lines, in a colour such as a faded
+    /// white when it is out of sight.
+    fn draw(&self, lines: &mut LineBatch, color: Color);
+
+    /// Check if the entity is still alive.
+    fn is_alive(&self) -> bool;
+
+    /// Destroy the entity by marking it dead.
+    fn destroy(&mut self);
+
+    /// Points scored for destroying the entity.
+    fn points(&self) -> u32 {
+        0
+    }
+
+    /// Check if a ship touching the entity loses a life.
+    fn is_harmful(&self) -> bool {
+        true
+    }
+
+    /// Check if the players' bullets can shoot the entity down.
+    fn is_shootable(&self) -> bool {
+        true
+    }
+}
+
+/// Factory making an entity of a kind, from the game's random number
+/// generator.
+pub type EntityFactory = Box<dyn Fn(&RandGenerator) -> Box<dyn CustomEntity>>;
+
+/// Entity registry
+///
+/// The kinds of custom entity plugins have registered, by name.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::collision::Collidable;
+/// # use asteroids::linebatch::LineBatch;
+/// # use asteroids::plugin::{CustomEntity, EntityRegistry};
+/// # use macroquad::prelude::*;
+/// # use macroquad::rand::RandGenerator;
+/// struct Mine { position: Vec2, is_alive: bool }
+///
+/// impl Collidable for Mine {
+///     fn is_colliding(&self, other: &dyn Collidable) -> bool { <dyn Collidable>::shape_intersection(self, other) }
+///     fn get_position(&self) -> Vec2 { self.position }
+///     fn get_radius(&self) -> f32 { 10.0 }
+/// }
+///
+/// impl CustomEntity for Mine {
+///     fn update(&mut self) {}
+///     fn draw(&self, _lines: &mut LineBatch, _color: Color) {}
+///     fn is_alive(&self) -> bool { self.is_alive }
+///     fn destroy(&mut self) { self.is_alive = false; }
+/// }
+///
+/// let mut registry = EntityRegistry::default();
+/// registry.register("mine", |_| Box::new(Mine { position: Vec2::new(100.0, 100.0), is_alive: true }));
+///
+/// let rng = RandGenerator::new();
+/// let mine = registry.spawn("mine", &rng).unwrap();
+///
+/// assert_eq!(mine.get_position(), Vec2::new(100.0, 100.0));
+/// assert!(mine.is_harmful() && mine.is_shootable());
+/// assert!(registry.spawn("comet", &rng).is_none());
+/// assert_eq!(registry.names().collect::<Vec<_>>(), ["mine"]);
+/// ```
+#[derive(Default)]
+pub struct EntityRegistry {
+    factories: Vec<(String, EntityFactory)>,
+}
+
+impl EntityRegistry {
+    /// Register a kind of entity under a name, made by a factory. A kind
+    /// registered again under the same name replaces the first.
+    pub fn register(&mut self, name: &str, factory: impl Fn(&RandGenerator) -> Box<dyn CustomEntity> + 'static) {
+        self.factories.retain(|(registered, _)| registered != name);
+        self.factories.push((name.to_string(), Box::new(factory)));
+    }
+
+    /// Make an entity of the kind registered under a name, if there is one.
+    pub fn spawn(&self, name: &str, rng: &RandGenerator) -> Option<Box<dyn CustomEntity>> {
+        self.factories.iter()
+            .find(|(registered, _)| registered == name)
+            .map(|(_, factory)| factory(rng))
+    }
+
+    /// Names of the kinds registered, in the order they were registered.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+/// Game plugin
+///
+/// Hazards and rules added to the game by a downstream crate. Every hook does
+/// nothing unless the plugin says otherwise.
+pub trait GamePlugin {
+    /// Register the kinds of entity the plugin adds, once, as the plugin is
+    /// registered with a world.
+    fn register_entities(&mut self, _registry: &mut EntityRegistry) {}
+
+    /// A wave has started, with its field in play.
+    fn on_wave_start(&mut self, _world: &mut GameWorld, _wave: u32) {}
+
+    /// The world has been updated by a frame.
+    fn on_update(&mut self, _world: &mut GameWorld) {}
+
+    /// An event has been dispatched, after the game's own systems and the
+    /// listeners subscribed to events have heard it.
+    fn on_event(&mut self, _world: &mut GameWorld, _event: &GameEvent) {}
+}
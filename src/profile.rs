@@ -0,0 +1,252 @@
+//! Portable profiles.
+//!
+//! A profile gathers everything the game saves about a player into a single
+//! JSON document, so it can be carried to another machine or to the web build:
+//! settings, key bindings, HUD layout, the tips already seen, the stars earned
+//! on the challenge ladder, and the high score table.
+//!
+//! Profiles are exported to and imported from the `profile.json` storage key,
+//! from the options screen. On native builds that is a file in the game's data
+//! directory; on the web, `storage.js` offers `asteroidsDownloadProfile()` and
+//! `asteroidsUploadProfile()` to move it in and out of the browser.
+//!
+//! Importing replaces settings, bindings and HUD layout, but merges progress:
+//! tips seen are added to those already seen, the better of the two star
+//! counts is kept for each challenge, and high scores join the local table.
+//!
+//! Every profile records the version of its schema. Profiles written by older
+//! versions of the game are brought up to date by the migrations in
+//! `Profile::MIGRATIONS` before they are read.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::challenge::{Challenge, ChallengeLadder};
+use crate::highscore::{HighScore, HighScoreTable};
+use crate::hud::HudLayout;
+use crate::input::Bindings;
+use crate::settings::Settings;
+use crate::storage;
+use crate::tutorial::{FirstSeen, Mechanic};
+
+/// Profile error
+///
+/// Reasons a profile could not be imported.
+#[derive(Debug)]
+pub enum ProfileError {
+    /// No profile has been exported or placed where imports are read from.
+    Missing,
+    /// The document is not valid JSON, or not a profile.
+    Malformed(serde_json::Error),
+    /// The document has no schema version.
+    Unversioned,
+    /// The profile was written by a newer version of the game.
+    TooNew(u32),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProfileError::Missing => write!(f, "no profile to import"),
+            ProfileError::Malformed(error) => write!(f, "profile is unreadable: {}", error),
+            ProfileError::Unversioned => write!(f, "profile has no version"),
+            ProfileError::TooNew(version) => write!(f, "profile version {} is too new", version),
+        }
+    }
+}
+
+/// Profile
+///
+/// A player's saved data, as exported. Sections missing from an imported
+/// profile keep their defaults.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::profile::Profile;
+/// # use asteroids::highscore::HighScoreTable;
+/// let mut profile = Profile::default();
+/// profile.high_scores.insert("KB", 12000);
+///
+/// let restored = Profile::parse(&profile.to_json()).unwrap();
+/// assert_eq!(restored.version, Profile::VERSION);
+///
+/// // Imported scores join the local table, without duplicating entries
+/// let mut table = HighScoreTable::default();
+/// table.insert("ZZ", 500);
+/// restored.merge_high_scores(&mut table);
+/// restored.merge_high_scores(&mut table);
+/// assert_eq!(table.entries.len(), 2);
+/// assert_eq!(table.entries[0].score, 12000);
+///
+/// assert!(Profile::parse("{}").is_err());
+/// assert!(Profile::parse(r#"{ "version": 99 }"#).is_err());
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub version: u32,
+    pub settings: Settings,
+    pub bindings: Bindings,
+    pub hud: HudLayout,
+    pub tips_seen: Vec<String>,
+    pub challenge_stars: BTreeMap<String, u8>,
+    #[serde(with = "score_list")]
+    pub high_scores: HighScoreTable,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            version: Self::VERSION,
+            settings: Settings::default(),
+            bindings: Bindings::default(),
+            hud: HudLayout::default(),
+            tips_seen: Vec::new(),
+            challenge_stars: BTreeMap::new(),
+            high_scores: HighScoreTable::default(),
+        }
+    }
+}
+
+impl Profile {
+    /// Version of the profile schema written by this build.
+    pub const VERSION: u32 = 1;
+
+    /// Migrations bringing a profile up to date, each taking a document of
+    /// one version to the next; the first takes version 1 to version 2. When
+    /// the schema changes, bump `VERSION` and add the migration here.
+    const MIGRATIONS: &'static [fn(&mut Value)] = &[];
+
+    /// Storage key profiles are exported to and imported from.
+    const STORAGE_KEY: &'static str = "profile.json";
+
+    /// Gather the player's saved data into a profile.
+    pub fn collect() -> Self {
+        let ladder = ChallengeLadder::load();
+        let first_seen = FirstSeen::load();
+
+        let challenge_stars = ladder.challenges.iter().enumerate()
+            .filter(|(index, _)| ladder.stars(*index) > 0)
+            .map(|(index, challenge)| (challenge.name.clone(), ladder.stars(index)))
+            .collect();
+
+        let tips_seen = Mechanic::ALL.iter()
+            .filter(|mechanic| first_seen.has_seen(**mechanic))
+            .map(|mechanic| mechanic.name().to_string())
+            .collect();
+
+        Self {
+            version: Self::VERSION,
+            settings: Settings::load(),
+            bindings: Bindings::load(),
+            hud: HudLayout::load(),
+            tips_seen,
+            challenge_stars,
+            high_scores: HighScoreTable::load(),
+        }
+    }
+
+    /// Export the player's saved data.
+    pub fn export() {
+        storage::save(Self::STORAGE_KEY, &Self::collect().to_json());
+    }
+
+    /// Import a profile into the player's saved data. The game's copies of
+    /// the data must be loaded again afterwards.
+    pub fn import() -> Result<(), ProfileError> {
+        let text = storage::load(Self::STORAGE_KEY).ok_or(ProfileError::Missing)?;
+
+        Self::parse(&text)?.apply();
+        Ok(())
+    }
+
+    /// Parse a profile from a JSON document, migrating it from older versions.
+    pub fn parse(text: &str) -> Result<Self, ProfileError> {
+        let mut document: Value = serde_json::from_str(text).map_err(ProfileError::Malformed)?;
+
+        let version = document.get("version")
+            .and_then(Value::as_u64)
+            .ok_or(ProfileError::Unversioned)? as u32;
+
+        if version > Self::VERSION {
+            return Err(ProfileError::TooNew(version));
+        }
+
+        for migrate in &Self::MIGRATIONS[version.saturating_sub(1) as usize..] {
+            migrate(&mut document);
+        }
+
+        document["version"] = Value::from(Self::VERSION);
+
+        serde_json::from_value(document).map_err(ProfileError::Malformed)
+    }
+
+    /// Format the profile as a JSON document.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Save the profile over the player's saved data, merging progress.
+    fn apply(&self) {
+        self.settings.save();
+        self.bindings.save();
+        self.hud.save();
+
+        let mut first_seen = FirstSeen::load();
+
+        for mechanic in self.tips_seen.iter().filter_map(|name| Mechanic::from_name(name)) {
+            first_seen.mark(mechanic);
+        }
+
+        first_seen.save();
+
+        let mut ladder = ChallengeLadder::load();
+
+        for (name, stars) in &self.challenge_stars {
+            if let Some(index) = ladder.challenges.iter().position(|challenge| challenge.name == *name) {
+                ladder.record(index, (*stars).min(Challenge::MAX_STARS));
+            }
+        }
+
+        ladder.save();
+
+        let mut high_scores = HighScoreTable::load();
+        self.merge_high_scores(&mut high_scores);
+        high_scores.save();
+    }
+
+    /// Add the profile's high scores to a table, skipping those already in it.
+    pub fn merge_high_scores(&self, table: &mut HighScoreTable) {
+        for entry in &self.high_scores.entries {
+            if !table.entries.contains(entry) {
+                table.insert(&entry.initials, entry.score);
+            }
+        }
+    }
+}
+
+/// Serialisation of a high score table as a list of its entries, keeping them
+/// in order and within capacity when read back.
+mod score_list {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{HighScore, HighScoreTable};
+
+    pub fn serialize<S: Serializer>(table: &HighScoreTable, serializer: S) -> Result<S::Ok, S::Error> {
+        table.entries.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HighScoreTable, D::Error> {
+        let mut table = HighScoreTable::default();
+
+        for entry in Vec::<HighScore>::deserialize(deserializer)? {
+            table.insert(&entry.initials, entry.score);
+        }
+
+        Ok(table)
+    }
+}
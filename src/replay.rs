@@ -0,0 +1,261 @@
+//! Flight recorder.
+//!
+//! Records what was in play on every frame of the last game, so it can be
+//! played back frame by frame from a hidden developer screen, opened with F4
+//! from the title screen. Objects are recorded as their collision circles,
+//! which makes the recorder a tool for looking into reported collision
+//! anomalies: a player's "that rock never touched me" can be stepped through a
+//! frame at a time. The ship and asteroids collide by their outlines, so for
+//! them a circle only shows where a collision was possible.
+//!
+//! Every frame is kept in full, so it can be stepped through without playing
+//! the game out again from its inputs. Only the most recent frames are
+//! kept, and once the recorder is full the oldest frame's storage is reused.
+//! Frames cleared away when a new game starts keep their storage for the
+//! next, so once the recorder has been filled it allocates no more.
+
+use std::collections::VecDeque;
+
+use macroquad::prelude::*;
+
+/// Collision circle of a recorded object.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Circle {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+impl Circle {
+    /// Create circle at a position.
+    pub fn new(position: Vec2, radius: f32) -> Self {
+        Self { position, radius }
+    }
+}
+
+/// Frame snapshot
+///
+/// Everything in play on one frame. Ships are recorded with their heading.
+#[derive(Default)]
+pub struct FrameSnapshot {
+    pub time: f64,
+    pub wave: u32,
+    pub score: u32,
+    pub ships: Vec<(Circle, f32)>,
+    pub asteroids: Vec<Circle>,
+    pub saucers: Vec<Circle>,
+    pub player_bullets: Vec<Circle>,
+    pub enemy_bullets: Vec<Circle>,
+}
+
+impl FrameSnapshot {
+    /// Empty the snapshot, keeping its storage.
+    fn clear(&mut self) {
+        self.ships.clear();
+        self.asteroids.clear();
+        self.saucers.clear();
+        self.player_bullets.clear();
+        self.enemy_bullets.clear();
+    }
+}
+
+/// Replay event
+///
+/// Moments marked on the timeline's event track.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReplayEvent {
+    Death,
+    Wave,
+    Saucer,
+}
+
+impl ReplayEvent {
+    /// Colour of the event's marks on the event track.
+    pub fn color(&self) -> Color {
+        match self {
+            ReplayEvent::Death => RED,
+            ReplayEvent::Wave => SKYBLUE,
+            ReplayEvent::Saucer => YELLOW,
+        }
+    }
+}
+
+/// Recording
+///
+/// The frames recorded of a game, with the events marked on them. Frames are
+/// numbered from the oldest one kept.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::replay::{Recording, ReplayEvent};
+/// let mut recording = Recording::with_capacity(3);
+///
+/// for wave in 1..=4 {
+///     recording.record().wave = wave;
+/// }
+///
+/// recording.mark(ReplayEvent::Death);
+///
+/// // The first frame has been dropped to make room for the fourth
+/// assert_eq!(recording.len(), 3);
+/// assert_eq!(recording.frame(0).unwrap().wave, 2);
+/// assert_eq!(recording.events().collect::<Vec<_>>(), vec![(2, ReplayEvent::Death)]);
+/// ```
+pub struct Recording {
+    frames: VecDeque<FrameSnapshot>,
+    spare: Vec<FrameSnapshot>,
+    events: Vec<(usize, ReplayEvent)>,
+    capacity: usize,
+    dropped: usize,
+}
+
+impl Recording {
+    /// Number of frames kept by default: five minutes at 60 frames a second.
+    pub const CAPACITY: usize = 5 * 60 * 60;
+
+    /// Create an empty recording keeping the default number of frames.
+    pub fn new() -> Self {
+        Self::with_capacity(Self::CAPACITY)
+    }
+
+    /// Create an empty recording keeping a number of frames.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            spare: Vec::new(),
+            events: Vec::new(),
+            capacity: capacity.max(1),
+            dropped: 0,
+        }
+    }
+
+    /// Forget all frames and events, for example when a new game starts.
+    /// The frames' storage is kept to record into again.
+    pub fn clear(&mut self) {
+        self.spare.extend(self.frames.drain(..));
+        self.events.clear();
+        self.dropped = 0;
+    }
+
+    /// Number of frames kept.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Check if no frames have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Start recording a new frame, returning an empty snapshot to fill in.
+    /// Once the recording is full, the oldest frame is dropped.
+    pub fn record(&mut self) -> &mut FrameSnapshot {
+        let mut snapshot = if self.frames.len() == self.capacity {
+            self.dropped += 1;
+            self.events.retain(|(frame, _)| *frame >= self.dropped);
+            self.frames.pop_front().unwrap_or_default()
+        } else {
+            self.spare.pop().unwrap_or_default()
+        };
+
+        snapshot.clear();
+        self.frames.push_back(snapshot);
+        self.frames.back_mut().expect("a frame was just recorded")
+    }
+
+    /// Mark an event on the latest frame.
+    pub fn mark(&mut self, event: ReplayEvent) {
+        if !self.frames.is_empty() {
+            self.events.push((self.dropped + self.frames.len() - 1, event));
+        }
+    }
+
+    /// Frame at a position in the recording.
+    pub fn frame(&self, index: usize) -> Option<&FrameSnapshot> {
+        self.frames.get(index)
+    }
+
+    /// Events marked on the frames kept, with the frames they were marked on.
+    pub fn events(&self) -> impl Iterator<Item = (usize, ReplayEvent)> + '_ {
+        self.events.iter().map(|(frame, event)| (frame - self.dropped, *event))
+    }
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Timeline
+///
+/// The playback position in a recording, and whether it is playing or paused.
+/// Stepping or scrubbing pauses playback.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::replay::Timeline;
+/// let mut timeline = Timeline::default();
+///
+/// timeline.advance(100);
+/// assert_eq!(timeline.position, 1);
+///
+/// timeline.step(-5, 100);
+/// assert_eq!(timeline.position, 0);
+/// assert!(!timeline.playing);
+///
+/// timeline.scrub(0.5, 101);
+/// assert_eq!(timeline.position, 50);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Timeline {
+    pub position: usize,
+    pub playing: bool,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            position: 0,
+            playing: true,
+        }
+    }
+}
+
+impl Timeline {
+    /// Move on a frame if playing, stopping at the end of a recording of a
+    /// given length.
+    pub fn advance(&mut self, len: usize) {
+        if !self.playing {
+            return;
+        }
+
+        if self.position + 1 < len {
+            self.position += 1;
+        } else {
+            self.playing = false;
+        }
+    }
+
+    /// Pause, and step a number of frames forward or, if negative, back.
+    pub fn step(&mut self, frames: isize, len: usize) {
+        self.playing = false;
+        self.position = self.position.saturating_add_signed(frames).min(len.saturating_sub(1));
+    }
+
+    /// Pause, and jump to a fraction of the way through the recording.
+    pub fn scrub(&mut self, fraction: f32, len: usize) {
+        self.playing = false;
+        self.position = (fraction.clamp(0.0, 1.0) * len.saturating_sub(1) as f32).round() as usize;
+    }
+
+    /// Play if paused, or pause if playing. Playing from the end starts over.
+    pub fn toggle(&mut self, len: usize) {
+        if !self.playing && self.position + 1 >= len {
+            self.position = 0;
+        }
+
+        self.playing = !self.playing;
+    }
+}
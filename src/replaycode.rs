@@ -0,0 +1,352 @@
+//! Replay codes.
+//!
+//! A classic game can be shared as a short code to paste into a chat message,
+//! and watched by pasting the code into the box opened with C from the title
+//! screen. Rather than what happened on screen, a code holds what it takes to
+//! play the game out again: its seed, settings and rules, and the controls the player
+//! worked on every frame along with how long the frame took. Controls rarely
+//! change from one frame to the next, so only the changes are stored, as runs
+//! of identical frames, before the whole is compressed and written out in
+//! URL-safe base64.
+//!
+//! Codes cover the first two minutes of a game. A game being watched moves
+//! its clock on by the recorded frame times, so saucer, respawn and other
+//! gameplay timers run just as they did when it was played.
+
+use std::fmt;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+use crate::difficulty::Difficulty;
+use crate::ship::Ship;
+
+/// Input frame
+///
+/// The controls worked on one frame: the turn rate passed to `Ship::steer`,
+/// the buttons held or pressed, and the frame's duration in milliseconds.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct InputFrame {
+    steer: i8,
+    pub buttons: u16,
+    duration: u8,
+}
+
+impl InputFrame {
+    pub const THRUST: u16 = 1 << 0;
+    pub const FIRE: u16 = 1 << 1;
+    pub const HYPERSPACE: u16 = 1 << 2;
+    pub const REVERSE_THRUST: u16 = 1 << 3;
+    pub const STRAFE_LEFT: u16 = 1 << 4;
+    pub const STRAFE_RIGHT: u16 = 1 << 5;
+    pub const DECOY: u16 = 1 << 6;
+    pub const LIGHTNING: u16 = 1 << 7;
+    pub const OVERDRIVE: u16 = 1 << 8;
+    pub const RADAR: u16 = 1 << 9;
+    pub const REWIND: u16 = 1 << 10;
+    pub const BOMB: u16 = 1 << 11;
+    pub const SHIELD: u16 = 1 << 12;
+
+    /// Steps a turn rate is recorded in, per unit of rate.
+    const STEER_STEPS: f32 = 1000.0;
+
+    /// Frame working the controls for a turn rate and set of buttons, lasting
+    /// a time in seconds.
+    pub fn new(steer: f32, buttons: u16, dt: f32) -> Self {
+        Self {
+            steer: (steer * Self::STEER_STEPS).round().clamp(-127.0, 127.0) as i8,
+            buttons,
+            duration: (dt * 1000.0).round().clamp(1.0, 255.0) as u8,
+        }
+    }
+
+    /// Turn rate, as passed to `Ship::steer`.
+    pub fn steer(&self) -> f32 {
+        self.steer as f32 / Self::STEER_STEPS
+    }
+
+    /// Check if a button was held or pressed.
+    pub fn is_down(&self, button: u16) -> bool {
+        self.buttons & button != 0
+    }
+
+    /// Duration of the frame in seconds.
+    pub fn dt(&self) -> f32 {
+        self.duration as f32 / 1000.0
+    }
+}
+
+/// Rules
+///
+/// The tunable rules a game is played by, fixed when it starts: how many
+/// seconds the shield lasts and takes to recharge, whether asteroids bounce
+/// off each other, and whether arcade scoring is used. Codes and movies
+/// record them, so a game plays out again the same way whatever the options
+/// say.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Rules {
+    pub shield_duration: f32,
+    pub shield_recharge: f32,
+    pub bouncing_asteroids: bool,
+    pub arcade_scoring: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            shield_duration: Ship::SHIELD_DURATION,
+            shield_recharge: Ship::SHIELD_RECHARGE,
+            bouncing_asteroids: false,
+            arcade_scoring: false,
+        }
+    }
+}
+
+/// Replay code error
+///
+/// Reasons a pasted code could not be read.
+#[derive(Debug, PartialEq)]
+pub enum CodeError {
+    /// The text is not a replay code.
+    NotACode,
+    /// The code is damaged or incomplete.
+    Corrupt,
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodeError::NotACode => write!(f, "not a replay code"),
+            CodeError::Corrupt => write!(f, "replay code is damaged or incomplete"),
+        }
+    }
+}
+
+/// Replay code
+///
+/// A classic game's seed, settings and rules, and the controls worked on each
+/// of its frames up to a limit.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::difficulty::Difficulty;
+/// # use asteroids::replaycode::{InputFrame, ReplayCode};
+/// let mut game = ReplayCode::new(42, Difficulty::Arcade, 3, false);
+/// game.rules.shield_duration = 4.5;
+/// game.rules.bouncing_asteroids = true;
+///
+/// for frame in 0..ReplayCode::MAX_FRAMES {
+///     let buttons = if frame % 120 < 30 { InputFrame::THRUST } else { InputFrame::FIRE };
+///     game.push(InputFrame::new(-0.1, buttons, 1.0 / 60.0));
+/// }
+///
+/// // Full codes stay short enough to paste into a chat message
+/// let code = game.encode();
+/// assert!(code.len() < 1000);
+///
+/// let shared = ReplayCode::decode(&code).unwrap();
+/// assert_eq!(shared, game);
+/// assert_eq!(shared.frame(0).unwrap().steer(), -0.1);
+/// assert_eq!(shared.rules.shield_duration, 4.5);
+/// assert!(shared.rules.bouncing_asteroids);
+///
+/// assert!(ReplayCode::decode("hello").is_err());
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct ReplayCode {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub starting_lives: u32,
+    pub bullet_limit: bool,
+    pub rules: Rules,
+    frames: Vec<InputFrame>,
+}
+
+impl ReplayCode {
+    /// Most frames a code holds: two minutes at 60 frames a second.
+    pub const MAX_FRAMES: usize = 2 * 60 * 60;
+
+    /// Marks the start of a code, and the version of its format.
+    const PREFIX: &'static str = "AST2:";
+
+    /// Marks the start of a code written before games recorded their rules,
+    /// which are read back as the defaults they were watched with.
+    const PREFIX_V1: &'static str = "AST1:";
+
+    /// Largest decompressed code accepted, well over the size of a full one.
+    const MAX_DECODED_SIZE: usize = 64 * 1024;
+
+    /// Start recording a game with a seed and settings, played by the default
+    /// rules.
+    pub fn new(seed: u64, difficulty: Difficulty, starting_lives: u32, bullet_limit: bool) -> Self {
+        Self {
+            seed,
+            difficulty,
+            starting_lives,
+            bullet_limit,
+            rules: Rules::default(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Code holding every frame of a game, however long, to watch. Only the
+    /// first `MAX_FRAMES` of them are written out by `encode`.
+    pub fn with_frames(seed: u64, difficulty: Difficulty, starting_lives: u32, bullet_limit: bool, frames: Vec<InputFrame>) -> Self {
+        Self { frames, ..Self::new(seed, difficulty, starting_lives, bullet_limit) }
+    }
+
+    /// Record a frame. Returns false, recording nothing, once the code is full.
+    pub fn push(&mut self, frame: InputFrame) -> bool {
+        if self.is_full() {
+            return false;
+        }
+
+        self.frames.push(frame);
+        true
+    }
+
+    /// Check if the code holds as many frames as it can.
+    pub fn is_full(&self) -> bool {
+        self.frames.len() >= Self::MAX_FRAMES
+    }
+
+    /// Number of frames recorded.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Check if no frames have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Frame at a position in the game.
+    pub fn frame(&self, index: usize) -> Option<InputFrame> {
+        self.frames.get(index).copied()
+    }
+
+    /// Length of the recorded game in seconds.
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(InputFrame::dt).sum()
+    }
+
+    /// Write the game out as a code.
+    pub fn encode(&self) -> String {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.push(Difficulty::ALL.iter().position(|difficulty| *difficulty == self.difficulty).unwrap_or(1) as u8);
+        bytes.push(self.starting_lives.min(u8::MAX as u32) as u8);
+        bytes.push(self.bullet_limit as u8);
+        bytes.extend_from_slice(&self.rules.shield_duration.to_le_bytes());
+        bytes.extend_from_slice(&self.rules.shield_recharge.to_le_bytes());
+        bytes.push(self.rules.bouncing_asteroids as u8);
+        bytes.push(self.rules.arcade_scoring as u8);
+
+        // Runs of identical frames, each as its length followed by the frame
+        let mut frames = self.frames.iter().take(Self::MAX_FRAMES).peekable();
+
+        while let Some(frame) = frames.next() {
+            let mut run = 1;
+
+            while frames.next_if_eq(&frame).is_some() {
+                run += 1;
+            }
+
+            write_varint(&mut bytes, run);
+            bytes.push(frame.steer as u8);
+            bytes.extend_from_slice(&frame.buttons.to_le_bytes());
+            bytes.push(frame.duration);
+        }
+
+        let compressed = miniz_oxide::deflate::compress_to_vec(&bytes, 9);
+
+        format!("{}{}", Self::PREFIX, URL_SAFE_NO_PAD.encode(compressed))
+    }
+
+    /// Read a game back from a code. Whitespace picked up when copying the
+    /// code around is ignored.
+    pub fn decode(code: &str) -> Result<Self, CodeError> {
+        let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+        let (body, has_rules) = match code.strip_prefix(Self::PREFIX) {
+            Some(body) => (body, true),
+            None => (code.strip_prefix(Self::PREFIX_V1).ok_or(CodeError::NotACode)?, false),
+        };
+
+        let compressed = URL_SAFE_NO_PAD.decode(body).map_err(|_| CodeError::Corrupt)?;
+        let bytes = miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, Self::MAX_DECODED_SIZE)
+            .map_err(|_| CodeError::Corrupt)?;
+
+        let mut reader = bytes.iter().copied();
+        let mut next = || reader.next().ok_or(CodeError::Corrupt);
+
+        let mut seed = [0; 8];
+        for byte in &mut seed {
+            *byte = next()?;
+        }
+
+        let difficulty = *Difficulty::ALL.get(next()? as usize).ok_or(CodeError::Corrupt)?;
+        let mut game = Self::new(u64::from_le_bytes(seed), difficulty, next()? as u32, next()? != 0);
+
+        if has_rules {
+            // Times are only ever positive
+            let mut read_time = || {
+                let time = f32::from_le_bytes([next()?, next()?, next()?, next()?]);
+                if time.is_finite() && time > 0.0 { Ok(time) } else { Err(CodeError::Corrupt) }
+            };
+
+            game.rules.shield_duration = read_time()?;
+            game.rules.shield_recharge = read_time()?;
+            game.rules.bouncing_asteroids = next()? != 0;
+            game.rules.arcade_scoring = next()? != 0;
+        }
+
+        while let Some(run) = read_varint(&mut next)? {
+            let frame = InputFrame {
+                steer: next()? as i8,
+                buttons: u16::from_le_bytes([next()?, next()?]),
+                duration: next()?,
+            };
+
+            if run > Self::MAX_FRAMES - game.frames.len() {
+                return Err(CodeError::Corrupt);
+            }
+
+            game.frames.extend(std::iter::repeat_n(frame, run));
+        }
+
+        Ok(game)
+    }
+}
+
+/// Write a number in as few bytes as it takes, seven bits at a time.
+fn write_varint(bytes: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(value as u8);
+}
+
+/// Read a number written by `write_varint`, or nothing at the end of the
+/// bytes.
+fn read_varint(next: &mut impl FnMut() -> Result<u8, CodeError>) -> Result<Option<usize>, CodeError> {
+    let mut value = 0;
+
+    for shift in (0..28).step_by(7) {
+        let Ok(byte) = next() else {
+            // Running out of bytes is only expected before a number starts
+            return if shift == 0 { Ok(None) } else { Err(CodeError::Corrupt) };
+        };
+
+        value |= ((byte & 0x7f) as usize) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+
+    Err(CodeError::Corrupt)
+}
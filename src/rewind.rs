@@ -0,0 +1,94 @@
+//! Rewind.
+//!
+//! The chrono, a rare find in salvage crates, rolls the field back by a few
+//! seconds, undoing a fatal mistake. While a player holds a chrono, the world
+//! is snapshotted a few times a second into a ring of snapshots covering the
+//! rewind; rewinding restores the oldest of them.
+//!
+//! Snapshots older than the rewind are dropped as new ones are taken, so the
+//! ring stays the same size however long a game runs. Gameplay timers run on
+//! the clock rather than in the snapshots, so timers such as a saucer's next
+//! shot carry on from where they were.
+
+use std::collections::VecDeque;
+
+/// Snapshot ring
+///
+/// Snapshots taken at an interval, covering a window of time.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::rewind::SnapshotRing;
+/// let mut ring = SnapshotRing::new(0.5, 3.0);
+///
+/// for tick in 0..20 {
+///     let now = tick as f64 * 0.25;
+///
+///     if ring.is_due(now) {
+///         ring.push(now, tick);
+///     }
+/// }
+///
+/// // Half a second apart, and no older than three seconds
+/// assert_eq!(ring.len(), 7);
+///
+/// // Rewinding goes back to the oldest, and starts over from there
+/// assert_eq!(ring.take_oldest(), Some(6));
+/// assert!(ring.is_empty());
+/// ```
+pub struct SnapshotRing<T> {
+    snapshots: VecDeque<(f64, T)>,
+    interval: f64,
+    window: f64,
+}
+
+impl<T> SnapshotRing<T> {
+    /// Create an empty ring taking a snapshot every interval, keeping those
+    /// within a window, both in seconds.
+    pub fn new(interval: f64, window: f64) -> Self {
+        Self {
+            snapshots: VecDeque::with_capacity((window / interval) as usize + 1),
+            interval,
+            window,
+        }
+    }
+
+    /// Check if it is time for another snapshot.
+    pub fn is_due(&self, now: f64) -> bool {
+        self.snapshots.back().is_none_or(|(time, _)| now - time >= self.interval)
+    }
+
+    /// Keep a snapshot, forgetting those that have fallen out of the window.
+    pub fn push(&mut self, now: f64, snapshot: T) {
+        while self.snapshots.front().is_some_and(|(time, _)| *time < now - self.window) {
+            self.snapshots.pop_front();
+        }
+
+        self.snapshots.push_back((now, snapshot));
+    }
+
+    /// Number of snapshots kept.
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Check if no snapshots are kept.
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Forget every snapshot, for example when a new game starts.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    /// Take the oldest snapshot to rewind to, forgetting the rest, which are
+    /// now in the future.
+    pub fn take_oldest(&mut self) -> Option<T> {
+        let oldest = self.snapshots.pop_front().map(|(_, snapshot)| snapshot);
+        self.snapshots.clear();
+
+        oldest
+    }
+}
@@ -0,0 +1,205 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::camera::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::collision::Collidable;
+use crate::wrap::{self, WrapDraw};
+
+/// Salvage
+///
+/// The small rewards found in a salvage crate: a spare decoy, ore worth a shop
+/// credit, a bounty in points, a coolant vent that sheds the hyperspace
+/// drive's strain, or, rarely, a chrono that rewinds the last few seconds.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Salvage {
+    Decoy,
+    Ore,
+    Points,
+    HeatVent,
+    Chrono,
+}
+
+impl Salvage {
+    /// Points awarded by a bounty.
+    pub const POINTS: u32 = 500;
+
+    /// Credits awarded for ore.
+    pub const ORE_CREDITS: u32 = 1;
+
+    /// Short description shown when the salvage is collected.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Salvage::Decoy => "Decoy +1",
+            Salvage::Ore => "Ore +1 Credit",
+            Salvage::Points => "Bounty +500",
+            Salvage::HeatVent => "Hyperspace Vented",
+            Salvage::Chrono => "Chrono +1 Rewind",
+        }
+    }
+}
+
+/// Loot table
+///
+/// The chance of a destroyed saucer dropping a salvage crate, and the weights
+/// with which each kind of salvage is found in one.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::salvage::{LootTable, Salvage};
+/// let table = LootTable::default();
+///
+/// assert_eq!(table.total_weight(), 11);
+/// assert_eq!(table.pick(0), Salvage::Decoy);
+/// assert_eq!(table.pick(3), Salvage::Ore);
+/// assert_eq!(table.pick(9), Salvage::HeatVent);
+/// ```
+#[derive(Clone)]
+pub struct LootTable {
+    /// Chance, from 0 to 1, of a destroyed saucer dropping a crate.
+    pub drop_chance: f32,
+    pub entries: Vec<(Salvage, u32)>,
+}
+
+impl Default for LootTable {
+    fn default() -> Self {
+        Self {
+            drop_chance: 0.4,
+            entries: vec![
+                (Salvage::Decoy, 3),
+                (Salvage::Ore, 3),
+                (Salvage::Points, 2),
+                (Salvage::HeatVent, 2),
+                (Salvage::Chrono, 1),
+            ],
+        }
+    }
+}
+
+impl LootTable {
+    /// Sum of the weights of all entries.
+    pub fn total_weight(&self) -> u32 {
+        self.entries.iter().map(|(_, weight)| weight).sum()
+    }
+
+    /// Salvage found at a position in the table's weight range, running from
+    /// zero up to the total weight.
+    pub fn pick(&self, mut roll: u32) -> Salvage {
+        for (salvage, weight) in &self.entries {
+            if roll < *weight {
+                return *salvage;
+            }
+
+            roll -= weight;
+        }
+
+        self.entries.last().map_or(Salvage::Points, |(salvage, _)| *salvage)
+    }
+
+    /// Roll for a crate dropped by a destroyed saucer, returning its contents
+    /// if one is dropped.
+    pub fn roll(&self, rng: &RandGenerator) -> Option<Salvage> {
+        if self.entries.is_empty() || rng.gen_range(0.0, 1.0) >= self.drop_chance {
+            return None;
+        }
+
+        Some(self.draw(rng))
+    }
+
+    /// Draw the contents of a crate that is certain to drop.
+    pub fn draw(&self, rng: &RandGenerator) -> Salvage {
+        self.pick(rng.gen_range(0, self.total_weight().max(1)))
+    }
+}
+
+/// Salvage crate object
+///
+/// Salvage crates are left behind by destroyed saucers. They drift slowly,
+/// wrap around the screen, and are collected by flying a ship into them. A
+/// crate not collected in time is lost, blinking for its last few seconds.
+#[derive(Clone)]
+pub struct SalvageCrate {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub radius: f32,
+    pub contents: Salvage,
+    pub expiry: f64,
+    pub is_alive: bool,
+}
+
+impl SalvageCrate {
+    /// Time in seconds a crate lasts before it is lost.
+    pub const LIFESPAN: f64 = 8.0;
+
+    /// Time in seconds before expiry the crate starts blinking.
+    const BLINK_TIME: f64 = 2.0;
+
+    /// Spawn new crate at a given position and time, drifting with a given
+    /// velocity.
+    pub fn spawn_new(position: Vec2, velocity: Vec2, contents: Salvage, now: f64) -> Self {
+        let screen_edge = WORLD_WIDTH.min(WORLD_HEIGHT);
+
+        Self {
+            position,
+            velocity,
+            radius: screen_edge * 0.012,
+            contents,
+            expiry: now + Self::LIFESPAN,
+            is_alive: true,
+        }
+    }
+
+    /// Destroy crate by marking it dead. Any calls to `is_alive` will return
+    /// false after this function is called.
+    pub fn destroy(&mut self) {
+        self.is_alive = false;
+    }
+
+    /// Check if crate is still alive at a time.
+    pub fn is_alive(&self, now: f64) -> bool {
+        self.is_alive && now < self.expiry
+    }
+
+    /// Update crate position.
+    pub fn update(&mut self) {
+        self.position += self.velocity;
+
+        // Wrap around screen
+        self.position = wrap::wrap(self.position, self.radius);
+    }
+
+    /// Draw crate as a braced box, blinking as it is about to be lost at a
+    /// time.
+    pub fn draw(&self, now: f64) {
+        let remaining = self.expiry - now;
+
+        if remaining < Self::BLINK_TIME && (remaining * 8.0).fract() < 0.5 {
+            return;
+        }
+
+        let r = self.radius;
+        let color = Color::new(1.0, 0.8, 0.3, 1.0);
+
+        self.draw_wrapped(|position| {
+            let (x, y) = (position.x, position.y);
+
+            draw_rectangle_lines(x - r, y - r, r * 2.0, r * 2.0, 2., color);
+            draw_line(x - r, y - r, x + r, y + r, 1., color);
+            draw_line(x - r, y + r, x + r, y - r, 1., color);
+        });
+    }
+}
+
+impl Collidable for SalvageCrate {
+    fn is_colliding(&self, other: &dyn Collidable) -> bool {
+        <dyn Collidable>::shape_intersection(self, other)
+    }
+
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn get_radius(&self) -> f32 {
+        self.radius
+    }
+}
@@ -0,0 +1,405 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::ai::{self, Target};
+use crate::asteroid::Asteroid;
+use crate::bullet::{Bullet, BulletPattern, BulletType};
+use crate::camera::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::collision::{wrapped_delta, Collidable};
+use crate::config;
+use crate::detmath;
+use crate::entity::EntityId;
+use crate::linebatch::LineBatch;
+use crate::wrap::{self, wrap_draw};
+
+/// SaucerSize
+/// 
+/// Saucers come in two sizes: small and large. The size determines the visual representation
+/// of the saucer as well as its logic. Small saucers are faster and aim at the player, while
+/// large saucers are slower and shoot in random directions.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SaucerSize {
+    Small,
+    Large,
+}
+
+/// Saucer cue
+///
+/// Moments a saucer gives away in its flight: turning onto a new heading, and
+/// charging a shot it is about to fire.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SaucerCue {
+    Turn,
+    Charge,
+}
+
+/// Saucer Object
+///
+/// Saucers move from left to right or right to left, and shoot bullets at the player. As in
+/// the arcade, they cross the screen once and leave by the far side, wrapping only across the
+/// top and bottom edges, and head off by the nearer side if they linger. They can change
+/// direction periodically. The direction change is always less that 10 degrees, and never
+/// takes them more than 45 degrees off the heading they cross on. Saucers come in two sizes: small and
+/// large. Small saucers are faster and aim at the player, while large saucers are slower and
+/// shoot in random directions. Either releases its shots in a bullet pattern, a single bullet
+/// unless a script says otherwise.
+#[derive(Clone)]
+pub struct Saucer {
+    pub id: EntityId,
+    pub size: SaucerSize,
+    pub diameter: f32,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub direction: f32,
+    pub heading: f32,
+    pub direction_change_period: f64,
+    pub shoot_period: f64,
+    pub shot_speed: f32,
+    pub spawned_at: f64,
+    pub vertices: Vec<Vec2>,
+    pub is_alive: bool,
+    pub is_lucky: bool,
+    pub is_departing: bool,
+    pub accuracy_bonus: f32,
+    pub pattern: BulletPattern,
+    next_shot_fires: bool,
+    charge_cued: bool,
+}
+
+impl Saucer {    
+    /// Spawn new saucer at a time
+    pub fn spawn_new(rng: &RandGenerator, size: SaucerSize, now: f64) -> Self {
+        let screen_edge: f32 = WORLD_WIDTH.min(WORLD_HEIGHT);
+        let balance = config::balance().saucers;
+
+        let diameter = match size {
+            SaucerSize::Small => screen_edge * balance.small_diameter,
+            SaucerSize::Large => screen_edge * balance.large_diameter,
+        };
+        
+        let speed = match size {
+            SaucerSize::Small => screen_edge * balance.small_speed,
+            SaucerSize::Large => screen_edge * balance.large_speed,
+        };
+
+        let (position, direction) = match rng.gen_range(0, 2) { 
+            0 => (Vec2::new(0., rng.gen_range(0.0, WORLD_HEIGHT)), 0.0),
+            1 => (Vec2::new(WORLD_WIDTH, rng.gen_range(0.0, WORLD_HEIGHT)), std::f32::consts::PI),
+            _ => (Vec2::new(0., 0.), 0.0),
+        };
+
+        // Generate random direction and velocity
+        let velocity = detmath::rotation(direction).mul_vec2(Vec2::X * speed);
+
+        // Generate vertices
+        let radius = diameter / 2.0;
+        let vertices: Vec<Vec2> = vec![
+            Vec2::new(-radius * 1.25, 0.0),
+            Vec2::new(-radius / 2.0, radius / 2.0),
+            Vec2::new(radius / 2.0, radius / 2.0),
+            Vec2::new(radius * 1.25, 0.0),
+            Vec2::new(-radius * 1.25, 0.0),
+            Vec2::new(-radius / 2.0, -radius / 2.0),
+            Vec2::new(-radius / 3.0, -radius),
+            Vec2::new(radius / 3.0, -radius),
+            Vec2::new(radius / 2.0, -radius / 2.0),
+            Vec2::new(radius * 1.25, 0.0),
+            Vec2::new(radius / 2.0, -radius / 2.0),
+            Vec2::new(-radius / 2.0, -radius / 2.0),
+        ];
+
+        Self {
+            id: EntityId::next(),
+            size,
+            diameter,
+            position,
+            velocity,
+            direction,
+            heading: direction,
+            direction_change_period: now + 1.0,
+            shoot_period: now + 1.0,
+            shot_speed: balance.shot_speed,
+            spawned_at: now,
+            vertices,
+            is_alive: true,
+            is_lucky: false,
+            is_departing: false,
+            accuracy_bonus: 0.0,
+            pattern: BulletPattern::Single,
+            next_shot_fires: rng.gen_range(0.0, 1.0) > 0.5,
+            charge_cued: false,
+        }
+    }
+
+    /// Spawn a lucky saucer: a large saucer in gold that never shoots, and is
+    /// worth a bonus for shooting down.
+    pub fn spawn_lucky(rng: &RandGenerator, now: f64) -> Self {
+        Self {
+            is_lucky: true,
+            ..Self::spawn_new(rng, SaucerSize::Large, now)
+        }
+    }
+
+    /// Destroy saucer by marking it dead. Any calls to `is_alive` will return
+    /// false after this function is called.
+    pub fn destroy(&mut self) {
+        self.is_alive = false;
+    }
+
+    /// Check if saucer is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.is_alive
+    }
+
+    /// Send the saucer away, once a game is over or it has lingered too long.
+    /// It holds its fire and heads for the nearer side of the screen, and is
+    /// gone once past it.
+    pub fn depart(&mut self) {
+        if self.is_departing {
+            return;
+        }
+
+        self.is_departing = true;
+        self.heading = if self.position.x < WORLD_WIDTH / 2.0 { std::f32::consts::PI } else { 0.0 };
+        self.direction = self.heading;
+        self.velocity = detmath::direction(self.direction) * self.velocity.length();
+    }
+
+    /// Check if the saucer has left the screen by either side.
+    pub fn has_left(&self) -> bool {
+        self.position.x < -self.diameter || self.position.x > WORLD_WIDTH + self.diameter
+    }
+
+    /// Check if the saucer is over the screen, rather than entering or
+    /// leaving past a side.
+    fn is_on_screen(&self) -> bool {
+        (0.0..=WORLD_WIDTH).contains(&self.position.x)
+    }
+
+    /// Damage needed to destroy the saucer with a single hit from weapons
+    /// that deal partial damage, such as chain lightning.
+    pub fn toughness(&self) -> f32 {
+        match self.size {
+            SaucerSize::Small => 0.5,
+            SaucerSize::Large => 0.3,
+        }
+    }
+
+    /// Points scored for destroying the saucer.
+    pub fn points(&self) -> u32 {
+        if self.is_lucky {
+            return Self::LUCKY_POINTS;
+        }
+
+        let balance = config::balance().saucers;
+
+        match self.size {
+            SaucerSize::Small => balance.small_points,
+            SaucerSize::Large => balance.large_points,
+        }
+    }
+
+    /// Points scored for shooting down a lucky saucer.
+    pub const LUCKY_POINTS: u32 = 1313;
+
+    /// Colour of a lucky saucer.
+    const LUCKY_GOLD: Color = Color::new(1.0, 0.8, 0.2, 1.0);
+
+    /// Time in seconds a saucer lingers before it heads off.
+    pub const LIFETIME: f64 = 30.0;
+
+    /// Furthest, in radians, a saucer strays from the heading it crosses on.
+    const MAX_DRIFT: f32 = std::f32::consts::FRAC_PI_4;
+
+    /// Time in seconds before a shot that a saucer is seen charging it.
+    const CHARGE_TIME: f64 = 0.4;
+
+    /// Largest aiming error, in radians, of a small saucer at the lowest
+    /// accuracy.
+    const MAX_AIM_ERROR: f32 = 0.35;
+
+    /// Accuracy of small saucers in a wave, from 0 to 1: they start out a
+    /// little wild and are deadly by wave 10.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::saucer::Saucer;
+    /// assert!(Saucer::accuracy_for_wave(1) < Saucer::accuracy_for_wave(5));
+    /// assert_eq!(Saucer::accuracy_for_wave(10), 1.0);
+    /// assert_eq!(Saucer::accuracy_for_wave(50), 1.0);
+    /// ```
+    pub fn accuracy_for_wave(wave: u32) -> f32 {
+        (0.4 + 0.6 * wave.saturating_sub(1) as f32 / 9.0).min(1.0)
+    }
+
+    /// Shoot bullets at a time. Saucers shoot bullets at the player. Small saucers lead
+    /// the target by its velocity, missing by up to a few degrees depending on
+    /// their accuracy, while large saucers shoot in random directions. Whether
+    /// each shot is fired or held is decided a shot ahead, so that shots about
+    /// to be fired can be seen charging. Saucers only shoot while over the
+    /// screen, and as they do not wrap across its sides, small saucers aim
+    /// the short way across the top and bottom edges only. A shot is released
+    /// in the saucer's bullet pattern.
+    pub fn shoot(&mut self, rng: &RandGenerator, target: Target, accuracy: f32, now: f64) -> Vec<Bullet> {
+        // Decide if we should shoot; lucky and departing saucers never do
+        if !self.is_lucky && !self.is_departing && self.is_on_screen() && self.shoot_period < now {            
+            
+            // Reset period, and decide on the next shot
+            self.shoot_period = now + 1.0;
+            self.charge_cued = false;
+
+            let fires = std::mem::replace(&mut self.next_shot_fires, rng.gen_range(0.0, 1.0) > 0.5);
+
+            // Shoot
+            if fires {
+                let direction = match self.size {
+                    SaucerSize::Small => {
+                        let delta = wrapped_delta(self.position, target.position);
+                        let delta = Vec2::new(target.position.x - self.position.x, delta.y);
+
+                        let aim = ai::lead(delta, target.velocity, self.shot_speed);
+                        let error = (1.0 - accuracy.clamp(0.0, 1.0)) * Self::MAX_AIM_ERROR * rng.gen_range(-1.0, 1.0);
+                        detmath::angle_of(aim) + error
+                    },
+                    SaucerSize::Large => rng.gen_range(0.0, 2.0 * std::f32::consts::PI),
+                };
+
+                return self.pattern.fire(self.position, direction, self.shot_speed, 100.0, BulletType::Enemy);
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// Distance, in saucer diameters beyond an asteroid's edge, at which a
+    /// saucer starts veering away from it.
+    const AVOID_RANGE: f32 = 2.0;
+
+    /// Fastest a saucer turns away from asteroids, in radians per frame.
+    const AVOID_TURN_RATE: f32 = 0.08;
+
+    /// Update saucer position at a time, veering away from asteroids in its
+    /// path. Returns the cue the saucer gives, if it turns onto a new heading
+    /// or starts charging a shot.
+    pub fn update(&mut self, rng: &RandGenerator, asteroids: &[Asteroid], now: f64) -> Option<SaucerCue> {
+        self.avoid(asteroids);
+        self.position += self.velocity;
+
+        // Saucers wrap across the top and bottom edges, but not the sides
+        self.position.y = wrap::wrap(self.position, self.diameter / 2.0).y;
+
+        // A saucer past either side has left the screen for good
+        if self.has_left() {
+            self.destroy();
+            return None;
+        }
+
+        // A saucer that lingers heads off
+        if now - self.spawned_at > Self::LIFETIME {
+            self.depart();
+        }
+
+        // A departing saucer keeps its heading until it is off the screen
+        if self.is_departing {
+            return None;
+        }
+
+        let mut cue = None;
+
+        // Navigation check
+        if self.direction_change_period < now {
+            
+            // Reset period
+            self.direction_change_period = now + 1.0;
+
+            // Change direction?
+            if rng.gen_range(0.0, 1.0) > 0.5 {
+                self.steer(self.direction + rng.gen_range(-1.0, 1.0) * 10.0 / 180.0 * std::f32::consts::PI);
+
+                cue = Some(SaucerCue::Turn);
+            }
+        }
+
+        // Charge a shot that is about to be fired
+        if !self.is_lucky && self.next_shot_fires && !self.charge_cued && self.shoot_period - now < Self::CHARGE_TIME {
+            self.charge_cued = true;
+            cue = Some(SaucerCue::Charge);
+        }
+
+        cue
+    }
+
+    /// Turn the saucer towards a direction, as far as it strays from the
+    /// heading it crosses on.
+    fn steer(&mut self, direction: f32) {
+        let drift = detmath::angle_of(detmath::direction(direction - self.heading));
+
+        self.direction = self.heading + drift.clamp(-Self::MAX_DRIFT, Self::MAX_DRIFT);
+        self.velocity = detmath::rotation(self.direction).mul_vec2(Vec2::X * self.velocity.length());
+    }
+
+    /// Turn away from nearby asteroids ahead of the saucer, more sharply the
+    /// closer they are.
+    fn avoid(&mut self, asteroids: &[Asteroid]) {
+        let heading = detmath::direction(self.direction);
+        let mut push = Vec2::ZERO;
+
+        for asteroid in asteroids.iter().filter(|asteroid| asteroid.is_alive()) {
+            let away = wrapped_delta(asteroid.position, self.position);
+            let range = asteroid.get_radius() + self.diameter * Self::AVOID_RANGE;
+            let distance = away.length();
+
+            // Only asteroids ahead are a worry
+            if distance > 0.0 && distance < range && away.dot(heading) < 0.0 {
+                push += away / distance * (1.0 - distance / range);
+            }
+        }
+
+        if push == Vec2::ZERO {
+            return;
+        }
+
+        let desired = detmath::angle_of(heading + push * 2.0);
+        let turn = detmath::angle_of(detmath::direction(desired - self.direction));
+
+        self.steer(self.direction + turn.clamp(-Self::AVOID_TURN_RATE, Self::AVOID_TURN_RATE));
+    }
+
+    /// Draw saucer into a batch of lines.
+    pub fn draw(&self, lines: &mut LineBatch) {
+        self.draw_tinted(lines, WHITE);
+    }
+
+    /// Draw saucer into a batch of lines in a colour, such as a faded white
+    /// when it is out of sight.
+    pub fn draw_tinted(&self, lines: &mut LineBatch, color: Color) {
+        let color = match self.is_lucky {
+            true => Color { a: color.a, ..Self::LUCKY_GOLD },
+            false => color,
+        };
+
+        // Draw saucer, and its ghosts across the top and bottom edges it
+        // straddles; it never wraps across the sides. The hull reaches past
+        // the collision circle at either side
+        wrap_draw(self.position, self.diameter / 2.0 * 1.25, |position| {
+            if position.x == self.position.x {
+                lines.push_polygon(position, &self.vertices, Mat2::IDENTITY, 2., color);
+            }
+        });
+    }
+}
+
+impl Collidable for Saucer {
+    fn is_colliding(&self, other: &dyn Collidable) -> bool {
+        <dyn Collidable>::shape_intersection(self, other)
+    }
+
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn get_radius(&self) -> f32 {
+        self.diameter / 2.0
+    }
+}
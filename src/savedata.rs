@@ -0,0 +1,152 @@
+//! Versioned save data.
+//!
+//! Sits between the saved documents (settings, bindings, high scores and the
+//! rest) and `storage`, so a change to a document's format never costs a
+//! player their history:
+//!
+//! - Every document is saved under a header line naming the version of its
+//!   format, `# asteroids save v1`. The header reads as a comment in TOML, and
+//!   the plain text formats skip it as an unreadable line, so older builds can
+//!   still read newer saves. Documents saved before versioning have no header,
+//!   and are taken to be version 1.
+//! - Documents of an older version are brought up to date on load by their
+//!   format's migrations, run in order.
+//! - Before a document is overwritten, the previous copy is kept under the
+//!   same key with `.bak` appended.
+//! - A document that cannot be read, whether corrupted or written by a newer
+//!   version of the game, is set aside with `.unreadable` appended rather than
+//!   overwritten, and the backup is restored in its place if it can be read.
+
+use serde::de::DeserializeOwned;
+
+use crate::storage;
+
+/// Migration of a document's body from one version of its format to the next.
+pub type Migration = fn(&str) -> String;
+
+/// Save format
+///
+/// The current version of a document's format, and the migrations bringing
+/// older versions up to date: the first migration takes version 1 to version
+/// 2, and so on. When a format changes, bump its version and add a migration.
+pub struct SaveFormat {
+    pub version: u32,
+    pub migrations: &'static [Migration],
+}
+
+impl SaveFormat {
+    /// First version of a format, with nothing to migrate.
+    pub const INITIAL: SaveFormat = SaveFormat {
+        version: 1,
+        migrations: &[],
+    };
+
+    /// Header line marking a document's version.
+    fn header(version: u32) -> String {
+        format!("# asteroids save v{}\n", version)
+    }
+
+    /// Add the format's header to a document's body.
+    pub fn encode(&self, body: &str) -> String {
+        Self::header(self.version) + body
+    }
+
+    /// Strip a document's header, migrating its body to the current version.
+    /// Returns nothing if the document was written by a newer version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::savedata::SaveFormat;
+    /// const FORMAT: SaveFormat = SaveFormat {
+    ///     version: 2,
+    ///     migrations: &[|body| body.replace("lives", "starting_lives")],
+    /// };
+    ///
+    /// assert_eq!(FORMAT.decode(&FORMAT.encode("volume = 1")).unwrap(), "volume = 1");
+    /// assert_eq!(FORMAT.decode("# asteroids save v1\nlives = 3").unwrap(), "starting_lives = 3");
+    ///
+    /// // Documents saved before versioning are version 1
+    /// assert_eq!(FORMAT.decode("lives = 3").unwrap(), "starting_lives = 3");
+    ///
+    /// assert!(FORMAT.decode("# asteroids save v3\n").is_none());
+    /// ```
+    pub fn decode(&self, text: &str) -> Option<String> {
+        let (version, body) = match text.split_once('\n') {
+            Some((first, rest)) => match first.trim().strip_prefix("# asteroids save v") {
+                Some(version) => (version.parse::<u32>().ok()?, rest),
+                None => (1, text),
+            },
+            None => (1, text),
+        };
+
+        if version > self.version {
+            return None;
+        }
+
+        let first = version.saturating_sub(1) as usize;
+        let mut body = body.to_string();
+
+        for migrate in self.migrations.iter().skip(first) {
+            body = migrate(&body);
+        }
+
+        Some(body)
+    }
+}
+
+/// Load a document saved under a key and parse it. An unreadable document is
+/// set aside and replaced by its backup, if the backup can be read. Returns
+/// nothing if no readable document is saved.
+pub fn load<T>(key: &str, format: &SaveFormat, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    let text = storage::load(key)?;
+
+    if let Some(value) = format.decode(&text).and_then(|body| parse(&body)) {
+        return Some(value);
+    }
+
+    storage::save(&format!("{}.unreadable", key), &text);
+
+    let backup = storage::load(&format!("{}.bak", key))?;
+    let value = format.decode(&backup).and_then(|body| parse(&body))?;
+
+    storage::save(key, &backup);
+    Some(value)
+}
+
+/// Save a document under a key, keeping the previous copy as a backup.
+pub fn save(key: &str, format: &SaveFormat, body: &str) {
+    if let Some(previous) = storage::load(key) {
+        storage::save(&format!("{}.bak", key), &previous);
+    }
+
+    storage::save(key, &format.encode(body));
+}
+
+/// Parse a TOML document, keeping whatever can be read from it. Entries which
+/// do not parse are dropped and keep their defaults, so one bad value does not
+/// reset the whole document. Returns nothing if the document is not TOML.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::savedata::salvage_toml;
+/// # use asteroids::settings::Settings;
+/// let settings: Settings = salvage_toml("volume = \"loud\"\nstarting_lives = 5").unwrap();
+/// assert_eq!(settings.volume, Settings::default().volume);
+/// assert_eq!(settings.starting_lives, 5);
+/// ```
+pub fn salvage_toml<T: DeserializeOwned>(text: &str) -> Option<T> {
+    let table: toml::Table = text.parse().ok()?;
+    let mut kept = toml::Table::new();
+
+    for (key, value) in table {
+        kept.insert(key.clone(), value);
+
+        if toml::Value::Table(kept.clone()).try_into::<T>().is_err() {
+            kept.remove(&key);
+        }
+    }
+
+    toml::Value::Table(kept).try_into().ok()
+}
@@ -0,0 +1,76 @@
+//! Scenario files.
+//!
+//! A scenario describes the field a game starts from: how many asteroids of
+//! each size and how many saucers are in play, and what the ship starts with.
+//! Scenarios are TOML documents where every entry is optional, for example:
+//!
+//! ```toml
+//! wave = 4
+//! large_asteroids = 3
+//! rocks = [2.5, 0.1, 0.1]
+//! small_saucers = 1
+//! lives = 1
+//! ```
+//!
+//! Asteroids of the three classic sizes are counted separately; `rocks` lists
+//! asteroids of any other scale relative to a large asteroid, such as boss
+//! rocks or gravel.
+//!
+//! The wave number sets the difficulty of the field: large saucers carry a
+//! turret from wave 4, and chain lightning is available from wave 5, just as
+//! in the endless game.
+
+use serde::Deserialize;
+
+/// Scenario
+///
+/// The starting field of a game. Entries missing from a scenario file keep
+/// their default, which is an empty first wave with three lives and three
+/// decoys.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::scenario::Scenario;
+/// let scenario = Scenario::parse("large_asteroids = 3\nlives = 1").unwrap();
+///
+/// assert_eq!(scenario.large_asteroids, 3);
+/// assert_eq!(scenario.lives, 1);
+/// assert_eq!(scenario.decoys, 3);
+/// ```
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct Scenario {
+    pub wave: u32,
+    pub large_asteroids: u32,
+    pub medium_asteroids: u32,
+    pub small_asteroids: u32,
+    pub rocks: Vec<f32>,
+    pub large_saucers: u32,
+    pub small_saucers: u32,
+    pub lives: u32,
+    pub decoys: u32,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            wave: 1,
+            large_asteroids: 0,
+            medium_asteroids: 0,
+            small_asteroids: 0,
+            rocks: Vec::new(),
+            large_saucers: 0,
+            small_saucers: 0,
+            lives: 3,
+            decoys: 3,
+        }
+    }
+}
+
+impl Scenario {
+    /// Parse a scenario from a TOML document.
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+}
@@ -0,0 +1,71 @@
+//! Score arithmetic and display.
+//!
+//! Scores are `u32` and only ever grow, so all score arithmetic saturates at
+//! `u32::MAX` instead of wrapping back to zero on a marathon run. A saturated
+//! score is displayed with a trailing `+`.
+
+/// Apply a multiplier to the points scored between two scores, saturating at
+/// the maximum score.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::score::multiply_gain;
+/// assert_eq!(multiply_gain(1000, 1200, 2), 1400);
+/// assert_eq!(multiply_gain(u32::MAX - 10, u32::MAX, 2), u32::MAX);
+/// ```
+pub fn multiply_gain(previous: u32, current: u32, multiplier: u32) -> u32 {
+    let gain = current.saturating_sub(previous).saturating_mul(multiplier);
+    previous.saturating_add(gain)
+}
+
+/// Format a score for display, with digits grouped in threes. A saturated
+/// score is marked with a trailing `+`.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::score::format_score;
+/// assert_eq!(format_score(0), "0");
+/// assert_eq!(format_score(12345), "12,345");
+/// assert_eq!(format_score(1234567), "1,234,567");
+/// assert_eq!(format_score(u32::MAX), "4,294,967,295+");
+/// ```
+pub fn format_score(score: u32) -> String {
+    let digits = score.to_string();
+    let mut text = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            text.push(',');
+        }
+
+        text.push(digit);
+    }
+
+    if score == u32::MAX {
+        text.push('+');
+    }
+
+    text
+}
+
+/// Format a score as an arcade counter, padded with zeros to six digits. A
+/// saturated score is marked with a trailing `+`.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::score::format_arcade_score;
+/// assert_eq!(format_arcade_score(0), "000000");
+/// assert_eq!(format_arcade_score(12345), "012345");
+/// assert_eq!(format_arcade_score(1234567), "1234567");
+/// assert_eq!(format_arcade_score(u32::MAX), "4294967295+");
+/// ```
+pub fn format_arcade_score(score: u32) -> String {
+    if score == u32::MAX {
+        format!("{}+", score)
+    } else {
+        format!("{:06}", score)
+    }
+}
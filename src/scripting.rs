@@ -0,0 +1,372 @@
+//! Scripting.
+//!
+//! Mods can change how enemies behave without forking the game, through
+//! Rhai scripts loaded at startup from the `scripts` folder of the asset
+//! directory, in order of their names. A script defines any of these
+//! functions, which the game calls as it is played:
+//!
+//! - `on_wave_start(wave)`, once a wave's field is in play, to change what
+//!   the wave is made of;
+//! - `on_update(time)`, every frame, with the seconds the wave has run;
+//! - `on_saucer(small)`, as a saucer arrives, to set how it fires.
+//!
+//! Scripts can read the state of play with `ship_x()`, `ship_y()`, `wave()`,
+//! `time()`, `asteroids()` and `saucers()`, and act on it with
+//! `spawn_asteroid(scale)`, `spawn_saucer(small)`, `spawn_hunter()`,
+//! `clear_asteroids()`, `message(text)`, and `saucer_spread(count, angle)`
+//! to have the saucer that arrived last fire a fan of shots. `after(seconds,
+//! name)` calls a function of the script once the wave has run a little
+//! longer; waiting calls are dropped as a new wave starts. Each script keeps
+//! whatever it needs between calls in `this`, an object map of its own, for
+//! example the phases of a boss rock:
+//!
+//! ```rhai
+//! fn on_wave_start(wave) {
+//!     if wave % 5 == 0 {
+//!         clear_asteroids();
+//!         spawn_asteroid(2.5);
+//!         message("Here comes the big one");
+//!         this.phase = 1;
+//!         after(20.0, "enrage");
+//!     }
+//! }
+//!
+//! fn enrage() {
+//!     this.phase = 2;
+//!     spawn_saucer(true);
+//! }
+//!
+//! fn on_saucer(small) {
+//!     if this.phase == 2 { saucer_spread(3, 0.4); }
+//! }
+//! ```
+//!
+//! Scripts only run in classic and co-op games, not in the demo, challenges,
+//! shared games or tool-assisted runs. A script that does not compile stops
+//! the game loading any, and says why on the title screen; one that fails
+//! while running is reported and not called again.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use macroquad::prelude::*;
+use rhai::{CallFnOptions, Dynamic, Engine, FuncArgs, Map, Scope, AST, INT};
+
+use crate::assets;
+use crate::saucer::SaucerSize;
+
+/// Script action
+///
+/// Something a script asked for. The world carries the actions out once the
+/// script returns.
+#[derive(Clone, PartialEq, Debug)]
+pub enum ScriptAction {
+    /// An asteroid of a scale, relative to a large one, drifts in from the
+    /// edge of the screen.
+    SpawnAsteroid(f32),
+    /// A saucer of a size arrives.
+    SpawnSaucer(SaucerSize),
+    /// A hunter arrives.
+    SpawnHunter,
+    /// Every asteroid in play is taken away.
+    ClearAsteroids,
+    /// A line of text is shown across the field.
+    Message(String),
+    /// The saucer that arrived last fires a fan of shots spread over an
+    /// angle in radians.
+    SaucerSpread { count: u32, angle: f32 },
+}
+
+/// Script context
+///
+/// What scripts can read of the game: the wave, the seconds it has run, the
+/// position of the player's ship and the asteroids and saucers in play.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct ScriptContext {
+    pub wave: u32,
+    pub time: f64,
+    pub ship: Vec2,
+    pub asteroids: usize,
+    pub saucers: usize,
+}
+
+/// Timer
+///
+/// A call to a function of a script waiting for the wave to run to a time.
+struct Timer {
+    script: usize,
+    due: f64,
+    function: String,
+}
+
+/// What a call into the scripts shares with the functions they call back:
+/// the context, the script being run, and what the scripts have asked for.
+#[derive(Default)]
+struct Shared {
+    context: ScriptContext,
+    script: usize,
+    actions: Vec<ScriptAction>,
+    timers: Vec<Timer>,
+}
+
+/// Script
+///
+/// A compiled script, its state, and whether it has failed.
+struct Script {
+    name: String,
+    ast: AST,
+    state: Dynamic,
+    failed: bool,
+}
+
+/// Scripts
+///
+/// The scripts loaded, and the engine running them.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::saucer::SaucerSize;
+/// # use asteroids::scripting::{ScriptAction, ScriptContext, Scripts};
+/// let source = r#"
+///     fn on_wave_start(wave) {
+///         if wave == 3 { spawn_asteroid(2.5); after(10.0, "reinforce"); }
+///     }
+///
+///     fn reinforce() { spawn_saucer(ship_x() > 500.0); }
+/// "#;
+///
+/// let mut scripts = Scripts::compile(&[("boss.rhai", source)]).unwrap();
+/// let mut context = ScriptContext { wave: 3, ..Default::default() };
+///
+/// assert_eq!(scripts.wave_started(context), [ScriptAction::SpawnAsteroid(2.5)]);
+/// assert!(scripts.update(context).is_empty());
+///
+/// // Ten seconds on, the timer calls for a saucer
+/// context.time = 10.0;
+/// context.ship.x = 600.0;
+/// assert_eq!(scripts.update(context), [ScriptAction::SpawnSaucer(SaucerSize::Small)]);
+/// assert!(scripts.update(context).is_empty());
+///
+/// assert!(Scripts::compile(&[("broken.rhai", "fn on_update(time) {")]).is_err());
+/// ```
+pub struct Scripts {
+    engine: Engine,
+    scripts: Vec<Script>,
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl Default for Scripts {
+    fn default() -> Self {
+        let shared = Rc::new(RefCell::new(Shared::default()));
+
+        Self {
+            engine: Self::engine(&shared),
+            scripts: Vec::new(),
+            shared,
+        }
+    }
+}
+
+impl Scripts {
+    /// Folder of the asset directory scripts are loaded from.
+    pub const FOLDER: &'static str = "scripts";
+
+    /// Most operations a script may run in one call, so one stuck in a loop
+    /// cannot hang the game.
+    const MAX_OPERATIONS: u64 = 100_000;
+
+    /// Engine exposing the game to scripts, sharing what they read and ask
+    /// for.
+    fn engine(shared: &Rc<RefCell<Shared>>) -> Engine {
+        let mut engine = Engine::new();
+        engine.set_max_operations(Self::MAX_OPERATIONS);
+
+        let read = |shared: &Rc<RefCell<Shared>>| {
+            let shared = shared.clone();
+            move || shared.borrow().context
+        };
+
+        let context = read(shared);
+        engine.register_fn("ship_x", move || context().ship.x as f64);
+        let context = read(shared);
+        engine.register_fn("ship_y", move || context().ship.y as f64);
+        let context = read(shared);
+        engine.register_fn("wave", move || context().wave as INT);
+        let context = read(shared);
+        engine.register_fn("time", move || context().time);
+        let context = read(shared);
+        engine.register_fn("asteroids", move || context().asteroids as INT);
+        let context = read(shared);
+        engine.register_fn("saucers", move || context().saucers as INT);
+
+        let act = |shared: &Rc<RefCell<Shared>>| {
+            let shared = shared.clone();
+            move |action: ScriptAction| shared.borrow_mut().actions.push(action)
+        };
+
+        let push = act(shared);
+        engine.register_fn("spawn_asteroid", move |scale: f64| push(ScriptAction::SpawnAsteroid(scale as f32)));
+        let push = act(shared);
+        engine.register_fn("spawn_asteroid", move |scale: INT| push(ScriptAction::SpawnAsteroid(scale as f32)));
+        let push = act(shared);
+        engine.register_fn("spawn_saucer", move |small: bool| {
+            push(ScriptAction::SpawnSaucer(if small { SaucerSize::Small } else { SaucerSize::Large }))
+        });
+        let push = act(shared);
+        engine.register_fn("spawn_hunter", move || push(ScriptAction::SpawnHunter));
+        let push = act(shared);
+        engine.register_fn("clear_asteroids", move || push(ScriptAction::ClearAsteroids));
+        let push = act(shared);
+        engine.register_fn("message", move |text: &str| push(ScriptAction::Message(text.to_string())));
+        let push = act(shared);
+        engine.register_fn("saucer_spread", move |count: INT, angle: f64| {
+            push(ScriptAction::SaucerSpread { count: count.clamp(1, 16) as u32, angle: angle as f32 })
+        });
+
+        let timers = shared.clone();
+        engine.register_fn("after", move |seconds: f64, function: &str| {
+            let mut shared = timers.borrow_mut();
+
+            let timer = Timer {
+                script: shared.script,
+                due: shared.context.time + seconds,
+                function: function.to_string(),
+            };
+
+            shared.timers.push(timer);
+        });
+
+        engine
+    }
+
+    /// Compile scripts from their names and sources, in the order given.
+    /// Returns the reason if any does not compile.
+    pub fn compile(sources: &[(&str, &str)]) -> Result<Self, String> {
+        let mut scripts = Self::default();
+
+        for (name, source) in sources {
+            let ast = scripts.engine.compile(source).map_err(|error| format!("Could not compile {}: {}", name, error))?;
+
+            scripts.scripts.push(Script {
+                name: name.to_string(),
+                ast,
+                state: Dynamic::from_map(Map::new()),
+                failed: false,
+            });
+        }
+
+        Ok(scripts)
+    }
+
+    /// Load the scripts in an asset directory, or the default one. Without
+    /// any, none are run; if one cannot be read or compiled, none are, and
+    /// the reason is returned.
+    pub fn load(directory: Option<&str>) -> (Self, Option<String>) {
+        let folder = assets::asset_path(directory.unwrap_or(assets::DEFAULT_DIRECTORY), Self::FOLDER);
+
+        let Ok(entries) = std::fs::read_dir(&folder) else {
+            return (Self::default(), None);
+        };
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|extension| extension == "rhai"))
+            .collect();
+
+        paths.sort();
+
+        let mut sources = Vec::new();
+
+        for path in &paths {
+            match std::fs::read_to_string(path) {
+                Ok(source) => sources.push((path.display().to_string(), source)),
+                Err(error) => return (Self::default(), Some(format!("Could not read {}: {}", path.display(), error))),
+            }
+        }
+
+        let sources: Vec<(&str, &str)> = sources.iter().map(|(name, source)| (name.as_str(), source.as_str())).collect();
+
+        match Self::compile(&sources) {
+            Ok(scripts) => (scripts, None),
+            Err(error) => (Self::default(), Some(error)),
+        }
+    }
+
+    /// Check if no scripts are loaded.
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Start a wave, dropping the calls waiting on the last one, and call
+    /// `on_wave_start` in every script.
+    pub fn wave_started(&mut self, context: ScriptContext) -> Vec<ScriptAction> {
+        self.shared.borrow_mut().timers.clear();
+        self.call_all(context, "on_wave_start", (context.wave as INT,))
+    }
+
+    /// Make the calls that have waited their time, then call `on_update` in
+    /// every script.
+    pub fn update(&mut self, context: ScriptContext) -> Vec<ScriptAction> {
+        self.shared.borrow_mut().context = context;
+
+        let due: Vec<Timer> = {
+            let mut shared = self.shared.borrow_mut();
+            let (due, waiting) = std::mem::take(&mut shared.timers).into_iter().partition(|timer| timer.due <= context.time);
+
+            shared.timers = waiting;
+            due
+        };
+
+        for timer in due {
+            self.call(timer.script, &timer.function, ());
+        }
+
+        self.call_all(context, "on_update", (context.time,))
+    }
+
+    /// Call `on_saucer` in every script, for a saucer of a size that has
+    /// just arrived.
+    pub fn saucer_arrived(&mut self, context: ScriptContext, size: SaucerSize) -> Vec<ScriptAction> {
+        self.call_all(context, "on_saucer", (size == SaucerSize::Small,))
+    }
+
+    /// Call a function in every script that defines it, taking what they ask
+    /// for.
+    fn call_all(&mut self, context: ScriptContext, function: &str, args: impl FuncArgs + Clone) -> Vec<ScriptAction> {
+        self.shared.borrow_mut().context = context;
+
+        for script in 0..self.scripts.len() {
+            self.call(script, function, args.clone());
+        }
+
+        std::mem::take(&mut self.shared.borrow_mut().actions)
+    }
+
+    /// Call a function of a script, if it defines one taking the arguments
+    /// and has not failed. A script that fails is reported and not called
+    /// again.
+    fn call(&mut self, index: usize, function: &str, args: impl FuncArgs) {
+        let Some(script) = self.scripts.get_mut(index) else {
+            return;
+        };
+
+        let mut arguments = Vec::new();
+        args.parse(&mut arguments);
+
+        let defined = script.ast.iter_functions().any(|defined| defined.name == function && defined.params.len() == arguments.len());
+
+        if script.failed || !defined {
+            return;
+        }
+
+        self.shared.borrow_mut().script = index;
+
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(&mut script.state);
+
+        if let Err(error) = self.engine.call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &script.ast, function, arguments) {
+            eprintln!("{}: {}", script.name, error);
+            script.failed = true;
+        }
+    }
+}
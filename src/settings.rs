@@ -0,0 +1,241 @@
+//! Player preferences.
+//!
+//! The preferences chosen on the options screen, saved alongside the other
+//! saved data as a TOML document, for example:
+//!
+//! ```toml
+//! volume = 0.5
+//! screen_shake = 1.0
+//! difficulty = "normal"
+//! starting_lives = 3
+//! bullet_limit = false
+//! low_visibility = false
+//! bouncing_asteroids = false
+//! arcade_scoring = false
+//! announcements = false
+//! deep_field = false
+//! antialiasing = "msaa4"
+//! line_width = 1.0
+//! shield_duration = 3.0
+//! shield_recharge = 12.0
+//! touch_layout = "stick_left"
+//! ```
+//!
+//! Preferences missing from the file keep their defaults. Key bindings and the
+//! HUD layout are saved in files of their own.
+
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::Difficulty;
+use crate::graphics::Antialiasing;
+use crate::replaycode::Rules;
+use crate::savedata::{self, SaveFormat};
+use crate::ship::Ship;
+use crate::touch::TouchLayout;
+
+/// Settings
+///
+/// The player's preferences, as chosen on the options screen. Volume and
+/// screen shake run from 0 to 1; shield times are in seconds.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::settings::Settings;
+/// let mut settings = Settings::default();
+///
+/// settings.adjust_volume(1.0);
+/// assert_eq!(settings.volume, 1.0);
+///
+/// settings.adjust_starting_lives(10);
+/// assert_eq!(settings.starting_lives, Settings::MAX_STARTING_LIVES);
+///
+/// settings.adjust_shield_recharge(-100.0);
+/// assert_eq!(settings.shield_recharge, Settings::MIN_SHIELD_RECHARGE);
+///
+/// let restored = Settings::parse(&settings.to_toml()).unwrap();
+/// assert_eq!(restored, settings);
+/// ```
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Sound volume.
+    pub volume: f32,
+    /// Intensity of the screen shake.
+    pub screen_shake: f32,
+    /// Difficulty of classic and co-op games.
+    pub difficulty: Difficulty,
+    /// Ships a game starts with.
+    pub starting_lives: u32,
+    /// Whether the classic limit on bullets in flight applies.
+    pub bullet_limit: bool,
+    /// Whether games are played in low visibility.
+    pub low_visibility: bool,
+    /// Whether asteroids bounce off each other.
+    pub bouncing_asteroids: bool,
+    /// Whether the first player scores for whatever is destroyed in play.
+    pub arcade_scoring: bool,
+    /// Whether key events are announced.
+    pub announcements: bool,
+    /// Whether the deep field drifts behind the playfield.
+    pub deep_field: bool,
+    /// How lines are smoothed.
+    pub antialiasing: Antialiasing,
+    /// How wide lines are drawn.
+    pub line_width: f32,
+    /// How long a full shield lasts.
+    pub shield_duration: f32,
+    /// How long an empty shield takes to recharge.
+    pub shield_recharge: f32,
+    /// Which side the touch joystick is on.
+    pub touch_layout: TouchLayout,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            volume: 0.5,
+            screen_shake: 1.0,
+            difficulty: Difficulty::Normal,
+            starting_lives: 3,
+            bullet_limit: false,
+            low_visibility: false,
+            bouncing_asteroids: false,
+            arcade_scoring: false,
+            announcements: false,
+            deep_field: false,
+            antialiasing: Antialiasing::default(),
+            line_width: 1.0,
+            shield_duration: Ship::SHIELD_DURATION,
+            shield_recharge: Ship::SHIELD_RECHARGE,
+            touch_layout: TouchLayout::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Storage key under which settings are saved.
+    pub const STORAGE_KEY: &'static str = "settings.toml";
+
+    /// Format settings are saved in.
+    const SAVE_FORMAT: SaveFormat = SaveFormat::INITIAL;
+
+    /// Step by which volume and screen shake are adjusted.
+    pub const STEP: f32 = 0.1;
+
+    /// Fewest ships a game can start with.
+    pub const MIN_STARTING_LIVES: u32 = 1;
+
+    /// Most ships a game can start with.
+    pub const MAX_STARTING_LIVES: u32 = 5;
+
+    /// Step by which line width is adjusted.
+    pub const LINE_WIDTH_STEP: f32 = 0.25;
+
+    /// Narrowest and widest lines, as a factor of their usual width.
+    pub const MIN_LINE_WIDTH: f32 = 0.5;
+    pub const MAX_LINE_WIDTH: f32 = 3.0;
+
+    /// Step, in seconds, by which the shield's duration is adjusted.
+    pub const SHIELD_DURATION_STEP: f32 = 0.5;
+
+    /// Shortest and longest time, in seconds, a full shield can last.
+    pub const MIN_SHIELD_DURATION: f32 = 1.0;
+    pub const MAX_SHIELD_DURATION: f32 = 6.0;
+
+    /// Step, in seconds, by which the shield's recharge time is adjusted.
+    pub const SHIELD_RECHARGE_STEP: f32 = 2.0;
+
+    /// Shortest and longest time, in seconds, a shield can take to recharge.
+    pub const MIN_SHIELD_RECHARGE: f32 = 4.0;
+    pub const MAX_SHIELD_RECHARGE: f32 = 30.0;
+
+    /// Load saved settings. Settings that cannot be read keep their defaults,
+    /// as do all of them if none are saved.
+    pub fn load() -> Self {
+        savedata::load(Self::STORAGE_KEY, &Self::SAVE_FORMAT, |text| {
+            Self::parse(text).ok().or_else(|| savedata::salvage_toml(text).map(Self::clamped))
+        })
+        .unwrap_or_default()
+    }
+
+    /// Save settings.
+    pub fn save(&self) {
+        savedata::save(Self::STORAGE_KEY, &Self::SAVE_FORMAT, &self.to_toml());
+    }
+
+    /// Parse settings from a TOML document. Values out of range are brought
+    /// back within it.
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text).map(Self::clamped)
+    }
+
+    /// Settings with values out of range brought back within it.
+    fn clamped(mut self) -> Self {
+        self.adjust_volume(0.0);
+        self.adjust_screen_shake(0.0);
+        self.adjust_starting_lives(0);
+        self.adjust_line_width(0.0);
+        self.adjust_shield_duration(0.0);
+        self.adjust_shield_recharge(0.0);
+
+        self
+    }
+
+    /// Format settings as a TOML document.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).unwrap_or_default()
+    }
+
+    /// Raise or lower the volume, keeping it between 0 and 1.
+    pub fn adjust_volume(&mut self, change: f32) {
+        self.volume = Self::adjust_fraction(self.volume, change);
+    }
+
+    /// Raise or lower the screen shake intensity, keeping it between 0 and 1.
+    pub fn adjust_screen_shake(&mut self, change: f32) {
+        self.screen_shake = Self::adjust_fraction(self.screen_shake, change);
+    }
+
+    /// Add or take away starting ships, keeping within the allowed range.
+    pub fn adjust_starting_lives(&mut self, change: i32) {
+        self.starting_lives = self.starting_lives
+            .saturating_add_signed(change)
+            .clamp(Self::MIN_STARTING_LIVES, Self::MAX_STARTING_LIVES);
+    }
+
+    /// Widen or narrow lines, keeping within the allowed range.
+    pub fn adjust_line_width(&mut self, change: f32) {
+        self.line_width = (self.line_width + change).clamp(Self::MIN_LINE_WIDTH, Self::MAX_LINE_WIDTH);
+    }
+
+    /// Lengthen or shorten the time a full shield lasts, keeping within the
+    /// allowed range.
+    pub fn adjust_shield_duration(&mut self, change: f32) {
+        self.shield_duration = (self.shield_duration + change).clamp(Self::MIN_SHIELD_DURATION, Self::MAX_SHIELD_DURATION);
+    }
+
+    /// Lengthen or shorten the time a shield takes to recharge, keeping within
+    /// the allowed range.
+    pub fn adjust_shield_recharge(&mut self, change: f32) {
+        self.shield_recharge = (self.shield_recharge + change).clamp(Self::MIN_SHIELD_RECHARGE, Self::MAX_SHIELD_RECHARGE);
+    }
+
+    /// Rules a game started now is played by.
+    pub fn rules(&self) -> Rules {
+        Rules {
+            shield_duration: self.shield_duration,
+            shield_recharge: self.shield_recharge,
+            bouncing_asteroids: self.bouncing_asteroids,
+            arcade_scoring: self.arcade_scoring,
+        }
+    }
+
+    /// Fraction changed and rounded to a whole step, so repeated steps do
+    /// not drift, and kept between 0 and 1.
+    fn adjust_fraction(value: f32, change: f32) -> f32 {
+        let stepped = ((value + change) / Self::STEP).round() * Self::STEP;
+
+        stepped.clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,587 @@
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::bullet::{Bullet, BulletType, WeaponKind};
+use crate::camera::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::collision::{Collidable, Outline};
+use crate::config;
+use crate::decoy::Decoy;
+use crate::detmath;
+use crate::linebatch::LineBatch;
+use crate::wrap::{self, wrap_draw, WrapDraw};
+
+/// Hyperspace outcome
+/// 
+/// How a hyperspace jump ended. Jumps usually end safely, but hyperspace may
+/// malfunction, either destroying the ship on re-entry or dropping it perilously
+/// close to an asteroid.
+#[derive(Clone, Copy, PartialEq)]
+pub enum HyperspaceOutcome {
+    Safe,
+    Destroyed,
+    Perilous,
+}
+
+/// Hyperspace jump
+/// 
+/// A completed hyperspace jump: where the ship jumped from, and how the jump
+/// ended. The ship only decides the outcome; acting on it is up to the world.
+pub struct HyperspaceJump {
+    pub origin: Vec2,
+    pub outcome: HyperspaceOutcome,
+}
+
+/// Ship object
+/// 
+/// The ship is controlled by the player. It can move in any direction, and shoot
+/// bullets. The ship has a cooldown on shooting, and can only shoot again after
+/// a certain amount of time has passed. The ship has a maximum speed, and will
+/// not accelerate past this speed. 
+/// 
+#[derive(Clone)]
+pub struct Ship {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub max_speed: f32,
+    pub drag: f32,
+    pub thrust: f32,
+    pub reverse_thrusters: bool,
+    pub lateral_thrusters: bool,
+    pub rotation: f32,
+    pub rotation_speed: f32,
+    pub radius: f32,
+    pub hyperspace_cooldown: f64,
+    pub hyperspace_recharge: f64,
+    pub hyperspace_malfunction_chance: f32,
+    pub hyperspace_strain: f32,
+    pub hyperspace_last_jump: f64,
+    pub guided_hyperspace: bool,
+    pub hyperspace_target: Option<(Vec2, f64)>,
+    pub shot_cooldown: f64,
+    pub shot_recharge: f64,
+    pub shot_speed: f32,
+    pub shot_lifespan: f32,
+    pub bullet_limit: Option<usize>,
+    pub weapon: WeaponKind,
+    pub arc_unlocked: bool,
+    pub arc_cooldown: f64,
+    pub arc_recharge: f64,
+    pub arc_range: f32,
+    pub decoys: u32,
+    pub bombs: u32,
+    pub overdrive_expiry: f64,
+    pub respawn_lifespan: f64,
+    pub shield_lifespan: f64,
+    pub shield_energy: f32,
+    pub shield_raised: bool,
+    pub spawn_offset: Vec2,
+    pub color: Color,
+    pub vertices: Vec<Vec2>,    
+}
+
+impl Ship {
+    /// Player bullets allowed in flight at once under the classic rules.
+    pub const CLASSIC_BULLET_LIMIT: usize = 4;
+
+    /// Construct ship object
+    pub fn spawn_new() -> Self {
+        let screen_edge: f32 = WORLD_WIDTH.min(WORLD_HEIGHT);
+        let balance = config::balance().ship;
+
+        let thrust = screen_edge * balance.thrust;
+        let max_speed = screen_edge * balance.max_speed;
+        
+        Self {
+            position: Vec2::new(WORLD_WIDTH / 2.0, WORLD_HEIGHT / 2.0),
+            velocity: Vec2::new(0., 0.),
+            max_speed,
+            drag: balance.drag,
+            thrust,
+            reverse_thrusters: false,
+            lateral_thrusters: false,
+            rotation: 0.0,
+            rotation_speed: 0.0,
+            radius: screen_edge / 80.0,
+            hyperspace_cooldown: 0.0,
+            hyperspace_recharge: 5.0,
+            hyperspace_malfunction_chance: 0.08,
+            hyperspace_strain: 0.0,
+            hyperspace_last_jump: 0.0,
+            guided_hyperspace: false,
+            hyperspace_target: None,
+            shot_cooldown: 0.0,
+            shot_recharge: 0.2,
+            shot_speed: screen_edge * 0.01,
+            shot_lifespan: 0.8,
+            bullet_limit: None,
+            weapon: WeaponKind::Standard,
+            arc_unlocked: false,
+            arc_cooldown: 0.0,
+            arc_recharge: 2.0,
+            arc_range: screen_edge * 0.2,
+            decoys: 0,
+            bombs: 0,
+            overdrive_expiry: 0.0,
+            respawn_lifespan: 0.0,
+            shield_lifespan: 0.0,
+            shield_energy: 1.0,
+            shield_raised: false,
+            spawn_offset: Vec2::ZERO,
+            color: WHITE,
+            vertices: vec![
+                Vec2::new(0., -screen_edge / 30.0),
+                Vec2::new(screen_edge / 60.0, screen_edge / 60.0),
+                Vec2::new(0., screen_edge / 100.0),
+                Vec2::new(-screen_edge / 60.0, screen_edge / 60.0),
+            ],
+        }
+    }
+
+    /// Tune the ship's handling to the balance the game is played with.
+    pub fn apply_balance(&mut self) {
+        let screen_edge: f32 = WORLD_WIDTH.min(WORLD_HEIGHT);
+        let balance = config::balance().ship;
+
+        self.thrust = screen_edge * balance.thrust;
+        self.max_speed = screen_edge * balance.max_speed;
+        self.drag = balance.drag;
+    }
+
+    /// Respawn ship.
+    /// 
+    /// When player dies, respawn the ship after a short delay from a time. The
+    /// ship comes back with its shield fully charged, ready to be raised.
+    pub fn respawn(&mut self, now: f64) {
+        self.respawn_lifespan = now + 2.0;
+        self.shield_lifespan = self.respawn_lifespan;
+        self.shield_energy = 1.0;
+        self.shield_raised = false;
+
+        self.reset();
+    }
+
+    /// Hold the ship back for a little longer before it respawns. The shield
+    /// is extended along with it.
+    pub fn delay_respawn(&mut self, seconds: f64) {
+        self.respawn_lifespan += seconds;
+        self.shield_lifespan += seconds;
+    }
+
+    /// Take the ship out of play for good, once its player has no lives left
+    /// but the game goes on for another player.
+    pub fn retire(&mut self) {
+        self.respawn_lifespan = f64::INFINITY;
+    }
+
+    /// Check if the ship has been taken out of play.
+    pub fn is_retired(&self) -> bool {
+        self.respawn_lifespan == f64::INFINITY
+    }
+
+    /// Position the ship respawns at: the centre of the screen, moved by the
+    /// ship's spawn offset.
+    pub fn spawn_position(&self) -> Vec2 {
+        Vec2::new(WORLD_WIDTH / 2.0, WORLD_HEIGHT / 2.0) + self.spawn_offset
+    }
+
+    /// Reset player position and velocity.
+    pub fn reset(&mut self) {
+        self.position = self.spawn_position();
+        self.velocity = Vec2::new(0., 0.);
+        self.rotation = 0.0;
+        self.rotation_speed = 0.0;
+        self.hyperspace_target = None;
+    }
+
+    /// Check if ship is still during respawn period at a time.
+    pub fn is_respawning(&self, now: f64) -> bool {
+        now < self.respawn_lifespan
+    }
+
+    /// Check if shield is up at a time: raised by the player, or still granted
+    /// for a while, such as after a rewind.
+    pub fn is_shield_active(&self, now: f64) -> bool {
+        self.shield_raised || now < self.shield_lifespan
+    }
+
+    /// Seconds a fully charged shield can be held up by default.
+    pub const SHIELD_DURATION: f32 = 3.0;
+
+    /// Seconds a drained shield takes to recharge fully by default.
+    pub const SHIELD_RECHARGE: f32 = 12.0;
+
+    /// Energy a drained shield must recharge to before it can be raised again.
+    pub const SHIELD_RESERVE: f32 = 0.25;
+
+    /// Raise the shield while held, if it has the energy, and lower it
+    /// otherwise. Once drained, the shield stays down until it has recharged
+    /// to its reserve.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::ship::Ship;
+    /// let mut ship = Ship::spawn_new();
+    ///
+    /// // Held up, the shield drains until it drops
+    /// ship.hold_shield(true);
+    /// assert!(ship.shield_raised);
+    ///
+    /// ship.update_shield(Ship::SHIELD_DURATION, Ship::SHIELD_DURATION, Ship::SHIELD_RECHARGE);
+    /// assert_eq!(ship.shield_energy, 0.0);
+    /// assert!(!ship.shield_raised);
+    ///
+    /// // It cannot be raised again until it has recharged to its reserve
+    /// ship.update_shield(1.0, Ship::SHIELD_DURATION, Ship::SHIELD_RECHARGE);
+    /// ship.hold_shield(true);
+    /// assert!(!ship.shield_raised);
+    ///
+    /// ship.update_shield(Ship::SHIELD_RECHARGE * Ship::SHIELD_RESERVE, Ship::SHIELD_DURATION, Ship::SHIELD_RECHARGE);
+    /// ship.hold_shield(true);
+    /// assert!(ship.shield_raised);
+    /// ```
+    pub fn hold_shield(&mut self, held: bool) {
+        self.shield_raised = held && self.is_shield_ready() && self.shield_energy > 0.0;
+    }
+
+    /// Check if the shield is raised, or has the energy to be.
+    pub fn is_shield_ready(&self) -> bool {
+        self.shield_raised || self.shield_energy >= Self::SHIELD_RESERVE
+    }
+
+    /// Drain the raised shield's energy over the time the frame took, dropping
+    /// it once empty, or recharge the lowered shield. A full shield lasts for
+    /// a duration, and an empty one takes a recharge time to fill, in seconds.
+    pub fn update_shield(&mut self, dt: f32, duration: f32, recharge: f32) {
+        if self.shield_raised {
+            self.shield_energy = (self.shield_energy - dt / duration).max(0.0);
+            self.shield_raised = self.shield_energy > 0.0;
+        } else {
+            self.shield_energy = (self.shield_energy + dt / recharge).min(1.0);
+        }
+    }
+
+    /// Size of the shield relative to the ship.
+    pub const SHIELD_RATIO: f32 = 2.5;
+
+    /// Radius of the shield around the ship.
+    pub fn shield_radius(&self) -> f32 {
+        self.radius * Self::SHIELD_RATIO
+    }
+
+    /// Get position of exhaust. This is used to fire particles when the ship is
+    /// accelerating.
+    pub fn get_exhaust_position(&self) -> Vec2 {
+        self.position + detmath::rotation(self.rotation).mul_vec2(self.vertices[2])
+    }
+
+    /// Get position of the reverse thruster exhaust, at the nose of the ship.
+    pub fn get_reverse_exhaust_position(&self) -> Vec2 {
+        self.position + detmath::rotation(self.rotation).mul_vec2(self.vertices[0])
+    }
+
+    /// Get position of the lateral thruster exhaust that pushes the ship in the
+    /// given direction. Strafing left fires from the right wing, and vice versa.
+    pub fn get_lateral_exhaust_position(&self, direction: f32) -> Vec2 {
+        let vertex = if direction < 0.0 { self.vertices[1] } else { self.vertices[3] };
+        self.position + detmath::rotation(self.rotation).mul_vec2(vertex)
+    }
+
+    /// Strain shed by the hyperspace drive per second.
+    pub const HYPERSPACE_STRAIN_RECOVERY: f32 = 0.05;
+
+    /// Highest chance of a hyperspace malfunction, however strained the drive.
+    pub const HYPERSPACE_MAX_MALFUNCTION_CHANCE: f32 = 0.75;
+
+    /// Chance that a hyperspace jump at a time malfunctions. Every jump
+    /// strains the drive, and strain only wears off slowly, so jumping often
+    /// makes a malfunction more likely.
+    pub fn hyperspace_malfunction_risk(&self, now: f64) -> f32 {
+        let strain = self.hyperspace_strain_at(now);
+
+        (self.hyperspace_malfunction_chance * (1.0 + strain)).min(Self::HYPERSPACE_MAX_MALFUNCTION_CHANCE)
+    }
+
+    /// Strain left on the hyperspace drive at a given time.
+    fn hyperspace_strain_at(&self, time: f64) -> f32 {
+        let elapsed = (time - self.hyperspace_last_jump) as f32;
+        (self.hyperspace_strain - elapsed * Self::HYPERSPACE_STRAIN_RECOVERY).max(0.0)
+    }
+
+    /// Shed a fraction of the strain on the hyperspace drive at a given time.
+    /// Returns the amount of strain shed.
+    pub fn cool_hyperspace(&mut self, fraction: f32, time: f64) -> f32 {
+        let strain = self.hyperspace_strain_at(time);
+        let shed = strain * fraction;
+
+        self.hyperspace_strain = strain - shed;
+        self.hyperspace_last_jump = time;
+
+        shed
+    }
+
+    /// Time in seconds a guided hyperspace destination is shown before the
+    /// next one is picked.
+    pub const HYPERSPACE_TARGET_PERIOD: f64 = 0.3;
+
+    /// Radius of the marker showing a guided hyperspace destination.
+    const HYPERSPACE_MARKER_RADIUS: f32 = 10.0;
+
+    /// Pick destinations for a guided hyperspace jump while the key is held,
+    /// a new one every so often, unless hyperspace is still recharging at a
+    /// time.
+    pub fn aim_hyperspace(&mut self, rng: &RandGenerator, now: f64) {
+        if self.hyperspace_cooldown >= now {
+            return;
+        }
+
+        if self.hyperspace_target.is_none_or(|(_, until)| until <= now) {
+            let target = Vec2::new(rng.gen_range(0.0, WORLD_WIDTH), rng.gen_range(0.0, WORLD_HEIGHT));
+            self.hyperspace_target = Some((target, now + Self::HYPERSPACE_TARGET_PERIOD));
+        }
+    }
+
+    /// Activate hyperspace at a time. This teleports the ship to the guided destination
+    /// shown, or else a random location on the screen, unless hyperspace is
+    /// still recharging. The jump may malfunction; the outcome is returned so
+    /// the world can act on it.
+    pub fn hyperspace(&mut self, rng: &RandGenerator, now: f64) -> Option<HyperspaceJump> {
+        // Make sure we're not in cooldown
+        if self.hyperspace_cooldown < now {
+            let origin = self.position;
+            let risk = self.hyperspace_malfunction_risk(now);
+
+            let outcome = if rng.gen_range(0.0, 1.0) >= risk {
+                HyperspaceOutcome::Safe
+            } else if rng.gen_range(0, 2) == 0 {
+                HyperspaceOutcome::Destroyed
+            } else {
+                HyperspaceOutcome::Perilous
+            };
+
+            // Every jump strains the drive further
+            self.hyperspace_strain = self.hyperspace_strain_at(now) + 1.0;
+            self.hyperspace_last_jump = now;
+
+            self.hyperspace_cooldown = now + self.hyperspace_recharge;
+            self.position = match self.hyperspace_target.take() {
+                Some((target, _)) => target,
+                None => Vec2::new(rng.gen_range(0.0, WORLD_WIDTH), rng.gen_range(0.0, WORLD_HEIGHT)),
+            };
+        
+            Some(HyperspaceJump { origin, outcome })
+        } else {
+            None
+        }
+    }
+
+    /// Check if chain lightning is unlocked and charged at a time.
+    pub fn is_arc_ready(&self, now: f64) -> bool {
+        self.arc_unlocked && self.arc_cooldown < now
+    }
+
+    /// Discharge chain lightning. Returns the origin of the arc (the ship's nose)
+    /// if the weapon is unlocked and charged at a time.
+    pub fn discharge(&mut self, now: f64) -> Option<Vec2> {
+        if !self.is_arc_ready(now) {
+            return None;
+        }
+
+        self.arc_cooldown = now + self.arc_recharge;
+
+        Some(detmath::rotation(self.rotation).mul_vec2(self.vertices[0]) + self.position)
+    }
+
+    /// Accelerate ship along a direction given in ship space, where (0, -1)
+    /// points out of the nose and (1, 0) out of the right wing.
+    pub fn thrust_towards(&mut self, direction: Vec2, acceleration: f32) {
+        let rotation_matrix = detmath::rotation(self.rotation);
+        self.velocity += rotation_matrix.mul_vec2(direction.normalize_or_zero() * acceleration);
+
+        if self.velocity.length() > self.max_speed {
+            self.velocity = self.velocity.normalize() * self.max_speed;
+        }
+    }
+
+    /// Accelerate ship in direction of rotation
+    pub fn thrust(&mut self) {
+        self.thrust_towards(Vec2::new(0., -1.), self.thrust);
+    }
+
+    /// Fraction of the main engine's thrust delivered by the reverse thrusters.
+    pub const REVERSE_THRUST_RATIO: f32 = 0.5;
+
+    /// Fraction of the main engine's thrust delivered by the lateral thrusters.
+    pub const LATERAL_THRUST_RATIO: f32 = 0.35;
+
+    /// Decelerate ship using the reverse thrusters, if fitted. Returns false if
+    /// the ship has no reverse thrusters.
+    pub fn reverse_thrust(&mut self) -> bool {
+        if !self.reverse_thrusters {
+            return false;
+        }
+
+        self.thrust_towards(Vec2::new(0., 1.), self.thrust * Self::REVERSE_THRUST_RATIO);
+
+        true
+    }
+
+    /// Strafe ship sideways using the lateral thrusters, if fitted. A negative
+    /// direction strafes left, a positive one right. Returns false if the ship
+    /// has no lateral thrusters.
+    pub fn strafe(&mut self, direction: f32) -> bool {
+        if !self.lateral_thrusters {
+            return false;
+        }
+
+        self.thrust_towards(Vec2::new(direction.signum(), 0.), self.thrust * Self::LATERAL_THRUST_RATIO);
+
+        true
+    }
+
+    /// Steer ship, faster if in overdrive at a time.
+    pub fn steer(&mut self, direction: f32, now: f64) {
+        self.rotation_speed = if self.is_overdrive(now) {
+            direction * Self::OVERDRIVE_TURN_RATE
+        } else {
+            direction
+        };
+    }
+
+    /// Factor by which overdrive speeds up turning.
+    pub const OVERDRIVE_TURN_RATE: f32 = 1.5;
+
+    /// Factor by which overdrive speeds up the rate of fire.
+    pub const OVERDRIVE_FIRE_RATE: f64 = 2.0;
+
+    /// Engage overdrive for a number of seconds from a time, temporarily
+    /// speeding up turning and the rate of fire.
+    pub fn engage_overdrive(&mut self, duration: f64, now: f64) {
+        self.overdrive_expiry = now + duration;
+    }
+
+    /// Check if overdrive is engaged at a time.
+    pub fn is_overdrive(&self, now: f64) -> bool {
+        now < self.overdrive_expiry
+    }
+
+    /// Shoot the cannon at a time, given the number of the player's bullets
+    /// still in flight. Returns the bullets fired by the fitted weapon, or none if the
+    /// cannon is still recharging or, with a bullet limit set, too many bullets
+    /// are already in flight.
+    pub fn shoot(&mut self, live_bullets: usize, now: f64) -> Vec<Bullet> {
+        if self.bullet_limit.is_some_and(|limit| live_bullets >= limit) {
+            return Vec::new();
+        }
+
+        // If we're still in cooldown, don't shoot
+        if self.shot_cooldown < now {
+            let recharge = if self.is_overdrive(now) {
+                self.shot_recharge / Self::OVERDRIVE_FIRE_RATE
+            } else {
+                self.shot_recharge
+            };
+
+            self.shot_cooldown = now + recharge;
+        } else {
+            return Vec::new();
+        }
+
+        // Spawn bullets from the nose; patterns measure directions from the x-axis
+        let rotation_matrix = detmath::rotation(self.rotation);
+        let position = rotation_matrix.mul_vec2(self.vertices[0]) + self.position;
+        let direction = self.rotation - std::f32::consts::FRAC_PI_2;
+        let pierce = self.weapon.pierce();
+
+        self.weapon.pattern()
+            .fire(position, direction, self.shot_speed, self.shot_lifespan, BulletType::Player)
+            .into_iter()
+            .map(|bullet| bullet.with_pierce(pierce))
+            .collect()
+    }
+
+    /// Deploy a decoy at a time, if any are left. The decoy is released from
+    /// the exhaust and drifts away behind the ship.
+    pub fn deploy_decoy(&mut self, now: f64) -> Option<Decoy> {
+        if self.decoys == 0 {
+            return None;
+        }
+
+        self.decoys -= 1;
+
+        let backwards = detmath::rotation(self.rotation).mul_vec2(Vec2::new(0.0, self.thrust * 10.0));
+        Some(Decoy::spawn_new(self.get_exhaust_position(), self.velocity * 0.5 + backwards, self.radius, now))
+    }
+
+    /// Set off a smart bomb, if any are left. Returns false if none are.
+    pub fn detonate_bomb(&mut self) -> bool {
+        if self.bombs == 0 {
+            return false;
+        }
+
+        self.bombs -= 1;
+
+        true
+    }
+
+    /// Update ship position and rotation
+    pub fn update(&mut self) {
+        self.position += self.velocity;
+        self.rotation += self.rotation_speed;        
+
+        // Wrap around screen
+        self.position = wrap::wrap(self.position, self.radius);
+
+        self.velocity *= self.drag;
+    }
+
+    /// Render ship as it is at a time, its hull into a batch of lines.
+    pub fn draw(&self, lines: &mut LineBatch, now: f64) {
+        if !self.is_respawning(now) {
+
+            // A raised shield holds steady; one granted for a while flickers
+            if self.is_shield_active(now) && (self.shield_raised || ((now * 50.0) as u32).is_multiple_of(2)) {
+                let radius = self.shield_radius();
+                wrap_draw(self.position, radius, |position| draw_circle_lines(position.x, position.y, radius, 2.0, self.color));
+            }
+
+            // Mark the guided hyperspace destination with a crosshair
+            if let Some((target, _)) = self.hyperspace_target {
+                let r = Self::HYPERSPACE_MARKER_RADIUS;
+
+                wrap_draw(target, r, |position| {
+                    draw_circle_lines(position.x, position.y, r, 1.0, self.color);
+                    draw_line(position.x - r * 1.5, position.y, position.x - r * 0.5, position.y, 1.0, self.color);
+                    draw_line(position.x + r * 0.5, position.y, position.x + r * 1.5, position.y, 1.0, self.color);
+                    draw_line(position.x, position.y - r * 1.5, position.x, position.y - r * 0.5, 1.0, self.color);
+                    draw_line(position.x, position.y + r * 0.5, position.x, position.y + r * 1.5, 1.0, self.color);
+                });
+            }
+
+            let rotation = detmath::rotation(self.rotation);
+
+            self.draw_wrapped(|position| lines.push_polygon(position, &self.vertices, rotation, 2., self.color));
+        }
+    }
+}
+
+impl Collidable for Ship {
+    fn is_colliding(&self, other: &dyn Collidable) -> bool {
+        <dyn Collidable>::shape_intersection(self, other)
+    }
+
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn get_radius(&self) -> f32 {
+        self.radius
+    }
+
+    fn get_outline(&self) -> Option<Outline<'_>> {
+        Some(Outline {
+            vertices: &self.vertices,
+            rotation: self.rotation,
+        })
+    }
+}
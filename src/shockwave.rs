@@ -0,0 +1,108 @@
+//! Smart bombs.
+//!
+//! Every game starts with a few smart bombs. Setting one off sends a
+//! shockwave racing out from the ship, destroying every asteroid and saucer
+//! its ring sweeps over. Rocks caught in the blast are vaporised rather than
+//! split, and everything it destroys is worth only part of its usual points,
+//! so bombs are for getting out of trouble rather than for scoring.
+
+use macroquad::prelude::*;
+
+use crate::collision::wrapped_delta;
+use crate::wrap::wrap_draw;
+
+/// Shockwave
+///
+/// The ring of a smart bomb, growing to its full radius while it expands and
+/// then fading out. It only destroys what it reaches while expanding.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::shockwave::Shockwave;
+/// # use macroquad::prelude::*;
+/// let mut shockwave = Shockwave::spawn_new(Vec2::new(500.0, 375.0));
+/// let rock = Vec2::new(500.0 + Shockwave::RADIUS * 0.75, 375.0);
+///
+/// // The ring has yet to reach the rock, then sweeps over it
+/// shockwave.update(Shockwave::EXPANSION / 4.0);
+/// assert!(!shockwave.reaches(rock, 10.0));
+///
+/// shockwave.update(Shockwave::EXPANSION / 2.0);
+/// assert!(shockwave.reaches(rock, 10.0));
+///
+/// // Nothing beyond the full radius is touched
+/// assert!(!shockwave.reaches(Vec2::new(500.0, 375.0 + Shockwave::RADIUS + 20.0), 10.0));
+///
+/// assert_eq!(Shockwave::points(1000), 500);
+/// ```
+pub struct Shockwave {
+    pub position: Vec2,
+    pub age: f32,
+}
+
+impl Shockwave {
+    /// Smart bombs every game starts with.
+    pub const STARTING_BOMBS: u32 = 3;
+
+    /// Full radius of the ring.
+    pub const RADIUS: f32 = 280.0;
+
+    /// Time in seconds the ring takes to reach its full radius.
+    pub const EXPANSION: f32 = 0.4;
+
+    /// Time in seconds the ring takes to fade out once fully expanded.
+    const FADE: f32 = 0.3;
+
+    /// Part of its usual points scored for anything the ring destroys.
+    const POINTS_DIVISOR: u32 = 2;
+
+    /// Set off a shockwave at a position.
+    pub fn spawn_new(position: Vec2) -> Self {
+        Self { position, age: 0.0 }
+    }
+
+    /// Points scored for destroying something worth a number of points with
+    /// a smart bomb.
+    pub fn points(points: u32) -> u32 {
+        points / Self::POINTS_DIVISOR
+    }
+
+    /// Current radius of the ring.
+    pub fn radius(&self) -> f32 {
+        Self::RADIUS * (self.age / Self::EXPANSION).min(1.0)
+    }
+
+    /// Check if the ring is still expanding.
+    pub fn is_expanding(&self) -> bool {
+        self.age < Self::EXPANSION
+    }
+
+    /// Check if the ring is still shown.
+    pub fn is_alive(&self) -> bool {
+        self.age < Self::EXPANSION + Self::FADE
+    }
+
+    /// Check if the expanding ring has reached an object within a radius of
+    /// a position.
+    pub fn reaches(&self, position: Vec2, radius: f32) -> bool {
+        self.is_expanding() && wrapped_delta(self.position, position).length() <= self.radius() + radius
+    }
+
+    /// Grow the ring by the time the frame took.
+    pub fn update(&mut self, dt: f32) {
+        self.age += dt;
+    }
+
+    /// Draw the ring, with a fainter echo trailing inside it, fading out once
+    /// fully expanded.
+    pub fn draw(&self) {
+        let fade = 1.0 - ((self.age - Self::EXPANSION) / Self::FADE).clamp(0.0, 1.0);
+        let radius = self.radius();
+
+        wrap_draw(self.position, radius, |position| {
+            draw_circle_lines(position.x, position.y, radius, 3.0, Color::new(1.0, 1.0, 1.0, fade));
+            draw_circle_lines(position.x, position.y, radius * 0.8, 1.5, Color::new(1.0, 1.0, 1.0, fade * 0.4));
+        });
+    }
+}
@@ -0,0 +1,68 @@
+use crate::ship::Ship;
+
+/// Ship upgrade
+///
+/// Upgrades are bought from the shop with credits earned while playing. Each
+/// upgrade can be bought once per game, and is lost when the game ends.
+///
+#[derive(Clone, Copy, PartialEq)]
+pub enum Upgrade {
+    ReverseThrusters,
+    LateralThrusters,
+    GuidedHyperspace,
+}
+
+impl Upgrade {
+    /// All upgrades, in the order they are listed in the shop.
+    pub const ALL: [Upgrade; 3] = [Upgrade::ReverseThrusters, Upgrade::LateralThrusters, Upgrade::GuidedHyperspace];
+
+    /// Name shown in the shop.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Upgrade::ReverseThrusters => "Reverse Thrusters",
+            Upgrade::LateralThrusters => "Strafe Thrusters",
+            Upgrade::GuidedHyperspace => "Guided Hyperspace",
+        }
+    }
+
+    /// Price in credits.
+    pub fn price(&self) -> u32 {
+        match self {
+            Upgrade::ReverseThrusters => 3,
+            Upgrade::LateralThrusters => 5,
+            Upgrade::GuidedHyperspace => 6,
+        }
+    }
+
+    /// Check if the ship is already fitted with this upgrade.
+    pub fn is_fitted(&self, ship: &Ship) -> bool {
+        match self {
+            Upgrade::ReverseThrusters => ship.reverse_thrusters,
+            Upgrade::LateralThrusters => ship.lateral_thrusters,
+            Upgrade::GuidedHyperspace => ship.guided_hyperspace,
+        }
+    }
+
+    /// Fit the upgrade to a ship.
+    pub fn fit(&self, ship: &mut Ship) {
+        match self {
+            Upgrade::ReverseThrusters => ship.reverse_thrusters = true,
+            Upgrade::LateralThrusters => ship.lateral_thrusters = true,
+            Upgrade::GuidedHyperspace => ship.guided_hyperspace = true,
+        }
+    }
+
+    /// Buy the upgrade for a ship, paying from the given credits. Returns false,
+    /// leaving the credits untouched, if the ship already has the upgrade or the
+    /// credits do not cover the price.
+    pub fn buy(&self, ship: &mut Ship, credits: &mut u32) -> bool {
+        if self.is_fitted(ship) || *credits < self.price() {
+            return false;
+        }
+
+        *credits -= self.price();
+        self.fit(ship);
+
+        true
+    }
+}
@@ -0,0 +1,280 @@
+//! Soak tests.
+//!
+//! Launched with `--soak <hours>`, the game lets the auto pilot play for that
+//! many hours of frames, back to back as fast as the simulation runs, starting
+//! a new game whenever one ends. After every tick the world is checked against
+//! invariants that should always hold: no position or velocity has become
+//! NaN or infinite, wrapping objects are on the playfield, there are no more
+//! objects of a kind than the game can produce, and the score has not gone
+//! down. The only time a score may fall is when a new game starts, which
+//! happens between ticks. The most objects of each kind seen at once, and,
+//! with the `alloc-tracking` feature, the high-water mark of the heap, are
+//! reported at the end, so slow leaks and numeric drift introduced by new
+//! features show up long before a player would notice them.
+//!
+//! Each tick moves the game clock and the field on by a sixtieth of a second,
+//! so timers such as a saucer's next shot or the ship's respawn keep pace with
+//! the field however fast it runs. A soak in which no points are ever scored
+//! fails too, since the game has stopped moving on and checking it proves
+//! nothing.
+//!
+//! Timing needs a clock, so soak tests only run on native builds.
+
+use macroquad::prelude::*;
+
+use crate::camera::WORLD_SIZE;
+use crate::wrap;
+
+/// Ticks in an hour of frames at 60 frames per second.
+pub const TICKS_PER_HOUR: usize = 60 * 60 * 60;
+
+/// Time in seconds each tick steps the field by.
+pub const STEP: f32 = 1.0 / 60.0;
+
+/// Most violations kept to report; any more are only counted.
+const MAX_REPORTED: usize = 20;
+
+/// Ticks within which the auto pilot should have scored, a minute of frames.
+pub const STALL_TICKS: usize = 60 * 60;
+
+/// Entity counts
+///
+/// Objects of each kind in the world at once.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::soak::EntityCounts;
+/// let counts = EntityCounts { asteroids: 12, saucers: 1, bullets: 10, particles: 300 };
+/// let peak = EntityCounts { asteroids: 20, saucers: 0, bullets: 4, particles: 500 };
+///
+/// assert_eq!(peak.max(&counts), EntityCounts { asteroids: 20, saucers: 1, bullets: 10, particles: 500 });
+/// assert_eq!(counts.over(&EntityCounts::CAPS), None);
+///
+/// let crowded = EntityCounts { saucers: 40, ..counts };
+/// assert_eq!(crowded.over(&EntityCounts::CAPS), Some("saucers"));
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct EntityCounts {
+    pub asteroids: usize,
+    pub saucers: usize,
+    pub bullets: usize,
+    pub particles: usize,
+}
+
+impl EntityCounts {
+    /// Most objects of each kind the game should ever have at once: a full
+    /// wave shattered to its smallest rocks with room to spare, a few
+    /// saucers, every gun firing, and several explosions' worth of particles.
+    pub const CAPS: EntityCounts = EntityCounts {
+        asteroids: 128,
+        saucers: 8,
+        bullets: 256,
+        particles: 8192,
+    };
+
+    /// Objects of each kind in a world.
+    pub fn of(world: &crate::world::GameWorld) -> Self {
+        Self {
+            asteroids: world.asteroids.len(),
+            saucers: world.saucers.len(),
+            bullets: world.player_bullets.len() + world.enemy_bullets.len(),
+            particles: world.particles.len(),
+        }
+    }
+
+    /// The larger of two counts, kind by kind.
+    pub fn max(&self, other: &EntityCounts) -> Self {
+        Self {
+            asteroids: self.asteroids.max(other.asteroids),
+            saucers: self.saucers.max(other.saucers),
+            bullets: self.bullets.max(other.bullets),
+            particles: self.particles.max(other.particles),
+        }
+    }
+
+    /// First kind of object there are more of than caps allow, if any.
+    pub fn over(&self, caps: &EntityCounts) -> Option<&'static str> {
+        [
+            ("asteroids", self.asteroids, caps.asteroids),
+            ("saucers", self.saucers, caps.saucers),
+            ("bullets", self.bullets, caps.bullets),
+            ("particles", self.particles, caps.particles),
+        ].into_iter().find(|(_, count, cap)| count > cap).map(|(kind, _, _)| kind)
+    }
+}
+
+/// Soak report
+///
+/// What a soak test saw: how long it ran, the invariants broken along the
+/// way, and the high-water marks reached.
+#[derive(Clone, Default, Debug)]
+pub struct SoakReport {
+    pub ticks: usize,
+    pub games: usize,
+    pub violations: Vec<String>,
+    pub violation_count: usize,
+    pub peak: EntityCounts,
+    pub top_score: u32,
+    pub top_wave: u32,
+    pub peak_bytes: usize,
+    pub live_bytes: usize,
+}
+
+impl SoakReport {
+    /// Record an invariant broken on a tick.
+    pub fn violate(&mut self, tick: usize, message: String) {
+        if self.violations.len() < MAX_REPORTED {
+            self.violations.push(format!("tick {}: {}", tick, message));
+        }
+
+        self.violation_count += 1;
+    }
+
+    /// Check if every invariant held throughout.
+    pub fn passed(&self) -> bool {
+        self.violation_count == 0
+    }
+
+    /// Lines summing up the test.
+    pub fn report(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("soak     {} ticks ({:.2} hours)  {} games", self.ticks, self.ticks as f64 / TICKS_PER_HOUR as f64, self.games),
+            format!(
+                "peak     {} asteroids  {} saucers  {} bullets  {} particles",
+                self.peak.asteroids, self.peak.saucers, self.peak.bullets, self.peak.particles
+            ),
+            format!("best     score {}  wave {}", self.top_score, self.top_wave),
+        ];
+
+        if self.peak_bytes > 0 {
+            lines.push(format!("heap     peak {} KiB  live at end {} KiB", self.peak_bytes / 1024, self.live_bytes / 1024));
+        } else {
+            lines.push("heap     not tracked (build with --features alloc-tracking)".to_string());
+        }
+
+        lines.extend(self.violations.iter().cloned());
+
+        if self.violation_count > self.violations.len() {
+            lines.push(format!("... and {} more", self.violation_count - self.violations.len()));
+        }
+
+        lines.push(if self.passed() { "passed".to_string() } else { format!("FAILED with {} violations", self.violation_count) });
+
+        lines
+    }
+}
+
+/// Check if a vector is neither NaN nor infinite.
+fn is_finite(vector: Vec2) -> bool {
+    vector.x.is_finite() && vector.y.is_finite()
+}
+
+/// Check if the position of an object within a radius of it is on the
+/// playfield, or beyond an edge no further than it carries on before wrapping.
+fn is_on_playfield(position: Vec2, radius: f32) -> bool {
+    wrap::is_within(position, radius * wrap::WRAP_MARGIN, WORLD_SIZE)
+}
+
+/// Check the invariants of a world after a tick, given the score before it,
+/// recording any broken.
+pub fn check(world: &crate::world::GameWorld, previous_score: u32, tick: usize, report: &mut SoakReport) {
+    let ship = &world.ship;
+
+    if !is_finite(ship.position) || !is_finite(ship.velocity) || !ship.rotation.is_finite() {
+        report.violate(tick, format!("ship is adrift at {} moving {}", ship.position, ship.velocity));
+    } else if !is_on_playfield(ship.position, ship.radius) {
+        report.violate(tick, format!("ship is off the playfield at {}", ship.position));
+    }
+
+    for asteroid in &world.asteroids {
+        if !is_finite(asteroid.position) || !is_finite(asteroid.velocity) || !asteroid.rotation.is_finite() {
+            report.violate(tick, format!("asteroid is adrift at {} moving {}", asteroid.position, asteroid.velocity));
+        } else if !is_on_playfield(asteroid.position, asteroid.diameter / 2.0) {
+            report.violate(tick, format!("asteroid is off the playfield at {}", asteroid.position));
+        }
+    }
+
+    // Departing saucers fly off the playfield, so only their numbers are checked
+    for saucer in &world.saucers {
+        if !is_finite(saucer.position) || !is_finite(saucer.velocity) {
+            report.violate(tick, format!("saucer is adrift at {} moving {}", saucer.position, saucer.velocity));
+        }
+    }
+
+    for bullet in world.player_bullets.iter().chain(&world.enemy_bullets) {
+        if !is_finite(bullet.position) || !is_finite(bullet.velocity) || !bullet.lifespan.is_finite() {
+            report.violate(tick, format!("bullet is adrift at {} moving {}", bullet.position, bullet.velocity));
+        }
+    }
+
+    for particle in &world.particles {
+        if !is_finite(particle.position) || !is_finite(particle.velocity) {
+            report.violate(tick, format!("particle is adrift at {} moving {}", particle.position, particle.velocity));
+        }
+    }
+
+    let counts = EntityCounts::of(world);
+
+    if let Some(kind) = counts.over(&EntityCounts::CAPS) {
+        report.violate(tick, format!("too many {}: {:?}", kind, counts));
+    }
+
+    report.peak = report.peak.max(&counts);
+    report.top_score = report.top_score.max(world.player_score);
+    report.top_wave = report.top_wave.max(world.wave_number);
+
+    if world.player_score < previous_score {
+        report.violate(tick, format!("score fell from {} to {}", previous_score, world.player_score));
+    }
+}
+
+/// Let the auto pilot play for a number of hours of ticks, checking the world
+/// after each.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::options::LaunchOptions;
+/// # use asteroids::soak;
+/// # use asteroids::world::GameWorld;
+/// let options = LaunchOptions { seed: Some(1979), ..Default::default() };
+/// let mut world = GameWorld::headless(&options);
+///
+/// // Two minutes of frames
+/// let report = soak::run(&mut world, 2.0 / 60.0);
+///
+/// assert!(report.passed(), "{:?}", report.violations);
+/// assert!(report.top_score > 0);
+/// assert!(world.clock.now() > 100.0);
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run(world: &mut crate::world::GameWorld, hours: f64) -> SoakReport {
+    let ticks = (hours * TICKS_PER_HOUR as f64) as usize;
+    let mut report = SoakReport::default();
+
+    for tick in 0..ticks {
+        if !world.is_playing() {
+            world.start(false);
+            report.games += 1;
+        }
+
+        let previous_score = world.player_score;
+
+        world.autopilot_input();
+        world.clock.advance(STEP);
+        world.update();
+
+        check(world, previous_score, tick, &mut report);
+        report.ticks += 1;
+    }
+
+    if report.ticks >= STALL_TICKS && report.top_score == 0 {
+        report.violate(report.ticks, "no points were scored; the game is not moving on".to_string());
+    }
+
+    report.peak_bytes = crate::alloc::peak_bytes();
+    report.live_bytes = crate::alloc::live_bytes();
+
+    report
+}
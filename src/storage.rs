@@ -0,0 +1,107 @@
+//! Persistent storage
+//! 
+//! Loads and saves small text documents by key. Native builds store each key as
+//! a file in the user's data directory; web builds store them in the browser's
+//! localStorage through the `storage.js` plugin shipped alongside the game, or
+//! in IndexedDB where localStorage is unavailable. The plugin reads IndexedDB
+//! before the game starts, so both backends load synchronously.
+//! Storage is best effort: failures to save are ignored and failures to load
+//! are reported as missing documents. Binary files, such as bug reports, are
+//! saved as they are on native builds, and in base64 on the web, where only
+//! text can be stored.
+
+/// Load document stored under key.
+pub fn load(key: &str) -> Option<String> {
+    backend::load(key)
+}
+
+/// Save document under key, replacing any previous contents.
+pub fn save(key: &str, contents: &str) {
+    backend::save(key, contents)
+}
+
+/// Save binary contents under key, replacing any previous contents.
+pub fn save_bytes(key: &str, contents: &[u8]) {
+    backend::save_bytes(key, contents)
+}
+
+/// Path of the file a key is stored in, on native builds.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn path(key: &str) -> Option<std::path::PathBuf> {
+    backend::path(key)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod backend {
+    use std::path::PathBuf;
+
+    /// Platform-appropriate directory for game data.
+    fn data_dir() -> Option<PathBuf> {
+        let base = if cfg!(target_os = "windows") {
+            std::env::var_os("APPDATA").map(PathBuf::from)
+        } else if cfg!(target_os = "macos") {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+        } else {
+            std::env::var_os("XDG_DATA_HOME")
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        };
+
+        base.map(|base| base.join("asteroids"))
+    }
+
+    pub fn path(key: &str) -> Option<PathBuf> {
+        Some(data_dir()?.join(key))
+    }
+
+    pub fn load(key: &str) -> Option<String> {
+        std::fs::read_to_string(path(key)?).ok()
+    }
+
+    pub fn save(key: &str, contents: &str) {
+        if let Some(dir) = data_dir() {
+            if std::fs::create_dir_all(&dir).is_ok() {
+                let _ = std::fs::write(dir.join(key), contents);
+            }
+        }
+    }
+
+    pub fn save_bytes(key: &str, contents: &[u8]) {
+        if let Some(dir) = data_dir() {
+            if std::fs::create_dir_all(&dir).is_ok() {
+                let _ = std::fs::write(dir.join(key), contents);
+            }
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod backend {
+    extern "C" {
+        fn storage_length(key: *const u8, key_len: u32) -> i32;
+        fn storage_read(key: *const u8, key_len: u32, buffer: *mut u8, buffer_len: u32);
+        fn storage_write(key: *const u8, key_len: u32, value: *const u8, value_len: u32);
+    }
+
+    pub fn load(key: &str) -> Option<String> {
+        let length = unsafe { storage_length(key.as_ptr(), key.len() as u32) };
+        if length < 0 {
+            return None;
+        }
+
+        let mut buffer = vec![0u8; length as usize];
+        unsafe { storage_read(key.as_ptr(), key.len() as u32, buffer.as_mut_ptr(), buffer.len() as u32) };
+
+        String::from_utf8(buffer).ok()
+    }
+
+    pub fn save(key: &str, contents: &str) {
+        unsafe { storage_write(key.as_ptr(), key.len() as u32, contents.as_ptr(), contents.len() as u32) };
+    }
+
+    pub fn save_bytes(key: &str, contents: &[u8]) {
+        use base64::Engine;
+
+        save(key, &base64::engine::general_purpose::STANDARD.encode(contents));
+    }
+}
@@ -0,0 +1,122 @@
+//! Wave tally.
+//!
+//! When a wave of a classic, co-op or weekly game is cleared, play stops for
+//! a few seconds on a tally of how it went, which pays out bonus points: for
+//! the share of shots that hit, for clearing it under par time, for getting
+//! through it without losing a ship, and for each bomb left unused. Time is
+//! counted in the frames played, so a paused game does not run the clock.
+
+/// Wave stats
+///
+/// What happened over the wave being played: the shots fired and the hits
+/// they landed, the time in seconds spent playing it, and the ships lost.
+/// A piercing bullet can land more than one hit.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct WaveStats {
+    pub shots: u32,
+    pub hits: u32,
+    pub elapsed: f64,
+    pub lives_lost: u32,
+}
+
+impl WaveStats {
+    /// Share of the shots fired that hit, from 0 to 1, if any were fired.
+    pub fn accuracy(&self) -> Option<f32> {
+        (self.shots > 0).then(|| (self.hits as f32 / self.shots as f32).min(1.0))
+    }
+}
+
+/// Wave tally
+///
+/// The bonuses paid out for a wave.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::tally::{WaveStats, WaveTally};
+/// let stats = WaveStats { shots: 40, hits: 30, elapsed: 45.0, lives_lost: 0 };
+/// let tally = WaveTally::new(3, &stats, 2);
+///
+/// assert_eq!(tally.accuracy_bonus, 750);
+/// assert_eq!(tally.time_bonus, 300);
+/// assert_eq!(tally.no_damage_bonus, WaveTally::NO_DAMAGE_POINTS);
+/// assert_eq!(tally.bomb_bonus, 2 * WaveTally::BOMB_POINTS);
+/// assert_eq!(tally.total(), 750 + 300 + 1000 + 500);
+///
+/// // A slow wave that cost a ship earns only its accuracy
+/// let stats = WaveStats { shots: 10, hits: 10, elapsed: 90.0, lives_lost: 1 };
+/// assert_eq!(WaveTally::new(4, &stats, 0).total(), 1000);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct WaveTally {
+    pub wave: u32,
+    pub accuracy: Option<f32>,
+    pub elapsed: f64,
+    pub bombs: u32,
+    pub accuracy_bonus: u32,
+    pub time_bonus: u32,
+    pub no_damage_bonus: u32,
+    pub bomb_bonus: u32,
+}
+
+impl WaveTally {
+    /// Time in seconds the tally is shown for.
+    pub const DURATION: f64 = 3.0;
+
+    /// Points for a wave with every shot on target, paid in proportion to
+    /// accuracy.
+    pub const ACCURACY_POINTS: u32 = 1000;
+
+    /// Time in seconds to clear a wave within to earn the time bonus.
+    pub const PAR_TIME: f64 = 60.0;
+
+    /// Points for each second under par time.
+    pub const TIME_POINTS: u32 = 20;
+
+    /// Points for a wave cleared without losing a ship.
+    pub const NO_DAMAGE_POINTS: u32 = 1000;
+
+    /// Points for each bomb left.
+    pub const BOMB_POINTS: u32 = 250;
+
+    /// Tally a wave from its stats and the bombs left at its end.
+    pub fn new(wave: u32, stats: &WaveStats, bombs: u32) -> Self {
+        let accuracy = stats.accuracy();
+        let under_par = (Self::PAR_TIME - stats.elapsed).max(0.0);
+
+        Self {
+            wave,
+            accuracy,
+            elapsed: stats.elapsed,
+            bombs,
+            accuracy_bonus: (accuracy.unwrap_or(0.0) * Self::ACCURACY_POINTS as f32).round() as u32,
+            time_bonus: under_par.floor() as u32 * Self::TIME_POINTS,
+            no_damage_bonus: if stats.lives_lost == 0 { Self::NO_DAMAGE_POINTS } else { 0 },
+            bomb_bonus: bombs * Self::BOMB_POINTS,
+        }
+    }
+
+    /// Bonus points paid out in all.
+    pub fn total(&self) -> u32 {
+        self.accuracy_bonus + self.time_bonus + self.no_damage_bonus + self.bomb_bonus
+    }
+
+    /// Lines of the tally, each with the bonus it pays.
+    pub fn lines(&self) -> Vec<(String, u32)> {
+        let accuracy = match self.accuracy {
+            Some(accuracy) => format!("Accuracy  {:.0}%", accuracy * 100.0),
+            None => "Accuracy  -".to_string(),
+        };
+
+        let minutes = (self.elapsed / 60.0).floor();
+        let seconds = self.elapsed - minutes * 60.0;
+        let no_damage = if self.no_damage_bonus > 0 { "No damage" } else { "Ship lost" };
+
+        vec![
+            (accuracy, self.accuracy_bonus),
+            (format!("Time  {}:{:04.1}", minutes, seconds), self.time_bonus),
+            (no_damage.to_string(), self.no_damage_bonus),
+            (format!("Bombs  {}", self.bombs), self.bomb_bonus),
+        ]
+    }
+}
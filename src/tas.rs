@@ -0,0 +1,395 @@
+//! Tool-assisted play.
+//!
+//! Launched with `--tas`, the game is played a frame at a time, for runs
+//! worked out to the frame. A classic game starts paused; F6 pauses and
+//! resumes, and F7 plays one frame with the controls held, along with any
+//! pressed since the last frame. Savestates are kept in ten slots: F8 saves
+//! to the slot selected with F10, and F9 loads it. F12 exports the movie, the
+//! controls worked on every frame so far, to `movie.tas` alongside the saved
+//! data.
+//!
+//! A movie is a text file, so it can be edited by hand:
+//!
+//! ```text
+//! asteroids-movie 2
+//! seed 42
+//! difficulty normal
+//! lives 3
+//! bullet_limit false
+//! shield_duration 3
+//! shield_recharge 12
+//! bouncing_asteroids false
+//! arcade_scoring false
+//! score 1230
+//! wave 2
+//! frames
+//! +0.000 T............ 17
+//! -0.100 .F........... 17
+//! ```
+//!
+//! The header holds the game's seed, settings and rules and, optionally, the
+//! score and wave it ends on. Rules left out keep their defaults, as they do
+//! in movies written before the format recorded them. Each line after `frames` is a frame: the turn rate
+//! passed to `Ship::steer`, the buttons down, and the frame's duration in
+//! milliseconds. Buttons are written in a fixed order, as the letters of
+//! `BUTTONS` for those down and dots for those up: thrust, fire, hyperspace,
+//! reverse thrust, strafe left and right, decoy, lightning, overdrive, radar,
+//! rewind, bomb and shield.
+//!
+//! A movie plays back like a shared replay code, launched with
+//! `--movie <file>`, and is checked with `--verify <file>`, which plays it
+//! back as fast as it can and compares the score and wave reached with those
+//! in the header. A savestate is kept as the frame it was saved on, and
+//! loading it plays the movie back up to that frame, so every state loaded is
+//! one the movie reaches when played back. As with replay codes, the game
+//! clock moves on by each frame's recorded duration, so gameplay timers run
+//! the same however slowly the frames were worked out.
+
+use std::fmt;
+
+use crate::alloc::AllocationBudget;
+use crate::difficulty::Difficulty;
+use crate::replaycode::{InputFrame, ReplayCode, Rules};
+
+/// Letters standing for each button down, in the order of their bits.
+pub const BUTTONS: &str = "TFHR<>DLOPWBS";
+
+/// Movie error
+///
+/// Reasons a movie could not be read.
+#[derive(Debug, PartialEq)]
+pub enum MovieError {
+    /// The text is not a movie.
+    NotAMovie,
+    /// A line of the movie, numbered from 1, could not be read.
+    BadLine(usize),
+}
+
+impl fmt::Display for MovieError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MovieError::NotAMovie => write!(f, "not a movie"),
+            MovieError::BadLine(line) => write!(f, "movie line {} could not be read", line),
+        }
+    }
+}
+
+/// Movie
+///
+/// A classic game's seed, settings and rules, the controls worked on every
+/// one of its frames, and the score and wave it ends on, if known.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::difficulty::Difficulty;
+/// # use asteroids::replaycode::InputFrame;
+/// # use asteroids::tas::{Movie, MovieError};
+/// let mut movie = Movie::new(42, Difficulty::Normal, 3, false);
+/// movie.rules.shield_recharge = 8.0;
+/// movie.rules.bouncing_asteroids = true;
+/// movie.frames.push(InputFrame::new(0.0, InputFrame::THRUST, 1.0 / 60.0));
+/// movie.frames.push(InputFrame::new(-0.1, InputFrame::FIRE | InputFrame::SHIELD, 1.0 / 60.0));
+/// movie.result = Some((20, 1));
+///
+/// let text = movie.to_text();
+/// assert!(text.contains("-0.100 .F..........S 17"));
+/// assert_eq!(Movie::parse(&text), Ok(movie));
+///
+/// assert_eq!(Movie::parse("hello"), Err(MovieError::NotAMovie));
+/// assert_eq!(Movie::parse(&text.replace("17", "lots")), Err(MovieError::BadLine(13)));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Movie {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub starting_lives: u32,
+    pub bullet_limit: bool,
+    pub rules: Rules,
+    pub frames: Vec<InputFrame>,
+    pub result: Option<(u32, u32)>,
+}
+
+impl Movie {
+    /// First line of a movie, with the version of its format.
+    const HEADER: &'static str = "asteroids-movie 2";
+
+    /// First line of a movie written before the format recorded rules.
+    const HEADER_V1: &'static str = "asteroids-movie 1";
+
+    /// Start a movie of a game with a seed and settings, played by the
+    /// default rules.
+    pub fn new(seed: u64, difficulty: Difficulty, starting_lives: u32, bullet_limit: bool) -> Self {
+        Self {
+            seed,
+            difficulty,
+            starting_lives,
+            bullet_limit,
+            rules: Rules::default(),
+            frames: Vec::new(),
+            result: None,
+        }
+    }
+
+    /// Code to watch the movie with. Codes only share a game's first
+    /// `ReplayCode::MAX_FRAMES` frames, but can be watched in full.
+    pub fn to_code(&self) -> ReplayCode {
+        let mut code = ReplayCode::with_frames(self.seed, self.difficulty, self.starting_lives, self.bullet_limit, self.frames.clone());
+        code.rules = self.rules;
+        code
+    }
+
+    /// Write the movie out as text.
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "{}\nseed {}\ndifficulty {}\nlives {}\nbullet_limit {}\nshield_duration {}\nshield_recharge {}\nbouncing_asteroids {}\narcade_scoring {}\n",
+            Self::HEADER, self.seed, self.difficulty.label().to_lowercase(), self.starting_lives, self.bullet_limit,
+            self.rules.shield_duration, self.rules.shield_recharge, self.rules.bouncing_asteroids,
+            self.rules.arcade_scoring
+        );
+
+        if let Some((score, wave)) = self.result {
+            text += &format!("score {}\nwave {}\n", score, wave);
+        }
+
+        text += "frames\n";
+
+        for frame in &self.frames {
+            let buttons: String = BUTTONS.chars().enumerate()
+                .map(|(bit, letter)| if frame.is_down(1 << bit) { letter } else { '.' })
+                .collect();
+
+            text += &format!("{:+.3} {} {}\n", frame.steer(), buttons, (frame.dt() * 1000.0).round());
+        }
+
+        text
+    }
+
+    /// Read a movie back from text. Blank lines are ignored, as is anything
+    /// after a `#`.
+    pub fn parse(text: &str) -> Result<Self, MovieError> {
+        let mut lines = text.lines().enumerate()
+            .map(|(index, line)| (index + 1, line.split('#').next().unwrap_or("").trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        if !matches!(lines.next(), Some((_, Self::HEADER | Self::HEADER_V1))) {
+            return Err(MovieError::NotAMovie);
+        }
+
+        let mut movie = Self::new(0, Difficulty::Normal, 3, false);
+        let (mut score, mut wave) = (None, None);
+
+        // The header, up to the frames
+        for (number, line) in lines.by_ref() {
+            if line == "frames" {
+                break;
+            }
+
+            let bad = MovieError::BadLine(number);
+            let (key, value) = line.split_once(' ').ok_or(MovieError::BadLine(number))?;
+
+            match key {
+                "seed" => movie.seed = value.parse().map_err(|_| bad)?,
+                "difficulty" => {
+                    movie.difficulty = *Difficulty::ALL.iter()
+                        .find(|difficulty| difficulty.label().eq_ignore_ascii_case(value))
+                        .ok_or(bad)?
+                },
+                "lives" => movie.starting_lives = value.parse().map_err(|_| bad)?,
+                "bullet_limit" => movie.bullet_limit = value.parse().map_err(|_| bad)?,
+                "shield_duration" => movie.rules.shield_duration = parse_time(value).ok_or(bad)?,
+                "shield_recharge" => movie.rules.shield_recharge = parse_time(value).ok_or(bad)?,
+                "bouncing_asteroids" => movie.rules.bouncing_asteroids = value.parse().map_err(|_| bad)?,
+                "arcade_scoring" => movie.rules.arcade_scoring = value.parse().map_err(|_| bad)?,
+                "score" => score = Some(value.parse().map_err(|_| bad)?),
+                "wave" => wave = Some(value.parse().map_err(|_| bad)?),
+                _ => return Err(bad),
+            }
+        }
+
+        movie.result = score.zip(wave);
+
+        for (number, line) in lines {
+            movie.frames.push(parse_frame(line).ok_or(MovieError::BadLine(number))?);
+        }
+
+        Ok(movie)
+    }
+}
+
+/// Read a time in seconds from the header of a movie. Times are only ever
+/// positive.
+fn parse_time(value: &str) -> Option<f32> {
+    value.parse().ok().filter(|time: &f32| time.is_finite() && *time > 0.0)
+}
+
+/// Read a frame from a line of a movie.
+fn parse_frame(line: &str) -> Option<InputFrame> {
+    let mut fields = line.split_whitespace();
+    let steer: f32 = fields.next()?.parse().ok()?;
+    let letters = fields.next()?;
+    let duration: f32 = fields.next()?.parse().ok()?;
+
+    if fields.next().is_some() || letters.chars().count() != BUTTONS.len() {
+        return None;
+    }
+
+    let mut buttons = 0;
+
+    for (bit, (letter, expected)) in letters.chars().zip(BUTTONS.chars()).enumerate() {
+        if letter == expected {
+            buttons |= 1 << bit;
+        } else if letter != '.' {
+            return None;
+        }
+    }
+
+    Some(InputFrame::new(steer, buttons, duration / 1000.0))
+}
+
+/// Save slots
+///
+/// The savestates of a tool-assisted run, kept as the frames they were saved
+/// on, and the slot selected to save to and load from.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::tas::SaveSlots;
+/// let mut slots = SaveSlots::default();
+///
+/// slots.save(120);
+/// assert_eq!(slots.load(), Some(120));
+///
+/// // The next slot is empty until saved to
+/// slots.next_slot();
+/// assert_eq!(slots.selected, 1);
+/// assert_eq!(slots.load(), None);
+///
+/// // Going back past the frame a state was saved on forgets it
+/// slots.save(300);
+/// slots.forget_after(200);
+/// assert_eq!(slots.load(), None);
+/// ```
+#[derive(Default)]
+pub struct SaveSlots {
+    slots: [Option<usize>; SaveSlots::SLOTS],
+    pub selected: usize,
+}
+
+impl SaveSlots {
+    /// Number of slots.
+    pub const SLOTS: usize = 10;
+
+    /// Save a frame to the selected slot.
+    pub fn save(&mut self, frame: usize) {
+        self.slots[self.selected] = Some(frame);
+    }
+
+    /// Frame saved in the selected slot, if any.
+    pub fn load(&self) -> Option<usize> {
+        self.slots[self.selected]
+    }
+
+    /// Select the next slot, wrapping around to the first.
+    pub fn next_slot(&mut self) {
+        self.selected = (self.selected + 1) % Self::SLOTS;
+    }
+
+    /// Forget states saved after a frame, which the movie no longer reaches
+    /// once it is cut short there.
+    pub fn forget_after(&mut self, frame: usize) {
+        for slot in &mut self.slots {
+            if slot.is_some_and(|saved| saved > frame) {
+                *slot = None;
+            }
+        }
+    }
+}
+
+/// Tool-assisted session
+///
+/// The movie recorded so far, whether play is paused for frame advance,
+/// the savestates, the buttons pressed while paused, to be worked on the
+/// next frame played, and a notice of the last thing done with the session.
+pub struct TasSession {
+    pub movie: Movie,
+    pub paused: bool,
+    pub slots: SaveSlots,
+    pub pending: u16,
+    pub notice: Option<String>,
+}
+
+impl TasSession {
+    /// Time in seconds every frame of a run steps the game by.
+    pub const STEP: f32 = 1.0 / 60.0;
+
+    /// Start a session recording a movie, paused on its first frame.
+    pub fn new(movie: Movie) -> Self {
+        Self {
+            movie,
+            paused: true,
+            slots: SaveSlots::default(),
+            pending: 0,
+            notice: None,
+        }
+    }
+}
+
+/// Verification
+///
+/// What playing a movie back reached, against what its header says it
+/// should, and, when held to an allocation budget, the frames over it and
+/// the most allocations made on one.
+#[derive(Clone, Debug)]
+pub struct Verification {
+    pub frames: usize,
+    pub played: usize,
+    pub reached: (u32, u32),
+    pub expected: Option<(u32, u32)>,
+    pub budget: Option<(usize, usize, usize)>,
+}
+
+impl Verification {
+    /// Check if the movie reached the score and wave it claims, within its
+    /// allocation budget. A movie claiming nothing passes if it plays
+    /// through.
+    pub fn passed(&self) -> bool {
+        self.expected.is_none_or(|expected| expected == self.reached)
+            && self.played == self.frames
+            && self.budget.is_none_or(|(_, overruns, _)| overruns == 0)
+    }
+
+    /// Lines summing up the verification.
+    pub fn report(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("played   {} of {} frames", self.played, self.frames),
+            format!("reached  score {}  wave {}", self.reached.0, self.reached.1),
+        ];
+
+        if let Some((score, wave)) = self.expected {
+            lines.push(format!("expected score {}  wave {}", score, wave));
+        }
+
+        if let Some((limit, overruns, worst)) = self.budget {
+            lines.push(format!("budget   {} frames over {} allocations  worst {}", overruns, limit, worst));
+        }
+
+        lines.push(if self.passed() { "verified".to_string() } else { "FAILED".to_string() });
+        lines
+    }
+}
+
+/// Play a movie back in a world, as fast as it runs, and check what it
+/// reaches, holding each frame to an allocation budget if given one.
+pub fn verify(world: &mut crate::world::GameWorld, movie: &Movie, mut budget: Option<&mut AllocationBudget>) -> Verification {
+    let played = world.play_movie(movie, movie.frames.len(), budget.as_deref_mut());
+
+    Verification {
+        frames: movie.frames.len(),
+        played,
+        reached: (world.player_score, world.wave_number),
+        expected: movie.result,
+        budget: budget.map(|budget| (budget.limit, budget.overruns, budget.worst)),
+    }
+}
@@ -0,0 +1,102 @@
+use std::cell::{Ref, RefCell};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use macroquad::prelude::*;
+
+/// Cached text
+///
+/// Text formatted from a value, such as the score in the HUD. The text is only
+/// formatted again when the value changes, so frames where nothing changed do
+/// not allocate. The cache uses interior mutability so it can be refreshed from
+/// drawing code, which only has shared access to the world.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::textcache::CachedText;
+/// let score = CachedText::new();
+///
+/// assert_eq!(&*score.get(1200, |score| format!("Score: {}", score)), "Score: 1200");
+/// assert_eq!(&*score.get(1200, |_| unreachable!()), "Score: 1200");
+/// ```
+pub struct CachedText<T> {
+    cache: RefCell<Option<(T, String)>>,
+}
+
+impl<T: Copy + PartialEq> CachedText<T> {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self {
+            cache: RefCell::new(None),
+        }
+    }
+
+    /// Text for a value, formatting it only if the value differs from the one
+    /// last cached.
+    pub fn get(&self, value: T, format: impl FnOnce(T) -> String) -> Ref<'_, str> {
+        let is_dirty = self.cache.borrow().as_ref().is_none_or(|(cached, _)| *cached != value);
+
+        if is_dirty {
+            *self.cache.borrow_mut() = Some((value, format(value)));
+        }
+
+        Ref::map(self.cache.borrow(), |cache| cache.as_ref().map_or("", |(_, text)| text.as_str()))
+    }
+}
+
+impl<T: Copy + PartialEq> Default for CachedText<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Text measure cache
+///
+/// Remembers the measured width of text drawn in a given font and size, so
+/// labels centred every frame are only measured once. The cache only holds one
+/// font's measurements; it is cleared if it grows large, which only happens
+/// when many distinct texts are measured.
+pub struct TextMeasureCache {
+    widths: RefCell<HashMap<(u64, u16), f32>>,
+}
+
+impl TextMeasureCache {
+    /// Most measurements kept before the cache is cleared.
+    const CAPACITY: usize = 256;
+
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self {
+            widths: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Width of text drawn in a font at a size, measuring it if it has not
+    /// been measured before.
+    pub fn width(&self, font: &Font, text: &str, font_size: u16) -> f32 {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        let key = (hasher.finish(), font_size);
+
+        if let Some(width) = self.widths.borrow().get(&key) {
+            return *width;
+        }
+
+        let width = measure_text(text, Some(font), font_size, 1.0).width;
+        let mut widths = self.widths.borrow_mut();
+
+        if widths.len() >= Self::CAPACITY {
+            widths.clear();
+        }
+
+        widths.insert(key, width);
+        width
+    }
+}
+
+impl Default for TextMeasureCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
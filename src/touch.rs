@@ -0,0 +1,183 @@
+//! Touch controls.
+//!
+//! On a touch screen the ship is flown with on-screen controls: a virtual
+//! joystick in one bottom corner, and fire and hyperspace buttons in the
+//! other. Pushing the stick turns the ship to face the way it is pushed, and
+//! pushing it past the thrust ring fires the thrusters as well. The controls
+//! are drawn as translucent outlines over the field, and only once a game has
+//! been started by touch.
+//!
+//! Which hand the stick is under is a setting, saved with the others. The
+//! controls are laid out in screen coordinates and scale with the smaller
+//! side of the window, so they stay under the thumbs on any screen.
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Touch layout
+///
+/// Which side of the screen the joystick is on; the buttons are on the other.
+#[derive(Clone, Copy, Default, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TouchLayout {
+    #[default]
+    StickLeft,
+    StickRight,
+}
+
+impl TouchLayout {
+    /// Label shown in the settings.
+    pub fn label(&self) -> &'static str {
+        match self {
+            TouchLayout::StickLeft => "Stick Left",
+            TouchLayout::StickRight => "Stick Right",
+        }
+    }
+
+    /// The other layout.
+    pub fn next(&self) -> TouchLayout {
+        match self {
+            TouchLayout::StickLeft => TouchLayout::StickRight,
+            TouchLayout::StickRight => TouchLayout::StickLeft,
+        }
+    }
+}
+
+/// Touch input
+///
+/// The controls touched on a frame. The stick is how far and which way the
+/// joystick is pushed, no more than 1 long, and zero when it is not touched.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct TouchInput {
+    pub stick: Vec2,
+    pub thrust: bool,
+    pub fire: bool,
+    pub hyperspace: bool,
+}
+
+/// Touch controls
+///
+/// Where the joystick and buttons sit on a screen of a size.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::touch::{TouchControls, TouchLayout};
+/// # use macroquad::prelude::*;
+/// let controls = TouchControls::new(TouchLayout::StickLeft, Vec2::new(800.0, 600.0));
+///
+/// // Nothing touched
+/// let input = controls.read(std::iter::empty());
+/// assert_eq!(input.stick, Vec2::ZERO);
+/// assert!(!input.fire);
+///
+/// // The stick nudged right steers without thrusting, while fire is held
+/// let nudge = controls.stick_centre + Vec2::new(controls.stick_radius * 0.3, 0.0);
+/// let input = controls.read([nudge, controls.fire_centre].into_iter());
+/// assert!(input.stick.x > 0.0 && !input.thrust);
+/// assert!(input.fire && !input.hyperspace);
+///
+/// // Pushed all the way up, and beyond, it thrusts
+/// let push = controls.stick_centre - Vec2::new(0.0, controls.stick_radius * 1.5);
+/// let input = controls.read(std::iter::once(push));
+/// assert_eq!(input.stick, Vec2::new(0.0, -1.0));
+/// assert!(input.thrust);
+///
+/// // Touches away from the controls do nothing
+/// let input = controls.read(std::iter::once(Vec2::new(400.0, 100.0)));
+/// assert_eq!(input, Default::default());
+/// ```
+pub struct TouchControls {
+    pub stick_centre: Vec2,
+    pub stick_radius: f32,
+    pub fire_centre: Vec2,
+    pub hyperspace_centre: Vec2,
+    pub button_radius: f32,
+}
+
+impl TouchControls {
+    /// Radius of the joystick, as a part of the smaller side of the screen.
+    const STICK_SIZE: f32 = 0.14;
+
+    /// Radius of a button, as a part of the smaller side of the screen.
+    const BUTTON_SIZE: f32 = 0.08;
+
+    /// How far out from the stick's centre, as a part of its radius, a touch
+    /// still works the stick, so a thumb sliding off the edge keeps steering.
+    const STICK_REACH: f32 = 1.6;
+
+    /// How far the stick must be pushed, as a part of its radius, to thrust.
+    pub const THRUST_RING: f32 = 0.6;
+
+    /// Opacity of the controls drawn over the field.
+    const OPACITY: f32 = 0.25;
+
+    /// Lay the controls out on a screen of a size.
+    pub fn new(layout: TouchLayout, screen: Vec2) -> Self {
+        let unit = screen.x.min(screen.y);
+        let stick_radius = unit * Self::STICK_SIZE;
+        let button_radius = unit * Self::BUTTON_SIZE;
+
+        // Controls sit in from the corners by a little more than their size
+        let stick_inset = stick_radius * 1.4;
+        let (stick_x, buttons_x) = match layout {
+            TouchLayout::StickLeft => (stick_inset, screen.x - button_radius * 1.6),
+            TouchLayout::StickRight => (screen.x - stick_inset, button_radius * 1.6),
+        };
+
+        let towards_stick = if layout == TouchLayout::StickLeft { -1.0 } else { 1.0 };
+
+        Self {
+            stick_centre: Vec2::new(stick_x, screen.y - stick_inset),
+            stick_radius,
+            fire_centre: Vec2::new(buttons_x, screen.y - button_radius * 1.6),
+            hyperspace_centre: Vec2::new(buttons_x + towards_stick * button_radius * 2.6, screen.y - button_radius * 3.6),
+            button_radius,
+        }
+    }
+
+    /// Read the controls worked by touches at positions on the screen.
+    pub fn read(&self, touches: impl Iterator<Item = Vec2>) -> TouchInput {
+        let mut input = TouchInput::default();
+
+        for position in touches {
+            let offset = position - self.stick_centre;
+
+            if position.distance(self.fire_centre) <= self.button_radius {
+                input.fire = true;
+            } else if position.distance(self.hyperspace_centre) <= self.button_radius {
+                input.hyperspace = true;
+            } else if offset.length() <= self.stick_radius * Self::STICK_REACH {
+                input.stick = (offset / self.stick_radius).clamp_length_max(1.0);
+                input.thrust = input.stick.length() >= Self::THRUST_RING;
+            }
+        }
+
+        input
+    }
+
+    /// Draw the controls, showing the stick where it is pushed and the
+    /// buttons lit while held.
+    pub fn draw(&self, input: &TouchInput) {
+        let color = Color::new(1.0, 1.0, 1.0, Self::OPACITY);
+        let lit = Color::new(1.0, 1.0, 1.0, Self::OPACITY * 2.0);
+
+        // The stick's base, its thrust ring, and the knob
+        draw_circle_lines(self.stick_centre.x, self.stick_centre.y, self.stick_radius, 2.0, color);
+        draw_circle_lines(self.stick_centre.x, self.stick_centre.y, self.stick_radius * Self::THRUST_RING, 1.0, color);
+
+        let knob = self.stick_centre + input.stick * self.stick_radius;
+        draw_circle(knob.x, knob.y, self.stick_radius * 0.35, if input.thrust { lit } else { color });
+
+        for (centre, held, label) in [(self.fire_centre, input.fire, "FIRE"), (self.hyperspace_centre, input.hyperspace, "HYPER")] {
+            draw_circle_lines(centre.x, centre.y, self.button_radius, 2.0, color);
+
+            if held {
+                draw_circle(centre.x, centre.y, self.button_radius, color);
+            }
+
+            let size = measure_text(label, None, 16, 1.0);
+            draw_text(label, centre.x - size.width / 2.0, centre.y + size.offset_y / 2.0, 16.0, lit);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use macroquad::prelude::*;
+
+use crate::bullet::{Bullet, BulletPattern, BulletType};
+use crate::camera::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::collision::Collidable;
+use crate::detmath;
+use crate::entity::EntityId;
+use crate::wrap::wrap_draw;
+
+/// Turret object
+/// 
+/// Turrets are hardpoints mounted on a parent object (e.g. a saucer, or in the
+/// future bosses and stations) at a fixed offset. The parent is referenced by
+/// its entity handle, and the turret follows it around every update. Turrets
+/// swivel to track the player, but only within a firing arc centred on their
+/// facing direction, and fire using a bullet pattern. Destroying a turret only
+/// disables that hardpoint; the parent carries on without it. A turret whose
+/// parent no longer exists is destroyed along with it.
+/// 
+/// # Examples
+/// 
+/// ```
+/// # use asteroids::bullet::BulletPattern;
+/// # use asteroids::entity::EntityId;
+/// # use asteroids::turret::Turret;
+/// # use macroquad::prelude::*;
+/// let parent = EntityId::next();
+/// let mut turret = Turret::attach(parent, Vec2::new(0., 10.), std::f32::consts::FRAC_PI_2, std::f32::consts::PI, BulletPattern::Single, 0.0);
+///
+/// // A target dead ahead is fired on once the turret has charged
+/// let target = Vec2::new(0., 110.);
+/// turret.update(Vec2::ZERO, target);
+///
+/// assert!(turret.shoot(target, 1.0).is_empty());
+/// assert_eq!(turret.shoot(target, 2.5).len(), 1);
+/// ```
+#[derive(Clone)]
+pub struct Turret {
+    pub parent: EntityId,
+    pub offset: Vec2,
+    pub position: Vec2,
+    pub facing: f32,
+    pub arc: f32,
+    pub rotation: f32,
+    pub turn_speed: f32,
+    pub radius: f32,
+    pub pattern: BulletPattern,
+    pub shoot_period: f64,
+    pub shoot_recharge: f64,
+    pub shot_speed: f32,
+    pub is_alive: bool,
+}
+
+impl Turret {
+    /// Points scored for knocking out a turret.
+    pub const POINTS: u32 = 500;
+
+    /// Attach a new turret to a parent at the given offset, at a time. The
+    /// turret faces `facing` (in radians) and can swivel `arc / 2` radians
+    /// either side.
+    pub fn attach(parent: EntityId, offset: Vec2, facing: f32, arc: f32, pattern: BulletPattern, now: f64) -> Self {
+        let screen_edge: f32 = WORLD_WIDTH.min(WORLD_HEIGHT);
+
+        Self {
+            parent,
+            offset,
+            position: Vec2::ZERO,
+            facing,
+            arc,
+            rotation: facing,
+            turn_speed: 0.03,
+            radius: screen_edge * 0.01,
+            pattern,
+            shoot_period: now + 2.0,
+            shoot_recharge: 2.0,
+            shot_speed: 2.5,
+            is_alive: true,
+        }
+    }
+
+    /// Destroy turret by marking it dead. Any calls to `is_alive` will return
+    /// false after this function is called.
+    pub fn destroy(&mut self) {
+        self.is_alive = false;
+    }
+
+    /// Check if turret is still alive.
+    pub fn is_alive(&self) -> bool {
+        self.is_alive
+    }
+
+    /// Wrap angle to the range [-PI, PI].
+    fn wrap_angle(angle: f32) -> f32 {
+        let tau = std::f32::consts::TAU;
+        (angle + std::f32::consts::PI).rem_euclid(tau) - std::f32::consts::PI
+    }
+
+    /// Angle to target relative to the turret facing.
+    fn relative_angle_to(&self, target: Vec2) -> f32 {
+        let to_target = target - self.position;
+        Self::wrap_angle(detmath::atan2(to_target.y, to_target.x) - self.facing)
+    }
+
+    /// Check if target lies within the firing arc.
+    pub fn in_arc(&self, target: Vec2) -> bool {
+        self.relative_angle_to(target).abs() <= self.arc / 2.0
+    }
+
+    /// Update turret position from its parent, and swivel towards the target
+    /// without leaving the firing arc.
+    pub fn update(&mut self, parent_position: Vec2, target: Vec2) {
+        self.position = parent_position + self.offset;
+
+        let half_arc = self.arc / 2.0;
+        let desired = self.relative_angle_to(target).clamp(-half_arc, half_arc);
+        let current = Self::wrap_angle(self.rotation - self.facing);
+        let step = (desired - current).clamp(-self.turn_speed, self.turn_speed);
+
+        self.rotation = self.facing + current + step;
+    }
+
+    /// Shoot at target at a time. Turrets only fire when the target is within their arc
+    /// and the barrel is roughly lined up with it.
+    pub fn shoot(&mut self, target: Vec2, now: f64) -> Vec<Bullet> {
+        if self.shoot_period >= now || !self.in_arc(target) {
+            return Vec::new();
+        }
+
+        let aim_error = Self::wrap_angle(self.relative_angle_to(target) + self.facing - self.rotation);
+        if aim_error.abs() > 0.1 {
+            return Vec::new();
+        }
+
+        // Reset period
+        self.shoot_period = now + self.shoot_recharge;
+
+        let muzzle = self.position + detmath::rotation(self.rotation).mul_vec2(Vec2::X * self.radius * 2.0);
+        self.pattern.fire(muzzle, self.rotation, self.shot_speed, 100.0, BulletType::Enemy)
+    }
+
+    /// Draw turret as a small mount with a barrel pointing along its rotation.
+    pub fn draw(&self) {
+        self.draw_tinted(WHITE);
+    }
+
+    /// Draw turret in a colour, such as a faded white when it is out of sight.
+    pub fn draw_tinted(&self, color: Color) {
+        let barrel = detmath::rotation(self.rotation).mul_vec2(Vec2::X * self.radius * 2.0);
+
+        // Drawn out to the muzzle, which reaches past the collision circle
+        wrap_draw(self.position, self.radius * 2.0, |position| {
+            draw_circle_lines(position.x, position.y, self.radius, 2.0, color);
+
+            let muzzle = position + barrel;
+            draw_line(position.x, position.y, muzzle.x, muzzle.y, 2.0, color);
+        });
+    }
+}
+
+impl Collidable for Turret {
+    fn is_colliding(&self, other: &dyn Collidable) -> bool {
+        <dyn Collidable>::shape_intersection(self, other)
+    }
+
+    fn get_position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn get_radius(&self) -> f32 {
+        self.radius
+    }
+}
@@ -0,0 +1,162 @@
+//! First-time tips.
+//!
+//! The first time a player meets a mechanic, the game pauses to show a short
+//! tip explaining it. The mechanics already met are remembered between
+//! sessions, so each tip is only ever shown once.
+
+use crate::savedata::{self, SaveFormat};
+
+/// Mechanic
+///
+/// Something in the game worth a tip the first time it turns up.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Mechanic {
+    Saucer,
+    Turret,
+    Salvage,
+    Credits,
+    Overdrive,
+}
+
+impl Mechanic {
+    /// All mechanics with tips.
+    pub const ALL: [Mechanic; 5] = [
+        Mechanic::Saucer,
+        Mechanic::Turret,
+        Mechanic::Salvage,
+        Mechanic::Credits,
+        Mechanic::Overdrive,
+    ];
+
+    /// Name under which the mechanic is remembered once seen.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mechanic::Saucer => "saucer",
+            Mechanic::Turret => "turret",
+            Mechanic::Salvage => "salvage",
+            Mechanic::Credits => "credits",
+            Mechanic::Overdrive => "overdrive",
+        }
+    }
+
+    /// Mechanic remembered under a name.
+    pub fn from_name(name: &str) -> Option<Mechanic> {
+        Self::ALL.into_iter().find(|mechanic| mechanic.name() == name)
+    }
+
+    /// Title of the mechanic's tip.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Mechanic::Saucer => "Flying Saucer",
+            Mechanic::Turret => "Turrets",
+            Mechanic::Salvage => "Salvage Crate",
+            Mechanic::Credits => "Credits",
+            Mechanic::Overdrive => "Overdrive",
+        }
+    }
+
+    /// Lines of the mechanic's tip.
+    pub fn tip(&self) -> &'static [&'static str] {
+        match self {
+            Mechanic::Saucer => &[
+                "Saucers fire back, and small ones aim ahead of you.",
+                "Shoot them down for big points.",
+            ],
+            Mechanic::Turret => &[
+                "Turrets on a saucer track you within their firing arc.",
+                "Destroying a turret disables it without downing the saucer.",
+            ],
+            Mechanic::Salvage => &[
+                "Downed saucers sometimes leave a crate behind.",
+                "Fly into it before it drifts away to see what is inside.",
+            ],
+            Mechanic::Credits => &[
+                "Every 1000 points earns a credit.",
+                "Spend credits on upgrades from the pause menu.",
+            ],
+            Mechanic::Overdrive => &[
+                "Your overdrive meter is full.",
+                "Trigger it for a burst of rapid fire.",
+            ],
+        }
+    }
+}
+
+/// First seen registry
+///
+/// The mechanics the player has already met, saved between sessions.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::tutorial::{FirstSeen, Mechanic};
+/// let mut seen = FirstSeen::default();
+///
+/// assert!(seen.mark(Mechanic::Saucer));
+/// assert!(!seen.mark(Mechanic::Saucer));
+///
+/// let restored = FirstSeen::parse(&seen.to_text());
+/// assert!(restored.has_seen(Mechanic::Saucer));
+/// assert!(!restored.has_seen(Mechanic::Turret));
+/// ```
+#[derive(Clone, Default)]
+pub struct FirstSeen {
+    seen: Vec<Mechanic>,
+}
+
+impl FirstSeen {
+    /// Storage key under which the registry is saved.
+    const STORAGE_KEY: &'static str = "tutorial.txt";
+
+    /// Format the registry is saved in.
+    const SAVE_FORMAT: SaveFormat = SaveFormat::INITIAL;
+
+    /// Check if the player has met a mechanic.
+    pub fn has_seen(&self, mechanic: Mechanic) -> bool {
+        self.seen.contains(&mechanic)
+    }
+
+    /// Record the player meeting a mechanic. Returns true if this is the
+    /// first time.
+    pub fn mark(&mut self, mechanic: Mechanic) -> bool {
+        if self.has_seen(mechanic) {
+            return false;
+        }
+
+        self.seen.push(mechanic);
+        true
+    }
+
+    /// Forget every mechanic met, so all tips are shown again.
+    pub fn clear(&mut self) {
+        self.seen.clear();
+    }
+
+    /// Load the saved registry, starting afresh if none is saved.
+    pub fn load() -> Self {
+        savedata::load(Self::STORAGE_KEY, &Self::SAVE_FORMAT, |text| Some(Self::parse(text)))
+            .unwrap_or_default()
+    }
+
+    /// Save the registry.
+    pub fn save(&self) {
+        savedata::save(Self::STORAGE_KEY, &Self::SAVE_FORMAT, &self.to_text());
+    }
+
+    /// Parse a registry from one mechanic name per line. Unknown names are
+    /// ignored.
+    pub fn parse(text: &str) -> Self {
+        let mut registry = Self::default();
+
+        for mechanic in text.lines().filter_map(|line| Mechanic::from_name(line.trim())) {
+            registry.mark(mechanic);
+        }
+
+        registry
+    }
+
+    /// Format the registry as one mechanic name per line.
+    pub fn to_text(&self) -> String {
+        self.seen.iter().map(|mechanic| format!("{}\n", mechanic.name())).collect()
+    }
+}
@@ -0,0 +1,110 @@
+use crate::coop::{PLAYER_ONE, PLAYER_TWO};
+
+/// Versus match
+///
+/// A dogfight between two players, played as a best-of-N series of rounds. A
+/// round ends when a ship is destroyed, whether shot down by the other player,
+/// wrecked on an asteroid or lost in hyperspace; once the wreckage settles the
+/// surviving player takes the round. If both ships are lost before then, the
+/// round is drawn and replayed. The first player to win a majority of the
+/// rounds wins the match.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::versus::VersusMatch;
+/// let mut versus = VersusMatch::new(3);
+///
+/// versus.ship_destroyed(1, 10.0);
+/// assert!(!versus.is_round_over(10.5));
+/// assert!(versus.is_round_over(12.0));
+/// assert_eq!(versus.finish_round(), Some(0));
+///
+/// versus.ship_destroyed(0, 20.0);
+/// versus.ship_destroyed(1, 20.5);
+/// assert_eq!(versus.finish_round(), None);
+/// assert_eq!(versus.winner(), None);
+///
+/// versus.ship_destroyed(1, 30.0);
+/// versus.finish_round();
+/// assert_eq!(versus.winner(), Some(0));
+/// ```
+pub struct VersusMatch {
+    pub best_of: u32,
+    pub wins: [u32; 2],
+    pub round: u32,
+    destroyed: [bool; 2],
+    round_end: Option<f64>,
+}
+
+impl VersusMatch {
+    /// Number of rounds a match is played over, unless chosen otherwise.
+    pub const DEFAULT_BEST_OF: u32 = 5;
+
+    /// Time in seconds between a ship being destroyed and the round ending.
+    pub const ROUND_END_DELAY: f64 = 2.0;
+
+    /// Start a match played over a number of rounds.
+    pub fn new(best_of: u32) -> Self {
+        Self {
+            best_of: best_of.max(1),
+            wins: [0, 0],
+            round: 1,
+            destroyed: [false, false],
+            round_end: None,
+        }
+    }
+
+    /// Rounds a player must win to win the match.
+    pub fn rounds_to_win(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+
+    /// Record a player's ship being destroyed at a given time. The round ends
+    /// a short while after the first ship is lost.
+    pub fn ship_destroyed(&mut self, player: usize, time: f64) {
+        self.destroyed[player] = true;
+        self.round_end.get_or_insert(time);
+    }
+
+    /// Check if the round is ending, with a ship already destroyed.
+    pub fn is_round_ending(&self) -> bool {
+        self.round_end.is_some()
+    }
+
+    /// Check if the round is over at a given time.
+    pub fn is_round_over(&self, time: f64) -> bool {
+        self.round_end.is_some_and(|end| time - end >= Self::ROUND_END_DELAY)
+    }
+
+    /// Player taking the round as it stands: the one whose ship survived, or
+    /// `None` if both or neither ship was destroyed.
+    pub fn round_winner(&self) -> Option<usize> {
+        match self.destroyed {
+            [false, true] => Some(PLAYER_ONE),
+            [true, false] => Some(PLAYER_TWO),
+            _ => None,
+        }
+    }
+
+    /// Finish the round, awarding it to the surviving player, and move on to
+    /// the next one. Returns the player who took the round, if any.
+    pub fn finish_round(&mut self) -> Option<usize> {
+        let winner = self.round_winner();
+
+        if let Some(winner) = winner {
+            self.wins[winner] += 1;
+        }
+
+        self.round += 1;
+        self.destroyed = [false, false];
+        self.round_end = None;
+
+        winner
+    }
+
+    /// Player who has won the match, if either has yet.
+    pub fn winner(&self) -> Option<usize> {
+        self.wins.iter().position(|wins| *wins >= self.rounds_to_win())
+    }
+}
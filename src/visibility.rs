@@ -0,0 +1,126 @@
+//! Low visibility.
+//!
+//! With the Low Visibility option on, the players only see clearly what is
+//! near their ships; everything further away is drawn as a faint ghost. A
+//! radar pulse sweeps out from the ship on demand, briefly lighting up
+//! whatever it passes. Distances are measured the short way around the
+//! wrapping field, so a rock about to come across the edge is seen in time.
+
+use macroquad::prelude::*;
+
+use crate::camera::{WORLD_HEIGHT, WORLD_WIDTH};
+use crate::collision::wrapped_delta;
+
+/// Visibility
+///
+/// Works out how brightly things are drawn, from how close they are to the
+/// players' ships and when the last radar pulse reached them.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::visibility::Visibility;
+/// assert_eq!(Visibility::sight(0.0), 1.0);
+/// assert_eq!(Visibility::sight(Visibility::SIGHT_RADIUS), 1.0);
+/// assert!(Visibility::sight(Visibility::SIGHT_RADIUS + 40.0) < 1.0);
+/// assert_eq!(Visibility::sight(1000.0), 0.0);
+///
+/// // A pulse lights things up once it reaches them, then fades
+/// assert_eq!(Visibility::echo(500.0, 0.5), 0.0);
+/// assert_eq!(Visibility::echo(500.0, 1.0), 1.0);
+/// assert!(Visibility::echo(500.0, 2.0) < 1.0);
+/// ```
+#[derive(Default)]
+pub struct Visibility {
+    pulse: Option<(Vec2, f64)>,
+}
+
+impl Visibility {
+    /// Distance from a ship within which everything is seen clearly.
+    pub const SIGHT_RADIUS: f32 = 160.0;
+
+    /// Distance beyond the sight radius over which things fade to ghosts.
+    const FALLOFF: f32 = 100.0;
+
+    /// Brightness of things out of sight.
+    pub const GHOST: f32 = 0.1;
+
+    /// Speed of a radar pulse, in pixels per second.
+    pub const PULSE_SPEED: f32 = 500.0;
+
+    /// Time in seconds things stay lit up after a pulse reaches them.
+    const ECHO_DURATION: f64 = 1.5;
+
+    /// Time in seconds before the radar can pulse again.
+    pub const PULSE_COOLDOWN: f64 = 4.0;
+
+    /// Send out a radar pulse from a position, unless the radar is still
+    /// recharging. Returns true if the pulse went out.
+    pub fn pulse(&mut self, origin: Vec2, now: f64) -> bool {
+        if !self.is_pulse_ready(now) {
+            return false;
+        }
+
+        self.pulse = Some((origin, now));
+        true
+    }
+
+    /// Check if the radar has recharged since its last pulse.
+    pub fn is_pulse_ready(&self, now: f64) -> bool {
+        self.pulse.is_none_or(|(_, time)| now - time >= Self::PULSE_COOLDOWN)
+    }
+
+    /// Forget the last pulse, for example when a new game starts.
+    pub fn clear(&mut self) {
+        self.pulse = None;
+    }
+
+    /// Brightness from 0 to 1 of something seen at a distance from a ship:
+    /// full within the sight radius, falling off smoothly beyond it.
+    pub fn sight(distance: f32) -> f32 {
+        let t = ((distance - Self::SIGHT_RADIUS) / Self::FALLOFF).clamp(0.0, 1.0);
+
+        1.0 - t * t * (3.0 - 2.0 * t)
+    }
+
+    /// Brightness from 0 to 1 of something at a distance from where a pulse
+    /// went out, a time in seconds after it did.
+    pub fn echo(distance: f32, elapsed: f64) -> f32 {
+        let since_reached = elapsed - (distance / Self::PULSE_SPEED) as f64;
+
+        if (0.0..Self::ECHO_DURATION).contains(&since_reached) {
+            (1.0 - since_reached / Self::ECHO_DURATION) as f32
+        } else {
+            0.0
+        }
+    }
+
+    /// Brightness from [`GHOST`](Self::GHOST) to 1 of something at a position,
+    /// as seen from the players' ships.
+    pub fn brightness(&self, viewers: impl Iterator<Item = Vec2>, position: Vec2, now: f64) -> f32 {
+        let seen = viewers
+            .map(|viewer| Self::sight(wrapped_delta(viewer, position).length()))
+            .fold(0.0, f32::max);
+
+        let echo = self.pulse.map_or(0.0, |(origin, time)| {
+            Self::echo(wrapped_delta(origin, position).length(), now - time)
+        });
+
+        Self::GHOST + (1.0 - Self::GHOST) * seen.max(echo)
+    }
+
+    /// Draw the last pulse's ring as it sweeps across the field.
+    pub fn draw(&self, now: f64) {
+        let Some((origin, time)) = self.pulse else {
+            return;
+        };
+
+        let radius = Self::PULSE_SPEED * (now - time) as f32;
+        let reach = WORLD_WIDTH.hypot(WORLD_HEIGHT) / 2.0;
+
+        if radius < reach {
+            let alpha = 0.5 * (1.0 - radius / reach);
+            draw_circle_lines(origin.x, origin.y, radius, 1.5, Color::new(0.6, 1.0, 0.6, alpha));
+        }
+    }
+}
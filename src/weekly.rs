@@ -0,0 +1,202 @@
+//! Weekly challenge.
+//!
+//! Each week everyone plays the same seeded game with the same pair of
+//! mutators, and competes on a leaderboard of its own. The week's challenge is
+//! published in a small TOML manifest the game fetches at startup, served
+//! alongside the game (`weekly.toml`), for example:
+//!
+//! ```toml
+//! [[week]]
+//! week = 2912
+//! seed = 1979
+//! mutators = ["newtonian", "double_saucers"]
+//! ```
+//!
+//! Weeks missing from the manifest, or every week when it cannot be fetched,
+//! fall back to a schedule worked out from the week number alone, so players
+//! offline still get the same challenge as each other.
+
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+use serde::Deserialize;
+
+/// Mutator
+///
+/// A twist on the rules of the classic game.
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mutator {
+    /// The ship keeps its momentum instead of slowing to a stop.
+    Newtonian,
+    /// Saucers arrive in pairs.
+    DoubleSaucers,
+    /// Waves are made of larger, slower asteroids.
+    GiantRocks,
+    /// The game is played with a single ship.
+    OneLife,
+}
+
+impl Mutator {
+    /// All mutators, in the order the schedule picks from.
+    pub const ALL: [Mutator; 4] = [
+        Mutator::Newtonian,
+        Mutator::DoubleSaucers,
+        Mutator::GiantRocks,
+        Mutator::OneLife,
+    ];
+
+    /// Display name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mutator::Newtonian => "Newtonian Physics",
+            Mutator::DoubleSaucers => "Double Saucers",
+            Mutator::GiantRocks => "Giant Rocks",
+            Mutator::OneLife => "One Life",
+        }
+    }
+}
+
+/// Weekly challenge
+///
+/// The seed and mutators of one week's challenge.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::weekly::WeeklyChallenge;
+/// let challenge = WeeklyChallenge::scheduled(2912);
+///
+/// assert_eq!(challenge.mutators.len(), 2);
+/// assert_ne!(challenge.mutators[0], challenge.mutators[1]);
+/// assert_eq!(challenge.seed, WeeklyChallenge::scheduled(2912).seed);
+/// assert_eq!(challenge.storage_key(), "weekly-2912.txt");
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct WeeklyChallenge {
+    pub week: u32,
+    pub seed: u64,
+    pub mutators: Vec<Mutator>,
+}
+
+impl WeeklyChallenge {
+    /// Number of mutators in a scheduled challenge.
+    pub const SCHEDULED_MUTATORS: usize = 2;
+
+    /// Seconds in a week.
+    const WEEK: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+
+    /// The Unix epoch fell on a Thursday; weeks are counted from Mondays.
+    const EPOCH_OFFSET: f64 = 3.0 * 24.0 * 60.0 * 60.0;
+
+    /// Week number, counted from the week of the Unix epoch, of a time in
+    /// seconds since the epoch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::weekly::WeeklyChallenge;
+    /// // Sunday 4 January 1970 is still in the first week; Monday is not
+    /// assert_eq!(WeeklyChallenge::week_of(3.0 * 86400.0 + 86399.0), 0);
+    /// assert_eq!(WeeklyChallenge::week_of(4.0 * 86400.0), 1);
+    /// ```
+    pub fn week_of(time: f64) -> u32 {
+        ((time + Self::EPOCH_OFFSET) / Self::WEEK).max(0.0) as u32
+    }
+
+    /// Week number of the current week.
+    pub fn current_week() -> u32 {
+        Self::week_of(miniquad::date::now())
+    }
+
+    /// Challenge scheduled for a week when the manifest has none: a seed and
+    /// distinct mutators derived from the week number alone.
+    pub fn scheduled(week: u32) -> Self {
+        let rng = RandGenerator::new();
+        rng.srand(week as u64);
+
+        let seed = (rng.rand() as u64) << 32 | rng.rand() as u64;
+
+        let mut pool = Mutator::ALL.to_vec();
+        let mut mutators = Vec::new();
+
+        while mutators.len() < Self::SCHEDULED_MUTATORS && !pool.is_empty() {
+            mutators.push(pool.remove(rng.gen_range(0, pool.len())));
+        }
+
+        Self {
+            week,
+            seed,
+            mutators,
+        }
+    }
+
+    /// Challenge for a week: the manifest's entry for it if there is one,
+    /// otherwise the scheduled one.
+    pub fn for_week(week: u32, manifest: Option<&WeeklyManifest>) -> Self {
+        manifest
+            .and_then(|manifest| manifest.weeks.iter().find(|challenge| challenge.week == week))
+            .cloned()
+            .unwrap_or_else(|| Self::scheduled(week))
+    }
+
+    /// Check if a mutator is in play.
+    pub fn has(&self, mutator: Mutator) -> bool {
+        self.mutators.contains(&mutator)
+    }
+
+    /// Mutators joined for display, such as "Newtonian Physics + One Life".
+    pub fn description(&self) -> String {
+        let names: Vec<&str> = self.mutators.iter().map(Mutator::name).collect();
+        names.join(" + ")
+    }
+
+    /// Storage key of the week's leaderboard.
+    pub fn storage_key(&self) -> String {
+        format!("weekly-{}.txt", self.week)
+    }
+}
+
+/// Weekly manifest
+///
+/// The published list of weekly challenges.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::weekly::{Mutator, WeeklyChallenge, WeeklyManifest};
+/// let manifest = WeeklyManifest::parse(r#"
+///     [[week]]
+///     week = 2912
+///     seed = 1979
+///     mutators = ["newtonian", "double_saucers"]
+/// "#).unwrap();
+///
+/// let challenge = WeeklyChallenge::for_week(2912, Some(&manifest));
+/// assert_eq!(challenge.seed, 1979);
+/// assert!(challenge.has(Mutator::Newtonian));
+///
+/// let fallback = WeeklyChallenge::for_week(2913, Some(&manifest));
+/// assert_eq!(fallback.seed, WeeklyChallenge::scheduled(2913).seed);
+/// ```
+#[derive(Default, Deserialize)]
+pub struct WeeklyManifest {
+    #[serde(default, rename = "week")]
+    pub weeks: Vec<WeeklyChallenge>,
+}
+
+impl WeeklyManifest {
+    /// Path the manifest is fetched from, relative to the game.
+    pub const PATH: &'static str = "weekly.toml";
+
+    /// Parse a manifest from a TOML document.
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// Fetch the manifest, over HTTP on the web or from beside the game on
+    /// native builds. Returns `None` if it cannot be fetched or parsed.
+    pub async fn fetch() -> Option<Self> {
+        let text = load_string(Self::PATH).await.ok()?;
+        Self::parse(&text).ok()
+    }
+}
@@ -0,0 +1,5617 @@
+use std::cell::RefCell;
+
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+use crate::achievements::{AchievementEvent, AchievementToasts, Achievements};
+use crate::alloc::AllocationBudget;
+use crate::ai::{AutoPilot, Target};
+use crate::announcer::{Announcer, CommandSpeech};
+use crate::asteroid::{Asteroid, AsteroidKind, AsteroidSize};
+use crate::audio::{Audio, SoundDirector, SoundEffect};
+use crate::broadcast::{BroadcastSnapshot, Broadcaster};
+use crate::bugreport::{self, BugReport, DebugLog};
+use crate::bullet::{Bullet, BulletPattern, WeaponKind};
+use crate::camera::{self, layer_camera, ScreenShake, Shake, WORLD_HEIGHT, WORLD_SIZE, WORLD_WIDTH};
+use crate::carryover::CarryOver;
+use crate::challenge::{self, ChallengeLadder, ChallengeStats};
+use crate::chatter::RadioChatter;
+use crate::clock::GameClock;
+use crate::collision::{resolve_elastic, wrapped_delta, Body, Collidable, Contact};
+use crate::coop::{Wingman, PLAYER_ONE, PLAYER_TWO};
+use crate::decoy::Decoy;
+use crate::deepfield::DeepField;
+use crate::detmath;
+use crate::diagnostics::LatencyProbe;
+use crate::difficulty::Difficulty;
+use crate::director::SaucerDirector;
+use crate::events::{Cause, EventBus, GameEvent};
+use crate::floatingtext::FloatingText;
+use crate::gamepad::{Gamepad, GamepadState, Rumble};
+use crate::goldseam::GoldSeam;
+use crate::graphics;
+use crate::gravitywell::GravityWell;
+use crate::highscore::{HighScoreTable, InitialsEntry};
+use crate::hooks::{self, EventHooks, HookAction, HookContext};
+use crate::hunter::Hunter;
+use crate::hud::{ComboAnchor, Hud, HudElement, HudLayout, HudPreset, HudStatus};
+use crate::input::{self, Action, Bindings, ControlScheme};
+use crate::lifetime::LifetimeStats;
+use crate::lightning::ChainLightning;
+use crate::linebatch::LineBatch;
+use crate::lurker::LurkerWatch;
+use crate::menu::{self, Menu, MenuEvent, MenuItem, MenuLayout};
+use crate::options::LaunchOptions;
+use crate::overdrive::Overdrive;
+use crate::overlay::Overlay;
+use crate::particle::Particle;
+use crate::plugin::{CustomEntity, EntityRegistry, GamePlugin};
+use crate::profile::Profile;
+use crate::salvage::{LootTable, Salvage, SalvageCrate};
+use crate::replay::{Circle, Recording, ReplayEvent, Timeline};
+use crate::replaycode::{InputFrame, ReplayCode, Rules};
+use crate::rewind::SnapshotRing;
+use crate::saucer::{Saucer, SaucerSize};
+use crate::scenario::Scenario;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::scripting::{ScriptAction, ScriptContext, Scripts};
+use crate::settings::Settings;
+use crate::ship::{HyperspaceJump, HyperspaceOutcome, Ship};
+use crate::score::{format_score, multiply_gain};
+use crate::shockwave::Shockwave;
+use crate::shop::Upgrade;
+use crate::storage;
+use crate::tally::{WaveStats, WaveTally};
+use crate::tas::{Movie, TasSession};
+use crate::textcache::TextMeasureCache;
+use crate::touch::{TouchControls, TouchInput};
+use crate::turret::Turret;
+use crate::tutorial::{FirstSeen, Mechanic};
+use crate::versus::VersusMatch;
+use crate::visibility::Visibility;
+use crate::weekly::{Mutator, WeeklyChallenge};
+
+/// Game state
+/// 
+/// The screen the game is on. Attract mode is the initial state, and the game
+/// returns to it when a game, challenge or match is over.
+/// 
+#[derive(PartialEq)]
+pub enum GameState {
+    /// The title screen, with the game playing itself behind it.
+    AttractMode,
+    /// The challenge ladder, to pick a challenge from.
+    Ladder,
+    /// The lifetime stats.
+    Stats,
+    /// A game under way.
+    Playing,
+    /// Play stopped for a tip on a mechanic seen for the first time.
+    Tip,
+    /// The tally of bonus points for the wave just cleared.
+    WaveTally,
+    /// The pause menu.
+    Paused,
+    /// The upgrade shop, opened from the pause menu.
+    Shop,
+    /// The controls screen, for rebinding keys, opened from the pause menu.
+    Controls,
+    /// The options screen, opened from the pause menu or the title screen.
+    Options,
+    /// A challenge cleared or failed, with the stars earned.
+    ChallengeComplete,
+    /// The winner of a versus match.
+    VersusResults,
+    /// Every life lost.
+    GameOver,
+    /// A final score making the high score table, waiting for initials.
+    EnterInitials,
+    /// The hidden latency diagnostics.
+    Diagnostics,
+    /// The hidden replay screen, playing back the last game.
+    Replay,
+    /// The replay code box, to copy the last classic game or watch a shared one.
+    ReplayCode,
+}
+
+impl GameState {
+    /// Name of the state as reported to external tools.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameState::AttractMode => "attract",
+            GameState::Ladder => "ladder",
+            GameState::Stats => "stats",
+            GameState::Playing => "playing",
+            GameState::Tip => "tip",
+            GameState::WaveTally => "wave_tally",
+            GameState::Paused => "paused",
+            GameState::Shop => "shop",
+            GameState::Controls => "controls",
+            GameState::Options => "options",
+            GameState::ChallengeComplete => "challenge_complete",
+            GameState::VersusResults => "versus_results",
+            GameState::GameOver => "game_over",
+            GameState::EnterInitials => "enter_initials",
+            GameState::Diagnostics => "diagnostics",
+            GameState::Replay => "replay",
+            GameState::ReplayCode => "replay_code",
+        }
+    }
+}
+
+/// Game mode
+/// 
+/// The kind of game being played: the classic endless waves, either alone or
+/// with a wingman in co-op, the week's seeded challenge with its mutators, a
+/// challenge from the ladder, or a versus dogfight between two players among a
+/// light asteroid field.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameMode {
+    Classic,
+    Coop,
+    Weekly,
+    Challenge,
+    Versus,
+}
+
+/// Game input
+/// 
+/// The game input is used to control the ship. The ship can be steered left or
+/// right, either at full rate or, from an analog stick, at a fraction of it, and
+/// can be accelerated. Ships fitted with the thruster upgrades can
+/// also reverse and strafe sideways. The ship can also shoot bullets.
+/// 
+pub enum GameInput {
+    Left,
+    Right,
+    Steer(f32),
+    Thruster,
+    ReverseThruster,
+    StrafeLeft,
+    StrafeRight,
+    Cannon,
+    None
+}
+
+/// Pause menu option
+/// 
+/// The options offered by the pause menu, in the order they are listed.
+#[derive(Clone, Copy, PartialEq)]
+pub enum PauseOption {
+    Resume,
+    Shop,
+    Controls,
+    Options,
+    Restart,
+    Quit,
+}
+
+impl PauseOption {
+    /// All options, in menu order.
+    pub const ALL: [PauseOption; 6] = [
+        PauseOption::Resume, PauseOption::Shop, PauseOption::Controls, PauseOption::Options, PauseOption::Restart, PauseOption::Quit,
+    ];
+
+    /// Label shown in the menu.
+    pub fn label(&self) -> &'static str {
+        match self {
+            PauseOption::Resume => "Resume",
+            PauseOption::Shop => "Upgrades",
+            PauseOption::Controls => "Controls",
+            PauseOption::Options => "Options",
+            PauseOption::Restart => "Restart",
+            PauseOption::Quit => "Quit to Title",
+        }
+    }
+}
+
+/// Setting
+/// 
+/// The settings offered by the options screen, in the order they are listed:
+/// sound, screen shake and controls, difficulty and the rules, the tips already seen, then a
+/// HUD preset followed by each HUD element on its own.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Setting {
+    Volume,
+    ScreenShake,
+    ControlScheme,
+    Difficulty,
+    StartingLives,
+    BulletLimit,
+    ShieldDuration,
+    ShieldRecharge,
+    LowVisibility,
+    BouncingAsteroids,
+    ArcadeScoring,
+    Announcements,
+    DeepField,
+    Antialiasing,
+    LineWidth,
+    TouchLayout,
+    TipsSeen,
+    HudPreset,
+    Hud(HudElement),
+    ComboAnchor,
+    ExportProfile,
+    ImportProfile,
+}
+
+impl Setting {
+    /// All settings, in menu order.
+    pub const ALL: [Setting; 31] = [
+        Setting::Volume,
+        Setting::ScreenShake,
+        Setting::ControlScheme,
+        Setting::Difficulty,
+        Setting::StartingLives,
+        Setting::BulletLimit,
+        Setting::ShieldDuration,
+        Setting::ShieldRecharge,
+        Setting::LowVisibility,
+        Setting::BouncingAsteroids,
+        Setting::ArcadeScoring,
+        Setting::Announcements,
+        Setting::DeepField,
+        Setting::Antialiasing,
+        Setting::LineWidth,
+        Setting::TouchLayout,
+        Setting::TipsSeen,
+        Setting::HudPreset,
+        Setting::Hud(HudElement::Score),
+        Setting::Hud(HudElement::Lives),
+        Setting::Hud(HudElement::Wave),
+        Setting::Hud(HudElement::Decoys),
+        Setting::Hud(HudElement::Credits),
+        Setting::Hud(HudElement::Overdrive),
+        Setting::Hud(HudElement::Weapon),
+        Setting::Hud(HudElement::Combo),
+        Setting::Hud(HudElement::Bombs),
+        Setting::Hud(HudElement::Shield),
+        Setting::ComboAnchor,
+        Setting::ExportProfile,
+        Setting::ImportProfile,
+    ];
+
+    /// Label shown in the menu.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Setting::Volume => "Volume",
+            Setting::ScreenShake => "Screen Shake",
+            Setting::ControlScheme => "Controls",
+            Setting::Difficulty => "Difficulty",
+            Setting::StartingLives => "Starting Lives",
+            Setting::BulletLimit => "Classic Bullet Limit",
+            Setting::ShieldDuration => "Shield Duration",
+            Setting::ShieldRecharge => "Shield Recharge",
+            Setting::LowVisibility => "Low Visibility",
+            Setting::BouncingAsteroids => "Bouncing Asteroids",
+            Setting::ArcadeScoring => "Arcade Scoring",
+            Setting::Announcements => "Announcements",
+            Setting::DeepField => "Deep Field",
+            Setting::Antialiasing => "Anti-aliasing",
+            Setting::LineWidth => "Line Width",
+            Setting::TouchLayout => "Touch Controls",
+            Setting::TipsSeen => "Tips Seen",
+            Setting::HudPreset => "HUD",
+            Setting::Hud(element) => element.label(),
+            Setting::ComboAnchor => "Combo Ring At",
+            Setting::ExportProfile => "Export Profile",
+            Setting::ImportProfile => "Import Profile",
+        }
+    }
+}
+
+/// Challenge run
+/// 
+/// The challenge being played from the ladder and how the run is going. Once
+/// the run is over, its result holds the stars earned and whether they are a
+/// new best.
+struct ChallengeRun {
+    index: usize,
+    stats: ChallengeStats,
+    result: Option<(u8, bool)>,
+}
+
+/// Field snapshot
+///
+/// What a rewind restores: the objects in play, and the players' ships,
+/// lives and scores.
+#[derive(Clone)]
+struct FieldSnapshot {
+    asteroids: Vec<Asteroid>,
+    saucers: Vec<Saucer>,
+    turrets: Vec<Turret>,
+    hunters: Vec<Hunter>,
+    player_bullets: Vec<Bullet>,
+    enemy_bullets: Vec<Bullet>,
+    decoys: Vec<Decoy>,
+    salvage: Vec<SalvageCrate>,
+    ship: Ship,
+    wingman: Option<Wingman>,
+    player_score: u32,
+    player_lives: u32,
+    wave_number: u32,
+}
+
+/// Collision pair
+///
+/// A pair of objects found touching, by their indices, for collisions to
+/// resolve once every pair has been found.
+#[derive(Clone, Copy)]
+enum CollisionPair {
+    ShipAsteroid { player: usize, asteroid: usize },
+    SaucerAsteroid { saucer: usize, asteroid: usize },
+    BulletAsteroid { bullet: BulletRef, asteroid: usize },
+    ShipSaucer { player: usize, saucer: usize },
+    BulletSaucer { bullet: usize, saucer: usize },
+    BulletShip { bullet: BulletRef, player: usize },
+    ShipHunter { player: usize, hunter: usize },
+    AsteroidHunter { asteroid: usize, hunter: usize },
+    BulletHunter { bullet: usize, hunter: usize },
+    BulletTurret { bullet: usize, turret: usize },
+    ShockwaveAsteroid { asteroid: usize },
+    ShockwaveSaucer { saucer: usize },
+    ShipEntity { player: usize, entity: usize },
+    BulletEntity { bullet: usize, entity: usize },
+}
+
+/// Bullet reference
+///
+/// A player's or an enemy bullet, by its index.
+#[derive(Clone, Copy)]
+enum BulletRef {
+    Player(usize),
+    Enemy(usize),
+}
+
+/// Strike
+///
+/// Where an asteroid was struck and the momentum it took, which the
+/// fragments it splits into carry away.
+#[derive(Clone, Copy)]
+struct Strike {
+    impulse: Vec2,
+    point: Vec2,
+}
+
+/// Game world object
+/// 
+/// The game world contains all game objects. It is responsible for updating and
+/// drawing all objects.
+/// 
+/// Everything random in a game is drawn from two generators seeded when the
+/// game starts: one for gameplay (asteroid shapes and headings, saucer moves,
+/// hyperspace) and one for cosmetic particles, so effects never disturb the
+/// game itself. Two games started from the same seed play out the same way
+/// given the same inputs.
+pub struct GameWorld {
+    pub ship: Ship,
+    pub wingman: Option<Wingman>,
+    pub versus: Option<VersusMatch>,
+    pub asteroids: Vec<Asteroid>,
+    pub saucers:Vec<Saucer>,
+    pub turrets: Vec<Turret>,
+    pub hunters: Vec<Hunter>,
+    pub entities: Vec<Box<dyn CustomEntity>>,
+    entity_registry: EntityRegistry,
+    plugins: Vec<Box<dyn GamePlugin>>,
+    hunter_due: Option<f64>,
+    pub bolts: Vec<ChainLightning>,
+    pub decoys: Vec<Decoy>,
+    pub shockwaves: Vec<Shockwave>,
+    pub gravity_well: Option<GravityWell>,
+    gravity_well_due: Option<f64>,
+    pub salvage: Vec<SalvageCrate>,
+    pub loot_table: LootTable,
+    salvage_notice: Option<(f64, Salvage)>,
+    gold_seam: Option<GoldSeam>,
+    pub hooks: EventHooks,
+    #[cfg(not(target_arch = "wasm32"))]
+    pub scripts: Scripts,
+    pub events: EventBus,
+    hook_message: Option<(f64, String)>,
+    announcer: Announcer,
+    debug_log: DebugLog,
+    bug_report_notice: Option<(f64, String)>,
+    chronos: u32,
+    rewound: bool,
+    snapshots: SnapshotRing<FieldSnapshot>,
+    pub deep_field: DeepField,
+    pub particles: Vec<Particle>,
+    pub floating_texts: Vec<FloatingText>,
+    pub chatter: Vec<RadioChatter>,
+    pub enemy_bullets: Vec<Bullet>,
+    pub player_bullets: Vec<Bullet>,    
+    pub player_lives: u32,
+    pub player_score: u32,
+    pub player_credits: u32,
+    pub overdrive: Overdrive,
+    pub wave_number: u32,
+    pub kill_screen_time: f64,
+    pub carry_over: CarryOver,
+    wave_summary: Option<(f64, String)>,
+    pub wave_stats: WaveStats,
+    wave_tally: Option<(f64, WaveTally)>,
+    pub respawn_clearance: f32,
+    pub high_scores: HighScoreTable,
+    pub weekly: WeeklyChallenge,
+    weekly_scores: HighScoreTable,
+    weekly_text: String,
+    pub ladder: ChallengeLadder,
+    pub lifetime: LifetimeStats,
+    asteroids_destroyed: u32,
+    pub achievements: Achievements,
+    achievement_toasts: AchievementToasts,
+    hyperspace_used: bool,
+    ladder_menu: Menu,
+    challenge: Option<ChallengeRun>,
+    initials_entry: InitialsEntry,
+    pause_menu: Menu,
+    shop_menu: Menu,
+    controls_menu: Menu,
+    options_menu: Menu,
+    options_in_game: bool,
+    profile_status: Option<String>,
+    asset_error: Option<String>,
+    first_seen: FirstSeen,
+    tip: Option<Mechanic>,
+    attract_start: f64,
+    font: Option<Font>,
+    hud: Hud,
+    text_widths: TextMeasureCache,
+    lines: RefCell<LineBatch>,
+    audio: Audio,
+    sound_director: SoundDirector,
+    saucer_director: SaucerDirector,
+    lurker_watch: LurkerWatch,
+    telemetry_visible: bool,
+    fullscreen: bool,
+    gamepad: Gamepad,
+    shake: ScreenShake,
+    visibility: Visibility,
+    touch: bool,
+    touch_input: TouchInput,
+    pub hud_visible: bool,
+    pub hud_layout: HudLayout,
+    pub settings: Settings,
+    sample_count: i32,
+    pub bindings: Bindings,
+    overlay: Overlay,
+    pub broadcaster: Broadcaster,
+    pub game_state: GameState,
+    pub game_mode: GameMode,
+    pub autopilot: AutoPilot,
+    latency_probe: LatencyProbe,
+    recording: Recording,
+    timeline: Timeline,
+    input_log: Option<ReplayCode>,
+    shared_game: Option<ReplayCode>,
+    watching: Option<(ReplayCode, usize)>,
+    rules: Rules,
+    tas: Option<TasSession>,
+    code_text: String,
+    code_status: Option<String>,
+    rng: RandGenerator,
+    effects_rng: RandGenerator,
+    fixed_seed: Option<u64>,
+    pub seed: u64,
+    pub fixed_step: Option<f32>,
+    pub clock: GameClock,
+    headless: bool,
+    persistent: bool,
+}
+
+impl GameWorld {
+    /// Create a new instance of the GameWorld object.
+    // fn new() -> Self {
+    pub fn new(font: Font, audio: Audio, options: &LaunchOptions) -> Self {
+        Self::create(Some(font), audio, options, false)
+    }
+
+    /// Create a world that runs without a window, for tests, training and
+    /// tools. It has no font or sound, is never drawn, and moves on only as
+    /// it is stepped. Tips and wave tallies, which wait on the player, are
+    /// skipped. It starts from the default settings and saves nothing, so it
+    /// plays the same on any machine and leaves the player's records alone.
+    pub fn headless(options: &LaunchOptions) -> Self {
+        Self::create(None, Audio::silent(), options, true)
+    }
+
+    /// Create a world with a font to draw text in, if it will be drawn. A
+    /// headless world neither loads nor saves anything.
+    fn create(font: Option<Font>, audio: Audio, options: &LaunchOptions, headless: bool) -> Self {
+        let mut gamepad = Gamepad::new();
+
+        if let Some(intensity) = options.rumble_intensity {
+            gamepad.rumble_intensity = intensity;
+        }
+
+        // The window was made with the saved anti-aliasing setting
+        let persistent = !headless;
+        let settings = Self::saved(persistent, Settings::load);
+        let sample_count = settings.antialiasing.sample_count();
+
+        // Attract mode is seeded from the clock, or the fixed seed if given
+        let seed = options.seed.unwrap_or_else(Self::clock_seed);
+        let rng = RandGenerator::new();
+        let effects_rng = RandGenerator::new();
+
+        rng.srand(seed);
+        effects_rng.srand(seed ^ Self::EFFECTS_STREAM);
+
+        let mut world = Self {
+            ship: Ship::spawn_new(),
+            wingman: None,
+            versus: None,
+            asteroids: Vec::new(),
+            saucers: Vec::new(),
+            turrets: Vec::new(),
+            hunters: Vec::new(),
+            entities: Vec::new(),
+            entity_registry: EntityRegistry::default(),
+            plugins: Vec::new(),
+            hunter_due: None,
+            bolts: Vec::new(),
+            decoys: Vec::new(),
+            shockwaves: Vec::new(),
+            gravity_well: None,
+            gravity_well_due: None,
+            salvage: Vec::new(),
+            loot_table: LootTable::default(),
+            salvage_notice: None,
+            gold_seam: None,
+            hooks: EventHooks::easter_eggs(if persistent { hooks::first_played() } else { miniquad::date::now() }),
+            #[cfg(not(target_arch = "wasm32"))]
+            scripts: Scripts::default(),
+            events: EventBus::default(),
+            hook_message: None,
+            debug_log: DebugLog::default(),
+            bug_report_notice: None,
+            announcer: match &options.speech_program {
+                Some(program) => Announcer::with_speech(Box::new(CommandSpeech::new(program))),
+                None => Announcer::default(),
+            },
+            chronos: 0,
+            rewound: false,
+            snapshots: SnapshotRing::new(Self::SNAPSHOT_INTERVAL, Self::REWIND_SECONDS),
+            deep_field: DeepField::generate(seed),
+            particles: Vec::new(),
+            floating_texts: Vec::new(),
+            chatter: Vec::new(),
+            enemy_bullets: Vec::new(),
+            player_bullets: Vec::new(),
+            player_lives: 0,
+            player_score: 0,
+            player_credits: 0,
+            overdrive: Overdrive::new(),
+            wave_number: 0,
+            kill_screen_time: 0.0,
+            carry_over: CarryOver::default(),
+            wave_summary: None,
+            wave_stats: WaveStats::default(),
+            wave_tally: None,
+            respawn_clearance: WORLD_WIDTH.min(WORLD_HEIGHT) * 0.15,
+            high_scores: Self::saved(persistent, HighScoreTable::load),
+            weekly: WeeklyChallenge::scheduled(0),
+            weekly_scores: HighScoreTable::default(),
+            weekly_text: String::new(),
+            ladder: Self::saved(persistent, ChallengeLadder::load),
+            lifetime: Self::saved(persistent, LifetimeStats::load),
+            asteroids_destroyed: 0,
+            achievements: Self::saved(persistent, Achievements::load),
+            achievement_toasts: AchievementToasts::default(),
+            hyperspace_used: false,
+            ladder_menu: Menu::new(Self::LADDER_MENU),
+            challenge: None,
+            initials_entry: InitialsEntry::new(),
+            pause_menu: Menu::new(Self::PAUSE_MENU),
+            shop_menu: Menu::new(Self::SHOP_MENU),
+            controls_menu: Menu::new(Self::CONTROLS_MENU),
+            options_menu: Menu::new(Self::OPTIONS_MENU),
+            options_in_game: false,
+            profile_status: None,
+            asset_error: None,
+            first_seen: Self::saved(persistent, FirstSeen::load),
+            tip: None,
+            attract_start: 0.0,
+            font,
+            hud: Hud::default(),
+            text_widths: TextMeasureCache::new(),
+            lines: RefCell::new(LineBatch::new()),
+            audio,
+            sound_director: SoundDirector::new(),
+            saucer_director: SaucerDirector::default(),
+            lurker_watch: LurkerWatch::default(),
+            telemetry_visible: false,
+            fullscreen: false,
+            gamepad,
+            shake: ScreenShake::new(0.0),
+            visibility: Visibility::default(),
+            touch: false,
+            touch_input: TouchInput::default(),
+            hud_visible: !options.no_hud,
+            hud_layout: Self::saved(persistent, HudLayout::load),
+            settings,
+            sample_count,
+            bindings: Self::saved(persistent, Bindings::load),
+            overlay: Overlay::new(options.overlay_file.clone()),
+            broadcaster: Broadcaster::default(),
+            game_state: GameState::AttractMode,
+            game_mode: GameMode::Classic,
+            autopilot: AutoPilot::default(),
+            latency_probe: LatencyProbe::new(),
+            recording: Recording::new(),
+            timeline: Timeline::default(),
+            input_log: None,
+            shared_game: None,
+            watching: None,
+            rules: Rules::default(),
+            code_text: String::new(),
+            code_status: None,
+            rng,
+            effects_rng,
+            fixed_seed: options.seed,
+            seed,
+            fixed_step: None,
+            clock: GameClock::new(),
+            headless,
+            persistent,
+            tas: None,
+        };
+
+        // A tool-assisted run steps every frame by the same amount
+        if options.tas {
+            let mut movie = Movie::new(seed, world.settings.difficulty, world.settings.starting_lives, world.settings.bullet_limit);
+            movie.rules = world.settings.rules();
+
+            world.tas = Some(TasSession::new(movie));
+            world.fixed_step = Some(TasSession::STEP);
+        }
+
+        world.audio.set_volume(world.settings.volume);
+        world.shake.intensity = world.settings.screen_shake;
+
+        // Until a published manifest is set, the week's challenge follows the
+        // local schedule
+        world.set_weekly(WeeklyChallenge::scheduled(WeeklyChallenge::current_week()));
+        world
+    }
+
+    /// Something saved, loaded if the world is persistent, or else its
+    /// default.
+    fn saved<T: Default>(persistent: bool, load: fn() -> T) -> T {
+        if persistent { load() } else { T::default() }
+    }
+
+    /// Value mixed into the seed of the effects generator, so its stream
+    /// differs from the gameplay one.
+    const EFFECTS_STREAM: u64 = 0x9e37_79b9_7f4a_7c15;
+
+    /// Seed taken from the system clock, for games started without a seed.
+    fn clock_seed() -> u64 {
+        (miniquad::date::now() * 1000.0) as u64
+    }
+
+    /// Seed the random number generators for a new game, from the fixed seed
+    /// given at launch or else a fresh one. The seed is kept so the game can
+    /// be reproduced.
+    fn seed_game(&mut self) {
+        self.seed_game_with(self.fixed_seed.unwrap_or_else(Self::clock_seed));
+    }
+
+    /// Seed the random number generators for a new game from a given seed.
+    fn seed_game_with(&mut self, seed: u64) {
+        self.seed = seed;
+
+        self.rng.srand(self.seed);
+        self.effects_rng.srand(self.seed ^ Self::EFFECTS_STREAM);
+        self.deep_field = DeepField::generate(self.seed);
+    }
+
+    /// Register a plugin with the world, along with the kinds of entity it
+    /// adds.
+    pub fn with_plugin(mut self, mut plugin: impl GamePlugin + 'static) -> Self {
+        plugin.register_entities(&mut self.entity_registry);
+        self.plugins.push(Box::new(plugin));
+
+        self
+    }
+
+    /// Spawn an entity of a kind a plugin registered under a name. Returns
+    /// false if no kind is registered under it.
+    pub fn spawn_entity(&mut self, name: &str) -> bool {
+        let Some(entity) = self.entity_registry.spawn(name, &self.rng) else {
+            return false;
+        };
+
+        self.entities.push(entity);
+        true
+    }
+
+    /// Let each plugin in turn act on the world. Plugins are set aside while
+    /// they are called, so they can be handed the world itself.
+    fn run_plugins(&mut self, mut call: impl FnMut(&mut dyn GamePlugin, &mut GameWorld)) {
+        let mut plugins = std::mem::take(&mut self.plugins);
+
+        for plugin in &mut plugins {
+            call(plugin.as_mut(), self);
+        }
+
+        self.plugins = plugins;
+    }
+
+    /// Report an asset that could not be loaded, to be shown on the title
+    /// screen.
+    pub fn report_asset_error(&mut self, error: String) {
+        self.asset_error = Some(error);
+    }
+
+    /// Set the week's challenge, loading its leaderboard if the world is
+    /// persistent.
+    pub fn set_weekly(&mut self, challenge: WeeklyChallenge) {
+        self.weekly_scores = if self.persistent {
+            HighScoreTable::load_from(&challenge.storage_key())
+        } else {
+            HighScoreTable::default()
+        };
+        self.weekly = challenge;
+        self.update_weekly_text();
+    }
+
+    /// Refresh the weekly challenge line shown on the title screen.
+    fn update_weekly_text(&mut self) {
+        self.weekly_text = match self.weekly_scores.entries.first() {
+            Some(best) => format!("Weekly: {}  Best: {} {}", self.weekly.description(), best.initials, format_score(best.score)),
+            None => format!("Weekly: {}", self.weekly.description()),
+        };
+    }
+
+    /// Check if a mutator of the weekly challenge is in play.
+    fn has_mutator(&self, mutator: Mutator) -> bool {
+        self.game_mode == GameMode::Weekly && self.weekly.has(mutator)
+    }
+
+    /// Update game world and render.
+    pub fn do_frame(&mut self) {
+        // The clock moves on with each frame, except while a game is held
+        // still; frames of a game being played move it on as they play
+        if !self.is_playing() && !self.is_held() {
+            self.clock.advance(self.frame_step());
+        }
+
+        // Toggle built-in HUD
+        if is_key_pressed(KeyCode::H) {
+            self.hud_visible = !self.hud_visible;
+        }
+
+        // Toggle fullscreen; the playfield is fitted to the window every frame
+        if is_key_pressed(KeyCode::F11) || (is_key_pressed(KeyCode::Enter) && menu::is_alt_down()) {
+            self.fullscreen = !self.fullscreen;
+            set_fullscreen(self.fullscreen);
+        }
+
+        // Toggle the saucer director's telemetry, for balance tuning
+        if is_key_pressed(KeyCode::F5) {
+            self.telemetry_visible = !self.telemetry_visible;
+        }
+
+        match self.game_state {
+            GameState::AttractMode => {
+                self.game_attract_mode();
+            },
+            GameState::Ladder => {
+                self.game_ladder_mode();
+            },
+            GameState::Stats => {
+                self.game_stats_mode();
+            },
+            GameState::Playing => {
+                self.game_play_mode();
+            },
+            GameState::Tip => {
+                self.game_tip_mode();
+            },
+            GameState::WaveTally => {
+                self.game_wave_tally_mode();
+            },
+            GameState::Paused => {
+                self.game_paused_mode();
+            },
+            GameState::Shop => {
+                self.game_shop_mode();
+            },
+            GameState::Controls => {
+                self.game_controls_mode();
+            },
+            GameState::Options => {
+                self.game_options_mode();
+            },
+            GameState::ChallengeComplete => {
+                self.game_challenge_complete_mode();
+            },
+            GameState::VersusResults => {
+                self.game_versus_results_mode();
+            },
+            GameState::GameOver => {
+                self.game_over_mode();
+            },
+            GameState::EnterInitials => {
+                self.game_enter_initials_mode();
+            },
+            GameState::Diagnostics => {
+                self.game_diagnostics_mode();
+            },
+            GameState::Replay => {
+                self.game_replay_mode();
+            },
+            GameState::ReplayCode => {
+                self.game_replay_code_mode();
+            },
+        }
+
+        // Looping effects only play during a game
+        if self.is_playing() {
+            self.audio.set_looping(SoundEffect::Saucer, !self.saucers.is_empty());
+        } else {
+            self.audio.stop_loops();
+        }
+
+        // Publish state for external overlays
+        let state = self.state_json();
+        self.overlay.publish(state);
+
+        // Broadcast state changes as events
+        self.broadcaster.observe(BroadcastSnapshot {
+            state: self.game_state.name(),
+            score: self.player_score,
+            lives: self.player_lives,
+            wave: self.wave_number,
+        });
+    }
+
+    /// Game state as a JSON document, for consumption by external overlays.
+    pub fn state_json(&self) -> String {
+        format!(
+            "{{\"state\":\"{}\",\"score\":{},\"lives\":{},\"wave\":{}}}",
+            self.game_state.name(), self.player_score, self.player_lives, self.wave_number
+        )
+    }
+    
+    /// Game running in attract mode.
+    fn game_attract_mode(&mut self) {
+        if is_key_pressed(KeyCode::Space) || is_mouse_button_pressed(MouseButton::Left) || !touches().is_empty() {
+            self.start(!touches().is_empty());
+        } else if is_key_pressed(KeyCode::Key2) {
+            self.start_coop();
+        } else if is_key_pressed(KeyCode::Key3) {
+            self.start_versus(VersusMatch::DEFAULT_BEST_OF);
+        } else if is_key_pressed(KeyCode::W) {
+            self.start_weekly();
+        } else if is_key_pressed(KeyCode::L) {
+            self.game_state = GameState::Ladder;
+        } else if is_key_pressed(KeyCode::S) {
+            self.game_state = GameState::Stats;
+        } else if is_key_pressed(KeyCode::O) {
+            self.open_options(false);
+        } else if is_key_pressed(KeyCode::C) {
+            self.code_text.clear();
+            self.code_status = None;
+            self.game_state = GameState::ReplayCode;
+        } else if is_key_pressed(KeyCode::F3) {
+            self.latency_probe.reset();
+            self.game_state = GameState::Diagnostics;
+        } else if is_key_pressed(KeyCode::F4) && !self.recording.is_empty() {
+            self.timeline = Timeline::default();
+            self.game_state = GameState::Replay;
+        } else {
+            self.autopilot_input();
+        }
+
+        self.update();
+        self.draw();
+    }
+
+    /// Fly the ship with the auto pilot, as in the attract mode demo.
+    pub fn autopilot_input(&mut self) {
+        let now = self.clock.now();
+
+        if self.ship.is_respawning(now) {
+            return;
+        }
+
+        let command = self.autopilot.fly(&self.ship, &self.asteroids, &self.saucers, now);
+
+        self.ship.steer(command.steer, now);
+
+        if command.thrust {
+            self.ship.thrust();
+
+            self.particles.append(&mut Particle::spawn_conical(&self.effects_rng, self.ship.get_exhaust_position(), self.ship.rotation, 0.5, 1));
+        }
+
+        if command.fire {
+            let mut bullets = self.ship.shoot(Self::live_bullets(&self.player_bullets, PLAYER_ONE), now);
+            self.player_bullets.append(&mut bullets);
+        }
+
+        if command.hyperspace {
+            if let Some(jump) = self.ship.hyperspace(&self.rng, now) {
+                self.resolve_hyperspace(PLAYER_ONE, jump);
+            }
+        }
+    }
+
+    /// Game on the challenge ladder. The attract mode field keeps drifting
+    /// beneath the ladder; only unlocked challenges can be started.
+    fn game_ladder_mode(&mut self) {
+        let items = self.ladder_items();
+
+        match self.ladder_menu.update(&items, &mut self.gamepad) {
+            Some(MenuEvent::Back) => self.return_to_attract_mode(false),
+            Some(MenuEvent::Accept(index)) => self.start_challenge(index),
+            _ => (),
+        }
+
+        self.update();
+        self.draw();
+
+        if self.game_state == GameState::Ladder {
+            self.draw_ladder();
+        }
+    }
+
+    /// Lifetime stats. The attract mode field keeps drifting beneath them.
+    fn game_stats_mode(&mut self) {
+        if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::S) || menu::is_enter_pressed() {
+            self.return_to_attract_mode(false);
+        }
+
+        self.update();
+        self.draw();
+
+        if self.game_state == GameState::Stats {
+            self.draw_stats();
+        }
+    }
+
+    /// Game running in play mode.
+    fn game_play_mode(&mut self) {
+        // A shared game plays out until its code runs out or Escape is pressed
+        if let Some((code, position)) = &self.watching {
+            if is_key_pressed(KeyCode::Escape) || *position >= code.len() {
+                self.return_to_attract_mode(false);
+                self.draw();
+                return;
+            }
+        }
+
+        if is_key_pressed(KeyCode::Escape) || is_key_pressed(KeyCode::P) {
+            self.pause_menu.reset();
+            self.game_state = GameState::Paused;
+
+            self.draw();
+            return;
+        }
+
+        // Freeze the game on the frame a bug report is captured of
+        if is_key_pressed(BugReport::KEY) && self.watching.is_none() {
+            self.draw();
+            self.capture_bug_report();
+
+            self.pause_menu.reset();
+            self.game_state = GameState::Paused;
+            return;
+        }
+
+        // Controls come from the code being watched, or else the player, whose
+        // input is logged to share the game
+        let input = match &self.watching {
+            Some((code, position)) => code.frame(*position).unwrap_or_default(),
+            None => self.read_input(),
+        };
+
+        // Played a frame at a time in a tool-assisted run
+        let input = match self.tas_input(input) {
+            Some(input) => input,
+            None => {
+                self.draw();
+                return;
+            },
+        };
+
+        self.play_frame(input);
+        self.draw();
+    }
+
+    /// Play a frame of the game with the controls worked on it, moving the
+    /// clock on by the time the frame takes.
+    fn play_frame(&mut self, input: InputFrame) {
+        // The clock moves on by the frame's duration as recorded, so a game
+        // played back keeps the same time as it did when it was played
+        self.clock.advance(input.dt());
+        let now = self.clock.now();
+
+        let was_flying = !self.ship.is_respawning(now);
+        let (wave_before, saucers_before) = (self.wave_number, self.saucers.len());
+        let position_before = self.ship.position;
+
+        // Only time spent playing counts against a challenge
+        if let Some(run) = &mut self.challenge {
+            run.stats.elapsed += self.clock.frame_time() as f64;
+        }
+
+        self.wave_stats.elapsed += self.clock.frame_time() as f64;
+
+        if let Some(log) = &mut self.input_log {
+            log.push(input);
+        }
+
+        if input.is_down(InputFrame::REWIND) && self.can_rewind() {
+            self.rewind();
+        }
+
+        if was_flying {
+            self.apply_input(input);
+        } else {
+            self.audio.set_looping(SoundEffect::Thrust, false);
+        }
+
+        // The second player drops in or out with their own key or gamepad,
+        // which a headless world has neither of
+        let second_pad = self.gamepad.poll_player(PLAYER_TWO);
+        let join_pressed = !self.headless && is_key_pressed(Wingman::JOIN);
+
+        if self.watching.is_none() && (join_pressed || second_pad.start) {
+            self.toggle_wingman();
+        }
+
+        if self.wingman.as_ref().is_some_and(|wingman| !wingman.ship.is_respawning(now)) {
+            self.wingman_input(second_pad);
+        }
+
+        self.sound_director.update(&self.audio, self.asteroids.len(), now);
+
+        self.update();
+        self.record_frame();
+
+        // The ship was hit if it was flying and is now respawning, or the game is over
+        if was_flying && (self.ship.is_respawning(now) || self.is_game_over()) {
+            self.gamepad.rumble(Rumble::Hit);
+            self.recording.mark(ReplayEvent::Death);
+            self.debug_log.push(now, format!("ship lost near {}, {} lives left", position_before, self.player_lives));
+
+            if let Some(run) = &mut self.challenge {
+                run.stats.deaths += 1;
+            }
+        }
+
+        // Losing the last ship with a chrono in hand rewinds rather than
+        // ending the game
+        if self.is_game_over() && self.can_rewind() {
+            self.rewind();
+        }
+
+        if self.is_game_over() {
+            self.record_lifetime_stats();
+        }
+
+        // Keep the last few seconds while there is a chrono to rewind them
+        if self.chronos > 0 && self.is_rewind_allowed() && self.snapshots.is_due(now) {
+            let snapshot = self.snapshot();
+            self.snapshots.push(now, snapshot);
+        }
+
+        if self.wave_number != wave_before {
+            self.recording.mark(ReplayEvent::Wave);
+            self.debug_log.push(now, format!("wave {} with {} asteroids", self.wave_number, self.asteroids.len()));
+        }
+
+        if self.saucers.len() > saucers_before {
+            self.recording.mark(ReplayEvent::Saucer);
+
+            for saucer in &self.saucers[saucers_before..] {
+                self.debug_log.push(now, format!("{:?} saucer spawned at {}", saucer.size, saucer.position));
+            }
+        }
+
+        // Start the next versus round once the last one has played out
+        if let Some(versus) = &mut self.versus {
+            if versus.is_round_over(now) {
+                versus.finish_round();
+
+                if versus.winner().is_some() {
+                    self.game_state = GameState::VersusResults;
+                } else {
+                    self.start_round();
+                }
+            }
+        }
+
+        // A challenge is failed when the last ship is lost or time runs out
+        if let Some(run) = &self.challenge {
+            let is_out_of_time = self.ladder.challenges[run.index].is_out_of_time(run.stats.elapsed);
+
+            if self.is_game_over() || (self.is_playing() && is_out_of_time) {
+                self.finish_challenge(false);
+            }
+        }
+
+        // Tips wait on a key, so are not shown to a headless world
+        if self.is_playing() && self.watching.is_none() && self.tas.is_none() && !self.headless {
+            self.show_first_tip();
+        }
+
+        if let Some((_, position)) = &mut self.watching {
+            *position += 1;
+        }
+    }
+
+    /// Mechanic in play that the player has not met before, if any.
+    fn unseen_mechanic(&self) -> Option<Mechanic> {
+        let in_play = |mechanic: Mechanic| match mechanic {
+            Mechanic::Saucer => !self.saucers.is_empty(),
+            Mechanic::Turret => !self.turrets.is_empty(),
+            Mechanic::Salvage => !self.salvage.is_empty(),
+            Mechanic::Credits => self.player_credits > 0,
+            Mechanic::Overdrive => self.overdrive.is_full(),
+        };
+
+        Mechanic::ALL.into_iter().find(|mechanic| !self.first_seen.has_seen(*mechanic) && in_play(*mechanic))
+    }
+
+    /// Stop play to show a tip on a mechanic the player has just met for the
+    /// first time. Tips are not shown in versus matches, which are played
+    /// between two players sharing the screen.
+    fn show_first_tip(&mut self) {
+        if self.game_mode == GameMode::Versus {
+            return;
+        }
+
+        if let Some(mechanic) = self.unseen_mechanic() {
+            self.first_seen.mark(mechanic);
+
+            if self.persistent {
+                self.first_seen.save();
+            }
+
+            self.tip = Some(mechanic);
+            self.game_state = GameState::Tip;
+        }
+    }
+
+    /// Game stopped for a tip. Nothing is updated until the tip is dismissed.
+    fn game_tip_mode(&mut self) {
+        if menu::is_enter_pressed() || is_key_pressed(KeyCode::Escape) || is_mouse_button_pressed(MouseButton::Left) {
+            self.tip = None;
+            self.game_state = GameState::Playing;
+        }
+
+        self.draw();
+        self.draw_tip();
+    }
+
+    /// Wave tally shown between waves. Nothing is updated until it has been
+    /// shown for long enough or is skipped, when the next wave starts.
+    fn game_wave_tally_mode(&mut self) {
+        let shown_for = self.wave_tally.as_ref().map_or(WaveTally::DURATION, |(time, _)| self.clock.now() - time);
+
+        if shown_for >= WaveTally::DURATION || menu::is_enter_pressed() {
+            self.wave_tally = None;
+            self.game_state = GameState::Playing;
+            self.next_wave();
+        }
+
+        self.draw();
+        self.draw_wave_tally();
+    }
+
+    /// Game paused. Nothing is updated; the world is drawn frozen beneath the
+    /// pause menu.
+    fn game_paused_mode(&mut self) {
+        let items: Vec<MenuItem> = PauseOption::ALL.iter().map(|option| MenuItem::button(option.label())).collect();
+
+        // P resumes as well as backing out, unless a dialog is open
+        let event = if is_key_pressed(KeyCode::P) && !self.pause_menu.is_confirming() {
+            Some(MenuEvent::Back)
+        } else {
+            self.pause_menu.update(&items, &mut self.gamepad)
+        };
+
+        match event {
+            Some(MenuEvent::Back) => self.game_state = GameState::Playing,
+            Some(MenuEvent::Accept(index)) => match PauseOption::ALL[index] {
+                PauseOption::Resume => self.game_state = GameState::Playing,
+                PauseOption::Shop => {
+                    self.shop_menu.reset();
+                    self.game_state = GameState::Shop;
+                },
+                PauseOption::Controls => {
+                    self.controls_menu.reset();
+                    self.game_state = GameState::Controls;
+                },
+                PauseOption::Options => self.open_options(true),
+                PauseOption::Restart => self.pause_menu.confirm("Restart, losing this game?"),
+                PauseOption::Quit => self.pause_menu.confirm("Quit to the title, losing this game?"),
+            },
+            Some(MenuEvent::Confirm(index)) => match PauseOption::ALL[index] {
+                PauseOption::Restart => match (self.game_mode, &self.challenge, &self.versus) {
+                    (GameMode::Challenge, Some(run), _) => self.start_challenge(run.index),
+                    (GameMode::Versus, _, Some(versus)) => self.start_versus(versus.best_of),
+                    (GameMode::Coop, _, _) => self.start_coop(),
+                    (GameMode::Weekly, _, _) => self.start_weekly(),
+                    _ => self.start(self.touch),
+                },
+                PauseOption::Quit => {
+                    self.dismiss_saucers();
+                    self.return_to_attract_mode(false);
+                },
+                _ => (),
+            },
+            _ => (),
+        }
+
+        self.draw();
+
+        if self.is_paused() {
+            self.draw_pause_menu();
+        }
+    }
+
+    /// Game in the upgrade shop. Like the pause menu, nothing is updated while
+    /// the shop is open; leaving the shop returns to the pause menu.
+    fn game_shop_mode(&mut self) {
+        let items = self.shop_items();
+
+        match self.shop_menu.update(&items, &mut self.gamepad) {
+            Some(MenuEvent::Back) => self.game_state = GameState::Paused,
+            Some(MenuEvent::Accept(index)) => {
+                Upgrade::ALL[index].buy(&mut self.ship, &mut self.player_credits);
+            },
+            _ => (),
+        }
+
+        self.draw();
+        self.draw_shop();
+    }
+
+    /// Game on the controls screen. The selected action is rebound to the next
+    /// key pressed after choosing it; bindings are saved as soon as they change.
+    /// The last entry puts every binding back to its default, once confirmed.
+    fn game_controls_mode(&mut self) {
+        let items = self.controls_items();
+
+        match self.controls_menu.update(&items, &mut self.gamepad) {
+            Some(MenuEvent::Back) => self.game_state = GameState::Paused,
+            Some(MenuEvent::Accept(index)) if index < Action::ALL.len() => self.controls_menu.capture(),
+            Some(MenuEvent::Accept(_)) => self.controls_menu.confirm("Reset every key to its default?"),
+            Some(MenuEvent::Capture(index, key)) if self.bindings.bind(Action::ALL[index], key) => self.save_bindings(),
+            Some(MenuEvent::Confirm(_)) => {
+                self.bindings = Bindings::default();
+                self.save_bindings();
+            },
+            _ => (),
+        }
+
+        self.draw();
+        self.draw_controls();
+    }
+
+    /// Open the options screen, from the pause menu during a game or from the
+    /// title screen.
+    fn open_options(&mut self, in_game: bool) {
+        self.options_menu.reset();
+        self.options_in_game = in_game;
+        self.profile_status = None;
+        self.game_state = GameState::Options;
+    }
+
+    /// Game on the options screen. The selected setting is changed in place,
+    /// taking effect straight away, and saved. Importing a profile and
+    /// forgetting the tips seen cannot be undone, so both ask first. Opened
+    /// from the title screen, the attract mode field keeps drifting beneath
+    /// the screen.
+    fn game_options_mode(&mut self) {
+        let items = self.options_items();
+
+        match self.options_menu.update(&items, &mut self.gamepad) {
+            Some(MenuEvent::Back) if self.options_in_game => self.game_state = GameState::Paused,
+            Some(MenuEvent::Back) => self.return_to_attract_mode(false),
+            Some(MenuEvent::Accept(index)) => match Setting::ALL[index] {
+                Setting::ImportProfile => self.options_menu.confirm("Replace your saved data with the profile?"),
+                Setting::TipsSeen => self.options_menu.confirm("Show every tip again?"),
+                setting => self.change_setting(setting, true),
+            },
+            Some(MenuEvent::Change(index, forward)) => match Setting::ALL[index] {
+                // Actions are only taken when accepted
+                Setting::ImportProfile | Setting::ExportProfile | Setting::TipsSeen => (),
+                setting => self.change_setting(setting, forward),
+            },
+            Some(MenuEvent::Confirm(index)) => self.change_setting(Setting::ALL[index], true),
+            _ => (),
+        }
+
+        if !self.options_in_game {
+            self.update();
+        }
+
+        self.draw();
+
+        if self.game_state == GameState::Options {
+            self.draw_options();
+        }
+    }
+
+    /// Change a setting to its next value, or its previous one where values
+    /// go up and down.
+    fn change_setting(&mut self, setting: Setting, forward: bool) {
+        let step = if forward { Settings::STEP } else { -Settings::STEP };
+
+        match setting {
+            Setting::Volume => {
+                self.settings.adjust_volume(step);
+                self.audio.set_volume(self.settings.volume);
+                self.audio.play(SoundEffect::Shot);
+            },
+            Setting::ScreenShake => {
+                self.settings.adjust_screen_shake(step);
+                self.shake.intensity = self.settings.screen_shake;
+            },
+            Setting::ControlScheme => {
+                // Custom bindings start over from the first scheme
+                let scheme = self.bindings.scheme().map_or(ControlScheme::Arrows, |scheme| scheme.next());
+
+                self.bindings = scheme.bindings();
+                self.save_bindings();
+            },
+            Setting::Difficulty => {
+                let difficulty = self.settings.difficulty;
+                self.settings.difficulty = if forward { difficulty.next() } else { difficulty.previous() };
+            },
+            Setting::StartingLives => self.settings.adjust_starting_lives(if forward { 1 } else { -1 }),
+            Setting::BulletLimit => {
+                self.settings.bullet_limit = !self.settings.bullet_limit;
+                self.apply_bullet_limit();
+            },
+            Setting::ShieldDuration => self.settings.adjust_shield_duration(if forward { Settings::SHIELD_DURATION_STEP } else { -Settings::SHIELD_DURATION_STEP }),
+            Setting::ShieldRecharge => self.settings.adjust_shield_recharge(if forward { Settings::SHIELD_RECHARGE_STEP } else { -Settings::SHIELD_RECHARGE_STEP }),
+            Setting::LowVisibility => self.settings.low_visibility = !self.settings.low_visibility,
+            Setting::BouncingAsteroids => self.settings.bouncing_asteroids = !self.settings.bouncing_asteroids,
+            Setting::ArcadeScoring => self.settings.arcade_scoring = !self.settings.arcade_scoring,
+            Setting::Announcements => {
+                self.settings.announcements = !self.settings.announcements;
+                self.announcer.clear();
+            },
+            Setting::DeepField => self.settings.deep_field = !self.settings.deep_field,
+            Setting::Antialiasing => self.settings.antialiasing = self.settings.antialiasing.next(),
+            Setting::LineWidth => self.settings.adjust_line_width(if forward { Settings::LINE_WIDTH_STEP } else { -Settings::LINE_WIDTH_STEP }),
+            Setting::TouchLayout => self.settings.touch_layout = self.settings.touch_layout.next(),
+            Setting::TipsSeen => {
+                // Forgetting the tips seen shows them all again
+                self.first_seen.clear();
+
+                if self.persistent {
+                    self.first_seen.save();
+                }
+            },
+            Setting::HudPreset => {
+                // A custom layout starts over from the first preset
+                let preset = self.hud_layout.preset().map_or(HudPreset::Minimal, |preset| preset.next());
+
+                self.hud_layout = HudLayout { combo_anchor: self.hud_layout.combo_anchor, ..HudLayout::from_preset(preset) };
+                self.save_hud_layout();
+            },
+            Setting::Hud(element) => {
+                self.hud_layout.toggle(element);
+                self.save_hud_layout();
+            },
+            Setting::ComboAnchor => {
+                self.hud_layout.combo_anchor = self.hud_layout.combo_anchor.next();
+                self.save_hud_layout();
+            },
+            Setting::ExportProfile => {
+                if forward && self.persistent {
+                    Profile::export();
+                    self.profile_status = Some("Profile exported".to_string());
+                }
+
+                return;
+            },
+            Setting::ImportProfile => {
+                if forward && self.persistent {
+                    self.import_profile();
+                }
+
+                return;
+            },
+        }
+
+        if self.persistent {
+            self.settings.save();
+        }
+    }
+
+    /// Save the key bindings, unless the world saves nothing.
+    fn save_bindings(&self) {
+        if self.persistent {
+            self.bindings.save();
+        }
+    }
+
+    /// Save the HUD layout, unless the world saves nothing.
+    fn save_hud_layout(&self) {
+        if self.persistent {
+            self.hud_layout.save();
+        }
+    }
+
+    /// Value of a setting as shown in the menu.
+    fn setting_value(&self, setting: Setting) -> String {
+        match setting {
+            Setting::Volume => format!("{}%", (self.settings.volume * 100.0).round()),
+            Setting::ScreenShake if self.settings.screen_shake == 0.0 => "Off".to_string(),
+            Setting::ScreenShake => format!("{}%", (self.settings.screen_shake * 100.0).round()),
+            Setting::ControlScheme => self.bindings.scheme().map_or("Custom", |scheme| scheme.label()).to_string(),
+            Setting::Difficulty => self.settings.difficulty.label().to_string(),
+            Setting::StartingLives => self.settings.starting_lives.to_string(),
+            Setting::BulletLimit => if self.settings.bullet_limit { "On" } else { "Off" }.to_string(),
+            Setting::ShieldDuration => format!("{:.1}s", self.settings.shield_duration),
+            Setting::ShieldRecharge => format!("{}s", self.settings.shield_recharge),
+            Setting::LowVisibility => if self.settings.low_visibility { "On" } else { "Off" }.to_string(),
+            Setting::BouncingAsteroids => if self.settings.bouncing_asteroids { "On" } else { "Off" }.to_string(),
+            Setting::ArcadeScoring => if self.settings.arcade_scoring { "On" } else { "Off" }.to_string(),
+            Setting::Announcements => if self.settings.announcements { "On" } else { "Off" }.to_string(),
+            Setting::DeepField => if self.settings.deep_field { "On" } else { "Off" }.to_string(),
+            Setting::Antialiasing => {
+                // Multisampling only changes once the window is made again
+                let mode = self.settings.antialiasing;
+
+                if mode.sample_count() == self.sample_count {
+                    mode.label().to_string()
+                } else {
+                    format!("{} (on restart)", mode.label())
+                }
+            },
+            Setting::LineWidth => format!("{}%", (self.settings.line_width * 100.0).round()),
+            Setting::TouchLayout => self.settings.touch_layout.label().to_string(),
+            Setting::TipsSeen => {
+                let seen = Mechanic::ALL.iter().filter(|mechanic| self.first_seen.has_seen(**mechanic)).count();
+                format!("{}/{}", seen, Mechanic::ALL.len())
+            },
+            Setting::HudPreset => self.hud_layout.preset().map_or("Custom", |preset| preset.label()).to_string(),
+            Setting::Hud(element) => if self.hud_layout.is_shown(element) { "Shown" } else { "Hidden" }.to_string(),
+            Setting::ComboAnchor => self.hud_layout.combo_anchor.label().to_string(),
+            Setting::ExportProfile | Setting::ImportProfile => String::new(),
+        }
+    }
+
+    /// Import a profile over the player's saved data, then load the data
+    /// again so the imported settings take effect straight away.
+    fn import_profile(&mut self) {
+        if let Err(error) = Profile::import() {
+            self.profile_status = Some(format!("Import failed: {}", error));
+            return;
+        }
+
+        self.reload_settings();
+        self.bindings = Bindings::load();
+        self.hud_layout = HudLayout::load();
+        self.first_seen = FirstSeen::load();
+        self.ladder = ChallengeLadder::load();
+        self.high_scores = HighScoreTable::load();
+
+        self.profile_status = Some("Profile imported".to_string());
+    }
+
+    /// Load the saved settings again, putting them into effect straight away.
+    pub fn reload_settings(&mut self) {
+        self.settings = Settings::load();
+
+        self.audio.set_volume(self.settings.volume);
+        self.shake.intensity = self.settings.screen_shake;
+        self.apply_bullet_limit();
+    }
+
+    /// Fit the players' ships with the classic bullet limit, or remove it,
+    /// following the setting.
+    fn apply_bullet_limit(&mut self) {
+        let limit = self.settings.bullet_limit.then_some(Ship::CLASSIC_BULLET_LIMIT);
+
+        self.ship.bullet_limit = limit;
+
+        if let Some(wingman) = &mut self.wingman {
+            wingman.ship.bullet_limit = limit;
+        }
+    }
+
+    /// Number of a player's bullets still in flight.
+    fn live_bullets(bullets: &[Bullet], owner: usize) -> usize {
+        bullets.iter()
+            .filter(|bullet| bullet.owner == owner && bullet.is_alive())
+            .count()
+    }
+
+    /// Game showing the result of a challenge. Continuing returns to the
+    /// ladder, moving on to the next challenge if this one unlocked it.
+    fn game_challenge_complete_mode(&mut self) {
+        if is_key_pressed(KeyCode::Space) || menu::is_enter_pressed() || is_mouse_button_pressed(MouseButton::Left) {
+            if let Some(run) = &self.challenge {
+                let next = run.index + 1;
+
+                self.ladder_menu.selection = if next < self.ladder.challenges.len() && self.ladder.is_unlocked(next) {
+                    next
+                } else {
+                    run.index
+                };
+            }
+
+            self.dismiss_saucers();
+            self.return_to_attract_mode(false);
+            self.game_state = GameState::Ladder;
+        }
+
+        self.update();
+        self.draw();
+        self.draw_challenge_result();
+    }
+
+    /// Game showing the winner of a versus match. Continuing returns to attract
+    /// mode.
+    fn game_versus_results_mode(&mut self) {
+        if is_key_pressed(KeyCode::Space) || menu::is_enter_pressed() || is_mouse_button_pressed(MouseButton::Left) {
+            self.dismiss_saucers();
+            self.return_to_attract_mode(false);
+        }
+
+        self.update();
+        self.draw();
+        self.draw_versus_results();
+    }
+
+    /// Game on the latency diagnostics screen. The game is not updated, so
+    /// frames are as cheap as possible; any key but Escape flashes the screen.
+    /// Latency is measured in real time, so by macroquad's clock rather than
+    /// the game's.
+    fn game_diagnostics_mode(&mut self) {
+        self.latency_probe.begin_frame(get_time());
+
+        match get_last_key_pressed() {
+            Some(KeyCode::Escape) => {
+                self.return_to_attract_mode(false);
+                return;
+            },
+            Some(_) => self.latency_probe.press(get_time()),
+            None if is_mouse_button_pressed(MouseButton::Left) => self.latency_probe.press(get_time()),
+            None => {},
+        }
+
+        self.draw_diagnostics();
+    }
+
+    /// Record what is in play this frame, as collision circles.
+    fn record_frame(&mut self) {
+        let frame = self.recording.record();
+
+        frame.time = self.clock.now();
+        frame.wave = self.wave_number;
+        frame.score = self.player_score;
+
+        let ships = std::iter::once(&self.ship).chain(self.wingman.as_ref().map(|wingman| &wingman.ship));
+
+        for ship in ships.filter(|ship| !ship.is_respawning(self.clock.now())) {
+            let heading = ship.rotation - std::f32::consts::FRAC_PI_2;
+            frame.ships.push((Circle::new(ship.position, ship.get_radius()), heading));
+        }
+
+        frame.asteroids.extend(self.asteroids.iter().filter(|asteroid| asteroid.is_alive()).map(|asteroid| Circle::new(asteroid.position, asteroid.get_radius())));
+        frame.saucers.extend(self.saucers.iter().filter(|saucer| saucer.is_alive()).map(|saucer| Circle::new(saucer.position, saucer.get_radius())));
+        frame.player_bullets.extend(self.player_bullets.iter().filter(|bullet| bullet.is_alive()).map(|bullet| Circle::new(bullet.position, bullet.get_radius())));
+        frame.enemy_bullets.extend(self.enemy_bullets.iter().filter(|bullet| bullet.is_alive()).map(|bullet| Circle::new(bullet.position, bullet.get_radius())));
+    }
+
+    /// Capture a bug report of the game as it is now, with the frame just
+    /// drawn, and save it, letting the player know where. A world that saves
+    /// nothing takes no reports.
+    fn capture_bug_report(&mut self) {
+        if !self.persistent {
+            return;
+        }
+
+        let mut report = BugReport::default();
+
+        let summary = format!(
+            "asteroids {}
+mode {:?}
+seed {}
+wave {}
+score {}
+lives {}
+time {:.3}
+",
+            env!("CARGO_PKG_VERSION"), self.game_mode, self.seed, self.wave_number, self.player_score, self.player_lives, self.clock.now()
+        );
+
+        report.add("report.txt", summary);
+        report.add("screenshot.png", bugreport::screenshot());
+        report.add("replay.txt", bugreport::replay_text(&self.recording, BugReport::REPLAY_SECONDS));
+        report.add("settings.toml", self.settings.to_toml());
+        report.add("log.txt", self.debug_log.to_text());
+
+        if let Some(log) = &self.input_log {
+            let mut movie = Movie::new(log.seed, log.difficulty, log.starting_lives, log.bullet_limit);
+            movie.rules = log.rules;
+            movie.frames = (0..log.len()).filter_map(|index| log.frame(index)).collect();
+
+            report.add("movie.tas", movie.to_text());
+        }
+
+        let key = format!("bugreport-{}.zip", miniquad::date::now() as u64);
+        storage::save_bytes(&key, &report.to_zip());
+
+        self.bug_report_notice = Some((self.clock.now(), format!("Bug report saved as {}", key)));
+    }
+
+    /// Frames skipped by a step on the replay screen with Shift held.
+    const REPLAY_FAST_STEP: isize = 10;
+
+    /// Game on the replay screen, playing back the last game. Play is paused
+    /// and resumed with Space, stepped a frame at a time with the arrow keys
+    /// and scrubbed by dragging along the timeline.
+    fn game_replay_mode(&mut self) {
+        let len = self.recording.len();
+        let step = if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) {
+            Self::REPLAY_FAST_STEP
+        } else {
+            1
+        };
+
+        if is_key_pressed(KeyCode::Escape) {
+            self.return_to_attract_mode(false);
+            return;
+        } else if is_key_pressed(KeyCode::Space) {
+            self.timeline.toggle(len);
+        } else if is_key_pressed(KeyCode::Left) {
+            self.timeline.step(-step, len);
+        } else if is_key_pressed(KeyCode::Right) {
+            self.timeline.step(step, len);
+        } else if is_key_pressed(KeyCode::Home) {
+            self.timeline.scrub(0.0, len);
+        } else if is_key_pressed(KeyCode::End) {
+            self.timeline.scrub(1.0, len);
+        }
+
+        let (bar, _) = self.replay_bar();
+        let (x, y) = mouse_position();
+
+        if is_mouse_button_down(MouseButton::Left) && (y - bar.y).abs() < Self::REPLAY_BAR_REACH {
+            self.timeline.scrub((x - bar.x) / bar.w, len);
+        } else {
+            self.timeline.advance(len);
+        }
+
+        self.draw_replay();
+    }
+
+    /// Longest text accepted in the replay code box.
+    const MAX_CODE_LENGTH: usize = 4096;
+
+    /// Game on the replay code box, over the attract mode field. The last
+    /// classic game is copied to the clipboard as a code with Ctrl+C, and a
+    /// shared code is typed or pasted with Ctrl+V, then watched with Enter.
+    fn game_replay_code_mode(&mut self) {
+        let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl)
+            || is_key_down(KeyCode::LeftSuper) || is_key_down(KeyCode::RightSuper);
+
+        // Characters arrive last first
+        let mut typed: Vec<char> = std::iter::from_fn(get_char_pressed).collect();
+        typed.reverse();
+
+        if is_key_pressed(KeyCode::Escape) {
+            self.return_to_attract_mode(false);
+        } else if ctrl && is_key_pressed(KeyCode::C) {
+            self.code_status = Some(match &self.shared_game {
+                Some(game) => {
+                    miniquad::window::clipboard_set(&game.encode());
+                    format!("Copied the last game ({:.0} seconds)", game.duration())
+                },
+                None => "Play a classic game to share it".to_string(),
+            });
+        } else if ctrl && is_key_pressed(KeyCode::V) {
+            if let Some(text) = miniquad::window::clipboard_get() {
+                self.code_text = text.trim().chars().take(Self::MAX_CODE_LENGTH).collect();
+                self.code_status = None;
+            }
+        } else if is_key_pressed(KeyCode::Backspace) {
+            self.code_text.pop();
+            self.code_status = None;
+        } else if menu::is_enter_pressed() {
+            match ReplayCode::decode(&self.code_text) {
+                Ok(game) => self.watch(game),
+                Err(error) => self.code_status = Some(format!("Cannot watch: {}", error)),
+            }
+        } else if !ctrl {
+            for c in typed.into_iter().filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':')) {
+                if self.code_text.len() < Self::MAX_CODE_LENGTH {
+                    self.code_text.push(c);
+                    self.code_status = None;
+                }
+            }
+        }
+
+        self.update();
+        self.draw();
+
+        if self.game_state == GameState::ReplayCode {
+            self.draw_replay_code();
+        }
+    }
+
+    /// Watch a shared game: a classic game from its seed and settings, flown
+    /// with the controls it was played with.
+    fn watch(&mut self, game: ReplayCode) {
+        self.seed_game_with(game.seed);
+        self.reset_player();
+        self.game_mode = GameMode::Classic;
+        self.challenge = None;
+        self.wingman = None;
+        self.versus = None;
+
+        self.player_lives = game.starting_lives;
+        self.ship.bullet_limit = game.bullet_limit.then_some(Ship::CLASSIC_BULLET_LIMIT);
+        self.rules = game.rules;
+        self.input_log = None;
+        self.watching = Some((game, 0));
+
+        self.wave_number = 0;
+        self.next_wave();
+
+        self.touch = false;
+        self.game_state = GameState::Playing;
+    }
+
+    /// Watch a movie of a tool-assisted run, as a shared game.
+    pub fn watch_movie(&mut self, movie: &Movie) {
+        self.watch(movie.to_code());
+    }
+
+    /// Start a game from a movie and play up to a number of its frames
+    /// silently, without drawing, as fast as they run. Returns the number of
+    /// frames played, fewer if the game ended first. The game is left
+    /// playing from there, no longer watched. Each frame is held to an
+    /// allocation budget, if given one.
+    pub fn play_movie(&mut self, movie: &Movie, frames: usize, mut budget: Option<&mut AllocationBudget>) -> usize {
+        self.watch(movie.to_code());
+        self.audio.set_volume(0.0);
+
+        // Nothing is announced of a movie played back out of sight
+        let announcements = std::mem::replace(&mut self.settings.announcements, false);
+
+        let mut played = 0;
+
+        while played < frames && self.game_state == GameState::Playing {
+            let Some(input) = movie.frames.get(played) else {
+                break;
+            };
+
+            if let Some(budget) = budget.as_deref_mut() {
+                budget.begin_frame();
+            }
+
+            self.play_frame(*input);
+            played += 1;
+
+            if let Some(budget) = budget.as_deref_mut() {
+                budget.end_frame();
+            }
+        }
+
+        self.watching = None;
+        self.audio.set_volume(self.settings.volume);
+        self.settings.announcements = announcements;
+
+        played
+    }
+
+    /// Storage key under which the movie of a tool-assisted run is exported.
+    const MOVIE_KEY: &'static str = "movie.tas";
+
+    /// Controls for the next frame of a tool-assisted run, or none while it
+    /// is paused for frame advance. The session's keys pause and resume play,
+    /// advance a frame, save and load states and export the movie, and
+    /// buttons pressed while paused are kept for the next frame played.
+    /// Outside a run, controls pass straight through.
+    fn tas_input(&mut self, input: InputFrame) -> Option<InputFrame> {
+        if self.watching.is_some() || self.game_mode != GameMode::Classic {
+            return Some(input);
+        }
+
+        let result = (self.player_score, self.wave_number);
+
+        let Some(tas) = &mut self.tas else {
+            return Some(input);
+        };
+
+        if is_key_pressed(KeyCode::F6) {
+            tas.paused = !tas.paused;
+        }
+
+        if is_key_pressed(KeyCode::F10) {
+            tas.slots.next_slot();
+            tas.notice = None;
+        }
+
+        if is_key_pressed(KeyCode::F8) {
+            tas.slots.save(tas.movie.frames.len());
+            tas.notice = Some(format!("Saved to slot {}", tas.slots.selected + 1));
+        }
+
+        if is_key_pressed(KeyCode::F12) && self.persistent {
+            let movie = Movie { result: Some(result), ..tas.movie.clone() };
+            storage::save(Self::MOVIE_KEY, &movie.to_text());
+            tas.notice = Some("Movie exported".to_string());
+        }
+
+        if is_key_pressed(KeyCode::F9) {
+            if let Some(frame) = tas.slots.load() {
+                self.load_tas_state(frame);
+                return None;
+            }
+        }
+
+        if tas.paused && !is_key_pressed(KeyCode::F7) {
+            tas.pending |= input.buttons;
+            return None;
+        }
+
+        let input = InputFrame::new(input.steer(), input.buttons | std::mem::take(&mut tas.pending), TasSession::STEP);
+        tas.movie.frames.push(input);
+
+        Some(input)
+    }
+
+    /// Load a savestate of a tool-assisted run: cut the movie short at the
+    /// frame the state was saved on and play it back up to there, paused.
+    fn load_tas_state(&mut self, frame: usize) {
+        let Some(mut tas) = self.tas.take() else {
+            return;
+        };
+
+        tas.movie.frames.truncate(frame);
+        tas.slots.forget_after(frame);
+        self.play_movie(&tas.movie, frame, None);
+
+        tas.paused = true;
+        tas.pending = 0;
+        tas.notice = Some(format!("Loaded slot {}", tas.slots.selected + 1));
+        self.tas = Some(tas);
+    }
+
+    /// Game running in game over mode.
+    fn game_over_mode(&mut self) {
+        if is_key_pressed(KeyCode::Space) || is_mouse_button_pressed(MouseButton::Left) || !touches().is_empty() {
+            if self.watching.is_none() && !self.rewound && self.leaderboard().qualifies(self.player_score) {
+                self.initials_entry = InitialsEntry::new();
+                self.game_state = GameState::EnterInitials;
+            } else {
+                self.return_to_attract_mode(false);
+            }
+        }
+
+        self.update();
+        self.draw();
+    }
+
+    /// High score table the current game is ranked on; the weekly challenge
+    /// has a leaderboard of its own.
+    fn leaderboard(&self) -> &HighScoreTable {
+        if self.game_mode == GameMode::Weekly {
+            &self.weekly_scores
+        } else {
+            &self.high_scores
+        }
+    }
+
+    /// Game running in initials entry mode.
+    fn game_enter_initials_mode(&mut self) {
+        let tapped = touches().iter().any(|touch| touch.phase == TouchPhase::Started);
+        let accepted = is_key_pressed(KeyCode::Space) || menu::is_enter_pressed() || is_key_pressed(KeyCode::Right) 
+            || is_mouse_button_pressed(MouseButton::Left) || tapped;
+
+        if is_key_pressed(KeyCode::Up) {
+            self.initials_entry.cycle(1);
+        } else if is_key_pressed(KeyCode::Down) {
+            self.initials_entry.cycle(-1);
+        } else if is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::Backspace) {
+            self.initials_entry.back();
+        } else if accepted && self.initials_entry.accept() {
+            let initials = self.initials_entry.initials();
+
+            if self.game_mode == GameMode::Weekly {
+                self.weekly_scores.insert(&initials, self.player_score);
+
+                if self.persistent {
+                    self.weekly_scores.save_to(&self.weekly.storage_key());
+                }
+                self.update_weekly_text();
+            } else {
+                self.high_scores.insert(&initials, self.player_score);
+
+                if self.persistent {
+                    self.high_scores.save();
+                }
+            }
+
+            self.return_to_attract_mode(true);
+        }
+
+        self.update();
+        self.draw();
+    }
+
+    /// Return to attract mode, optionally showing the high score table first.
+    fn return_to_attract_mode(&mut self, show_high_scores: bool) {
+        self.attract_start = self.clock.now();
+
+        // Keep the game just played to share, and stop watching a shared one
+        if let Some(log) = self.input_log.take().filter(|log| !log.is_empty()) {
+            self.shared_game = Some(log);
+        }
+
+        if self.watching.take().is_some() {
+            self.apply_bullet_limit();
+        }
+
+        // The field of the game just played drifts on behind the title,
+        // unless nothing is left of it
+        self.gold_seam = None;
+        self.gravity_well = None;
+        self.gravity_well_due = None;
+        self.hunter_due = None;
+
+        if self.asteroids.is_empty() {
+            self.spawn_attract_field();
+        }
+
+        // Leave whatever was being played and bring the ship back for the demo
+        self.game_mode = GameMode::Classic;
+        self.challenge = None;
+        self.wingman = None;
+        self.versus = None;
+
+        self.ship.reset();
+        self.ship.respawn_lifespan = 0.0;
+        self.ship.spawn_offset = Vec2::ZERO;
+
+        if show_high_scores {
+            self.attract_start -= Self::ATTRACT_TITLE_DURATION;
+        }
+
+        self.game_state = GameState::AttractMode;
+    }
+
+    /// Time in seconds the title is shown in each attract mode cycle.
+    const ATTRACT_TITLE_DURATION: f64 = 8.0;
+
+    /// Time in seconds the high score table is shown in each attract mode cycle.
+    const ATTRACT_TABLE_DURATION: f64 = 5.0;
+
+    /// Check if attract mode is currently showing the high score table rather
+    /// than the title.
+    fn is_showing_high_scores(&self) -> bool {
+        let cycle = Self::ATTRACT_TITLE_DURATION + Self::ATTRACT_TABLE_DURATION;
+
+        self.is_attract_mode() 
+            && !self.high_scores.entries.is_empty()
+            && (self.clock.now() - self.attract_start) % cycle >= Self::ATTRACT_TITLE_DURATION
+    }
+
+    /// Check if we're playing.
+    pub fn is_playing(&self) -> bool {
+        self.game_state == GameState::Playing
+    }
+
+    /// Check if we're paused.
+    pub fn is_paused(&self) -> bool {
+        self.game_state == GameState::Paused
+    }
+
+    /// Check if the upgrade shop is open.
+    pub fn is_shopping(&self) -> bool {
+        self.game_state == GameState::Shop
+    }
+
+    /// Check if a game is in progress, whether playing or in one of the menus
+    /// opened from the pause menu.
+    pub fn is_in_game(&self) -> bool {
+        matches!(self.game_state, GameState::Playing | GameState::Tip | GameState::WaveTally | GameState::Paused | GameState::Shop | GameState::Controls)
+            || (self.game_state == GameState::Options && self.options_in_game)
+    }
+
+    /// Check if the auto pilot is flying the ship in the attract mode demo.
+    pub fn is_demo(&self) -> bool {
+        self.game_state == GameState::AttractMode
+    }
+
+    /// Check if we're dead.
+    pub fn is_game_over(&self) -> bool {
+        self.game_state == GameState::GameOver
+    }
+
+    /// Check if we're in attract mode.
+    pub fn is_attract_mode(&self) -> bool {
+        self.game_state == GameState::AttractMode
+    }
+
+    /// Start attract mode.
+    pub fn attract_mode(&mut self) {
+        self.spawn_attract_field();
+        self.return_to_attract_mode(false);
+    }
+
+    /// Replace the field with a fresh one for attract mode: rocks of all sizes
+    /// and a large saucer.
+    fn spawn_attract_field(&mut self) {
+        self.asteroids.clear();
+
+        for _ in 0..20 {
+            let scale = self.rng.gen_range(AsteroidSize::Small.scale(), AsteroidSize::Large.scale());
+            
+            self.asteroids.push(Asteroid::spawn_new(&self.rng, scale));
+        }
+
+        self.saucers.clear();
+        self.turrets.clear();
+        self.hunters.clear();
+        self.entities.clear();
+        self.gold_seam = None;
+        self.saucers.push(Saucer::spawn_new(&self.rng, SaucerSize::Large, self.clock.now()));
+    }
+
+    /// Send the saucers of a game that is over on their way, leaving the rest
+    /// of its field to drift on behind whatever comes next.
+    fn dismiss_saucers(&mut self) {
+        for saucer in &mut self.saucers {
+            saucer.depart();
+        }
+    }
+
+    /// Start a new game, logging the player's input so the game can be
+    /// shared.
+    pub fn start(&mut self, touch: bool) {
+        self.seed_game();
+        self.reset_player();
+
+        if self.watching.take().is_some() {
+            self.apply_bullet_limit();
+        }
+
+        let mut log = ReplayCode::new(self.seed, self.settings.difficulty, self.settings.starting_lives, self.settings.bullet_limit);
+        log.rules = self.rules;
+
+        self.input_log = Some(log);
+        self.game_mode = GameMode::Classic;
+
+        if let Some(tas) = &mut self.tas {
+            let mut movie = Movie::new(self.seed, self.settings.difficulty, self.settings.starting_lives, self.settings.bullet_limit);
+            movie.rules = self.rules;
+
+            *tas = TasSession::new(movie);
+        }
+        self.challenge = None;
+        self.wingman = None;
+        self.versus = None;
+
+        self.wave_number = 0;
+        self.next_wave();
+
+        // Used to enable autofire during gameplay
+        self.touch = touch;
+        self.game_state = GameState::Playing;
+    }
+
+    /// Start the week's challenge: a classic game from the week's seed, with
+    /// its mutators in play, ranked on its own leaderboard.
+    pub fn start_weekly(&mut self) {
+        self.seed_game_with(self.weekly.seed);
+        self.reset_player();
+        self.game_mode = GameMode::Weekly;
+        self.challenge = None;
+        self.wingman = None;
+        self.versus = None;
+
+        if self.has_mutator(Mutator::Newtonian) {
+            self.ship.drag = 1.0;
+        }
+
+        if self.has_mutator(Mutator::OneLife) {
+            self.player_lives = 0;
+        }
+
+        self.wave_number = 0;
+        self.next_wave();
+
+        self.touch = false;
+        self.game_state = GameState::Playing;
+    }
+
+    /// Start a new two-player co-op game, with the wingman on the keyboard
+    /// alongside the first player.
+    pub fn start_coop(&mut self) {
+        self.start(false);
+        self.game_mode = GameMode::Coop;
+        self.input_log = None;
+        self.wingman = Some(Wingman {
+            lives: self.settings.starting_lives,
+            ..Wingman::new()
+        });
+        self.apply_bullet_limit();
+    }
+
+    /// Drop a second player in to a classic or co-op game under way, turning it
+    /// into co-op, or drop them out of it again. A player dropping out leaves
+    /// the first to carry on alone, unless they have already lost their last
+    /// ship.
+    fn toggle_wingman(&mut self) {
+        if !matches!(self.game_mode, GameMode::Classic | GameMode::Coop) {
+            return;
+        }
+
+        match self.wingman.take() {
+            Some(_) => {
+                self.player_bullets.retain(|bullet| bullet.owner != PLAYER_TWO);
+
+                if self.ship.is_retired() {
+                    self.game_state = GameState::GameOver;
+                    self.dismiss_saucers();
+                }
+            },
+            None => {
+                self.game_mode = GameMode::Coop;
+                self.input_log = None;
+                self.wingman = Some(Wingman::join(self.settings.starting_lives, self.clock.now()));
+                self.apply_bullet_limit();
+            },
+        }
+    }
+
+    /// Start a new versus match between two players on the keyboard, played
+    /// over a number of rounds.
+    pub fn start_versus(&mut self, best_of: u32) {
+        self.seed_game();
+        self.reset_player();
+        self.game_mode = GameMode::Versus;
+        self.challenge = None;
+        self.wingman = Some(Wingman::new());
+        self.versus = Some(VersusMatch::new(best_of));
+        self.apply_bullet_limit();
+
+        self.saucers.clear();
+        self.turrets.clear();
+        self.hunters.clear();
+        self.entities.clear();
+        self.wave_number = 1;
+        self.sound_director.reset(self.clock.now());
+
+        self.start_round();
+
+        self.touch = false;
+        self.game_state = GameState::Playing;
+    }
+
+    /// Number of large asteroids drifting through a versus round.
+    const VERSUS_ASTEROIDS: u32 = 3;
+
+    /// Start a versus round: both ships respawn on opposite sides of the
+    /// screen among a fresh, light asteroid field.
+    fn start_round(&mut self) {
+        let offset = Vec2::new(WORLD_WIDTH * 0.25, 0.0);
+
+        self.ship.spawn_offset = -offset;
+        self.ship.respawn(self.clock.now());
+
+        if let Some(wingman) = &mut self.wingman {
+            wingman.ship.spawn_offset = offset;
+            wingman.ship.respawn(self.clock.now());
+        }
+
+        self.player_bullets.clear();
+        self.enemy_bullets.clear();
+        self.spawn_versus_field();
+    }
+
+    /// Replace the asteroid field with a light one for a versus round.
+    fn spawn_versus_field(&mut self) {
+        self.asteroids.clear();
+
+        for _ in 0..Self::VERSUS_ASTEROIDS {
+            self.asteroids.push(Asteroid::spawn_new(&self.rng, AsteroidSize::Large.scale()));
+        }
+    }
+
+    /// Start a challenge from the ladder.
+    pub fn start_challenge(&mut self, index: usize) {
+        let scenario = self.ladder.challenges[index].scenario.clone();
+
+        self.seed_game();
+        self.reset_player();
+        self.game_mode = GameMode::Challenge;
+        self.wingman = None;
+        self.versus = None;
+        self.load_scenario(&scenario);
+
+        self.challenge = Some(ChallengeRun {
+            index,
+            stats: ChallengeStats::default(),
+            result: None,
+        });
+
+        self.touch = false;
+        self.game_state = GameState::Playing;
+    }
+
+    /// Start a classic game on a scenario's field from a given seed, with no
+    /// one at the controls, for example to benchmark the simulation.
+    pub fn start_scenario(&mut self, scenario: &Scenario, seed: u64) {
+        self.seed_game_with(seed);
+        self.reset_player();
+        self.game_mode = GameMode::Classic;
+        self.input_log = None;
+        self.watching = None;
+        self.challenge = None;
+        self.wingman = None;
+        self.versus = None;
+        self.load_scenario(scenario);
+
+        self.touch = false;
+        self.game_state = GameState::Playing;
+    }
+
+    /// End the challenge being played, recording the stars earned. Progress
+    /// is saved if the run beat the best on the challenge.
+    fn finish_challenge(&mut self, cleared: bool) {
+        if let Some(run) = &mut self.challenge {
+            run.stats.score = self.player_score;
+
+            let stars = self.ladder.challenges[run.index].stars(cleared, &run.stats);
+            let is_best = self.ladder.record(run.index, stars);
+
+            if is_best && self.persistent {
+                self.ladder.save();
+            }
+
+            if cleared {
+                self.audio.play(SoundEffect::ExtraLife);
+            }
+
+            run.result = Some((stars, is_best));
+        }
+
+        self.record_lifetime_stats();
+        self.game_state = GameState::ChallengeComplete;
+    }
+
+    /// Check something that happened in play against the achievements, and
+    /// pop up a toast for each one it earns. Nothing is earned in the demo,
+    /// or in a game being watched or played a frame at a time.
+    fn earn_achievements(&mut self, event: AchievementEvent) {
+        if self.is_demo() || self.watching.is_some() || self.tas.is_some() {
+            return;
+        }
+
+        let earned = self.achievements.check(&event);
+
+        for achievement in &earned {
+            self.achievement_toasts.push(*achievement, self.clock.now());
+            self.debug_log.push(self.clock.now(), format!("achievement {} earned", achievement.name()));
+        }
+
+        if !earned.is_empty() {
+            if self.persistent {
+                self.achievements.save();
+            }
+
+            self.audio.play(SoundEffect::ExtraLife);
+        }
+    }
+
+    /// Add the game just ended to the lifetime stats, unless it was being
+    /// watched or played a frame at a time, or the world saves nothing.
+    fn record_lifetime_stats(&mut self) {
+        if self.watching.is_some() || self.tas.is_some() || !self.persistent {
+            return;
+        }
+
+        self.lifetime.record_game(self.player_score, self.wave_number, self.asteroids_destroyed);
+        self.lifetime.save();
+    }
+
+    /// Reset the player's ship, lives, score and upgrades for a new game, and
+    /// start a new recording of it.
+    fn reset_player(&mut self) {
+        self.recording.clear();
+        self.debug_log.push(self.clock.now(), format!("game started with seed {}", self.seed));
+        self.saucer_director.telemetry.clear();
+        self.floating_texts.clear();
+        self.shake.clear();
+        self.visibility.clear();
+
+        // The rules hold for the whole game, whatever is changed while paused
+        self.rules = self.settings.rules();
+        self.player_lives = self.settings.starting_lives;
+        self.player_score = 0;
+        self.asteroids_destroyed = 0;
+        self.hyperspace_used = false;
+        self.player_credits = 0;
+        self.overdrive.reset();
+        self.ship.overdrive_expiry = 0.0;
+        self.ship.reset();
+        self.ship.respawn_lifespan = 0.0;
+        self.ship.spawn_offset = Vec2::ZERO;
+        self.ship.apply_balance();
+        self.ship.arc_unlocked = false;
+        self.ship.reverse_thrusters = false;
+        self.ship.lateral_thrusters = false;
+        self.ship.guided_hyperspace = false;
+        self.ship.weapon = WeaponKind::Standard;
+        self.ship.hyperspace_strain = 0.0;
+        self.ship.decoys = 3;
+        self.ship.bombs = Shockwave::STARTING_BOMBS;
+        self.ship.shield_energy = 1.0;
+        self.ship.shield_raised = false;
+        self.decoys.clear();
+        self.shockwaves.clear();
+        self.salvage.clear();
+        self.gold_seam = None;
+        self.gravity_well = None;
+        self.wave_tally = None;
+        self.gravity_well_due = None;
+        self.hunter_due = None;
+        self.player_bullets.clear();
+
+        self.hooks.rearm();
+        self.hook_message = None;
+
+        self.chronos = 0;
+        self.rewound = false;
+        self.snapshots.clear();
+    }
+
+    /// Set up the field described by a scenario, replacing whatever is in
+    /// play.
+    pub fn load_scenario(&mut self, scenario: &Scenario) {
+        self.wave_number = scenario.wave.clamp(1, Self::KILL_SCREEN_WAVE);
+        self.player_lives = scenario.lives;
+        self.ship.decoys = scenario.decoys;
+        self.ship.arc_unlocked = self.wave_number >= 5;
+
+        self.asteroids.clear();
+        self.saucers.clear();
+        self.turrets.clear();
+        self.hunters.clear();
+        self.entities.clear();
+        self.player_bullets.clear();
+        self.enemy_bullets.clear();
+
+        for _ in 0..scenario.large_asteroids {
+            self.asteroids.push(Asteroid::spawn_new(&self.rng, AsteroidSize::Large.scale()));
+        }
+
+        for _ in 0..scenario.medium_asteroids {
+            self.asteroids.push(Asteroid::spawn_new(&self.rng, AsteroidSize::Medium.scale()));
+        }
+
+        for _ in 0..scenario.small_asteroids {
+            self.asteroids.push(Asteroid::spawn_new(&self.rng, AsteroidSize::Small.scale()));
+        }
+
+        for scale in &scenario.rocks {
+            self.asteroids.push(Asteroid::spawn_new(&self.rng, *scale));
+        }
+
+        for _ in 0..scenario.large_saucers {
+            self.spawn_saucer(SaucerSize::Large);
+        }
+
+        for _ in 0..scenario.small_saucers {
+            self.spawn_saucer(SaucerSize::Small);
+        }
+
+        self.sound_director.reset(self.clock.now());
+        self.saucer_director.reset(self.clock.now(), self.difficulty(), self.wave_number, self.player_score);
+    }
+
+    /// Spawn a saucer. Large saucers carry a turret hardpoint in later waves.
+    /// Their bullets fly faster or slower with the difficulty.
+    fn spawn_saucer(&mut self, size: SaucerSize) {
+        let bullet_speed = self.difficulty().bullet_speed();
+
+        let mut saucer = Saucer::spawn_new(&self.rng, size, self.clock.now());
+        saucer.shot_speed *= bullet_speed;
+
+        if matches!(saucer.size, SaucerSize::Large) && self.wave_number >= 4 {
+            let offset = Vec2::new(0.0, saucer.diameter / 2.0);
+            let facing = std::f32::consts::FRAC_PI_2;
+            let pattern = BulletPattern::Spread { count: 3, angle: 0.3 };
+
+            let mut turret = Turret::attach(saucer.id, offset, facing, std::f32::consts::PI, pattern, self.clock.now());
+            turret.shot_speed *= bullet_speed;
+
+            self.turrets.push(turret);
+        }
+
+        let arrived = saucer.size;
+
+        let size = match saucer.size {
+            SaucerSize::Small => "Small",
+            SaucerSize::Large => "Large",
+        };
+
+        // Saucers cross from one side of the field to the other
+        let side = if saucer.velocity.x > 0.0 { "left" } else { "right" };
+
+        self.announce(format!("{} saucer approaching from the {}", size, side));
+        self.saucers.push(saucer);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.run_scripts(|scripts, context| scripts.saucer_arrived(context, arrived));
+    }
+
+    /// Spawn a small saucer to hunt down a lurking player, aiming better than
+    /// the wave's saucers by some accuracy.
+    fn spawn_lurker_hunter(&mut self, accuracy_bonus: f32) {
+        self.spawn_saucer(SaucerSize::Small);
+
+        if let Some(saucer) = self.saucers.last_mut() {
+            saucer.accuracy_bonus = accuracy_bonus;
+        }
+    }
+
+    /// The final wave. Waves never count past it: once reached, every
+    /// following wave is the kill screen again.
+    pub const KILL_SCREEN_WAVE: u32 = 99;
+
+    /// Most large asteroids a wave starts with.
+    pub const MAX_WAVE_ASTEROIDS: u32 = Difficulty::MAX_WAVE_ASTEROIDS;
+
+    /// Number of the wave following the given one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::GameWorld;
+    /// let mut wave = 0;
+    ///
+    /// for _ in 0..100_000 {
+    ///     wave = GameWorld::next_wave_number(wave);
+    ///     assert!(GameWorld::wave_asteroid_count(wave) <= GameWorld::MAX_WAVE_ASTEROIDS);
+    /// }
+    ///
+    /// assert_eq!(wave, GameWorld::KILL_SCREEN_WAVE);
+    /// ```
+    pub fn next_wave_number(wave: u32) -> u32 {
+        wave.saturating_add(1).min(Self::KILL_SCREEN_WAVE)
+    }
+
+    /// Number of large asteroids a wave starts with on Normal difficulty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::GameWorld;
+    /// assert_eq!(GameWorld::wave_asteroid_count(1), 5);
+    /// assert_eq!(GameWorld::wave_asteroid_count(GameWorld::KILL_SCREEN_WAVE), GameWorld::MAX_WAVE_ASTEROIDS);
+    /// ```
+    pub fn wave_asteroid_count(wave: u32) -> u32 {
+        Difficulty::Normal.asteroid_count(wave)
+    }
+
+    /// Scale of the asteroids making up a wave with the giant rocks mutator.
+    const GIANT_ROCK_SCALE: f32 = 1.5;
+
+    /// Difficulty of the game being played. Weekly and ladder challenges are
+    /// always played on Normal, so their scores and stars compare fairly, and
+    /// shared games on the difficulty they were played on.
+    pub fn difficulty(&self) -> Difficulty {
+        match (self.game_mode, &self.watching) {
+            (GameMode::Weekly | GameMode::Challenge, _) => Difficulty::Normal,
+            (_, Some((code, _))) => code.difficulty,
+            _ => self.settings.difficulty,
+        }
+    }
+
+    /// Seconds the player's shield lasts and takes to recharge, by the rules
+    /// fixed when the game started. Like the difficulty, weekly and ladder
+    /// challenges keep the defaults, and shared games the tuning they were
+    /// played with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::options::LaunchOptions;
+    /// # use asteroids::world::GameWorld;
+    /// let mut world = GameWorld::headless(&LaunchOptions::default());
+    /// world.settings.shield_duration = 5.0;
+    /// world.start(false);
+    ///
+    /// // Changing the options while paused waits for the next game
+    /// world.settings.shield_duration = 1.0;
+    /// assert_eq!(world.shield_tuning().0, 5.0);
+    /// ```
+    pub fn shield_tuning(&self) -> (f32, f32) {
+        match self.game_mode {
+            GameMode::Weekly | GameMode::Challenge => (Ship::SHIELD_DURATION, Ship::SHIELD_RECHARGE),
+            _ => (self.rules.shield_duration, self.rules.shield_recharge),
+        }
+    }
+
+    /// Check if asteroids bounce off each other, by the rules fixed when the
+    /// game started. Like the shield's tuning, asteroids always pass through
+    /// each other in weekly and ladder challenges.
+    pub fn is_bouncing_asteroids(&self) -> bool {
+        match self.game_mode {
+            GameMode::Weekly | GameMode::Challenge => false,
+            _ => self.rules.bouncing_asteroids,
+        }
+    }
+
+    /// Check if the arcade scoring ruleset is used, crediting the first player
+    /// with whatever is destroyed in play, not only what the players destroy,
+    /// by the rules fixed when the game started. Like bouncing asteroids, it
+    /// is not used in weekly and ladder challenges.
+    pub fn is_arcade_scoring(&self) -> bool {
+        match self.game_mode {
+            GameMode::Weekly | GameMode::Challenge => false,
+            _ => self.rules.arcade_scoring,
+        }
+    }
+
+    /// Player credited with what a cause destroyed: the player who destroyed
+    /// it, or with arcade scoring, the first player whatever destroyed it.
+    fn credited_player(&self, cause: Cause) -> Option<usize> {
+        match cause.player() {
+            Some(player) => Some(player),
+            None if self.is_arcade_scoring() => Some(PLAYER_ONE),
+            None => None,
+        }
+    }
+
+    /// Check if the current wave is the kill screen.
+    pub fn is_kill_screen(&self) -> bool {
+        self.wave_number == Self::KILL_SCREEN_WAVE
+    }
+
+    /// Start a new wave.
+    pub fn next_wave(&mut self) {
+        let now = self.clock.now();
+
+        let before_kill_screen = !self.is_kill_screen();
+
+        // Restore the ships' consumables between waves, but not before the first
+        self.wave_summary = None;
+        self.wave_stats = WaveStats::default();
+        self.lurker_watch.reset();
+
+        if self.wave_number > 0 {
+            let report = self.carry_over.apply(&mut self.ship, now);
+
+            if let Some(wingman) = &mut self.wingman {
+                self.carry_over.apply(&mut wingman.ship, now);
+            }
+
+            self.wave_summary = report.summary().map(|summary| (now, summary));
+        }
+
+        self.wave_number = Self::next_wave_number(self.wave_number);
+        self.announce(format!("Wave {}", self.wave_number));
+        self.earn_achievements(AchievementEvent::WaveReached { wave: self.wave_number, hyperspace_used: self.hyperspace_used });
+
+        // Celebrate the first time the kill screen is reached
+        if self.is_kill_screen() && before_kill_screen {
+            self.kill_screen_time = now;
+
+            for i in 1..=3 {
+                let centre = WORLD_SIZE / 2.0;
+                self.particles.append(&mut Particle::spawn_ring(&self.effects_rng, centre, WORLD_WIDTH * 0.1 * i as f32, 200));
+            }
+
+            self.audio.play(SoundEffect::ExtraLife);
+            self.announce("Kill screen");
+        }
+
+        self.asteroids.clear();
+
+        let scale = if self.has_mutator(Mutator::GiantRocks) {
+            Self::GIANT_ROCK_SCALE
+        } else {
+            AsteroidSize::Large.scale()
+        };
+
+        for _ in 0..self.difficulty().asteroid_count(self.wave_number) {
+            let kind = AsteroidKind::roll(&self.rng, self.wave_number);
+            self.asteroids.push(Asteroid::spawn_kind(&self.rng, scale, kind));
+        }
+
+        self.saucers.clear();
+        self.turrets.clear();
+        self.hunters.clear();
+        self.entities.clear();
+        self.gold_seam = None;
+        self.sound_director.reset(now);
+
+        // A gravity well may open partway through the wave
+        self.gravity_well = None;
+        self.gravity_well_due = match self.game_mode {
+            GameMode::Classic | GameMode::Coop | GameMode::Weekly => {
+                GravityWell::schedule(&self.rng, self.wave_number).map(|delay| now + delay)
+            },
+            _ => None,
+        };
+
+        // From a late enough wave, a hunter joins partway through
+        self.hunter_due = match self.game_mode {
+            GameMode::Classic | GameMode::Coop | GameMode::Weekly if self.wave_number >= Hunter::FIRST_WAVE => {
+                Some(now + Hunter::ARRIVAL_DELAY)
+            },
+            _ => None,
+        };
+
+        // Chain lightning becomes available in later waves
+        if self.wave_number >= 5 {
+            self.ship.arc_unlocked = true;
+        }
+        self.saucer_director.reset(now, self.difficulty(), self.wave_number, self.player_score);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        self.run_scripts(|scripts, context| scripts.wave_started(context));
+
+        let wave = self.wave_number;
+        self.run_plugins(|plugin, world| plugin.on_wave_start(world, wave));
+    }
+
+    /// Read the player's input for this frame from the keyboard, mouse, touch
+    /// screen and gamepad, as the controls it works. In a game started by
+    /// touch, the touch screen works the on-screen controls.
+    fn read_input(&mut self) -> InputFrame {
+        let gamepad = self.gamepad.poll();
+
+        let touch = if self.touch {
+            self.touch_controls().read(touches().iter().map(|touch| touch.position))
+        } else {
+            TouchInput::default()
+        };
+
+        self.touch_input = touch;
+
+        // Steering
+        let mut steering : GameInput = GameInput::None;
+        
+        // Translate inputs into steering; touches also move the mouse, so it
+        // only steers when the game was not started by touch
+        if touch.stick != Vec2::ZERO {
+            steering = self.steer_towards(touch.stick);
+        } else if !self.touch && is_mouse_button_down(MouseButton::Left) {
+            let mouse_position = camera::screen_to_world(mouse_position().into());
+            steering = self.steer_towards(mouse_position - self.ship.position);
+        } else if self.bindings.is_down(Action::SteerLeft) {
+            steering = GameInput::Left;
+        } else if self.bindings.is_down(Action::SteerRight) {
+            steering = GameInput::Right;
+        } else if gamepad.steer != 0.0 {
+            steering = GameInput::Steer(gamepad.steer);
+        }
+
+        let steer = match steering {
+            GameInput::Left => -0.1,
+            GameInput::Right => 0.1,
+            GameInput::Steer(amount) => 0.1 * amount,
+            _ => 0.0,
+        };
+
+        // Thrusters
+        let mut buttons = 0;
+
+        if is_mouse_button_down(MouseButton::Right) || self.bindings.is_down(Action::Thrust) || touch.thrust || gamepad.thrust {
+            buttons |= InputFrame::THRUST;
+        } 
+
+        // Reverse and lateral thrusters, if fitted
+        if self.bindings.is_down(Action::ReverseThrust) {
+            buttons |= InputFrame::REVERSE_THRUST;
+        } else if self.bindings.is_down(Action::StrafeLeft) {
+            buttons |= InputFrame::STRAFE_LEFT;
+        } else if self.bindings.is_down(Action::StrafeRight) {
+            buttons |= InputFrame::STRAFE_RIGHT;
+        }
+
+        if self.bindings.is_down(Action::Hyperspace) || touch.hyperspace || gamepad.hyperspace {
+            buttons |= InputFrame::HYPERSPACE;
+        }
+
+        if self.bindings.is_pressed(Action::Radar) {
+            buttons |= InputFrame::RADAR;
+        }
+
+        if self.bindings.is_pressed(Action::Decoy) {
+            buttons |= InputFrame::DECOY;
+        }
+
+        if self.bindings.is_pressed(Action::Rewind) {
+            buttons |= InputFrame::REWIND;
+        }
+
+        if self.bindings.is_pressed(Action::Bomb) || gamepad.bomb {
+            buttons |= InputFrame::BOMB;
+        }
+
+        if self.bindings.is_down(Action::Shield) || gamepad.shield {
+            buttons |= InputFrame::SHIELD;
+        }
+
+        // Cycle weapons; a debugging aid until weapons can be picked up
+        if cfg!(debug_assertions) && is_key_pressed(KeyCode::F2) {
+            self.ship.weapon = self.ship.weapon.next();
+        }
+
+        if self.bindings.is_pressed(Action::Overdrive) || gamepad.overdrive {
+            buttons |= InputFrame::OVERDRIVE;
+        }
+
+        if self.bindings.is_pressed(Action::Lightning) {
+            buttons |= InputFrame::LIGHTNING;
+        }
+
+        // Translate inputs into shooting
+        if self.bindings.is_pressed(Action::Fire) || touch.fire || gamepad.fire {
+            buttons |= InputFrame::FIRE;
+        }
+
+        InputFrame::new(steer, buttons, get_frame_time())
+    }
+
+    /// Steering that turns the ship to face a direction, if it is not already.
+    fn steer_towards(&self, direction: Vec2) -> GameInput {
+        let ship_direction = Mat2::from_angle(self.ship.rotation).mul_vec2(Vec2::Y);
+        let angle_difference = ship_direction.angle_between(direction.normalize_or_zero());
+
+        if angle_difference > 0.1 {
+            GameInput::Left
+        } else if angle_difference < -0.1 {
+            GameInput::Right
+        } else {
+            GameInput::None
+        }
+    }
+
+    /// On-screen touch controls, laid out for the window.
+    fn touch_controls(&self) -> TouchControls {
+        TouchControls::new(self.settings.touch_layout, Vec2::new(screen_width(), screen_height()))
+    }
+
+    /// Step the world on by a time in seconds, with the controls worked by
+    /// player one, without reading any device or drawing. A game in play
+    /// plays a frame as it would from the player's controls; otherwise the
+    /// world just updates, so the demo and the field behind the menus drift
+    /// on. The world's clock moves on by the time, to the millisecond in
+    /// which frames of a game are recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use asteroids::options::LaunchOptions;
+    /// # use asteroids::replaycode::InputFrame;
+    /// # use asteroids::world::GameWorld;
+    /// let options = LaunchOptions { seed: Some(1979), ..Default::default() };
+    /// let mut world = GameWorld::headless(&options);
+    /// world.start(false);
+    ///
+    /// for frame in 0..600 {
+    ///     let buttons = if frame % 20 == 0 { InputFrame::FIRE } else { 0 };
+    ///     world.step(1.0 / 60.0, InputFrame::new(0.02, buttons, 1.0 / 60.0));
+    /// }
+    ///
+    /// // A seeded game plays out the same way every time
+    /// let mut again = GameWorld::headless(&options);
+    /// again.start(false);
+    ///
+    /// for frame in 0..600 {
+    ///     let buttons = if frame % 20 == 0 { InputFrame::FIRE } else { 0 };
+    ///     again.step(1.0 / 60.0, InputFrame::new(0.02, buttons, 1.0 / 60.0));
+    /// }
+    ///
+    /// assert_eq!(world.player_score, again.player_score);
+    /// assert_eq!(world.ship.position, again.ship.position);
+    /// assert_eq!(world.asteroids.len(), again.asteroids.len());
+    /// ```
+    pub fn step(&mut self, dt: f32, input: InputFrame) {
+        if self.is_playing() {
+            self.play_frame(InputFrame::new(input.steer(), input.buttons, dt));
+        } else {
+            self.clock.advance(dt);
+            self.update();
+        }
+    }
+
+    /// Fly the player's ship with the controls worked on a frame. Code driving
+    /// the world itself, such as a bot, calls this before each `update` in
+    /// place of the game loop reading the player's controls.
+    pub fn apply_input(&mut self, input: InputFrame) {
+        let now = self.clock.now();
+
+        // Steer ship
+        self.ship.steer(input.steer(), now);
+
+        // Thrust and acceleration
+        let mut thrusting = false;
+
+        if input.is_down(InputFrame::THRUST) {
+            self.ship.thrust();
+
+            self.particles.append(&mut Particle::spawn_conical(&self.effects_rng, self.ship.get_exhaust_position(), self.ship.rotation, 0.5, 1));
+            thrusting = true;
+        }
+
+        if input.is_down(InputFrame::REVERSE_THRUST) && self.ship.reverse_thrust() {
+            let position = self.ship.get_reverse_exhaust_position();
+            self.particles.append(&mut Particle::spawn_conical(&self.effects_rng, position, self.ship.rotation + std::f32::consts::PI, 0.3, 1));
+            thrusting = true;
+        } else if input.is_down(InputFrame::STRAFE_LEFT) || input.is_down(InputFrame::STRAFE_RIGHT) {
+            let direction = if input.is_down(InputFrame::STRAFE_LEFT) { -1.0 } else { 1.0 };
+
+            if self.ship.strafe(direction) {
+                // Exhaust leaves the wing opposite the direction of travel
+                let position = self.ship.get_lateral_exhaust_position(direction);
+                let exhaust = self.ship.rotation + direction * std::f32::consts::FRAC_PI_2;
+                self.particles.append(&mut Particle::spawn_conical(&self.effects_rng, position, exhaust, 0.3, 1));
+                thrusting = true;
+            }
+        }
+
+        self.audio.set_looping(SoundEffect::Thrust, thrusting);
+
+        // Hyperspace; with the guided upgrade, holding the key shows where the
+        // ship would land and letting go jumps there
+        let jumping = match self.ship.guided_hyperspace {
+            true if input.is_down(InputFrame::HYPERSPACE) => {
+                self.ship.aim_hyperspace(&self.rng, now);
+                false
+            },
+            true => self.ship.hyperspace_target.is_some(),
+            false => input.is_down(InputFrame::HYPERSPACE),
+        };
+
+        if jumping {
+            if let Some(jump) = self.ship.hyperspace(&self.rng, now) {
+                self.resolve_hyperspace(PLAYER_ONE, jump);
+            }
+        }
+
+        // Radar pulse, lighting up the field in low visibility
+        if input.is_down(InputFrame::RADAR) && self.is_low_visibility() {
+            self.visibility.pulse(self.ship.position, now);
+        }
+
+        // Decoy
+        if input.is_down(InputFrame::DECOY) {
+            if let Some(decoy) = self.ship.deploy_decoy(now) {
+                self.decoys.push(decoy);
+            }
+        }
+
+        // Smart bomb
+        if input.is_down(InputFrame::BOMB) && self.ship.detonate_bomb() {
+            self.shockwaves.push(Shockwave::spawn_new(self.ship.position));
+            self.gamepad.rumble(Rumble::SmartBomb);
+            self.shake.add(Shake::LargeExplosion);
+            self.audio.play(SoundEffect::LargeExplosion);
+        }
+
+        // Shield, held up while its energy lasts
+        self.ship.hold_shield(input.is_down(InputFrame::SHIELD) && !self.ship.is_respawning(now));
+
+        // Overdrive
+        if input.is_down(InputFrame::OVERDRIVE) && self.overdrive.activate(now) {
+            self.ship.engage_overdrive(Overdrive::DURATION, now);
+        }
+
+        // Chain lightning
+        if input.is_down(InputFrame::LIGHTNING) {
+            if let Some(origin) = self.ship.discharge(now) {
+                self.chain_lightning(origin);
+            }
+        }
+
+        // Shooting
+        if input.is_down(InputFrame::FIRE) {
+            let mut bullets = self.ship.shoot(Self::live_bullets(&self.player_bullets, PLAYER_ONE), now);
+
+            if !bullets.is_empty() {
+                self.player_bullets.append(&mut bullets);
+
+                self.audio.play(SoundEffect::Shot);
+                self.gamepad.rumble(Rumble::Shot);
+                self.wave_stats.shots += 1;
+
+                if let Some(run) = &mut self.challenge {
+                    run.stats.shots += 1;
+                }
+            }
+        }
+    }
+
+    /// Handle the second player's input, from their keys on the shared
+    /// keyboard or the second gamepad.
+    fn wingman_input(&mut self, gamepad: GamepadState) {
+        let now = self.clock.now();
+
+        let Some(wingman) = &mut self.wingman else {
+            return;
+        };
+
+        // Steering
+        if is_key_down(Wingman::STEER_LEFT) {
+            wingman.ship.steer(-0.1, now);
+        } else if is_key_down(Wingman::STEER_RIGHT) {
+            wingman.ship.steer(0.1, now);
+        } else if gamepad.steer != 0.0 {
+            wingman.ship.steer(0.1 * gamepad.steer, now);
+        } else {
+            wingman.ship.steer(0.0, now);
+        }
+
+        // Thrust and acceleration
+        if is_key_down(Wingman::THRUST) || gamepad.thrust {
+            wingman.ship.thrust();
+
+            self.particles.append(&mut Particle::spawn_conical(&self.effects_rng, wingman.ship.get_exhaust_position(), wingman.ship.rotation, 0.5, 1));
+        }
+
+        // Cannon
+        if is_key_pressed(Wingman::FIRE) || gamepad.fire {
+            let mut bullets: Vec<Bullet> = wingman.ship.shoot(Self::live_bullets(&self.player_bullets, PLAYER_TWO), now).into_iter()
+                .map(|bullet| bullet.with_owner(PLAYER_TWO))
+                .collect();
+
+            if !bullets.is_empty() {
+                self.player_bullets.append(&mut bullets);
+                self.audio.play(SoundEffect::Shot);
+                self.wave_stats.shots += 1;
+            }
+        }
+
+        // Hyperspace
+        if is_key_down(Wingman::HYPERSPACE) || gamepad.hyperspace {
+            if let Some(jump) = wingman.ship.hyperspace(&self.rng, now) {
+                self.resolve_hyperspace(PLAYER_TWO, jump);
+            }
+        }
+    }
+
+    /// Ship flown by a player.
+    fn player_ship(&self, player: usize) -> &Ship {
+        match &self.wingman {
+            Some(wingman) if player == PLAYER_TWO => &wingman.ship,
+            _ => &self.ship,
+        }
+    }
+
+    /// Mutable reference to the ship flown by a player.
+    fn player_ship_mut(&mut self, player: usize) -> &mut Ship {
+        match &mut self.wingman {
+            Some(wingman) if player == PLAYER_TWO => &mut wingman.ship,
+            _ => &mut self.ship,
+        }
+    }
+
+    /// Score of a player, borrowed from the world's fields so it can be
+    /// updated while other objects are borrowed.
+    fn player_score_mut<'a>(player_score: &'a mut u32, wingman: &'a mut Option<Wingman>, player: usize) -> &'a mut u32 {
+        match wingman {
+            Some(wingman) if player == PLAYER_TWO => &mut wingman.score,
+            _ => player_score,
+        }
+    }
+
+    /// Act on the outcome of a player's hyperspace jump. A perilous re-entry
+    /// drops the ship right beside an asteroid; a failed one destroys it.
+    fn resolve_hyperspace(&mut self, player: usize, jump: HyperspaceJump) {
+        if jump.outcome == HyperspaceOutcome::Perilous && !self.asteroids.is_empty() {
+            let asteroid = &self.asteroids[self.rng.gen_range(0, self.asteroids.len())];
+            let offset = detmath::direction(self.rng.gen_range(0.0, std::f32::consts::TAU));
+            let distance = asteroid.get_radius() + self.player_ship(player).radius * 2.0;
+            let position = asteroid.position + offset * distance;
+
+            self.player_ship_mut(player).position = position;
+        }
+
+        let position = self.player_ship(player).position;
+        let radius = self.player_ship(player).radius;
+
+        self.particles.append(&mut Particle::spawn_ring(&self.effects_rng, jump.origin, radius * 6.0, 200));
+        self.particles.append(&mut Particle::spawn_ring(&self.effects_rng, position, radius * 6.0, 200));
+
+        self.audio.play(SoundEffect::Hyperspace);
+
+        self.hyperspace_used = true;
+
+        if let Some(run) = &mut self.challenge {
+            run.stats.jumps += 1;
+        }
+
+        if jump.outcome == HyperspaceOutcome::Destroyed {
+            self.particles.append(&mut Particle::spawn_radial(&self.effects_rng, position, 100));
+            self.particles.append(&mut Particle::spawn_debris(&self.effects_rng, position, 50));
+
+            self.audio.play(SoundEffect::LargeExplosion);
+            self.lose_life(player);
+        }
+    }
+
+    /// Font text is drawn in. Headless worlds are never drawn, so have none.
+    fn font(&self) -> &Font {
+        self.font.as_ref().expect("a headless world cannot be drawn")
+    }
+
+    /// Draw all game objects.
+    pub fn draw(&self) {        
+        let now = self.clock.now();
+
+        let (antialiasing, line_width) = (self.settings.antialiasing, self.settings.line_width);
+
+        // Draw the deep field first, through a camera of its own
+        if self.settings.deep_field {
+            let mut lines = self.lines.borrow_mut();
+
+            set_camera(&layer_camera(DeepField::SCALE, self.deep_field.offset(self.shake.offset())));
+            lines.set_style(graphics::line_style(antialiasing, line_width, DeepField::SCALE));
+            self.deep_field.draw(&mut lines);
+        }
+
+        // Draw the game world through a camera fitting the playfield to the
+        // window, and offset to shake it; the HUD is drawn over it unshaken
+        set_camera(&self.shake.camera());
+        self.lines.borrow_mut().set_style(graphics::line_style(antialiasing, line_width, 1.0));
+
+        // Draw the kill screen's garbage beneath everything else
+        if self.is_in_game() && self.is_kill_screen() {
+            self.draw_kill_screen();
+        }
+
+        // Outlines are gathered and drawn a kind of object at a time
+        let mut lines = self.lines.borrow_mut();
+
+        // Draw ship if we're playing, or flying the demo
+        if self.is_in_game() || self.is_demo() {
+            self.ship.draw(&mut lines, now);
+
+            if let Some(wingman) = &self.wingman {
+                wingman.ship.draw(&mut lines, now);
+            }
+
+            lines.draw();
+        }
+
+        // Draw bullets
+        for bullet in &self.player_bullets {
+            bullet.draw();
+        }
+
+        // Draw enemy bullets
+        for bullet in &self.enemy_bullets {
+            bullet.draw_tinted(self.visibility_tint(bullet.position));
+        }
+
+        // Draw asteroids
+        for asteroid in &self.asteroids {
+            asteroid.draw_tinted(&mut lines, self.visibility_tint(asteroid.position));
+        }
+
+        lines.draw();
+
+        // Draw saucers, hunters and custom entities
+        for saucer in &self.saucers {
+            saucer.draw_tinted(&mut lines, self.visibility_tint(saucer.position));
+        }
+
+        for hunter in &self.hunters {
+            hunter.draw_tinted(&mut lines, self.visibility_tint(hunter.position));
+        }
+
+        for entity in &self.entities {
+            entity.draw(&mut lines, self.visibility_tint(entity.get_position()));
+        }
+
+        lines.draw();
+
+        // Draw radio chatter beside the saucers it comes from
+        for burst in &self.chatter {
+            if let Some(saucer) = self.saucers.iter().find(|saucer| saucer.id == burst.saucer) {
+                burst.draw(self.font(), &self.text_widths, saucer.position, saucer.diameter);
+            }
+        }
+
+        // Draw turrets
+        for turret in &self.turrets {
+            turret.draw_tinted(self.visibility_tint(turret.position));
+        }
+
+        // Draw the gold seam bonus round's rock
+        if let Some(gold_seam) = &self.gold_seam {
+            gold_seam.draw(&mut lines, now);
+        }
+
+        // Draw the radar pulse sweeping the field
+        if self.is_low_visibility() {
+            self.visibility.draw(now);
+        }
+
+        // Draw decoys
+        for decoy in &self.decoys {
+            decoy.draw(now);
+        }
+
+        // Draw the gravity well
+        if let Some(well) = &self.gravity_well {
+            well.draw();
+        }
+
+        // Draw smart bomb shockwaves
+        for shockwave in &self.shockwaves {
+            shockwave.draw();
+        }
+
+        // Draw salvage crates
+        for salvage in &self.salvage {
+            salvage.draw(now);
+        }
+
+        // Draw lightning bolts
+        for bolt in &self.bolts {
+            bolt.draw();
+        }
+
+        // Draw particles
+        for particle in &self.particles {
+            particle.draw();
+        }
+
+        // Draw score popups
+        if self.is_in_game() {
+            for popup in &self.floating_texts {
+                popup.draw(self.font(), &self.text_widths);
+            }
+        }
+
+        // Draw the running combo around the ship, if the HUD puts it there
+        if self.is_playing() && self.hud_visible && self.hud_layout.combo && self.hud_layout.combo_anchor == ComboAnchor::Ship {
+            if let Some(combo) = self.overdrive.combo(now) {
+                self.hud.draw_combo_ring(self.font(), combo, self.ship.position, self.ship.radius * 2.5);
+            }
+        }
+
+        set_default_camera();
+
+        // Announce the kill screen when it is first reached
+        if self.is_in_game() && self.is_kill_screen() && now - self.kill_screen_time < Self::KILL_SCREEN_BANNER_DURATION {
+            self.draw_centered_text("Kill Screen", screen_height() * 0.4, 60);
+            self.draw_centered_text("You have beaten the machine", screen_height() * 0.4 + 50.0, 30);
+        }
+
+        // Draw the round score in a versus match instead of the HUD
+        if self.is_in_game() && self.hud_visible && self.game_mode == GameMode::Versus {
+            self.draw_versus_hud();
+        }
+
+        // Draw HUD
+        if self.is_in_game() && self.hud_visible && self.game_mode != GameMode::Versus {
+            self.hud.draw(self.font(), &self.hud_layout, &self.hud_status());
+        }
+
+        self.draw_achievement_toast();
+
+        // Draw the on-screen controls in a game started by touch
+        if self.touch && self.is_playing() {
+            self.touch_controls().draw(&self.touch_input);
+        }
+
+        // Draw where a tool-assisted run is up to
+        if let Some(tas) = self.tas.as_ref().filter(|_| self.is_in_game() && self.game_mode == GameMode::Classic && self.watching.is_none()) {
+            let mut status = format!(
+                "TAS  frame {}  slot {}{}",
+                tas.movie.frames.len(), tas.slots.selected + 1, if tas.paused { "  paused" } else { "" }
+            );
+
+            if let Some(notice) = &tas.notice {
+                status += &format!("  {}", notice);
+            }
+
+            draw_text_ex(&status, 20.0, screen_height() - 20.0, TextParams { font_size: 20, font: Some(self.font()), ..Default::default() });
+        }
+
+        if self.telemetry_visible && self.is_in_game() {
+            self.saucer_director.telemetry.draw(self.font());
+        }
+
+        // Draw what was restored between waves
+        if let Some((time, summary)) = &self.wave_summary {
+            if self.is_playing() && now - time < Self::WAVE_SUMMARY_DURATION {
+                self.draw_centered_text(summary, screen_height() * 0.25, 30);
+            }
+        }
+
+        // Draw the time left in a gold seam bonus round, or to start it
+        if let Some(gold_seam) = &self.gold_seam {
+            let time_left = gold_seam.time_left(now);
+            let banner = match gold_seam.started_at {
+                Some(_) => format!("Gold Seam  {:.1}", time_left),
+                None => "Gold Seam! Shoot the rock to mine it".to_string(),
+            };
+
+            self.draw_centered_text(&banner, screen_height() * 0.2, 36);
+        }
+
+        // Draw what was found in the last salvage crate collected
+        if let Some((time, contents)) = &self.salvage_notice {
+            if self.is_playing() && now - time < Self::SALVAGE_NOTICE_DURATION {
+                self.draw_centered_text(contents.description(), screen_height() * 0.3, 30);
+            }
+        }
+
+        // Draw the line shown by the last event hook to fire
+        if let Some((time, text)) = &self.hook_message {
+            if self.is_playing() && now - time < Self::HOOK_MESSAGE_DURATION {
+                self.draw_centered_text(text, screen_height() * 0.35, 30);
+            }
+        }
+
+        // Draw captions of the latest announcements, above anything along the
+        // bottom of the screen
+        if self.settings.announcements {
+            let captions: Vec<&str> = self.announcer.captions.visible(now).collect();
+
+            for (row, caption) in captions.iter().rev().enumerate() {
+                self.draw_centered_text(caption, screen_height() - 60.0 - row as f32 * 28.0, 24);
+            }
+        }
+
+        // Draw where the last bug report was saved
+        if let Some((time, text)) = &self.bug_report_notice {
+            if now - time < Self::BUG_REPORT_NOTICE_DURATION {
+                self.draw_centered_text(text, 40.0, 24);
+            }
+        }
+
+        // Draw game over if we're dead
+        if self.is_game_over() {
+            let text_width = self.text_widths.width(self.font(), "Game Over", 60);
+            draw_text_ex(
+                "Game Over", (screen_width() - text_width) / 2.0, screen_height() / 2.0,
+                TextParams {
+                    font_size: 60,
+                    font: Some(self.font()),
+                    ..Default::default()
+                },
+            );
+        }
+
+        // Draw initials entry
+        if self.game_state == GameState::EnterInitials {
+            self.draw_initials_entry();
+        }
+
+        // Draw high score table in attract mode
+        if self.is_showing_high_scores() {
+            self.draw_high_scores();
+        }
+
+        // Draw attract mode text
+        if self.is_attract_mode() && !self.is_showing_high_scores() {
+            let text_width = self.text_widths.width(self.font(), "Asteroids", 90);
+            draw_text_ex(
+                "Asteroids", (screen_width() - text_width) / 2.0, screen_height() / 2.0,
+                TextParams {
+                    font_size: 90,
+                    font: Some(self.font()),
+                    ..Default::default()
+                },
+            );
+
+            self.draw_centered_text(&self.weekly_text, screen_height() / 2.0 + 50.0, 24);
+        }
+
+        if self.is_attract_mode() {
+            let text_width = self.text_widths.width(self.font(), "Press [SPACE] to Start", 40);
+            draw_text_ex(
+                "Press [SPACE] to Start", (screen_width() - text_width) / 2.0, screen_height() - 50.0,
+                TextParams {
+                    font_size: 40,
+                    font: Some(self.font()),
+                    ..Default::default()
+                },
+            );
+
+            self.draw_centered_text("[2] Co-op  [3] Versus  [W] Weekly  [L] Challenges  [S] Stats  [C] Replay Code  [O] Options", screen_height() - 15.0, 24);
+
+            // Let the player know an asset could not be loaded
+            if let Some(error) = &self.asset_error {
+                self.draw_centered_text(error, 30.0, 20);
+            }
+        }
+
+    }
+
+    /// Draw text horizontally centred on the screen.
+    fn draw_centered_text(&self, text: &str, y: f32, font_size: u16) {
+        let text_width = self.text_widths.width(self.font(), text, font_size);
+        draw_text_ex(
+            text, (screen_width() - text_width) / 2.0, y,
+            TextParams {
+                font_size,
+                font: Some(self.font()),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Layout of the pause menu.
+    const PAUSE_MENU: MenuLayout = MenuLayout { top: 0.35, offset: 80.0, row_height: 50.0, font_size: 40, width: 400.0, visible_rows: 6 };
+
+    /// Layout of the upgrade shop.
+    const SHOP_MENU: MenuLayout = MenuLayout { top: 0.3, offset: 120.0, row_height: 50.0, font_size: 36, width: 520.0, visible_rows: 6 };
+
+    /// Layout of the controls screen.
+    const CONTROLS_MENU: MenuLayout = MenuLayout { top: 0.12, offset: 60.0, row_height: 32.0, font_size: 30, width: 480.0, visible_rows: 13 };
+
+    /// Draw pause menu over a translucent backdrop, marking the selected option.
+    fn draw_pause_menu(&self) {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
+
+        self.draw_centered_text("Paused", screen_height() * 0.35, 60);
+
+        let items: Vec<MenuItem> = PauseOption::ALL.iter().map(|option| MenuItem::button(option.label())).collect();
+        self.pause_menu.draw(&items, self.font(), &self.text_widths);
+    }
+
+    /// Upgrades on sale, with their prices, or marked as fitted. Upgrades
+    /// already fitted cannot be bought again.
+    fn shop_items(&self) -> Vec<MenuItem> {
+        Upgrade::ALL.iter().map(|upgrade| {
+            if upgrade.is_fitted(&self.ship) {
+                MenuItem::choice(upgrade.label(), "Fitted").enabled(false)
+            } else {
+                MenuItem::choice(upgrade.label(), format!("{} cr", upgrade.price()))
+            }
+        }).collect()
+    }
+
+    /// Draw upgrade shop over a translucent backdrop, marking the selected
+    /// upgrade and those already fitted.
+    fn draw_shop(&self) {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
+
+        self.draw_centered_text("Upgrades", screen_height() * 0.3, 60);
+        self.draw_centered_text(&format!("Credits: {}", self.player_credits), screen_height() * 0.3 + 50.0, 30);
+
+        self.shop_menu.draw(&self.shop_items(), self.font(), &self.text_widths);
+
+        self.draw_centered_text("[ENTER] Buy  [ESC] Back", screen_height() - 50.0, 30);
+    }
+
+    /// Each action with its key, followed by the entry resetting them all.
+    fn controls_items(&self) -> Vec<MenuItem> {
+        Action::ALL.iter()
+            .map(|action| MenuItem::key(action.label(), input::key_label(self.bindings.key(*action))))
+            .chain(std::iter::once(MenuItem::button("Reset to Defaults")))
+            .collect()
+    }
+
+    /// Draw controls screen over a translucent backdrop, listing each action
+    /// with its key and marking the selected action.
+    fn draw_controls(&self) {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.8));
+
+        self.draw_centered_text("Controls", screen_height() * 0.12, 60);
+
+        self.controls_menu.draw(&self.controls_items(), self.font(), &self.text_widths);
+
+        let help = if self.controls_menu.is_capturing() {
+            "Press a key to bind  [ESC] Cancel"
+        } else {
+            "[ENTER] Rebind  [ESC] Back"
+        };
+
+        self.draw_centered_text(help, screen_height() - 50.0, 30);
+    }
+
+    /// Draw the tip being shown over a translucent backdrop.
+    fn draw_tip(&self) {
+        let Some(mechanic) = self.tip else {
+            return;
+        };
+
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
+
+        let top = screen_height() * 0.35;
+
+        self.draw_centered_text(mechanic.title(), top, 50);
+
+        for (i, line) in mechanic.tip().iter().enumerate() {
+            self.draw_centered_text(line, top + 60.0 + i as f32 * 36.0, 30);
+        }
+
+        self.draw_centered_text("[ENTER] Continue", screen_height() - 50.0, 30);
+    }
+
+    /// Draw the tally of the wave just cleared, a line for each bonus.
+    fn draw_wave_tally(&self) {
+        let Some((_, tally)) = &self.wave_tally else {
+            return;
+        };
+
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
+
+        let top = screen_height() * 0.3;
+
+        self.draw_centered_text(&format!("Wave {} Cleared", tally.wave), top, 50);
+
+        for (i, (line, bonus)) in tally.lines().iter().enumerate() {
+            self.draw_centered_text(&format!("{}   +{}", line, bonus), top + 60.0 + i as f32 * 36.0, 30);
+        }
+
+        self.draw_centered_text(&format!("Bonus  {}", tally.total()), top + 80.0 + 4.0 * 36.0, 36);
+    }
+
+    /// Layout of the options screen, listing twelve settings at once.
+    const OPTIONS_MENU: MenuLayout = MenuLayout { top: 0.12, offset: 60.0, row_height: 32.0, font_size: 28, width: 560.0, visible_rows: 12 };
+
+    /// Each setting with its value. Volume and screen shake are shown as
+    /// sliders.
+    fn options_items(&self) -> Vec<MenuItem> {
+        Setting::ALL.iter().map(|setting| {
+            let value = self.setting_value(*setting);
+
+            match setting {
+                Setting::Volume => MenuItem::slider(setting.label(), self.settings.volume, value),
+                Setting::ScreenShake => MenuItem::slider(setting.label(), self.settings.screen_shake, value),
+                Setting::ExportProfile | Setting::ImportProfile => MenuItem::button(setting.label()),
+                _ => MenuItem::choice(setting.label(), value),
+            }
+        }).collect()
+    }
+
+    /// Draw options screen over a translucent backdrop, listing the settings
+    /// around the selected one with their values, and the outcome of the last
+    /// profile export or import.
+    fn draw_options(&self) {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.8));
+
+        self.draw_centered_text("Options", screen_height() * 0.12, 60);
+
+        self.options_menu.draw(&self.options_items(), self.font(), &self.text_widths);
+
+        if let Some(status) = &self.profile_status {
+            self.draw_centered_text(status, screen_height() - 90.0, 24);
+        }
+
+        self.draw_centered_text("[LEFT/RIGHT] Change  [ESC] Back", screen_height() - 50.0, 30);
+    }
+
+    /// Layout of the challenge ladder, listing eight challenges at once.
+    const LADDER_MENU: MenuLayout = MenuLayout { top: 0.12, offset: 100.0, row_height: 36.0, font_size: 30, width: 420.0, visible_rows: 8 };
+
+    /// Each challenge with the stars earned on it. Locked challenges can be
+    /// selected, to see what unlocks them, but not played.
+    fn ladder_items(&self) -> Vec<MenuItem> {
+        (0..self.ladder.challenges.len()).map(|index| {
+            if self.ladder.is_unlocked(index) {
+                let challenge = &self.ladder.challenges[index];
+                MenuItem::choice(format!("{:>2}. {}", index + 1, challenge.name), challenge::rating_text(self.ladder.stars(index)))
+            } else {
+                MenuItem::choice(format!("{:>2}. Locked", index + 1), "").enabled(false)
+            }
+        }).collect()
+    }
+
+    /// Draw challenge ladder over a translucent backdrop: the challenges
+    /// around the selected one with the stars earned on each, followed by the
+    /// selected challenge's objectives or what it takes to unlock it.
+    fn draw_ladder(&self) {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.8));
+
+        let top = screen_height() * 0.12;
+
+        self.draw_centered_text("Challenges", top, 60);
+        self.draw_centered_text(&format!("Stars: {}", self.ladder.total_stars()), top + 45.0, 30);
+
+        self.ladder_menu.draw(&self.ladder_items(), self.font(), &self.text_widths);
+
+        let selection = self.ladder_menu.selection;
+        let challenge = &self.ladder.challenges[selection];
+        let y = top + 130.0 + Self::LADDER_MENU.visible_rows as f32 * Self::LADDER_MENU.row_height;
+
+        if self.ladder.is_unlocked(selection) {
+            let clear = match challenge.time_limit {
+                Some(limit) => format!("*   Clear the field in {} seconds", limit),
+                None => "*   Clear the field".to_string(),
+            };
+
+            self.draw_centered_text(&clear, y, 26);
+            self.draw_centered_text(&format!("**  {}", challenge.objectives[0].description()), y + 32.0, 26);
+            self.draw_centered_text(&format!("*** {}", challenge.objectives[1].description()), y + 64.0, 26);
+        } else if self.ladder.total_stars() < challenge.unlock_stars {
+            self.draw_centered_text(&format!("Earn {} stars to unlock", challenge.unlock_stars), y, 26);
+        } else {
+            self.draw_centered_text("Clear the previous challenge to unlock", y, 26);
+        }
+
+        self.draw_centered_text("[ENTER] Play  [ESC] Back", screen_height() - 50.0, 30);
+    }
+
+    /// Draw the toast of an achievement just earned, over the top of the HUD.
+    fn draw_achievement_toast(&self) {
+        let Some(achievement) = self.achievement_toasts.visible(self.clock.now()) else {
+            return;
+        };
+
+        let (width, height) = (420.0, 86.0);
+        let x = (screen_width() - width) / 2.0;
+
+        draw_rectangle(x, 12.0, width, height, Color::new(0.0, 0.0, 0.0, 0.8));
+        draw_rectangle_lines(x, 12.0, width, height, 2.0, WHITE);
+
+        self.draw_centered_text(&format!("Achievement: {}", achievement.title()), 46.0, 30);
+        self.draw_centered_text(achievement.description(), 80.0, 24);
+    }
+
+    /// Draw the lifetime stats, a line for each total.
+    fn draw_stats(&self) {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.8));
+
+        let top = screen_height() * 0.25;
+
+        self.draw_centered_text("Lifetime Stats", top, 60);
+
+        for (i, (label, value)) in self.lifetime.lines().iter().enumerate() {
+            self.draw_centered_text(&format!("{}  {}", label, value), top + 80.0 + i as f32 * 40.0, 32);
+        }
+
+        self.draw_centered_text("[ESC] Back", screen_height() - 50.0, 30);
+    }
+
+    /// Draw the result of the challenge just played over a translucent
+    /// backdrop: the stars earned and which objectives were met.
+    fn draw_challenge_result(&self) {
+        let Some(run) = &self.challenge else {
+            return;
+        };
+
+        let Some((stars, is_best)) = run.result else {
+            return;
+        };
+
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
+
+        let challenge = &self.ladder.challenges[run.index];
+        let title = if stars > 0 { "Challenge Complete" } else { "Challenge Failed" };
+        let top = screen_height() * 0.25;
+
+        self.draw_centered_text(title, top, 60);
+        self.draw_centered_text(&challenge.name, top + 50.0, 36);
+        self.draw_centered_text(&challenge::rating_text(stars), top + 130.0, 80);
+
+        if is_best {
+            self.draw_centered_text("New Best!", top + 175.0, 30);
+        }
+
+        for (i, objective) in challenge.objectives.iter().enumerate() {
+            let mark = if stars > 0 && objective.is_met(&run.stats) { "*" } else { "-" };
+            self.draw_centered_text(&format!("{} {}", mark, objective.description()), top + 230.0 + i as f32 * 36.0, 30);
+        }
+
+        self.draw_centered_text("[SPACE] Continue", screen_height() - 50.0, 30);
+    }
+
+    /// Time in seconds a rewind rolls the field back.
+    const REWIND_SECONDS: f64 = 3.0;
+
+    /// Time in seconds between snapshots kept for a rewind.
+    const SNAPSHOT_INTERVAL: f64 = 0.25;
+
+    /// Time in seconds the ships are shielded for after a rewind, to find
+    /// their bearings.
+    const REWIND_SHIELD: f64 = 1.5;
+
+    /// Check if chronos can be found and used: in classic and co-op games,
+    /// but not in the ranked challenges.
+    fn is_rewind_allowed(&self) -> bool {
+        matches!(self.game_mode, GameMode::Classic | GameMode::Coop)
+    }
+
+    /// Chronos held, each good for one rewind. While any are held, the game
+    /// is snapshotted every `SNAPSHOT_INTERVAL` to rewind to.
+    pub fn chronos(&self) -> u32 {
+        self.chronos
+    }
+
+    /// Check if a chrono can be used to rewind now.
+    fn can_rewind(&self) -> bool {
+        self.chronos > 0 && self.is_rewind_allowed() && !self.snapshots.is_empty()
+    }
+
+    /// Snapshot of the field and players, for a rewind.
+    fn snapshot(&self) -> FieldSnapshot {
+        FieldSnapshot {
+            asteroids: self.asteroids.clone(),
+            saucers: self.saucers.clone(),
+            turrets: self.turrets.clone(),
+            hunters: self.hunters.clone(),
+            player_bullets: self.player_bullets.clone(),
+            enemy_bullets: self.enemy_bullets.clone(),
+            decoys: self.decoys.clone(),
+            salvage: self.salvage.clone(),
+            ship: self.ship.clone(),
+            wingman: self.wingman.clone(),
+            player_score: self.player_score,
+            player_lives: self.player_lives,
+            wave_number: self.wave_number,
+        }
+    }
+
+    /// Spend a chrono rolling the field and players back to the oldest
+    /// snapshot kept. A classic game rewound is kept off the high score table.
+    fn rewind(&mut self) {
+        let Some(snapshot) = self.snapshots.take_oldest() else {
+            return;
+        };
+
+        self.asteroids = snapshot.asteroids;
+        self.saucers = snapshot.saucers;
+        self.turrets = snapshot.turrets;
+        self.hunters = snapshot.hunters;
+        self.player_bullets = snapshot.player_bullets;
+        self.enemy_bullets = snapshot.enemy_bullets;
+        self.decoys = snapshot.decoys;
+        self.salvage = snapshot.salvage;
+        self.ship = snapshot.ship;
+        self.wingman = snapshot.wingman;
+        self.player_score = snapshot.player_score;
+        self.player_lives = snapshot.player_lives;
+        self.wave_number = snapshot.wave_number;
+        self.gold_seam = None;
+
+        self.chronos -= 1;
+        self.rewound = true;
+        self.game_state = GameState::Playing;
+
+        let shield = self.clock.now() + Self::REWIND_SHIELD;
+        let ships = std::iter::once(&mut self.ship).chain(self.wingman.as_mut().map(|wingman| &mut wingman.ship));
+
+        for ship in ships {
+            ship.shield_lifespan = ship.shield_lifespan.max(shield);
+        }
+
+        self.particles.append(&mut Particle::spawn_ring(&self.effects_rng, self.ship.position, WORLD_WIDTH * 0.2, 150));
+        self.audio.play(SoundEffect::Hyperspace);
+    }
+
+    /// Check if the game is played in low visibility: an option for classic
+    /// and co-op games, leaving the weekly and ladder challenges as designed.
+    fn is_low_visibility(&self) -> bool {
+        self.settings.low_visibility
+            && self.is_in_game()
+            && matches!(self.game_mode, GameMode::Classic | GameMode::Coop)
+    }
+
+    /// Colour to draw something at a position in: white, faded out of the
+    /// players' sight in low visibility.
+    fn visibility_tint(&self, position: Vec2) -> Color {
+        if !self.is_low_visibility() {
+            return WHITE;
+        }
+
+        let viewers = std::iter::once(&self.ship)
+            .chain(self.wingman.as_ref().map(|wingman| &wingman.ship))
+            .filter(|ship| !ship.is_retired())
+            .map(|ship| ship.position);
+
+        Color::new(1.0, 1.0, 1.0, self.visibility.brightness(viewers, position, self.clock.now()))
+    }
+
+    /// What the HUD shows for the current frame.
+    fn hud_status(&self) -> HudStatus {
+        let now = self.clock.now();
+
+        let time = self.challenge.as_ref().map(|run| {
+            match self.ladder.challenges[run.index].time_limit {
+                Some(limit) => (limit as f64 - run.stats.elapsed).max(0.0).ceil() as u32,
+                None => run.stats.elapsed as u32,
+            }
+        });
+
+        let overdrive_label = if self.overdrive.is_active(now) {
+            "Overdrive!"
+        } else if self.overdrive.is_full() {
+            "Drive: Ready"
+        } else {
+            "Drive"
+        };
+
+        HudStatus {
+            score: self.player_score,
+            lives: self.player_lives,
+            wave: self.wave_number,
+            time,
+            decoys: self.ship.decoys,
+            bombs: self.ship.bombs,
+            credits: self.player_credits,
+            arc_ready: self.ship.arc_unlocked.then(|| self.ship.is_arc_ready(now)),
+            overdrive_label,
+            overdrive_meter: self.overdrive.meter,
+            shield_energy: self.ship.shield_energy,
+            shield_ready: self.ship.is_shield_ready(),
+            combo: self.overdrive.combo(now),
+            weapon: self.ship.weapon,
+            chronos: self.chronos,
+            wingman: self.wingman.as_ref().map(|wingman| (wingman.score, wingman.lives)),
+        }
+    }
+
+    /// Draw the versus round score, and who is taking the round once a ship is
+    /// lost.
+    fn draw_versus_hud(&self) {
+        let Some(versus) = &self.versus else {
+            return;
+        };
+
+        self.draw_centered_text(&format!("P1  {} - {}  P2", versus.wins[0], versus.wins[1]), 40.0, 30);
+        self.draw_centered_text(&format!("Round {}  (Best of {})", versus.round, versus.best_of), 70.0, 24);
+
+        if versus.is_round_ending() {
+            let banner = match versus.round_winner() {
+                Some(PLAYER_ONE) => "Player 1 takes the round",
+                Some(_) => "Player 2 takes the round",
+                None => "Round drawn",
+            };
+
+            self.draw_centered_text(banner, screen_height() * 0.4, 40);
+        }
+    }
+
+    /// Draw the winner of a versus match over a translucent backdrop.
+    fn draw_versus_results(&self) {
+        let Some(versus) = &self.versus else {
+            return;
+        };
+
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
+
+        let title = match versus.winner() {
+            Some(PLAYER_ONE) => "Player 1 Wins",
+            _ => "Player 2 Wins",
+        };
+
+        self.draw_centered_text(title, screen_height() * 0.35, 60);
+        self.draw_centered_text(&format!("{} - {}", versus.wins[0], versus.wins[1]), screen_height() * 0.35 + 70.0, 60);
+        self.draw_centered_text("[SPACE] Continue", screen_height() - 50.0, 30);
+    }
+
+    /// Draw the latency diagnostics screen, or flash it white while a key press
+    /// is being measured.
+    fn draw_diagnostics(&self) {
+        if self.latency_probe.is_flashing() {
+            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), WHITE);
+            return;
+        }
+
+        let probe = &self.latency_probe;
+        let ms = |seconds: Option<f64>| seconds.unwrap_or(0.0) * 1000.0;
+
+        let frame_time = ms(probe.frame_times.mean());
+        let frame_rate = if frame_time > 0.0 { 1000.0 / frame_time } else { 0.0 };
+
+        let lines = [
+            format!("Frame time   {:.2} ms ({:.1} fps)", frame_time, frame_rate),
+            format!("Min / Max    {:.2} / {:.2} ms", ms(probe.frame_times.min()), ms(probe.frame_times.max())),
+            format!("Jitter       {:.2} ms", ms(probe.frame_times.std_dev())),
+            format!("Hitches      {} of last {} frames", probe.hitches(), probe.frame_times.len()),
+            String::new(),
+            match probe.latency.mean() {
+                Some(latency) => format!("Latency      ~{:.1} ms over {} presses", latency * 1000.0, probe.latency.len()),
+                None => "Latency      press a key to measure".to_string(),
+            },
+            format!("Min / Max    {:.1} / {:.1} ms", ms(probe.latency.min()), ms(probe.latency.max())),
+        ];
+
+        self.draw_centered_text("Latency Diagnostics", screen_height() * 0.2, 50);
+
+        for (i, line) in lines.iter().enumerate() {
+            draw_text_ex(
+                line, screen_width() * 0.25, screen_height() * 0.3 + 40.0 * i as f32,
+                TextParams {
+                    font_size: 30,
+                    font: Some(self.font()),
+                    ..Default::default()
+                },
+            );
+        }
+
+        self.draw_centered_text("Latency is estimated from frame timing and excludes the display", screen_height() - 90.0, 24);
+        self.draw_centered_text("[ANY KEY] Flash  [ESC] Back", screen_height() - 50.0, 30);
+    }
+
+    /// Distance in pixels above or below the replay timeline within which a
+    /// click scrubs it.
+    const REPLAY_BAR_REACH: f32 = 30.0;
+
+    /// Replay timeline bar, and the height of the event track beneath it.
+    fn replay_bar(&self) -> (Rect, f32) {
+        (Rect::new(80.0, screen_height() - 90.0, screen_width() - 160.0, 12.0), 16.0)
+    }
+
+    /// Draw the replay screen: the recorded frame's collision circles, the
+    /// frame's details, and the timeline with its event track.
+    fn draw_replay(&self) {
+        let Some(frame) = self.recording.frame(self.timeline.position) else {
+            return;
+        };
+
+        // Objects are drawn on the playfield, and the timeline over it
+        set_camera(&camera::world_camera(Vec2::ZERO));
+
+        for circle in &frame.asteroids {
+            draw_circle_lines(circle.position.x, circle.position.y, circle.radius, 1.0, WHITE);
+        }
+
+        for circle in &frame.saucers {
+            draw_circle_lines(circle.position.x, circle.position.y, circle.radius, 1.0, YELLOW);
+        }
+
+        for (circle, heading) in &frame.ships {
+            let nose = circle.position + detmath::direction(*heading) * circle.radius * 2.0;
+
+            draw_circle_lines(circle.position.x, circle.position.y, circle.radius, 1.0, GREEN);
+            draw_line(circle.position.x, circle.position.y, nose.x, nose.y, 1.0, GREEN);
+        }
+
+        for circle in &frame.player_bullets {
+            draw_circle(circle.position.x, circle.position.y, circle.radius, WHITE);
+        }
+
+        for circle in &frame.enemy_bullets {
+            draw_circle(circle.position.x, circle.position.y, circle.radius, RED);
+        }
+
+        set_default_camera();
+
+        let len = self.recording.len();
+        let status = format!("Frame {}/{}  Wave {}  Score {}", self.timeline.position + 1, len, frame.wave, format_score(frame.score));
+
+        draw_text_ex(
+            &status, 80.0, 40.0,
+            TextParams {
+                font_size: 30,
+                font: Some(self.font()),
+                ..Default::default()
+            },
+        );
+
+        draw_text_ex(
+            if self.timeline.playing { "Playing" } else { "Paused" }, screen_width() * 0.75, 40.0,
+            TextParams {
+                font_size: 30,
+                font: Some(self.font()),
+                ..Default::default()
+            },
+        );
+
+        // Timeline, filled up to the frame shown, with the event track beneath
+        let (bar, track) = self.replay_bar();
+        let frame_x = |index: usize| bar.x + bar.w * index as f32 / len.saturating_sub(1).max(1) as f32;
+
+        draw_rectangle_lines(bar.x, bar.y, bar.w, bar.h, 2.0, WHITE);
+        draw_rectangle(bar.x, bar.y, frame_x(self.timeline.position) - bar.x, bar.h, WHITE);
+
+        for (index, event) in self.recording.events() {
+            let x = frame_x(index);
+            draw_line(x, bar.y + bar.h + 2.0, x, bar.y + bar.h + track, 2.0, event.color());
+        }
+
+        self.draw_centered_text("[SPACE] Play/Pause  [LEFT/RIGHT] Step  [SHIFT] x10  [ESC] Back", screen_height() - 15.0, 24);
+        self.draw_centered_text("Deaths: red  Waves: blue  Saucers: yellow", screen_height() - 45.0, 24);
+    }
+
+    /// Longest stretch of a code shown in the replay code box; longer codes
+    /// show their end.
+    const CODE_DISPLAY_LENGTH: usize = 40;
+
+    /// Draw the replay code box over a translucent backdrop: the code typed or
+    /// pasted so far, and whether the last game was copied or a code could
+    /// not be watched.
+    fn draw_replay_code(&self) {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
+
+        self.draw_centered_text("Replay Code", screen_height() * 0.3, 60);
+
+        let skipped = self.code_text.len().saturating_sub(Self::CODE_DISPLAY_LENGTH);
+        let shown = if skipped > 0 {
+            format!("...{}_", &self.code_text[skipped..])
+        } else {
+            format!("{}_", self.code_text)
+        };
+
+        let width = self.text_widths.width(self.font(), &shown, 24).max(screen_width() * 0.5);
+        let x = (screen_width() - width) / 2.0;
+        let y = screen_height() * 0.45;
+
+        draw_rectangle_lines(x - 12.0, y - 30.0, width + 24.0, 44.0, 2.0, WHITE);
+        self.draw_centered_text(&shown, y, 24);
+
+        if let Some(status) = &self.code_status {
+            self.draw_centered_text(status, y + 60.0, 30);
+        }
+
+        self.draw_centered_text("Paste a shared code to watch the game", screen_height() - 90.0, 24);
+        self.draw_centered_text("[CTRL+C] Copy Last Game  [CTRL+V] Paste  [ENTER] Watch  [ESC] Back", screen_height() - 50.0, 30);
+    }
+
+    /// Time in seconds the contents of a collected salvage crate are shown.
+    const SALVAGE_NOTICE_DURATION: f64 = 1.5;
+
+    /// Time in seconds the line shown by an event hook stays up.
+    const HOOK_MESSAGE_DURATION: f64 = 3.0;
+
+    /// Time in seconds the notice of a saved bug report is shown.
+    const BUG_REPORT_NOTICE_DURATION: f64 = 5.0;
+
+    /// Time in seconds the between-wave summary is shown.
+    const WAVE_SUMMARY_DURATION: f64 = 3.0;
+
+    /// Time in seconds the kill screen banner is shown.
+    const KILL_SCREEN_BANNER_DURATION: f64 = 5.0;
+
+    /// Draw the kill screen: the right half of the screen filled with garbled
+    /// characters, as if the wave counter had overrun the machine, and a
+    /// celebratory banner when it is first reached.
+    fn draw_kill_screen(&self) {
+        const GLYPHS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ#%&*+-=<>?";
+
+        let cell = 24.0;
+        let columns = (WORLD_WIDTH / 2.0 / cell) as u32;
+        let rows = (WORLD_HEIGHT / cell) as u32;
+        let color = Color::new(1.0, 1.0, 1.0, 0.3);
+
+        for column in 0..columns {
+            for row in 0..rows {
+                // Stable per-cell garbage, so the screen looks corrupted rather than animated
+                let hash = (column.wrapping_mul(73856093) ^ row.wrapping_mul(19349663)) % 97;
+
+                if hash.is_multiple_of(3) {
+                    continue;
+                }
+
+                let glyph = (GLYPHS[hash as usize % GLYPHS.len()] as char).to_string();
+                let x = WORLD_WIDTH / 2.0 + column as f32 * cell;
+                let y = (row + 1) as f32 * cell;
+
+                draw_text_ex(
+                    &glyph, x, y,
+                    TextParams {
+                        font_size: 24,
+                        font: Some(self.font()),
+                        color,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+    }
+
+    /// Draw high score table.
+    fn draw_high_scores(&self) {
+        self.draw_centered_text("High Scores", screen_height() * 0.2, 60);
+
+        for (rank, entry) in self.high_scores.entries.iter().enumerate() {
+            let y = screen_height() * 0.2 + 60.0 + rank as f32 * 40.0;
+            self.draw_centered_text(&format!("{:>2}. {:<3} {:>14}", rank + 1, entry.initials, format_score(entry.score)), y, 36);
+        }
+    }
+
+    /// Draw initials entry, underlining the letter under the cursor.
+    fn draw_initials_entry(&self) {
+        self.draw_centered_text("New High Score", screen_height() * 0.3, 60);
+        self.draw_centered_text(&format_score(self.player_score), screen_height() * 0.3 + 60.0, 40);
+
+        let spacing = 60.0;
+        let left = (screen_width() - spacing * 3.0) / 2.0;
+        let y = screen_height() / 2.0 + 40.0;
+
+        for (i, letter) in self.initials_entry.letters.iter().enumerate() {
+            let x = left + spacing * i as f32;
+            let text = (*letter as char).to_string();
+            let text_width = self.text_widths.width(self.font(), &text, 60);
+
+            draw_text_ex(
+                &text, x + (spacing - text_width) / 2.0, y,
+                TextParams {
+                    font_size: 60,
+                    font: Some(self.font()),
+                    ..Default::default()
+                },
+            );
+
+            if i == self.initials_entry.cursor {
+                draw_line(x + 10.0, y + 10.0, x + spacing - 10.0, y + 10.0, 2., WHITE);
+            }
+        }
+
+        self.draw_centered_text("[UP]/[DOWN] Change  [SPACE] Accept", screen_height() - 50.0, 30);
+    }
+
+    /// Select the target enemies at a given position should aim at. Live
+    /// decoys emit the ships' signature, so the nearest decoy is preferred over
+    /// the ships themselves; otherwise the nearest ship in play at a time is
+    /// targeted.
+    pub fn select_target<'a>(ships: impl Iterator<Item = &'a Ship> + Clone, decoys: &[Decoy], from: Vec2, now: f64) -> Target {
+        let distance = |target: &Target| wrapped_delta(from, target.position).length_squared();
+        let ship_target = |ship: &Ship| Target { position: ship.position, velocity: ship.velocity };
+
+        let fallback = ships.clone().next().map_or(Target { position: from, velocity: Vec2::ZERO }, ship_target);
+        let ship = ships
+            .filter(|ship| !ship.is_respawning(now))
+            .map(ship_target)
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .unwrap_or(fallback);
+
+        decoys.iter()
+            .filter(|decoy| decoy.is_alive(now))
+            .map(|decoy| Target { position: decoy.position, velocity: decoy.velocity })
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .unwrap_or(ship)
+    }
+
+    /// Time in seconds before a respawn at which the spawn area is checked. If
+    /// it is not clear, the respawn is pushed back by the same amount.
+    const RESPAWN_CHECK_WINDOW: f64 = 0.25;
+
+    /// Check that no asteroid, saucer, turret or enemy bullet is within the
+    /// respawn clearance of a ship's spawn position, usually the centre of the
+    /// screen.
+    pub fn is_spawn_area_clear(&self, centre: Vec2) -> bool {
+        let is_clear = |position: Vec2, radius: f32| {
+            wrapped_delta(centre, position).length() > self.respawn_clearance + radius
+        };
+
+        self.asteroids.iter().all(|asteroid| is_clear(asteroid.position, asteroid.get_radius()))
+            && self.saucers.iter().all(|saucer| is_clear(saucer.position, saucer.get_radius()))
+            && self.turrets.iter().all(|turret| is_clear(turret.position, turret.get_radius()))
+            && self.hunters.iter().all(|hunter| is_clear(hunter.position, hunter.get_radius()))
+            && self.entities.iter().all(|entity| is_clear(entity.get_position(), entity.get_radius()))
+            && self.enemy_bullets.iter().all(|bullet| is_clear(bullet.position, bullet.get_radius()))
+    }
+
+    /// Time in seconds a frame out of play takes; a frame played takes as
+    /// long as its controls were recorded for. While a shared game is
+    /// watched, frames take as long as they did when it was played, and with
+    /// a fixed step each frame takes that long.
+    fn frame_step(&self) -> f32 {
+        match (&self.watching, self.fixed_step) {
+            (Some((code, position)), _) => code.frame(*position).map_or_else(get_frame_time, |input| input.dt()),
+            (None, Some(step)) => step,
+            (None, None) => get_frame_time(),
+        }
+    }
+
+    /// Check if a game is held still beneath the pause menu, a menu opened
+    /// from it, or a tip, when its clock stands still too.
+    fn is_held(&self) -> bool {
+        matches!(self.game_state, GameState::Paused | GameState::Shop | GameState::Controls | GameState::Tip)
+            || (self.game_state == GameState::Options && self.options_in_game)
+    }
+
+    /// Update all game objects. Objects move a step each frame, while bullets
+    /// and particles move and age by the time the clock last moved on by, so
+    /// they fly as far and last as long whatever the frame rate. The clock is not moved on here; the
+    /// game loop and `step` do so before updating.
+    pub fn update(&mut self) {
+        let (now, dt) = (self.clock.now(), self.clock.frame_time());
+
+        self.shake.update(dt);
+
+        if self.is_in_game() || self.is_demo() {
+            let (duration, recharge) = self.shield_tuning();
+            self.ship.update_shield(dt, duration, recharge);
+        }
+
+        if self.settings.deep_field {
+            let target = if self.is_in_game() || self.is_demo() { self.ship.position } else { WORLD_SIZE / 2.0 };
+            self.deep_field.update(dt, target);
+        }
+
+        // Wait for a clear area before respawning the ship
+        if self.is_playing() && self.ship.is_respawning(now)
+            && self.ship.respawn_lifespan - now < Self::RESPAWN_CHECK_WINDOW
+            && !self.is_spawn_area_clear(self.ship.spawn_position())
+        {
+            self.ship.delay_respawn(Self::RESPAWN_CHECK_WINDOW);
+        }
+
+        if let Some(wingman) = &self.wingman {
+            if self.is_playing() && wingman.ship.is_respawning(now)
+                && wingman.ship.respawn_lifespan - now < Self::RESPAWN_CHECK_WINDOW
+                && !self.is_spawn_area_clear(wingman.ship.spawn_position())
+            {
+                self.player_ship_mut(PLAYER_TWO).delay_respawn(Self::RESPAWN_CHECK_WINDOW);
+            }
+        }
+
+        // Update ships
+        self.ship.update();
+
+        if let Some(wingman) = &mut self.wingman {
+            wingman.ship.update();
+        }
+
+        // Leave a rainbow trail while in overdrive
+        if self.is_playing() && self.ship.is_overdrive(now) && !self.ship.is_respawning(now) {
+            let hue = (now * 0.5).fract() as f32;
+            let color = macroquad::color::hsl_to_rgb(hue, 1.0, 0.5);
+
+            self.particles.push(Particle::spawn_trail(self.ship.get_exhaust_position(), color));
+        }
+
+        // Update player bullets
+        for bullet in &mut self.player_bullets {
+            bullet.update(dt);
+        }
+        
+        // Update enemy bullets
+        for bullet in &mut self.enemy_bullets {
+            bullet.update(dt);
+        }
+
+        // Update asteroids
+        for asteroid in &mut self.asteroids {
+            asteroid.update();
+        }
+
+        // Update saucers; their aim improves from wave to wave, and hunters
+        // sent after a lurking player aim better still
+        let accuracy = self.difficulty().saucer_accuracy(self.wave_number);
+
+        for saucer in &mut self.saucers {
+            let ships = std::iter::once(&self.ship).chain(self.wingman.as_ref().map(|wingman| &wingman.ship));
+            let target = Self::select_target(ships, &self.decoys, saucer.position, now);
+
+            self.enemy_bullets.append(&mut saucer.shoot(&self.rng, target, accuracy + saucer.accuracy_bonus, now));
+            
+            // Saucers give away their turns and shots with radio chatter
+            if let Some(cue) = saucer.update(&self.rng, &self.asteroids, now) {
+                self.chatter.push(RadioChatter::burst(&self.effects_rng, saucer.id, cue));
+            }
+        }
+
+        // Update hunters; they hold their fire once the game is over
+        let hunters_fire = !self.is_game_over();
+
+        for hunter in &mut self.hunters {
+            let ships = std::iter::once(&self.ship).chain(self.wingman.as_ref().map(|wingman| &wingman.ship));
+            let target = Self::select_target(ships, &self.decoys, hunter.position, now);
+            let command = hunter.pilot(target, &self.player_bullets, &self.asteroids);
+
+            hunter.update(&command);
+
+            if let Some(bullet) = hunter.shoot(command.fire && hunters_fire, now) {
+                self.enemy_bullets.push(bullet);
+            }
+        }
+
+        // Update the entities plugins have added
+        for entity in &mut self.entities {
+            entity.update();
+        }
+
+        // Update turrets; turrets follow their parent and are destroyed with it
+        for turret in &mut self.turrets {
+            match self.saucers.iter().find(|saucer| saucer.id == turret.parent && saucer.is_alive()) {
+                Some(parent) => {
+                    let ships = std::iter::once(&self.ship).chain(self.wingman.as_ref().map(|wingman| &wingman.ship));
+                    let target = Self::select_target(ships, &self.decoys, turret.position, now);
+
+                    turret.update(parent.position, target.position);
+
+                    if !parent.is_departing {
+                        self.enemy_bullets.append(&mut turret.shoot(target.position, now));
+                    }
+                },
+                None => turret.destroy(),
+            }
+        }
+
+        // Update decoys
+        for decoy in &mut self.decoys {
+            decoy.update();
+        }
+
+        // Update smart bomb shockwaves
+        for shockwave in &mut self.shockwaves {
+            shockwave.update(dt);
+        }
+
+        // Update salvage crates
+        for salvage in &mut self.salvage {
+            salvage.update();
+        }
+
+        // Update the gravity well, pulling everything towards it
+        if self.is_playing() {
+            self.update_gravity_well(dt);
+        }
+
+        // Update particles
+        for particle in &mut self.particles {
+            particle.update(dt);
+        }
+
+        // Update score popups
+        for popup in &mut self.floating_texts {
+            popup.update(dt);
+        }
+
+        // Update radio chatter
+        for burst in &mut self.chatter {
+            burst.update(dt);
+        }
+        
+        self.collision();
+
+        // Charge overdrive meter with this frame's kills; only the first player
+        // can trigger it, so it stays out of versus matches. Saucers that have
+        // left the screen were not killed
+        if self.is_playing() && self.game_mode != GameMode::Versus {
+            let kills = self.asteroids.iter().filter(|asteroid| !asteroid.is_alive()).count()
+                + self.saucers.iter().filter(|saucer| !saucer.is_alive() && !saucer.has_left()).count()
+                + self.turrets.iter().filter(|turret| !turret.is_alive()).count()
+                + self.hunters.iter().filter(|hunter| !hunter.is_alive()).count()
+                + self.entities.iter().filter(|entity| !entity.is_alive()).count();
+
+            self.overdrive.register_kills(kills, now);
+        }
+
+        self.overdrive.update(now);
+
+        // Remove dead player bullets
+        self.player_bullets.retain(|bullet| bullet.is_alive());
+
+        // Remove dead enemy bullets
+        self.enemy_bullets.retain(|bullet| bullet.is_alive());
+
+        // Remove dead asteroids, counting those destroyed during a game
+        let asteroid_count = self.asteroids.len();
+        self.asteroids.retain(|asteroid| asteroid.is_alive());
+
+        if self.is_playing() && self.asteroids.len() < asteroid_count {
+            self.asteroids_destroyed += (asteroid_count - self.asteroids.len()) as u32;
+
+            let total = self.lifetime.asteroids_destroyed + self.asteroids_destroyed as u64;
+            self.earn_achievements(AchievementEvent::AsteroidsDestroyed(total));
+        }
+
+        // Remove dead saucers
+        self.saucers.retain(|saucer| saucer.is_alive());
+
+        // Remove dead turrets
+        self.turrets.retain(|turret| turret.is_alive());
+
+        // Remove dead hunters
+        self.hunters.retain(|hunter| hunter.is_alive());
+
+        // Remove dead custom entities
+        self.entities.retain(|entity| entity.is_alive());
+
+        // Remove dead particles
+        self.particles.retain(|particle| particle.is_alive());
+
+        // Remove faded score popups
+        self.floating_texts.retain(|popup| popup.is_alive());
+
+        // Remove faded radio chatter, and chatter from saucers now gone
+        self.chatter.retain(|burst| burst.is_alive() && self.saucers.iter().any(|saucer| saucer.id == burst.saucer));
+
+        // Remove faded lightning bolts
+        self.bolts.retain(|bolt| bolt.is_alive(now));
+
+        // Remove expired or destroyed decoys
+        self.decoys.retain(|decoy| decoy.is_alive(now));
+
+        // Remove faded shockwaves
+        self.shockwaves.retain(|shockwave| shockwave.is_alive());
+
+        // Remove lost or collected salvage crates
+        self.salvage.retain(|salvage| salvage.is_alive(now));
+
+        // Check if all asteroids are destroyed; a challenge ends with its field,
+        // versus rounds are fought among a field that is always restocked, and
+        // every few waves a bonus round is offered before the next one
+        if self.asteroids.len() + self.saucers.len() == 0 {
+            match self.game_mode {
+                GameMode::Challenge if self.is_playing() => self.finish_challenge(true),
+                GameMode::Challenge => {},
+                GameMode::Versus => self.spawn_versus_field(),
+                GameMode::Classic | GameMode::Coop if self.is_playing() && GoldSeam::is_due(self.wave_number) => self.update_gold_seam(),
+                GameMode::Classic | GameMode::Coop | GameMode::Weekly if self.is_playing() => self.finish_wave(),
+                GameMode::Classic | GameMode::Coop | GameMode::Weekly => self.next_wave(),
+            }
+        } else if matches!(self.game_mode, GameMode::Classic | GameMode::Coop | GameMode::Weekly) && !self.is_game_over() {
+            // Spawn saucers, but not once the game is over; in pairs with the
+            // double saucers mutator
+
+            // Hunt down a player lurking with the last few asteroids
+            if self.is_playing() {
+                if let Some(accuracy_bonus) = self.lurker_watch.update(self.wave_stats.elapsed, self.asteroids.len()) {
+                    self.spawn_lurker_hunter(accuracy_bonus);
+                }
+            }
+
+            if let Some(size) = self.saucer_director.update(now, self.difficulty(), self.wave_number, self.player_score, self.saucers.len(), &self.rng) {
+                let count = if self.has_mutator(Mutator::DoubleSaucers) { 2 } else { 1 };
+
+                for _ in 0..count {
+                    self.spawn_saucer(size);
+                }
+            }
+
+            self.saucer_director.sample(now, self.sound_director.tension());
+
+            // Open the wave's gravity well once it is due
+            if self.is_playing() && self.gravity_well_due.is_some_and(|due| now >= due) {
+                self.gravity_well_due = None;
+                self.gravity_well = Some(GravityWell::spawn_away_from(&self.rng, self.ship.position));
+                self.audio.play(SoundEffect::Hyperspace);
+                self.announce("Gravity well opening");
+            }
+
+            // Send in the wave's hunter once it is due
+            if self.is_playing() && self.hunter_due.is_some_and(|due| now >= due) {
+                self.hunter_due = None;
+                self.hunters.push(Hunter::spawn_new(&self.rng, now));
+                self.announce("Hunter approaching");
+            }
+        }
+
+        if matches!(self.game_mode, GameMode::Classic | GameMode::Coop) && self.is_playing() {
+            self.update_hooks();
+
+            #[cfg(not(target_arch = "wasm32"))]
+            self.run_scripts(|scripts, context| scripts.update(context));
+        }
+
+        self.run_plugins(|plugin, world| plugin.on_update(world));
+
+        // Dispatch the events posted since collisions were resolved
+        self.dispatch_events();
+
+        // Put the frame's events into words, though not for the demo
+        if self.is_demo() {
+            self.announcer.clear();
+        } else {
+            self.announcer.deliver(now);
+        }
+    }
+
+    /// Announce an event, with the Announcements option on.
+    fn announce(&mut self, text: impl Into<String>) {
+        if self.settings.announcements {
+            self.announcer.announce(text);
+        }
+    }
+
+    /// Fire the event hooks whose triggers the game has now met, and carry out
+    /// their actions.
+    fn update_hooks(&mut self) {
+        let context = HookContext {
+            wave: self.wave_number,
+            score: self.player_score,
+            date: miniquad::date::now(),
+        };
+
+        for action in self.hooks.check(&context) {
+            match action {
+                HookAction::LuckySaucer => self.saucers.push(Saucer::spawn_lucky(&self.rng, self.clock.now())),
+                HookAction::Confetti => self.particles.append(&mut Particle::spawn_confetti(&self.effects_rng, 300)),
+                HookAction::Message(text) => self.hook_message = Some((self.clock.now(), text)),
+            }
+        }
+    }
+
+    /// Check if scripts run in the game being played: classic and co-op
+    /// games, but not the demo, shared games or tool-assisted runs.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn is_scripted(&self) -> bool {
+        matches!(self.game_mode, GameMode::Classic | GameMode::Coop)
+            && !self.is_demo()
+            && self.watching.is_none()
+            && self.tas.is_none()
+    }
+
+    /// Call into the scripts, if they run in the game being played, and carry
+    /// out what they ask for.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run_scripts(&mut self, call: impl FnOnce(&mut Scripts, ScriptContext) -> Vec<ScriptAction>) {
+        if !self.is_scripted() || self.scripts.is_empty() {
+            return;
+        }
+
+        let context = ScriptContext {
+            wave: self.wave_number,
+            time: self.wave_stats.elapsed,
+            ship: self.ship.position,
+            asteroids: self.asteroids.len(),
+            saucers: self.saucers.len(),
+        };
+
+        for action in call(&mut self.scripts, context) {
+            match action {
+                ScriptAction::SpawnAsteroid(scale) => self.asteroids.push(Asteroid::spawn_new(&self.rng, scale)),
+                ScriptAction::SpawnSaucer(size) => self.spawn_saucer(size),
+                ScriptAction::SpawnHunter => self.hunters.push(Hunter::spawn_new(&self.rng, self.clock.now())),
+                ScriptAction::ClearAsteroids => self.asteroids.clear(),
+                ScriptAction::Message(text) => self.hook_message = Some((self.clock.now(), text)),
+                ScriptAction::SaucerSpread { count, angle } => {
+                    if let Some(saucer) = self.saucers.last_mut() {
+                        saucer.pattern = BulletPattern::Spread { count, angle };
+                    }
+                },
+            }
+        }
+    }
+
+    /// Handle collisions between game objects. The pairs of objects touching
+    /// are found first, then resolved in turn; a pair is passed over once
+    /// either object has been destroyed by a pair resolved before it.
+    fn collision(&mut self) {
+        // Only work out collision if we're playing or flying the demo
+        if self.game_state != GameState::Playing && !self.is_demo() {
+            return;
+        }
+       
+        // Keep track of scores to add a life if we reach a certain threshold
+        let previous_score = self.player_score;
+        let previous_wingman_score = self.wingman.as_ref().map_or(0, |wingman| wingman.score);
+
+        // Points shown in popups include any overdrive multiplier
+        let multiplier = self.overdrive.score_multiplier(self.clock.now());
+
+        // Shields push asteroids aside rather than letting them through
+        self.shield_nudges();
+
+        // Asteroids may bounce off each other rather than pass through
+        if self.is_bouncing_asteroids() {
+            self.bounce_asteroids();
+        }
+
+        // Whether each player's ship was hit; a ship loses a life once,
+        // however many things hit it
+        let mut ships_hit = [false; 2];
+
+        // Asteroids of kinds with something more to them, once destroyed
+        let mut aftermaths = Vec::new();
+
+        for pair in self.asteroid_pairs() {
+            self.resolve_pair(pair, &mut ships_hit, &mut aftermaths);
+        }
+
+        for (asteroid, cause) in aftermaths {
+            self.asteroid_aftermath(&asteroid, cause);
+        }
+
+        self.gold_seam_collisions(multiplier);
+
+        for pair in self.shockwave_pairs() {
+            self.resolve_pair(pair, &mut ships_hit, &mut Vec::new());
+        }
+
+        for pair in self.saucer_pairs() {
+            self.resolve_pair(pair, &mut ships_hit, &mut Vec::new());
+        }
+
+        for pair in self.hunter_pairs() {
+            self.resolve_pair(pair, &mut ships_hit, &mut Vec::new());
+        }
+
+        for pair in self.entity_pairs() {
+            self.resolve_pair(pair, &mut ships_hit, &mut Vec::new());
+        }
+        
+        for pair in self.turret_pairs() {
+            self.resolve_pair(pair, &mut ships_hit, &mut Vec::new());
+        }
+
+        // Bullet and asteroid to decoy collisions
+        for decoy in &mut self.decoys {
+            let bullet = self.enemy_bullets.iter_mut().find(|bullet| bullet.is_alive() && bullet.is_colliding(decoy));
+            let hit = match bullet {
+                Some(bullet) => {
+                    bullet.destroy();
+                    true
+                },
+                None => self.asteroids.iter().any(|asteroid| asteroid.is_colliding(decoy)),
+            };
+
+            if hit {
+                self.particles.append(&mut Particle::spawn_radial(&self.effects_rng, decoy.position, 30));
+                decoy.destroy();
+            }
+        }
+
+        for pair in self.ship_pairs() {
+            self.resolve_pair(pair, &mut ships_hit, &mut Vec::new());
+        }
+
+        // Ship to salvage crate collisions; shields do not stop a ship picking
+        // up salvage
+        let can_collect = |ship: &Ship, salvage: &SalvageCrate| {
+            !ship.is_respawning(self.clock.now()) && <dyn Collidable>::circle_circle_intersection(ship, salvage)
+        };
+
+        // Salvage collected by each player
+        let mut collected = Vec::new();
+
+        for salvage in &mut self.salvage {
+            let player = if can_collect(&self.ship, salvage) {
+                Some(PLAYER_ONE)
+            } else {
+                self.wingman.as_ref().filter(|wingman| can_collect(&wingman.ship, salvage)).map(|_| PLAYER_TWO)
+            };
+
+            if let Some(player) = player {
+                salvage.destroy();
+                collected.push((player, salvage.contents));
+            }
+        }
+
+        for (player, contents) in collected {
+            self.collect_salvage(player, contents);
+        }
+
+        for player in [PLAYER_ONE, PLAYER_TWO] {
+            if ships_hit[player] {
+                self.handle_ship_hit(player);
+            }
+        }
+
+        self.dispatch_events();
+
+        // The demo scores nothing; otherwise check if we need to add a life
+        if self.is_demo() {
+            self.player_score = previous_score;
+        } else {
+            self.award_score_bonuses(previous_score);
+            self.award_wingman_bonuses(previous_wingman_score);
+        }
+    }
+
+    /// Ships in play, with the players flying them.
+    fn ships(&self) -> impl Iterator<Item = (usize, &Ship)> {
+        std::iter::once((PLAYER_ONE, &self.ship)).chain(self.wingman.as_ref().map(|wingman| (PLAYER_TWO, &wingman.ship)))
+    }
+
+    /// Ships in play that can crash into things: flying, with no shield up.
+    fn tangible_ships(&self) -> impl Iterator<Item = (usize, &Ship)> {
+        let now = self.clock.now();
+        self.ships().filter(move |(_, ship)| !ship.is_shield_active(now) && !ship.is_respawning(now))
+    }
+
+    /// Bullet in play, whether a player's or an enemy's.
+    fn bullet(&self, bullet: BulletRef) -> &Bullet {
+        match bullet {
+            BulletRef::Player(index) => &self.player_bullets[index],
+            BulletRef::Enemy(index) => &self.enemy_bullets[index],
+        }
+    }
+
+    /// Mutable reference to a bullet in play.
+    fn bullet_mut(&mut self, bullet: BulletRef) -> &mut Bullet {
+        match bullet {
+            BulletRef::Player(index) => &mut self.player_bullets[index],
+            BulletRef::Enemy(index) => &mut self.enemy_bullets[index],
+        }
+    }
+
+    /// Pairs of objects touching an asteroid: ships, saucers, and the players'
+    /// and enemy bullets, in that order for each asteroid.
+    fn asteroid_pairs(&self) -> Vec<CollisionPair> {
+        let bullets = || {
+            let player = self.player_bullets.iter().enumerate().map(|(index, bullet)| (BulletRef::Player(index), bullet));
+            let enemy = self.enemy_bullets.iter().enumerate().map(|(index, bullet)| (BulletRef::Enemy(index), bullet));
+
+            player.chain(enemy)
+        };
+
+        let mut pairs = Vec::new();
+
+        for (asteroid, rock) in self.asteroids.iter().enumerate() {
+            for (player, ship) in self.tangible_ships() {
+                if ship.is_colliding(rock) {
+                    pairs.push(CollisionPair::ShipAsteroid { player, asteroid });
+                }
+            }
+
+            for (saucer, craft) in self.saucers.iter().enumerate() {
+                if craft.is_colliding(rock) {
+                    pairs.push(CollisionPair::SaucerAsteroid { saucer, asteroid });
+                }
+            }
+
+            for (bullet, shot) in bullets() {
+                if shot.is_colliding(rock) {
+                    pairs.push(CollisionPair::BulletAsteroid { bullet, asteroid });
+                }
+            }
+        }
+
+        pairs
+    }
+
+    /// Pairs of objects touching a saucer: ships, then the players' bullets,
+    /// for each saucer.
+    fn saucer_pairs(&self) -> Vec<CollisionPair> {
+        let mut pairs = Vec::new();
+
+        for (saucer, craft) in self.saucers.iter().enumerate() {
+            for (player, ship) in self.tangible_ships() {
+                if ship.is_colliding(craft) {
+                    pairs.push(CollisionPair::ShipSaucer { player, saucer });
+                }
+            }
+
+            for (bullet, shot) in self.player_bullets.iter().enumerate() {
+                if shot.is_colliding(craft) {
+                    pairs.push(CollisionPair::BulletSaucer { bullet, saucer });
+                }
+            }
+        }
+
+        pairs
+    }
+
+    /// Pairs of objects touching a hunter: ships, asteroids, then the
+    /// players' bullets, for each hunter.
+    fn hunter_pairs(&self) -> Vec<CollisionPair> {
+        let mut pairs = Vec::new();
+
+        for (hunter, craft) in self.hunters.iter().enumerate() {
+            for (player, ship) in self.tangible_ships() {
+                if ship.is_colliding(craft) {
+                    pairs.push(CollisionPair::ShipHunter { player, hunter });
+                }
+            }
+
+            for (asteroid, rock) in self.asteroids.iter().enumerate() {
+                if craft.is_colliding(rock) {
+                    pairs.push(CollisionPair::AsteroidHunter { asteroid, hunter });
+                }
+            }
+
+            for (bullet, shot) in self.player_bullets.iter().enumerate() {
+                if shot.is_colliding(craft) {
+                    pairs.push(CollisionPair::BulletHunter { bullet, hunter });
+                }
+            }
+        }
+
+        pairs
+    }
+
+    /// Pairs of the players' bullets and the turrets they strike.
+    fn turret_pairs(&self) -> Vec<CollisionPair> {
+        let mut pairs = Vec::new();
+
+        for (turret, hardpoint) in self.turrets.iter().enumerate() {
+            for (bullet, shot) in self.player_bullets.iter().enumerate() {
+                if shot.is_colliding(hardpoint) {
+                    pairs.push(CollisionPair::BulletTurret { bullet, turret });
+                }
+            }
+        }
+
+        pairs
+    }
+
+    /// Pairs of objects touching a custom entity: ships, then the players'
+    /// bullets, for each entity that can be shot.
+    fn entity_pairs(&self) -> Vec<CollisionPair> {
+        let mut pairs = Vec::new();
+
+        for (entity, thing) in self.entities.iter().enumerate() {
+            let thing: &dyn Collidable = thing.as_ref();
+
+            for (player, ship) in self.tangible_ships() {
+                if ship.is_colliding(thing) {
+                    pairs.push(CollisionPair::ShipEntity { player, entity });
+                }
+            }
+
+            if !self.entities[entity].is_shootable() {
+                continue;
+            }
+
+            for (bullet, shot) in self.player_bullets.iter().enumerate() {
+                if shot.is_colliding(thing) {
+                    pairs.push(CollisionPair::BulletEntity { bullet, entity });
+                }
+            }
+        }
+
+        pairs
+    }
+
+    /// Bullets touching a ship: the enemy's, and in a versus match, the
+    /// other player's.
+    fn ship_pairs(&self) -> Vec<CollisionPair> {
+        let mut pairs = Vec::new();
+
+        for (player, ship) in self.ships() {
+            for (bullet, shot) in self.enemy_bullets.iter().enumerate() {
+                if shot.is_colliding(ship) {
+                    pairs.push(CollisionPair::BulletShip { bullet: BulletRef::Enemy(bullet), player });
+                }
+            }
+        }
+
+        if self.game_mode == GameMode::Versus && self.wingman.is_some() {
+            for (bullet, shot) in self.player_bullets.iter().enumerate() {
+                let player = if shot.owner == PLAYER_TWO { PLAYER_ONE } else { PLAYER_TWO };
+
+                if shot.is_colliding(self.player_ship(player)) {
+                    pairs.push(CollisionPair::BulletShip { bullet: BulletRef::Player(bullet), player });
+                }
+            }
+        }
+
+        pairs
+    }
+
+    /// Resolve a pair of objects found touching, unless either has since been
+    /// destroyed. Ships hit are marked rather than hit straight away, and
+    /// asteroids whose kind leaves an aftermath are kept for it, along with
+    /// what destroyed them.
+    fn resolve_pair(&mut self, pair: CollisionPair, ships_hit: &mut [bool; 2], aftermaths: &mut Vec<(Asteroid, Cause)>) {
+        match pair {
+            CollisionPair::ShipAsteroid { player, asteroid } => {
+                if self.asteroids[asteroid].is_alive() {
+                    ships_hit[player] = true;
+                }
+            },
+            CollisionPair::SaucerAsteroid { saucer, asteroid } => {
+                if self.saucers[saucer].is_alive() && self.asteroids[asteroid].is_alive() {
+                    self.handle_saucer_destroyed(saucer, Cause::Crash, false);
+                    self.handle_asteroid_destroyed(asteroid, Cause::Crash, None, aftermaths);
+                }
+            },
+            CollisionPair::BulletAsteroid { bullet, asteroid } => {
+                if !self.bullet(bullet).is_alive() || !self.asteroids[asteroid].is_alive() {
+                    return;
+                }
+
+                // Armour soaks up the hit, leaving the asteroid whole
+                if !self.asteroids[asteroid].hit() {
+                    let position = self.bullet(bullet).position;
+
+                    self.particles.append(&mut Particle::spawn_debris(&self.effects_rng, position, 10));
+                    self.audio.play(SoundEffect::SmallExplosion);
+
+                    self.bullet_mut(bullet).destroy();
+                    return;
+                }
+
+                if let BulletRef::Player(_) = bullet {
+                    self.wave_stats.hits += 1;
+                }
+
+                let shot = self.bullet(bullet);
+                let strike = Strike { impulse: shot.velocity, point: shot.position };
+                let cause = match bullet {
+                    BulletRef::Player(_) => Cause::Player(shot.owner),
+                    BulletRef::Enemy(_) => Cause::Enemy,
+                };
+
+                // Piercing bullets survive the hit
+                self.handle_asteroid_destroyed(asteroid, cause, Some(strike), aftermaths);
+                self.bullet_mut(bullet).hit();
+            },
+            CollisionPair::ShipSaucer { player, saucer } => {
+                if self.saucers[saucer].is_alive() {
+                    self.handle_saucer_destroyed(saucer, Cause::Player(player), false);
+                    ships_hit[player] = true;
+                }
+            },
+            CollisionPair::BulletSaucer { bullet, saucer } => {
+                let shot = &self.player_bullets[bullet];
+
+                if shot.is_alive() && self.saucers[saucer].is_alive() {
+                    let (owner, ricochet) = (shot.owner, shot.wrapped);
+                    let (position, velocity) = (self.saucers[saucer].position, self.saucers[saucer].velocity);
+
+                    self.handle_saucer_destroyed(saucer, Cause::Player(owner), ricochet);
+                    self.player_bullets[bullet].destroy();
+                    self.wave_stats.hits += 1;
+
+                    // Saucers shot down may leave salvage behind
+                    self.drop_salvage(position, velocity);
+                }
+            },
+            CollisionPair::BulletShip { bullet, player } => {
+                if self.bullet(bullet).is_alive() {
+                    self.bullet_mut(bullet).destroy();
+                    ships_hit[player] = true;
+                }
+            },
+            CollisionPair::ShipHunter { player, hunter } => {
+                if self.hunters[hunter].is_alive() {
+                    self.handle_hunter_destroyed(hunter, Cause::Player(player));
+                    ships_hit[player] = true;
+                }
+            },
+            CollisionPair::AsteroidHunter { asteroid, hunter } => {
+                if self.hunters[hunter].is_alive() && self.asteroids[asteroid].is_alive() {
+                    self.handle_hunter_destroyed(hunter, Cause::Crash);
+                    self.handle_asteroid_destroyed(asteroid, Cause::Crash, None, aftermaths);
+                }
+            },
+            CollisionPair::BulletHunter { bullet, hunter } => {
+                let shot = &self.player_bullets[bullet];
+
+                if shot.is_alive() && self.hunters[hunter].is_alive() {
+                    let owner = shot.owner;
+
+                    self.handle_hunter_destroyed(hunter, Cause::Player(owner));
+                    self.player_bullets[bullet].destroy();
+                    self.wave_stats.hits += 1;
+                }
+            },
+            CollisionPair::BulletTurret { bullet, turret } => {
+                let shot = &self.player_bullets[bullet];
+
+                // Only the hardpoint is disabled; its parent carries on
+                if shot.is_alive() && self.turrets[turret].is_alive() {
+                    let owner = shot.owner;
+
+                    self.handle_turret_destroyed(turret, Cause::Player(owner));
+                    self.player_bullets[bullet].destroy();
+                    self.wave_stats.hits += 1;
+                }
+            },
+            CollisionPair::ShockwaveAsteroid { asteroid } => {
+                if self.asteroids[asteroid].is_alive() {
+                    self.handle_asteroid_destroyed(asteroid, Cause::Bomb(PLAYER_ONE), None, aftermaths);
+                }
+            },
+            CollisionPair::ShockwaveSaucer { saucer } => {
+                if self.saucers[saucer].is_alive() {
+                    self.handle_saucer_destroyed(saucer, Cause::Bomb(PLAYER_ONE), false);
+                }
+            },
+            CollisionPair::ShipEntity { player, entity } => {
+                if self.entities[entity].is_alive() {
+                    ships_hit[player] |= self.entities[entity].is_harmful();
+                    self.handle_entity_destroyed(entity, Cause::Player(player));
+                }
+            },
+            CollisionPair::BulletEntity { bullet, entity } => {
+                let shot = &self.player_bullets[bullet];
+
+                if shot.is_alive() && self.entities[entity].is_alive() {
+                    let owner = shot.owner;
+
+                    self.handle_entity_destroyed(entity, Cause::Player(owner));
+                    self.player_bullets[bullet].destroy();
+                    self.wave_stats.hits += 1;
+                }
+            },
+        }
+    }
+
+    /// Destroy an asteroid. Struck by a bullet, a blast or an arc, it splits
+    /// where it was struck, and is kept for its aftermath, credited to the same
+    /// cause, if it is of a kind with one; rammed by a saucer or swept by a shockwave, it breaks up.
+    fn handle_asteroid_destroyed(&mut self, index: usize, cause: Cause, strike: Option<Strike>, aftermaths: &mut Vec<(Asteroid, Cause)>) {
+        let asteroid = &mut self.asteroids[index];
+
+        self.events.emit(GameEvent::AsteroidDestroyed {
+            position: asteroid.position,
+            size: asteroid.size(),
+            grade: asteroid.grade(),
+            points: Self::cause_points(cause, asteroid.points()),
+            cause,
+        });
+
+        let Some(strike) = strike else {
+            asteroid.destroy();
+            return;
+        };
+
+        let mut fragments = asteroid.split(&self.rng, strike.impulse, strike.point);
+        asteroid.destroy();
+
+        if asteroid.kind != AsteroidKind::Plain {
+            aftermaths.push((asteroid.clone(), cause));
+        }
+
+        self.asteroids.append(&mut fragments);
+    }
+
+    /// Destroy a saucer, shot, rammed or bombed by a player or crashed into
+    /// an asteroid.
+    fn handle_saucer_destroyed(&mut self, index: usize, cause: Cause, ricochet: bool) {
+        let saucer = &mut self.saucers[index];
+
+        self.events.emit(GameEvent::SaucerDestroyed {
+            position: saucer.position,
+            size: saucer.size,
+            points: Self::cause_points(cause, saucer.points()),
+            cause,
+            ricochet,
+        });
+
+        saucer.destroy();
+    }
+
+    /// Destroy a hunter, shot or rammed by a player or crashed into an
+    /// asteroid.
+    fn handle_hunter_destroyed(&mut self, index: usize, cause: Cause) {
+        let hunter = &mut self.hunters[index];
+
+        self.events.emit(GameEvent::HunterDestroyed {
+            position: hunter.position,
+            points: Hunter::POINTS,
+            cause,
+        });
+
+        hunter.destroy();
+    }
+
+    /// Knock out a turret shot by a player.
+    fn handle_turret_destroyed(&mut self, index: usize, cause: Cause) {
+        let turret = &mut self.turrets[index];
+
+        self.events.emit(GameEvent::TurretDestroyed {
+            position: turret.position,
+            points: Turret::POINTS,
+            cause,
+        });
+
+        turret.destroy();
+    }
+
+    /// Destroy a custom entity, shot or rammed by a player.
+    fn handle_entity_destroyed(&mut self, index: usize, cause: Cause) {
+        let entity = &mut self.entities[index];
+
+        self.events.emit(GameEvent::EntityDestroyed {
+            position: entity.get_position(),
+            points: entity.points(),
+            cause,
+        });
+
+        entity.destroy();
+    }
+
+    /// Points scored for destroying something worth some points: all of them,
+    /// or only part of them with a smart bomb.
+    fn cause_points(cause: Cause, points: u32) -> u32 {
+        match cause {
+            Cause::Bomb(_) => Shockwave::points(points),
+            _ => points,
+        }
+    }
+
+    /// Hit a player's ship, which loses a life once the events are
+    /// dispatched.
+    fn handle_ship_hit(&mut self, player: usize) {
+        let position = self.player_ship(player).position;
+        self.events.emit(GameEvent::ShipHit { player, position });
+    }
+
+    /// Hand the events posted so far to each system in turn, then to the
+    /// listeners subscribed to them and the plugins. Events posted while they are handled,
+    /// such as an extra life, are dispatched along with them.
+    fn dispatch_events(&mut self) {
+        loop {
+            let events = self.events.take();
+
+            if events.is_empty() {
+                break;
+            }
+
+            for event in &events {
+                self.score_event(event);
+                self.audio_event(event);
+                self.particle_event(event);
+                self.achievement_event(event);
+                self.events.notify(event);
+                self.run_plugins(|plugin, world| plugin.on_event(world, event));
+            }
+        }
+    }
+
+    /// Scoring: award points to the player who scored them, shown in a popup
+    /// including any overdrive multiplier, and take a life from a ship hit.
+    fn score_event(&mut self, event: &GameEvent) {
+        let multiplier = self.overdrive.score_multiplier(self.clock.now());
+
+        match *event {
+            GameEvent::AsteroidDestroyed { position, points, cause, .. }
+            | GameEvent::SaucerDestroyed { position, points, cause, .. }
+            | GameEvent::HunterDestroyed { position, points, cause }
+            | GameEvent::TurretDestroyed { position, points, cause }
+            | GameEvent::EntityDestroyed { position, points, cause } => {
+                if let Some(player) = self.credited_player(cause).filter(|_| points > 0) {
+                    let score = Self::player_score_mut(&mut self.player_score, &mut self.wingman, player);
+                    *score = score.saturating_add(points);
+
+                    self.floating_texts.push(Self::score_popup(points * multiplier, position, player));
+                }
+            },
+            GameEvent::ShipHit { player, .. } => self.lose_life(player),
+            GameEvent::WaveCleared { .. } | GameEvent::ExtraLife { .. } => {},
+        }
+    }
+
+    /// Audio: explosions, sized to what blew up, and the extra life chime.
+    fn audio_event(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::AsteroidDestroyed { size, .. } => self.audio.play(SoundEffect::explosion(size)),
+            GameEvent::SaucerDestroyed { .. } | GameEvent::HunterDestroyed { .. } | GameEvent::ShipHit { .. } => self.audio.play(SoundEffect::LargeExplosion),
+            GameEvent::TurretDestroyed { .. } => self.audio.play(SoundEffect::MediumExplosion),
+            GameEvent::EntityDestroyed { .. } => self.audio.play(SoundEffect::SmallExplosion),
+            GameEvent::ExtraLife { .. } => self.audio.play(SoundEffect::ExtraLife),
+            GameEvent::WaveCleared { .. } => {},
+        }
+    }
+
+    /// Particles: bursts of sparks and debris where things blew up, and a
+    /// shake of the screen for the largest asteroids.
+    fn particle_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::AsteroidDestroyed { position, size, grade, .. } => {
+                self.particles.append(&mut Self::explosion_particles(&self.effects_rng, position, grade));
+
+                if size == AsteroidSize::Large {
+                    self.shake.add(Shake::LargeExplosion);
+                }
+            },
+            GameEvent::SaucerDestroyed { position, size, .. } => {
+                let (sparks, debris) = match size {
+                    SaucerSize::Small => (100, 50),
+                    SaucerSize::Large => (200, 100),
+                };
+
+                self.particles.append(&mut Particle::spawn_radial(&self.effects_rng, position, sparks));
+                self.particles.append(&mut Particle::spawn_debris(&self.effects_rng, position, debris));
+            },
+            GameEvent::HunterDestroyed { position, .. } | GameEvent::ShipHit { position, .. } => {
+                self.particles.append(&mut Particle::spawn_radial(&self.effects_rng, position, 100));
+                self.particles.append(&mut Particle::spawn_debris(&self.effects_rng, position, 50));
+            },
+            GameEvent::TurretDestroyed { position, .. } => {
+                self.particles.append(&mut Particle::spawn_radial(&self.effects_rng, position, 50));
+                self.particles.append(&mut Particle::spawn_debris(&self.effects_rng, position, 20));
+            },
+            GameEvent::EntityDestroyed { position, .. } => {
+                self.particles.append(&mut Particle::spawn_debris(&self.effects_rng, position, 30));
+            },
+            GameEvent::WaveCleared { .. } | GameEvent::ExtraLife { .. } => {},
+        }
+    }
+
+    /// Achievements: check saucers downed against those earned by how a
+    /// saucer was shot.
+    fn achievement_event(&mut self, event: &GameEvent) {
+        if let GameEvent::SaucerDestroyed { size, cause: Cause::Player(_), ricochet, .. } = *event {
+            self.earn_achievements(AchievementEvent::SaucerShot { size, ricochet });
+        }
+    }
+
+    /// Restitution of asteroids bouncing off a shield; low, so rocks are
+    /// nudged rather than batted away.
+    const SHIELD_RESTITUTION: f32 = 0.5;
+
+    /// Share of a shield bounce's impulse felt by the ship.
+    const SHIELD_RECOIL: f32 = 0.3;
+
+    /// Bounce asteroids off the ships' shields. The asteroid is pushed clear
+    /// of the shield and takes most of the impulse, the heavier ones budging
+    /// less, while the ship is knocked back a little, so a shielded ship can
+    /// shove rocks around.
+    fn shield_nudges(&mut self) {
+        let ships = std::iter::once(&mut self.ship).chain(self.wingman.as_mut().map(|wingman| &mut wingman.ship));
+
+        for ship in ships {
+            if !ship.is_shield_active(self.clock.now()) || ship.is_respawning(self.clock.now()) {
+                continue;
+            }
+
+            for asteroid in &mut self.asteroids {
+                let Some(contact) = Contact::between(ship, ship.shield_radius(), asteroid) else {
+                    continue;
+                };
+
+                // Keep the asteroid from sinking into the shield
+                asteroid.position += contact.normal * contact.depth;
+
+                let impulse = contact.impulse(asteroid.velocity - ship.velocity, Self::SHIELD_RESTITUTION);
+
+                if impulse > 0.0 {
+                    asteroid.velocity += contact.normal * impulse / (asteroid.scale * 2.0).max(1.0);
+                    ship.velocity -= contact.normal * impulse * Self::SHIELD_RECOIL;
+
+                    let point = ship.position + contact.normal * ship.shield_radius();
+                    self.particles.append(&mut Particle::spawn_radial(&self.effects_rng, point, 5));
+                }
+            }
+        }
+    }
+
+    /// Furthest, in units, overlapping asteroids are pushed apart in a frame,
+    /// so rocks spawned on top of each other ease apart instead of jittering.
+    const BOUNCE_CORRECTION: f32 = 2.0;
+
+    /// Bounce the asteroids off each other, elastically, their mass growing
+    /// with their area so a boulder barely notices a pebble.
+    fn bounce_asteroids(&mut self) {
+        let mut bodies: Vec<Body> = self.asteroids.iter().map(|asteroid| Body {
+            position: asteroid.position,
+            velocity: asteroid.velocity,
+            radius: asteroid.diameter / 2.0,
+            mass: asteroid.scale * asteroid.scale,
+        }).collect();
+
+        resolve_elastic(&mut bodies, Self::BOUNCE_CORRECTION);
+
+        for (asteroid, body) in self.asteroids.iter_mut().zip(bodies) {
+            asteroid.position = body.position;
+            asteroid.velocity = body.velocity;
+        }
+    }
+
+    /// Age the gravity well, pull the ships, asteroids and bullets towards
+    /// it, and destroy what reaches its core. Asteroids swallowed score
+    /// nothing and leave no fragments; a raised shield keeps a ship from being
+    /// swallowed. Once the well has lasted its time it collapses in a ring of
+    /// particles.
+    fn update_gravity_well(&mut self, dt: f32) {
+        let Some(well) = &mut self.gravity_well else {
+            return;
+        };
+
+        well.update(dt);
+
+        if !well.is_alive() {
+            self.particles.append(&mut Particle::spawn_ring(&self.effects_rng, well.position, 120.0, 150));
+            self.audio.play(SoundEffect::Hyperspace);
+            self.gravity_well = None;
+            return;
+        }
+
+        let well = &*well;
+        let mut swallowed = Vec::new();
+
+        let ships = std::iter::once((PLAYER_ONE, &mut self.ship))
+            .chain(self.wingman.as_mut().map(|wingman| (PLAYER_TWO, &mut wingman.ship)));
+
+        for (player, ship) in ships {
+            if ship.is_respawning(self.clock.now()) {
+                continue;
+            }
+
+            ship.velocity += well.pull(ship.position);
+
+            if well.swallows(ship.position) && !ship.is_shield_active(self.clock.now()) {
+                self.particles.append(&mut Particle::spawn_radial(&self.effects_rng, ship.position, 100));
+                self.particles.append(&mut Particle::spawn_debris(&self.effects_rng, ship.position, 50));
+                swallowed.push(player);
+            }
+        }
+
+        for asteroid in self.asteroids.iter_mut().filter(|asteroid| asteroid.is_alive()) {
+            asteroid.velocity += well.pull(asteroid.position);
+
+            if well.swallows(asteroid.position) {
+                self.particles.append(&mut Self::asteroid_explosion(&self.effects_rng, asteroid));
+                asteroid.destroy();
+            }
+        }
+
+        for bullet in self.player_bullets.iter_mut().chain(&mut self.enemy_bullets) {
+            bullet.velocity += well.pull(bullet.position);
+
+            if well.swallows(bullet.position) {
+                bullet.destroy();
+            }
+        }
+
+        for player in swallowed {
+            self.audio.play(SoundEffect::LargeExplosion);
+            self.lose_life(player);
+        }
+    }
+
+    /// Lose a player's life, respawning their ship. A player with no lives
+    /// left is out of the game, which ends once every player is out.
+    fn lose_life(&mut self, player: usize) {
+        let now = self.clock.now();
+
+        // The demo ship has as many lives as it needs
+        if self.is_demo() {
+            self.ship.respawn(now);
+            return;
+        }
+
+        self.shake.add(Shake::ShipDestroyed);
+        self.wave_stats.lives_lost += 1;
+
+        // In a versus match, losing a ship loses the round
+        if let Some(versus) = &mut self.versus {
+            versus.ship_destroyed(player, now);
+            self.player_ship_mut(player).retire();
+            return;
+        }
+
+        let (lives, ship) = match &mut self.wingman {
+            Some(wingman) if player == PLAYER_TWO => (&mut wingman.lives, &mut wingman.ship),
+            _ => (&mut self.player_lives, &mut self.ship),
+        };
+
+        let remaining = if *lives == 0 {
+            ship.retire();
+            "Out of lives".to_string()
+        } else {
+            *lives -= 1;
+            ship.respawn(now);
+
+            match *lives {
+                0 => "Last life".to_string(),
+                1 => "One life remaining".to_string(),
+                lives => format!("{} lives remaining", lives),
+            }
+        };
+
+        // In co-op, say whose life it was
+        match (&self.wingman, player) {
+            (Some(_), PLAYER_TWO) => self.announce(format!("Player two: {}", remaining)),
+            (Some(_), _) => self.announce(format!("Player one: {}", remaining)),
+            (None, _) => self.announce(remaining),
+        }
+
+        let wingman_out = self.wingman.as_ref().is_none_or(|wingman| wingman.is_out());
+
+        if self.ship.is_retired() && wingman_out {
+            self.game_state = GameState::GameOver;
+            self.dismiss_saucers();
+            self.announce("Game over");
+        }
+    }
+
+    /// Apply the overdrive multiplier to points the wingman scored since
+    /// `previous_score`, then add them a life whenever their score crosses a
+    /// multiple of 10000. Credits are only earned by the first player, who
+    /// visits the shop.
+    fn award_wingman_bonuses(&mut self, previous_score: u32) {
+        let multiplier = self.overdrive.score_multiplier(self.clock.now());
+
+        if let Some(wingman) = &mut self.wingman {
+            wingman.score = multiply_gain(previous_score, wingman.score, multiplier);
+
+            if wingman.score / 10000 > previous_score / 10000 {
+                wingman.lives += 1;
+
+                self.events.emit(GameEvent::ExtraLife { player: PLAYER_TWO });
+            }
+        }
+    }
+
+    /// Apply the overdrive multiplier to points scored since `previous_score`,
+    /// then add a life whenever the score crosses a multiple of 10000, and a
+    /// shop credit whenever it crosses a multiple of 1000.
+    fn award_score_bonuses(&mut self, previous_score: u32) {
+        let multiplier = self.overdrive.score_multiplier(self.clock.now());
+        self.player_score = multiply_gain(previous_score, self.player_score, multiplier);
+
+        if self.player_score / 10000 > previous_score / 10000 {
+            self.player_lives += 1;
+
+            self.events.emit(GameEvent::ExtraLife { player: PLAYER_ONE });
+        }
+
+        self.player_credits += self.player_score / 1000 - previous_score / 1000;
+    }
+
+    /// Discharge chain lightning from an origin. The arc chains across nearby
+    /// asteroids and saucers, destroying those that take enough damage.
+    fn chain_lightning(&mut self, origin: Vec2) {
+        let previous_score = self.player_score;
+
+        // Asteroids and saucers are both targets; saucers follow asteroids
+        let targets: Vec<Vec2> = self.asteroids.iter().map(|asteroid| asteroid.position)
+            .chain(self.saucers.iter().map(|saucer| saucer.position))
+            .collect();
+
+        let asteroid_count = self.asteroids.len();
+        let (bolt, hits) = ChainLightning::strike(origin, &targets, self.ship.arc_range, self.clock.now());
+        let mut aftermaths = Vec::new();
+
+        for (index, damage) in hits {
+            let position = targets[index];
+
+            // The arc carries no momentum, so fragments only share the
+            // asteroid's own
+            if index < asteroid_count {
+                if damage >= self.asteroids[index].toughness() {
+                    let strike = Strike { impulse: Vec2::ZERO, point: position };
+                    self.handle_asteroid_destroyed(index, Cause::Player(PLAYER_ONE), Some(strike), &mut aftermaths);
+                }
+            } else if damage >= self.saucers[index - asteroid_count].toughness() {
+                self.destroy_saucer(index - asteroid_count, Cause::Player(PLAYER_ONE));
+            }
+
+            self.particles.append(&mut Particle::spawn_radial(&self.effects_rng, position, 10));
+        }
+
+        for (asteroid, cause) in aftermaths {
+            self.asteroid_aftermath(&asteroid, cause);
+        }
+
+        self.bolts.push(bolt);
+        self.audio.play(SoundEffect::Lightning);
+
+        // Score what the arc destroyed before paying out bonuses on it
+        self.dispatch_events();
+        self.award_score_bonuses(previous_score);
+    }
+
+    /// Damage a volatile asteroid's blast deals to the rocks and saucers it
+    /// reaches: enough to destroy all but large rocks and armour.
+    const BLAST_DAMAGE: f32 = 0.5;
+
+    /// Whatever follows the destruction of an asteroid of a kind other than
+    /// plain rock: the blast of a volatile one, or the salvage left by a
+    /// crystal.
+    fn asteroid_aftermath(&mut self, asteroid: &Asteroid, cause: Cause) {
+        let traits = asteroid.kind.traits();
+
+        if traits.blast > 0.0 {
+            self.detonate(asteroid.position, asteroid.get_radius() * traits.blast, cause);
+        }
+
+        if traits.salvage {
+            let contents = self.loot_table.draw(&self.rng);
+            self.spawn_salvage(asteroid.position, asteroid.velocity, contents);
+        }
+    }
+
+    /// Set off a blast, destroying the asteroids and saucers it reaches that
+    /// are not tough enough to withstand it, credited to whatever set it off.
+    /// Volatile asteroids caught in it go off in turn.
+    fn detonate(&mut self, position: Vec2, radius: f32, cause: Cause) {
+        self.particles.append(&mut Particle::spawn_radial(&self.effects_rng, position, 150));
+        self.particles.append(&mut Particle::spawn_debris(&self.effects_rng, position, 60));
+        self.audio.play(SoundEffect::LargeExplosion);
+        self.shake.add(Shake::LargeExplosion);
+
+        let reaches = |target: Vec2, target_radius: f32| wrapped_delta(position, target).length() < radius + target_radius;
+
+        let mut aftermaths = Vec::new();
+
+        // Fragments of rocks destroyed here are left for the next blast, and
+        // carry no momentum from this one
+        for index in 0..self.asteroids.len() {
+            let asteroid = &self.asteroids[index];
+
+            if asteroid.is_alive() && reaches(asteroid.position, asteroid.get_radius()) && Self::BLAST_DAMAGE >= asteroid.toughness() {
+                let strike = Strike { impulse: Vec2::ZERO, point: asteroid.position };
+                self.handle_asteroid_destroyed(index, cause, Some(strike), &mut aftermaths);
+            }
+        }
+
+        for index in 0..self.saucers.len() {
+            let saucer = &self.saucers[index];
+
+            if saucer.is_alive() && reaches(saucer.position, saucer.get_radius()) && Self::BLAST_DAMAGE >= saucer.toughness() {
+                self.destroy_saucer(index, cause);
+            }
+        }
+
+        for (asteroid, cause) in aftermaths {
+            self.asteroid_aftermath(&asteroid, cause);
+        }
+    }
+
+    /// Pay out the bonuses for the wave just cleared, and show the tally of
+    /// them before the next wave. A game being watched, played a frame at a
+    /// time or run headless goes straight on to the next wave.
+    fn finish_wave(&mut self) {
+        let tally = WaveTally::new(self.wave_number, &self.wave_stats, self.ship.bombs);
+        self.gold_seam = None;
+
+        let previous_score = self.player_score;
+
+        self.player_score = self.player_score.saturating_add(tally.total());
+        self.award_score_bonuses(previous_score);
+        self.debug_log.push(self.clock.now(), format!("wave {} cleared, {} bonus", tally.wave, tally.total()));
+        self.events.emit(GameEvent::WaveCleared { wave: tally.wave });
+
+        if self.watching.is_some() || self.tas.is_some() || self.headless {
+            self.next_wave();
+        } else {
+            self.wave_tally = Some((self.clock.now(), tally));
+            self.game_state = GameState::WaveTally;
+        }
+    }
+
+    /// Offer a gold seam bonus round once a wave is cleared, play it out, and
+    /// start the next wave when it is over. Mining every seam earns a bonus.
+    fn update_gold_seam(&mut self) {
+        let now = self.clock.now();
+
+        let Some(gold_seam) = &mut self.gold_seam else {
+            self.gold_seam = Some(GoldSeam::spawn(&self.rng, now));
+            return;
+        };
+
+        gold_seam.update();
+
+        if !gold_seam.is_over(now) {
+            return;
+        }
+
+        if gold_seam.started_at.is_some() {
+            let rock = &gold_seam.rock;
+
+            self.particles.append(&mut Self::asteroid_explosion(&self.effects_rng, rock));
+            self.particles.append(&mut Particle::spawn_ring(&self.effects_rng, rock.position, rock.diameter / 2.0, 100));
+            self.audio.play(SoundEffect::LargeExplosion);
+            self.shake.add(Shake::LargeExplosion);
+
+            if gold_seam.is_mined_out() {
+                self.player_score = self.player_score.saturating_add(GoldSeam::CLEAR_BONUS);
+                self.floating_texts.push(Self::score_popup(GoldSeam::CLEAR_BONUS, rock.position, PLAYER_ONE));
+                self.audio.play(SoundEffect::ExtraLife);
+            }
+        }
+
+        self.finish_wave();
+    }
+
+    /// Mine the gold seams the players' bullets strike, and chip the rock
+    /// where they miss the seams. The first shot to land starts the round.
+    fn gold_seam_collisions(&mut self, multiplier: u32) {
+        let Some(gold_seam) = &mut self.gold_seam else {
+            return;
+        };
+
+        let now = self.clock.now();
+
+        for bullet in self.player_bullets.iter_mut().filter(|bullet| bullet.is_alive()) {
+            if let Some(seam) = gold_seam.seams.iter_mut().find(|seam| seam.is_alive() && bullet.is_colliding(*seam)) {
+                let score = Self::player_score_mut(&mut self.player_score, &mut self.wingman, bullet.owner);
+                *score = score.saturating_add(GoldSeam::SEAM_POINTS);
+
+                self.floating_texts.push(Self::score_popup(GoldSeam::SEAM_POINTS * multiplier, seam.position, bullet.owner));
+                self.particles.append(&mut Particle::spawn_radial(&self.effects_rng, seam.position, 30));
+                self.audio.play(SoundEffect::SmallExplosion);
+
+                seam.mine();
+            } else if bullet.is_colliding(&gold_seam.rock) {
+                self.particles.append(&mut Particle::spawn_debris(&self.effects_rng, bullet.position, 5));
+                gold_seam.chip();
+            } else {
+                continue;
+            }
+
+            gold_seam.start(now);
+            bullet.destroy();
+        }
+    }
+
+    /// Asteroids and saucers swept over by the players' smart bomb shockwaves,
+    /// destroyed for part of their usual points. Asteroids are vaporised
+    /// rather than split.
+    fn shockwave_pairs(&self) -> Vec<CollisionPair> {
+        let mut pairs = Vec::new();
+
+        for shockwave in self.shockwaves.iter().filter(|shockwave| shockwave.is_expanding()) {
+            for (asteroid, rock) in self.asteroids.iter().enumerate() {
+                if shockwave.reaches(rock.position, rock.get_radius()) {
+                    pairs.push(CollisionPair::ShockwaveAsteroid { asteroid });
+                }
+            }
+
+            for (saucer, craft) in self.saucers.iter().enumerate() {
+                if shockwave.reaches(craft.position, craft.get_radius()) {
+                    pairs.push(CollisionPair::ShockwaveSaucer { saucer });
+                }
+            }
+        }
+
+        pairs
+    }
+
+    /// Popup showing points scored by a player where they were scored, in the
+    /// second player's colour for their points.
+    fn score_popup(points: u32, position: Vec2, player: usize) -> FloatingText {
+        let popup = FloatingText::points(points, position);
+
+        if player == PLAYER_TWO {
+            popup.with_color(Wingman::COLOR)
+        } else {
+            popup
+        }
+    }
+
+    /// Particles thrown off by a destroyed asteroid, more of them the larger
+    /// the asteroid: a burst of sparks, plus debris from medium asteroids up.
+    fn asteroid_explosion(rng: &RandGenerator, asteroid: &Asteroid) -> Vec<Particle> {
+        Self::explosion_particles(rng, asteroid.position, asteroid.grade())
+    }
+
+    /// Particles thrown off by an asteroid of a size grade destroyed at a
+    /// position.
+    fn explosion_particles(rng: &RandGenerator, position: Vec2, grade: f32) -> Vec<Particle> {
+        let grade = grade.max(0.0);
+        let mut particles = Particle::spawn_radial(rng, position, (10.0 + 10.0 * grade) as u32);
+        particles.append(&mut Particle::spawn_debris(rng, position, (5.0 * grade) as u32));
+
+        particles
+    }
+
+    /// Destroy a saucer struck down by a blast or an arc, which scores once
+    /// the events are dispatched, and may leave salvage behind if a player
+    /// brought it down.
+    fn destroy_saucer(&mut self, index: usize, cause: Cause) {
+        let (position, velocity) = (self.saucers[index].position, self.saucers[index].velocity);
+
+        self.handle_saucer_destroyed(index, cause, false);
+
+        if cause.player().is_some() {
+            self.drop_salvage(position, velocity);
+        }
+    }
+
+    /// Roll the loot table for a saucer shot down by a player, leaving a
+    /// salvage crate drifting from the wreck if one is dropped.
+    fn drop_salvage(&mut self, position: Vec2, velocity: Vec2) {
+        if let Some(contents) = self.loot_table.roll(&self.rng) {
+            self.spawn_salvage(position, velocity, contents);
+        }
+    }
+
+    /// Leave a salvage crate behind something destroyed at a position while
+    /// moving at a velocity.
+    fn spawn_salvage(&mut self, position: Vec2, velocity: Vec2, contents: Salvage) {
+        // Chronos are only found where they can be used
+        let contents = match contents {
+            Salvage::Chrono if !self.is_rewind_allowed() => Salvage::Points,
+            contents => contents,
+        };
+
+        self.salvage.push(SalvageCrate::spawn_new(position, velocity * 0.25, contents, self.clock.now()));
+    }
+
+    /// Grant a player the contents of a salvage crate they flew into.
+    fn collect_salvage(&mut self, player: usize, contents: Salvage) {
+        match contents {
+            Salvage::Decoy => self.player_ship_mut(player).decoys += 1,
+            Salvage::Ore => self.player_credits += Salvage::ORE_CREDITS,
+            Salvage::Points => {
+                let score = Self::player_score_mut(&mut self.player_score, &mut self.wingman, player);
+                *score = score.saturating_add(Salvage::POINTS);
+            },
+            Salvage::HeatVent => {
+                let now = self.clock.now();
+                self.player_ship_mut(player).cool_hyperspace(1.0, now);
+            },
+            Salvage::Chrono => self.chronos += 1,
+        }
+
+        self.audio.play(SoundEffect::ExtraLife);
+        self.salvage_notice = Some((self.clock.now(), contents));
+    }
+}
@@ -0,0 +1,155 @@
+//! Screen wrapping.
+//!
+//! Objects leaving the screen by one edge come back in by the opposite one.
+//! An object carries on past an edge until it is `WRAP_MARGIN` of its radii
+//! beyond it, clear of the screen, and only then wraps, keeping however far
+//! it overshot, so nothing jumps when it wraps. While an object straddles an
+//! edge it is drawn twice, once either side of it, and while it straddles a
+//! corner it is drawn four times: once in each of the corners it spans, the
+//! last being the diagonal ghost across both edges. Positions within the
+//! margin are the same point on the wrapping screen as those they wrap to,
+//! and collide alike.
+//!
+//! Anything that collides is drawn wrapped through `WrapDraw`, which takes
+//! the object's extent from its collision circle. Parts of an object reaching
+//! beyond that circle, such as a ship's shield, are drawn with `wrap_draw` and
+//! an extent of their own.
+
+use macroquad::prelude::*;
+
+use crate::camera::WORLD_SIZE;
+use crate::collision::Collidable;
+
+/// How far, in radii of the object, an object carries on beyond an edge
+/// before it wraps.
+pub const WRAP_MARGIN: f32 = 1.0;
+
+/// Wrap a position on a wrapping screen of a size once it is further than a
+/// margin beyond an edge, to the same point on the screen; within the margin
+/// it is left where it is.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::wrap::wrap_position;
+/// # use macroquad::prelude::*;
+/// let screen = Vec2::new(800.0, 600.0);
+///
+/// // Just past the right edge, within the margin
+/// assert_eq!(wrap_position(Vec2::new(810.0, 300.0), 20.0, screen), Vec2::new(810.0, 300.0));
+///
+/// // Beyond the margin, it comes back in by the left edge, keeping the overshoot
+/// assert_eq!(wrap_position(Vec2::new(825.0, 300.0), 20.0, screen), Vec2::new(25.0, 300.0));
+///
+/// // And likewise above the top edge
+/// assert_eq!(wrap_position(Vec2::new(400.0, -21.0), 20.0, screen), Vec2::new(400.0, 579.0));
+/// ```
+pub fn wrap_position(position: Vec2, margin: f32, screen: Vec2) -> Vec2 {
+    let wrap = |coordinate: f32, size: f32| {
+        if coordinate < -margin || coordinate > size + margin {
+            coordinate.rem_euclid(size)
+        } else {
+            coordinate
+        }
+    };
+
+    Vec2::new(wrap(position.x, screen.x), wrap(position.y, screen.y))
+}
+
+/// Check if a position is on a screen of a size, or within a margin beyond
+/// its edges.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::wrap::is_within;
+/// # use macroquad::prelude::*;
+/// let screen = Vec2::new(800.0, 600.0);
+///
+/// assert!(is_within(Vec2::new(400.0, 300.0), 0.0, screen));
+/// assert!(is_within(Vec2::new(-3.0, 300.0), 4.0, screen));
+/// assert!(!is_within(Vec2::new(400.0, 605.0), 4.0, screen));
+/// ```
+pub fn is_within(position: Vec2, margin: f32, screen: Vec2) -> bool {
+    position.cmpge(Vec2::splat(-margin)).all() && position.cmple(screen + margin).all()
+}
+
+/// Wrap the position of an object within a radius of it, once it is
+/// `WRAP_MARGIN` radii beyond an edge of the playfield.
+pub fn wrap(position: Vec2, radius: f32) -> Vec2 {
+    wrap_position(position, radius * WRAP_MARGIN, WORLD_SIZE)
+}
+
+/// Positions at which the ghost copies of an object are drawn, on a wrapping
+/// screen of a size, for an object within a radius of its position. There are
+/// none while the object is clear of the edges. The object may be beyond an
+/// edge, within its wrap margin, in which case its ghost is the copy on the
+/// screen.
+///
+/// # Examples
+///
+/// ```
+/// # use asteroids::wrap::ghost_positions;
+/// # use macroquad::prelude::*;
+/// let screen = Vec2::new(800.0, 600.0);
+///
+/// // Clear of the edges
+/// assert_eq!(ghost_positions(Vec2::new(400.0, 300.0), 20.0, screen).count(), 0);
+///
+/// // Across the right edge
+/// let ghosts: Vec<Vec2> = ghost_positions(Vec2::new(790.0, 300.0), 20.0, screen).collect();
+/// assert_eq!(ghosts, [Vec2::new(-10.0, 300.0)]);
+///
+/// // Across the top left corner, including the diagonal copy
+/// let ghosts: Vec<Vec2> = ghost_positions(Vec2::new(5.0, 10.0), 20.0, screen).collect();
+/// assert_eq!(ghosts, [Vec2::new(805.0, 10.0), Vec2::new(5.0, 610.0), Vec2::new(805.0, 610.0)]);
+///
+/// // Beyond the bottom edge, yet to wrap
+/// let ghosts: Vec<Vec2> = ghost_positions(Vec2::new(400.0, 615.0), 20.0, screen).collect();
+/// assert_eq!(ghosts, [Vec2::new(400.0, 15.0)]);
+/// ```
+pub fn ghost_positions(position: Vec2, radius: f32, screen: Vec2) -> impl Iterator<Item = Vec2> {
+    let shift = |coordinate: f32, size: f32| {
+        if coordinate > size - radius {
+            -size
+        } else if coordinate < radius {
+            size
+        } else {
+            0.0
+        }
+    };
+
+    let dx = shift(position.x, screen.x);
+    let dy = shift(position.y, screen.y);
+
+    [
+        (dx != 0.0).then(|| position + Vec2::new(dx, 0.0)),
+        (dy != 0.0).then(|| position + Vec2::new(0.0, dy)),
+        (dx != 0.0 && dy != 0.0).then(|| position + Vec2::new(dx, dy)),
+    ].into_iter().flatten()
+}
+
+/// Draw an object at its position and at each of its ghost positions on the
+/// screen, for an object within a radius of its position.
+pub fn wrap_draw(position: Vec2, radius: f32, mut draw_at: impl FnMut(Vec2)) {
+    draw_at(position);
+
+    for ghost in ghost_positions(position, radius, WORLD_SIZE) {
+        draw_at(ghost);
+    }
+}
+
+/// Wrap draw
+///
+/// Objects drawn at their position and at the ghost positions across the
+/// edges they straddle, as far as their collision circle reaches.
+pub trait WrapDraw {
+    /// Draw the object at its position and at each of its ghost positions.
+    fn draw_wrapped(&self, draw_at: impl FnMut(Vec2));
+}
+
+impl<T: Collidable> WrapDraw for T {
+    fn draw_wrapped(&self, draw_at: impl FnMut(Vec2)) {
+        wrap_draw(self.get_position(), self.get_radius(), draw_at);
+    }
+}
@@ -0,0 +1,150 @@
+//! Heap allocations made by frames of a busy game, counted by
+//! `CountingAllocator` installed as this test's global allocator.
+//!
+//! Frames allocate only when something happens on them:
+//!
+//! - something is destroyed, or a ship is hit, a wave cleared or a life
+//!   earned: every frame on which an event is dispatched;
+//! - something is spawned or goes away: bullets fired, exhaust and explosion
+//!   particles, fragments of split rocks, saucers and their shots, popups
+//!   and radio chatter;
+//! - a game starts, and its input log and flight recorder fill, over its
+//!   first `ReplayCode::MAX_FRAMES` and `Recording::CAPACITY` frames;
+//! - once the flight recorder has filled, more ships, rocks, saucers or
+//!   bullets are in play than on the frame it overwrites, whose storage it
+//!   reuses;
+//! - a chrono is held, and the game is snapshotted to rewind to.
+//!
+//! Every other frame allocates nothing.
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use asteroids::ai::AutoPilot;
+use asteroids::alloc::{allocations, CountingAllocator};
+use asteroids::options::LaunchOptions;
+use asteroids::replay::Recording;
+use asteroids::replaycode::{InputFrame, ReplayCode};
+use asteroids::world::GameWorld;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Time in seconds each frame steps the game by.
+const STEP: f32 = 1.0 / 60.0;
+
+/// Frames played after the first game's flight recorder has filled.
+const MEASURED: usize = 40_000;
+
+/// How many there are of each kind of object in play, and the wave, which
+/// change only when something is spawned, goes away or a wave starts.
+fn census(world: &GameWorld) -> [usize; 14] {
+    [
+        world.particles.len(),
+        world.player_bullets.len(),
+        world.enemy_bullets.len(),
+        world.asteroids.len(),
+        world.saucers.len(),
+        world.hunters.len(),
+        world.turrets.len(),
+        world.floating_texts.len(),
+        world.bolts.len(),
+        world.decoys.len(),
+        world.shockwaves.len(),
+        world.salvage.len(),
+        world.chatter.len(),
+        world.wave_number as usize,
+    ]
+}
+
+/// How many ships, rocks, saucers and bullets of each side the flight
+/// recorder recorded on the frame just played.
+fn recorded(world: &GameWorld) -> [usize; 5] {
+    let now = world.clock.now();
+
+    [
+        !world.ship.is_respawning(now) as usize,
+        world.asteroids.iter().filter(|asteroid| asteroid.is_alive()).count(),
+        world.saucers.iter().filter(|saucer| saucer.is_alive()).count(),
+        world.player_bullets.iter().filter(|bullet| bullet.is_alive()).count(),
+        world.enemy_bullets.iter().filter(|bullet| bullet.is_alive()).count(),
+    ]
+}
+
+/// Controls the auto pilot works for the frame.
+fn pilot_input(pilot: &AutoPilot, world: &GameWorld) -> InputFrame {
+    let command = pilot.fly(&world.ship, &world.asteroids, &world.saucers, world.clock.now());
+
+    let buttons = [
+        (command.thrust, InputFrame::THRUST),
+        (command.fire, InputFrame::FIRE),
+        (command.hyperspace, InputFrame::HYPERSPACE),
+    ].iter().filter(|(down, _)| *down).fold(0, |buttons, (_, button)| buttons | button);
+
+    InputFrame::new(command.steer, buttons, STEP)
+}
+
+#[test]
+fn quiet_frames_do_not_allocate() {
+    let options = LaunchOptions { seed: Some(1979), ..Default::default() };
+    let mut world = GameWorld::headless(&options);
+    let pilot = AutoPilot::default();
+
+    let dispatched = Rc::new(Cell::new(0));
+    let heard = dispatched.clone();
+    world.events.subscribe(move |_| heard.set(heard.get() + 1));
+
+    world.start(false);
+
+    // Lives enough for the game to go on long after the flight recorder fills
+    world.player_lives = 1000;
+
+    // Room in each frame the flight recorder holds, oldest first
+    let mut recorder = VecDeque::with_capacity(Recording::CAPACITY);
+    let mut quiet = 0;
+
+    for frame in 0..Recording::CAPACITY + MEASURED {
+        let input = pilot_input(&pilot, &world);
+        let (before, events, shot_cooldown) = (census(&world), dispatched.get(), world.ship.shot_cooldown);
+
+        let start = allocations();
+        world.step(STEP, input);
+        let count = allocations() - start;
+
+        // Thrusting throws off exhaust, and a jump leaves particles behind
+        let eventful = dispatched.get() != events
+            || census(&world) != before
+            || world.ship.shot_cooldown != shot_cooldown
+            || input.is_down(InputFrame::THRUST)
+            || input.is_down(InputFrame::HYPERSPACE);
+
+        let filling = recorder.len() < Recording::CAPACITY.max(ReplayCode::MAX_FRAMES);
+
+        // The frame the recorder overwrites has room for the most it has held
+        let recorded = recorded(&world);
+        let (mut room, mut outgrown) = (recorded, false);
+
+        if recorder.len() == Recording::CAPACITY {
+            let held: [usize; 5] = recorder.pop_front().unwrap_or_default();
+
+            outgrown = recorded.iter().zip(&held).any(|(now, then)| now > then);
+            room = std::array::from_fn(|kind| recorded[kind].max(held[kind]));
+        }
+
+        recorder.push_back(room);
+
+        if eventful || filling || outgrown || world.chronos() > 0 {
+            continue;
+        }
+
+        assert_eq!(count, 0, "frame {} made {} allocations with nothing happening on it", frame, count);
+        quiet += 1;
+    }
+
+    assert!(world.is_playing(), "the game ended");
+
+    // Enough frames were quiet for the check to mean something
+    assert!(quiet > MEASURED / 20, "only {} frames were quiet", quiet);
+    assert!(world.player_score > 0, "the auto pilot never scored");
+}
@@ -0,0 +1,109 @@
+//! Blasts from volatile asteroids, whose kills are credited to whatever set
+//! the asteroid off.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use asteroids::asteroid::{Asteroid, AsteroidKind, AsteroidSize};
+use asteroids::bullet::{Bullet, BulletType};
+use asteroids::camera::WORLD_SIZE;
+use asteroids::events::{Cause, GameEvent};
+use asteroids::options::LaunchOptions;
+use asteroids::world::GameWorld;
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+/// A game in play with a volatile asteroid and a small rock beside it, in
+/// reach of its blast, and a large rock far off so the wave goes on.
+fn volatile_field(coop: bool) -> GameWorld {
+    let options = LaunchOptions { seed: Some(1979), ..Default::default() };
+    let mut world = GameWorld::headless(&options);
+
+    if coop {
+        world.start_coop();
+    } else {
+        world.start(false);
+    }
+
+    let rng = RandGenerator::new();
+    let volatile = Asteroid::spawn_kind_at(&rng, AsteroidSize::Medium.scale(), Vec2::new(150.0, 150.0), AsteroidKind::Volatile);
+    let beside = volatile.position + Vec2::new(volatile.diameter, 0.0);
+
+    world.asteroids = vec![
+        volatile,
+        Asteroid::spawn_new_at(&rng, AsteroidSize::Small.scale(), beside),
+        Asteroid::spawn_new_at(&rng, AsteroidSize::Large.scale(), WORLD_SIZE - Vec2::splat(150.0)),
+    ];
+
+    world.saucers.clear();
+    world.hunters.clear();
+
+    world
+}
+
+/// Fire a bullet sitting on the volatile asteroid, step the world once, and
+/// return the causes of the asteroids destroyed.
+fn set_off(world: &mut GameWorld, mut bullet: Bullet, owner: usize) -> Vec<Cause> {
+    bullet.owner = owner;
+
+    match bullet.bullet_type {
+        BulletType::Player => world.player_bullets.push(bullet),
+        BulletType::Enemy => world.enemy_bullets.push(bullet),
+    }
+
+    let causes = Rc::new(RefCell::new(Vec::new()));
+    let heard = causes.clone();
+
+    world.events.subscribe(move |event| {
+        if let GameEvent::AsteroidDestroyed { cause, .. } = *event {
+            heard.borrow_mut().push(cause);
+        }
+    });
+
+    world.update();
+
+    let causes = causes.borrow().clone();
+    causes
+}
+
+/// A bullet of a type sitting still on the volatile asteroid.
+fn bullet_on_volatile(world: &GameWorld, bullet_type: BulletType) -> Bullet {
+    Bullet::spawn_new(world.asteroids[0].position, Vec2::ZERO, 1.0, bullet_type)
+}
+
+#[test]
+fn enemy_set_off_blast_scores_nothing() {
+    let mut world = volatile_field(false);
+    let bullet = bullet_on_volatile(&world, BulletType::Enemy);
+
+    let causes = set_off(&mut world, bullet, 0);
+
+    assert_eq!(causes, [Cause::Enemy, Cause::Enemy]);
+    assert_eq!(world.player_score, 0);
+    assert!(world.floating_texts.is_empty());
+}
+
+#[test]
+fn wingman_set_off_blast_scores_for_the_wingman() {
+    let mut world = volatile_field(true);
+    let points = world.asteroids[0].points() + world.asteroids[1].points();
+    let bullet = bullet_on_volatile(&world, BulletType::Player);
+
+    let causes = set_off(&mut world, bullet, 1);
+
+    assert_eq!(causes, [Cause::Player(1), Cause::Player(1)]);
+    assert_eq!(world.wingman.as_ref().map(|wingman| wingman.score), Some(points));
+    assert_eq!(world.player_score, 0);
+}
+
+#[test]
+fn player_set_off_blast_scores_for_the_player() {
+    let mut world = volatile_field(false);
+    let points = world.asteroids[0].points() + world.asteroids[1].points();
+    let bullet = bullet_on_volatile(&world, BulletType::Player);
+
+    let causes = set_off(&mut world, bullet, 0);
+
+    assert_eq!(causes, [Cause::Player(0), Cause::Player(0)]);
+    assert_eq!(world.player_score, points);
+}
@@ -0,0 +1,156 @@
+//! Wrapping at the edges of the playfield: objects carry on past an edge
+//! until they are `WRAP_MARGIN` of their radii beyond it, and collide across
+//! the seam with whatever is at the other side.
+
+use asteroids::asteroid::{Asteroid, AsteroidSize};
+use asteroids::bullet::{Bullet, BulletType};
+use asteroids::camera::{WORLD_HEIGHT, WORLD_SIZE, WORLD_WIDTH};
+use asteroids::collision::Collidable;
+use asteroids::options::LaunchOptions;
+use asteroids::ship::Ship;
+use asteroids::world::GameWorld;
+use asteroids::wrap::{self, WRAP_MARGIN};
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+/// Radius of the objects wrapped in these tests.
+const RADIUS: f32 = 20.0;
+
+/// How far beyond an edge an object of `RADIUS` carries on before it wraps.
+const MARGIN: f32 = RADIUS * WRAP_MARGIN;
+
+/// A small asteroid at a position, drifting at a velocity.
+fn asteroid_at(position: Vec2, velocity: Vec2) -> Asteroid {
+    let mut asteroid = Asteroid::spawn_new_at(&RandGenerator::new(), AsteroidSize::Small.scale(), position);
+    asteroid.velocity = velocity;
+
+    asteroid
+}
+
+/// A player's bullet at a position, moving at a velocity.
+fn bullet_at(position: Vec2, velocity: Vec2) -> Bullet {
+    Bullet::spawn_new(position, velocity, 1.0, BulletType::Player)
+}
+
+#[test]
+fn positions_within_the_margin_are_left_alone() {
+    let beyond = [
+        Vec2::new(WORLD_WIDTH + MARGIN, 300.0),
+        Vec2::new(-MARGIN, 300.0),
+        Vec2::new(400.0, WORLD_HEIGHT + MARGIN),
+        Vec2::new(400.0, -MARGIN),
+        Vec2::new(-MARGIN, -MARGIN),
+        Vec2::new(WORLD_WIDTH + MARGIN, WORLD_HEIGHT + MARGIN),
+    ];
+
+    for position in beyond {
+        assert_eq!(wrap::wrap(position, RADIUS), position);
+        assert!(wrap::is_within(position, MARGIN, WORLD_SIZE));
+    }
+}
+
+#[test]
+fn positions_beyond_the_margin_wrap_keeping_the_overshoot() {
+    let overshoot = 3.0;
+
+    let wrapped = [
+        (Vec2::new(WORLD_WIDTH + MARGIN + overshoot, 300.0), Vec2::new(MARGIN + overshoot, 300.0)),
+        (Vec2::new(-MARGIN - overshoot, 300.0), Vec2::new(WORLD_WIDTH - MARGIN - overshoot, 300.0)),
+        (Vec2::new(400.0, WORLD_HEIGHT + MARGIN + overshoot), Vec2::new(400.0, MARGIN + overshoot)),
+        (Vec2::new(400.0, -MARGIN - overshoot), Vec2::new(400.0, WORLD_HEIGHT - MARGIN - overshoot)),
+        (Vec2::splat(-MARGIN - overshoot), WORLD_SIZE - Vec2::splat(MARGIN + overshoot)),
+    ];
+
+    for (position, expected) in wrapped {
+        assert!(!wrap::is_within(position, MARGIN, WORLD_SIZE));
+        assert!(wrap::wrap(position, RADIUS).abs_diff_eq(expected, 1e-3), "{} wrapped to {}", position, wrap::wrap(position, RADIUS));
+    }
+}
+
+#[test]
+fn drifting_asteroids_wrap_without_jumping() {
+    let velocity = Vec2::new(1.5, -0.5);
+    let mut asteroid = asteroid_at(Vec2::new(WORLD_WIDTH - 10.0, 10.0), velocity);
+    let radius = asteroid.get_radius();
+    let mut wrapped = 0;
+
+    for _ in 0..200 {
+        let before = asteroid.position;
+        asteroid.update();
+
+        assert!(wrap::is_within(asteroid.position, radius * WRAP_MARGIN, WORLD_SIZE));
+
+        // Moved by its velocity, the short way around the screen
+        let moved = asteroid.position - before;
+        let seam = Vec2::new((moved.x / WORLD_WIDTH).round() * WORLD_WIDTH, (moved.y / WORLD_HEIGHT).round() * WORLD_HEIGHT);
+        assert!((moved - seam).abs_diff_eq(velocity, 1e-3));
+
+        wrapped += (seam != Vec2::ZERO) as u32;
+    }
+
+    // Across the right edge, and the top
+    assert_eq!(wrapped, 2);
+}
+
+#[test]
+fn objects_collide_across_the_seam() {
+    let asteroid = asteroid_at(Vec2::new(WORLD_WIDTH - 5.0, 300.0), Vec2::ZERO);
+    let radius = asteroid.get_radius();
+
+    // Just inside the opposite edge
+    assert!(bullet_at(Vec2::new(3.0, 300.0), Vec2::ZERO).is_colliding(&asteroid));
+
+    // Carried on past the edge, yet to wrap
+    assert!(bullet_at(Vec2::new(WORLD_WIDTH + 3.0, 300.0), Vec2::ZERO).is_colliding(&asteroid));
+    assert!(asteroid_at(Vec2::new(-radius * WRAP_MARGIN, 300.0), Vec2::ZERO).is_colliding(&asteroid));
+
+    // Across the corner
+    let cornered = asteroid_at(Vec2::new(-2.0, -2.0), Vec2::ZERO);
+    assert!(bullet_at(WORLD_SIZE - Vec2::splat(2.0), Vec2::ZERO).is_colliding(&cornered));
+
+    // A ship straddling the bottom edge, and a rock at the top
+    let mut ship = Ship::spawn_new();
+    ship.position = Vec2::new(400.0, WORLD_HEIGHT + 2.0);
+    assert!(ship.is_colliding(&asteroid_at(Vec2::new(400.0, 5.0), Vec2::ZERO)));
+
+    // Too far apart the short way around to touch
+    assert!(!bullet_at(Vec2::new(radius + 10.0, 300.0), Vec2::ZERO).is_colliding(&asteroid));
+    assert!(!bullet_at(Vec2::new(WORLD_WIDTH / 2.0, 300.0), Vec2::ZERO).is_colliding(&asteroid));
+}
+
+#[test]
+fn bullets_sweep_across_the_seam() {
+    let asteroid = asteroid_at(Vec2::new(4.0, 300.0), Vec2::ZERO);
+
+    // Fired across the right edge, it wraps to the left on the far side of
+    // the rock, and hits what it passed through on the way
+    let mut bullet = bullet_at(Vec2::new(WORLD_WIDTH - 30.0, 300.0), Vec2::new(90.0, 0.0));
+    assert!(!bullet.is_colliding(&asteroid));
+
+    bullet.update(1.0 / 60.0);
+
+    assert!(bullet.wrapped);
+    assert!(bullet.position.x > asteroid.position.x + asteroid.get_radius());
+    assert!(bullet.is_colliding(&asteroid));
+}
+
+#[test]
+fn shots_destroy_rocks_across_the_seam() {
+    let options = LaunchOptions { seed: Some(1979), ..Default::default() };
+    let mut world = GameWorld::headless(&options);
+    world.start(false);
+
+    world.saucers.clear();
+    world.hunters.clear();
+    world.asteroids = vec![
+        asteroid_at(Vec2::new(-4.0, 300.0), Vec2::ZERO),
+        asteroid_at(Vec2::new(400.0, 40.0), Vec2::ZERO),
+    ];
+    world.player_bullets.push(bullet_at(Vec2::new(WORLD_WIDTH - 1.0, 300.0), Vec2::ZERO));
+
+    let points = world.asteroids[0].points();
+    world.update();
+
+    assert!(world.player_bullets.is_empty());
+    assert_eq!(world.player_score, points);
+}